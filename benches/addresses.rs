@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate criterion;
+extern crate serde_osc;
+
+use criterion::{Criterion, Throughput};
+use serde_osc::de;
+use serde_osc::value::Message;
+
+/// A small control message, as a fader or sensor would send: most of its
+/// bytes are the address and typetag, which must be scanned for terminators.
+fn control_msg(depth: usize) -> Vec<u8> {
+    let address: String = (0..depth).map(|i| format!("/section{}", i)).collect();
+    serde_osc::to_vec(&Message::to(address).arg(0.5f32).arg(3).build()).unwrap()
+}
+
+// Run with `--features memchr` to compare the vectorized scanning.
+fn decode_addresses(c: &mut Criterion) {
+    let mut group = c.benchmark_group("addresses");
+    for &depth in &[1, 4, 16] {
+        let packet = control_msg(depth);
+        group.throughput(Throughput::Bytes(packet.len() as u64));
+        group.bench_function(format!("{}/from_slice", depth), |b| {
+            b.iter(|| de::from_slice::<(String, (f32, i32))>(&packet).unwrap())
+        });
+        group.bench_function(format!("{}/validate", depth), |b| {
+            b.iter(|| de::validate(&packet).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode_addresses);
+criterion_main!(benches);
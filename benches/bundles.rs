@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate criterion;
+extern crate serde_osc;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use criterion::{Criterion, Throughput};
+use serde_osc::ser;
+use serde_osc::value::{Bundle, Message, Packet};
+
+/// Counts allocations, to show how many each serialization makes.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Bundles nested `depth` deep, each holding a few messages besides the next bundle.
+fn nested(depth: u32) -> Packet {
+    let mut bundle = Bundle::new((depth, 0));
+    for i in 0..4 {
+        bundle.elements.push(Message::to(format!("/level/{}/{}", depth, i)).arg(i).arg(0.5f32).arg("x").build().into());
+    }
+    if depth > 1 {
+        bundle.elements.push(nested(depth - 1));
+    }
+    bundle.into()
+}
+
+fn encode_bundles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bundles");
+    for &depth in &[1, 4, 16] {
+        let packet = nested(depth);
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let encoded = ser::to_vec(&packet).unwrap();
+        println!("bundles/depth {}: {} allocations", depth, ALLOCATIONS.load(Ordering::Relaxed) - before);
+        group.throughput(Throughput::Bytes(encoded.len() as u64));
+        group.bench_function(format!("depth {}/to_vec", depth), |b| {
+            b.iter(|| ser::to_vec(&packet).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode_bundles);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+#[macro_use]
+extern crate criterion;
+extern crate serde;
+extern crate serde_osc;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use criterion::{BenchmarkGroup, Criterion, Throughput};
+use criterion::measurement::WallTime;
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+use serde_osc::{de, ser};
+
+type Msg = (String, Vec<String>);
+type Bundle = ((u32, u32), (Msg, Msg, Msg, Msg, Msg, Msg, Msg, Msg));
+
+/// A message with many string arguments of assorted lengths.
+fn strings_msg(n: usize) -> Msg {
+    let args = (0..32).map(|i| "x".repeat((i * 7 + n) % 40)).collect();
+    (format!("/strings/{}", n), args)
+}
+
+/// A reader without a buffer of its own, as a `TcpStream` would be (minus the system calls).
+struct Unbuffered<'a>(&'a [u8]);
+
+impl<'a> Read for Unbuffered<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+fn bench_packet<T>(group: &mut BenchmarkGroup<WallTime>, name: &str, value: &T)
+    where T: Serialize + DeserializeOwned
+{
+    let packet = ser::to_vec(value).unwrap();
+    group.throughput(Throughput::Bytes(packet.len() as u64));
+    group.bench_function(format!("{}/from_slice", name), |b| {
+        b.iter(|| de::from_slice::<T>(&packet).unwrap())
+    });
+    group.bench_function(format!("{}/from_read", name), |b| {
+        b.iter(|| de::from_read::<T, _>(Unbuffered(&packet)).unwrap())
+    });
+    // Each `read` is a system call here, so their number dominates.
+    let path = env::temp_dir().join(format!("serde_osc_bench_{}_{}", name, std::process::id()));
+    fs::write(&path, &packet).unwrap();
+    let mut file = File::open(&path).unwrap();
+    group.bench_function(format!("{}/from_file", name), |b| {
+        b.iter(|| {
+            file.seek(SeekFrom::Start(0)).unwrap();
+            de::from_read::<T, _>(&mut file).unwrap()
+        })
+    });
+    fs::remove_file(&path).unwrap();
+}
+
+fn decode_strings(c: &mut Criterion) {
+    let mut group = c.benchmark_group("strings");
+    bench_packet(&mut group, "message", &strings_msg(3));
+    let m = strings_msg;
+    let bundle: Bundle = ((0, 1), (m(0), m(1), m(2), m(3), m(4), m(5), m(6), m(7)));
+    bench_packet(&mut group, "bundle", &bundle);
+    group.finish();
+}
+
+criterion_group!(benches, decode_strings);
+criterion_main!(benches);
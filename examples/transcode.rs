@@ -0,0 +1,40 @@
+extern crate serde_json;
+extern crate serde_osc;
+extern crate serde_transcode;
+
+use std::io::Cursor;
+use serde_osc::{de, ser};
+
+/// Convert a (length-prefixed) OSC packet to JSON in one pass, without
+/// decoding it into any intermediate type first.
+///
+/// A message becomes `[address, [args...]]`, and a bundle becomes
+/// `[[seconds, fraction], [elements...]]`.
+fn osc_to_json(bytes: &[u8]) -> String {
+    let mut rd = Cursor::new(bytes);
+    let mut json = Vec::new();
+    serde_transcode::transcode(&mut de::Deserializer::new(&mut rd), &mut serde_json::Serializer::new(&mut json))
+        .expect("malformed OSC packet");
+    String::from_utf8(json).unwrap()
+}
+
+/// Convert JSON of the above form to an OSC packet.
+///
+/// JSON doesn't distinguish integer or floating-point widths, so numbers are
+/// encoded as the 64-bit 'h' and 'd' types.
+fn json_to_osc(json: &str) -> Vec<u8> {
+    let mut osc = Vec::new();
+    serde_transcode::transcode(&mut serde_json::Deserializer::from_str(json), &mut ser::Serializer::new(&mut osc))
+        .expect("JSON doesn't describe an OSC packet");
+    osc
+}
+
+fn main() {
+    let packet = ser::to_vec(&("/synth/1/note", (60, 0.5f32, "sine"))).unwrap();
+    let json = osc_to_json(&packet);
+    println!("OSC {:?}\n  as JSON: {}", packet, json);
+
+    let bundle = r#"[[0, 1], [["/mixer/gain", [3, 0.75]], ["/mixer/mute", [true]]]]"#;
+    let packet = json_to_osc(bundle);
+    println!("JSON {}\n  as OSC: {:?}\n  and back: {}", bundle, packet, osc_to_json(&packet));
+}
@@ -0,0 +1,551 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use serde::de::{self, Deserialize, DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use error::{Error, ResultE};
+use ser;
+use value::{Message, Packet, Value};
+
+/// An OSC address pattern, as carried in the address field of a message.
+///
+/// Besides literal characters, each '/'-delimited part of the pattern may contain:
+///   * `?`, matching any single character;
+///   * `*`, matching any sequence of zero or more characters;
+///   * `[abc]` or `[a-z]`, matching any one character in the set or range,
+///     or `[!abc]`, matching any one character *not* in the set;
+///   * `{foo,bar}`, matching any one of the comma-separated strings.
+///
+/// Wildcards never match across a '/' boundary.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AddressPattern {
+    pattern: String,
+}
+
+impl AddressPattern {
+    pub fn new<S: Into<String>>(pattern: S) -> Self {
+        Self { pattern: pattern.into() }
+    }
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+    /// Returns true if the pattern contains no wildcards, i.e. it can only
+    /// ever match one address.
+    pub fn is_literal(&self) -> bool {
+        !self.pattern.bytes().any(|c| b"?*[]{}".contains(&c))
+    }
+    /// Check whether the (literal) `address` is matched by this pattern.
+    pub fn matches(&self, address: &str) -> bool {
+        let mut pat_parts = self.pattern.split('/');
+        let mut addr_parts = address.split('/');
+        loop {
+            match (pat_parts.next(), addr_parts.next()) {
+                (None, None) => return true,
+                (Some(pat), Some(addr)) => if !match_part(pat.as_bytes(), addr.as_bytes()) {
+                    return false;
+                },
+                // Differing number of parts
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a str> for AddressPattern {
+    fn from(pattern: &'a str) -> Self {
+        Self::new(pattern)
+    }
+}
+
+impl From<String> for AddressPattern {
+    fn from(pattern: String) -> Self {
+        Self::new(pattern)
+    }
+}
+
+/// Match a single part of a pattern (i.e. containing no '/') against a part of an address.
+/// Malformed patterns (e.g. an unclosed '[') match nothing.
+///
+/// Rather than backtracking (which is exponential in the number of '*'s, and
+/// patterns come from the network), this tracks every position in `name`
+/// which the pattern read so far can have matched up to: O(pattern × name).
+fn match_part(mut pat: &[u8], name: &[u8]) -> bool {
+    let mut reached = vec![false; name.len() + 1];
+    let mut next = reached.clone();
+    reached[0] = true;
+    while let Some(&first) = pat.first() {
+        for n in next.iter_mut() {
+            *n = false;
+        }
+        pat = match first {
+            b'*' => {
+                let mut any = false;
+                for (r, n) in reached.iter().zip(next.iter_mut()) {
+                    any |= *r;
+                    *n = any;
+                }
+                &pat[1..]
+            },
+            b'?' => {
+                step(&reached, &mut next, name, |_| true);
+                &pat[1..]
+            },
+            b'[' => match pat.iter().position(|&c| c == b']') {
+                Some(close) => {
+                    step(&reached, &mut next, name, |c| class_matches(&pat[1..close], c));
+                    &pat[close+1..]
+                },
+                None => return false,
+            },
+            b'{' => match pat.iter().position(|&c| c == b'}') {
+                Some(close) => {
+                    for alt in pat[1..close].split(|&c| c == b',') {
+                        for i in 0..name.len() + 1 {
+                            if reached[i] && name[i..].starts_with(alt) {
+                                next[i + alt.len()] = true;
+                            }
+                        }
+                    }
+                    &pat[close+1..]
+                },
+                None => return false,
+            },
+            c => {
+                step(&reached, &mut next, name, |n| n == c);
+                &pat[1..]
+            },
+        };
+        ::std::mem::swap(&mut reached, &mut next);
+        if !reached.contains(&true) {
+            return false;
+        }
+    }
+    reached[name.len()]
+}
+
+/// Advance each reached position in `name` by one character, if it's accepted by `accept`.
+fn step<F: Fn(u8) -> bool>(reached: &[bool], next: &mut [bool], name: &[u8], accept: F) {
+    for (i, &c) in name.iter().enumerate() {
+        next[i + 1] = reached[i] && accept(c);
+    }
+}
+
+/// Check if `c` is in the character class, e.g. `a-z0`, or `!xyz` (negated).
+fn class_matches(class: &[u8], c: u8) -> bool {
+    let (negate, class) = match class.first() {
+        Some(&b'!') => (true, &class[1..]),
+        _ => (false, class),
+    };
+    let mut found = false;
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i+1] == b'-' {
+            // Range, e.g. 'a-z'
+            found |= class[i] <= c && c <= class[i+2];
+            i += 3;
+        } else {
+            found |= class[i] == c;
+            i += 1;
+        }
+    }
+    found != negate
+}
+
+/// A literal OSC address, split into its '/'-delimited segments, for
+/// protocols which encode IDs in the path (e.g. "/synth/3/freq").
+///
+/// When deserialized, it accepts any address; use [`segment`] to pick out
+/// the parts of interest, or [`Segments`] to deserialize the address
+/// straight into a tuple or struct of them.
+///
+/// ```
+/// use serde_osc::address::Address;
+///
+/// let packet = serde_osc::to_vec(&("/synth/3/freq", (440.0f32,))).unwrap();
+/// let (address, (freq,)): (Address, (f32,)) = serde_osc::from_slice(&packet).unwrap();
+/// assert_eq!(address.segment::<u32>(1).unwrap(), 3);
+/// assert_eq!((address.len(), freq), (3, 440.0));
+/// ```
+///
+/// [`segment`]: #method.segment
+/// [`Segments`]: struct.Segments.html
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address(String);
+
+impl Address {
+    pub fn new<S: Into<String>>(address: S) -> Self {
+        Address(address.into())
+    }
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    /// The segments of the address, without the leading '/'; e.g. "synth",
+    /// "3" and "freq" for "/synth/3/freq".
+    pub fn segments(&self) -> impl Iterator<Item=&str> {
+        segments(&self.0)
+    }
+    /// The number of segments.
+    pub fn len(&self) -> usize {
+        self.segments().count()
+    }
+    /// Whether the address is just "/" (or empty), and so has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Parse the segment at `index` (counting from 0) as a `T`.
+    ///
+    /// Fails with `Error::AddressMismatch` if there's no such segment, and
+    /// with `Error::Message` if it can't be parsed.
+    pub fn segment<T>(&self, index: usize) -> ResultE<T>
+        where T: FromStr, T::Err: Display
+    {
+        let segment = self.segments().nth(index).ok_or_else(|| Error::AddressMismatch(self.0.clone()))?;
+        segment.parse().map_err(|e| Error::Message(format!("segment {} of {}: {}", index, self.0, e)))
+    }
+}
+
+impl<'a> From<&'a str> for Address {
+    fn from(address: &'a str) -> Self {
+        Self::new(address)
+    }
+}
+
+impl From<String> for Address {
+    fn from(address: String) -> Self {
+        Self::new(address)
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Address)
+    }
+}
+
+/// An address deserialized as a tuple or struct with a field per segment,
+/// each parsed from its text (numbers, booleans, strings, or unit enum variants).
+///
+/// The address must have exactly as many segments as `T` has fields, else
+/// deserialization fails.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # extern crate serde_osc;
+/// use serde_osc::address::Segments;
+///
+/// #[derive(Deserialize)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Param { Freq, Gain }
+///
+/// #[derive(Deserialize)]
+/// struct SynthParam {
+///     _prefix: String,
+///     voice: u32,
+///     param: Param,
+/// }
+///
+/// # fn main() {
+/// let packet = serde_osc::to_vec(&("/synth/3/gain", (0.5f32,))).unwrap();
+/// let (Segments(path), (value,)): (Segments<SynthParam>, (f32,)) = serde_osc::from_slice(&packet).unwrap();
+/// assert_eq!(path.voice, 3);
+/// assert!(matches!(path.param, Param::Gain));
+/// # let _ = value;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Segments<T>(pub T);
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Segments<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        from_segments(&address).map(Segments).map_err(de::Error::custom)
+    }
+}
+
+/// Deserialize the segments of `address` as a `T` (see [`Segments`]).
+///
+/// [`Segments`]: struct.Segments.html
+pub fn from_segments<'a, T: Deserialize<'a>>(address: &'a str) -> ResultE<T> {
+    let mut access = SegmentsAccess(segments(address));
+    let value = T::deserialize(SegmentsDeserializer(&mut access))?;
+    match access.0.next() {
+        None => Ok(value),
+        // More segments than fields.
+        Some(_) => Err(Error::AddressMismatch(address.to_owned())),
+    }
+}
+
+/// The segments of `address`, without the leading '/'.
+fn segments(address: &str) -> impl Iterator<Item=&str> {
+    let path = address.strip_prefix('/').unwrap_or(address);
+    path.split('/').filter(move |_| !path.is_empty())
+}
+
+/// Presents the segments of an address as a sequence.
+struct SegmentsAccess<I>(I);
+
+struct SegmentsDeserializer<'s, I: 's>(&'s mut SegmentsAccess<I>);
+
+impl<'de, 's, I> de::Deserializer<'de> for SegmentsDeserializer<'s, I>
+    where I: Iterator<Item=&'de str>
+{
+    type Error = Error;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> ResultE<V::Value> {
+        visitor.visit_seq(self.0)
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> ResultE<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
+        seq bytes byte_buf map unit_struct
+        tuple_struct struct identifier tuple enum ignored_any
+    }
+}
+
+impl<'de, I> SeqAccess<'de> for SegmentsAccess<I>
+    where I: Iterator<Item=&'de str>
+{
+    type Error = Error;
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> ResultE<Option<T::Value>> {
+        match self.0.next() {
+            Some(segment) => seed.deserialize(SegmentDeserializer(segment)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Deserializes a single segment, parsing it as whichever type is asked for.
+struct SegmentDeserializer<'de>(&'de str);
+
+impl<'de> SegmentDeserializer<'de> {
+    fn parse<T>(&self) -> ResultE<T>
+        where T: FromStr, T::Err: Display
+    {
+        self.0.parse().map_err(|e| Error::Message(format!("address segment {:?}: {}", self.0, e)))
+    }
+}
+
+/// Implement type hints by parsing the segment as that type.
+macro_rules! deserialize_parsed {
+    ($($func:ident => $visit:ident,)*) => {
+        $(
+            fn $func<V: Visitor<'de>>(self, visitor: V) -> ResultE<V::Value> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for SegmentDeserializer<'de> {
+    type Error = Error;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> ResultE<V::Value> {
+        visitor.visit_borrowed_str(self.0)
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> ResultE<V::Value> {
+        visitor.visit_some(self)
+    }
+    // e.g. "freq" for a unit variant `Param::Freq`.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> ResultE<V::Value> {
+        visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(self.0))
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> ResultE<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        str string unit seq bytes byte_buf map unit_struct
+        tuple_struct struct identifier tuple ignored_any
+    }
+}
+
+/// An address with parameters, such as "/synth/{id}/freq", which are filled
+/// in from the fields of the same name of the value sent, so that messages
+/// to per-channel addresses don't each need to be formatted by hand.
+///
+/// The value must serialize as a struct, each field of which is one
+/// argument; the fields named by parameters are taken out of the arguments,
+/// and written into the address as text. They must be numbers, strings,
+/// characters or booleans.
+///
+/// A parameter is a name made of letters, digits and '_', enclosed in '{'
+/// and '}'; other braces (e.g. the alternatives of a pattern, "{a,b}") are
+/// copied verbatim.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # extern crate serde_osc;
+/// use serde_osc::address::AddressTemplate;
+///
+/// #[derive(Serialize)]
+/// struct Freq {
+///     id: u32,
+///     hz: f32,
+/// }
+///
+/// # fn main() {
+/// let template = AddressTemplate::new("/synth/{id}/freq");
+/// let packet = template.to_vec(&Freq { id: 3, hz: 440.0 }).unwrap();
+/// assert_eq!(packet, serde_osc::to_vec(&("/synth/3/freq", (440.0f32,))).unwrap());
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AddressTemplate {
+    template: String,
+}
+
+impl AddressTemplate {
+    pub fn new<S: Into<String>>(template: S) -> Self {
+        Self { template: template.into() }
+    }
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+    /// The names of the parameters, in the order they appear.
+    pub fn params(&self) -> Vec<&str> {
+        self.parts().filter_map(|part| match part {
+            TemplatePart::Param(name) => Some(name),
+            TemplatePart::Literal(_) => None,
+        }).collect()
+    }
+    /// Build the message for `value`: its fields become the arguments,
+    /// except for those named by parameters, which are written into the address.
+    ///
+    /// Fails with `Error::Message` if a parameter doesn't name a field, or
+    /// names one which can't be written as text.
+    pub fn message<T: ?Sized + Serialize>(&self, value: &T) -> ResultE<Message> {
+        let fields = ser::field_names(value)?;
+        let packet = ser::to_vec_unframed(&("/", value))?;
+        let mut args = match Packet::from_slice_unframed(&packet)? {
+            Packet::Message(msg) => msg.args.into_iter().map(Some).collect::<Vec<_>>(),
+            Packet::Bundle(_) => return Err(Error::BadFormat),
+        };
+        if args.len() != fields.len() {
+            // A field which isn't a single argument.
+            return Err(Error::BadFormat);
+        }
+        let mut address = String::with_capacity(self.template.len());
+        for part in self.parts() {
+            match part {
+                TemplatePart::Literal(text) => address.push_str(text),
+                TemplatePart::Param(name) => {
+                    let index = fields.iter().position(|&field| field == name)
+                        .ok_or_else(|| Error::Message(format!("no field named {:?} for address {}", name, self.template)))?;
+                    // A parameter may appear more than once.
+                    let arg = match args[index].take() {
+                        Some(arg) => arg,
+                        None => return Err(Error::Message(format!("parameter {:?} repeated in address {}", name, self.template))),
+                    };
+                    push_param(&mut address, name, arg)?;
+                },
+            }
+        }
+        Ok(Message { address, args: args.into_iter().flatten().collect() })
+    }
+    /// Serialize the message for `value` (see [`message`]) into a
+    /// length-prefixed packet.
+    ///
+    /// [`message`]: #method.message
+    pub fn to_vec<T: ?Sized + Serialize>(&self, value: &T) -> ResultE<Vec<u8>> {
+        ser::to_vec(&self.message(value)?)
+    }
+    /// Like `to_vec`, but omits the length prefix.
+    pub fn to_vec_unframed<T: ?Sized + Serialize>(&self, value: &T) -> ResultE<Vec<u8>> {
+        ser::to_vec_unframed(&self.message(value)?)
+    }
+    fn parts(&self) -> TemplateParts<'_> {
+        TemplateParts(&self.template)
+    }
+}
+
+impl<'a> From<&'a str> for AddressTemplate {
+    fn from(template: &'a str) -> Self {
+        Self::new(template)
+    }
+}
+
+impl From<String> for AddressTemplate {
+    fn from(template: String) -> Self {
+        Self::new(template)
+    }
+}
+
+/// Write the argument filling in a parameter as text.
+fn push_param(address: &mut String, name: &str, arg: Value) -> ResultE<()> {
+    use std::fmt::Write;
+    let written = match arg {
+        Value::Int(i) => write!(address, "{}", i),
+        Value::Long(h) => write!(address, "{}", h),
+        Value::Float(f) => write!(address, "{}", f),
+        Value::Double(d) => write!(address, "{}", d),
+        Value::String(ref s) | Value::Symbol(ref s) => write!(address, "{}", s),
+        Value::Char(c) => write!(address, "{}", c),
+        Value::Bool(b) => write!(address, "{}", b),
+        other => return Err(Error::Message(format!("parameter {:?} can't be written into an address: {:?}", name, other))),
+    };
+    written.map_err(|e| Error::Message(e.to_string()))
+}
+
+enum TemplatePart<'a> {
+    Literal(&'a str),
+    Param(&'a str),
+}
+
+/// Splits a template into literal text and parameters.
+struct TemplateParts<'a>(&'a str);
+
+impl<'a> Iterator for TemplateParts<'a> {
+    type Item = TemplatePart<'a>;
+    fn next(&mut self) -> Option<TemplatePart<'a>> {
+        let rest = self.0;
+        if rest.is_empty() {
+            return None;
+        }
+        let is_name = |name: &str| !name.is_empty() && name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_');
+        if rest.starts_with('{') {
+            if let Some(end) = rest.find('}') {
+                if is_name(&rest[1..end]) {
+                    self.0 = &rest[end + 1..];
+                    return Some(TemplatePart::Param(&rest[1..end]));
+                }
+            }
+        }
+        // Up to the next '{' which could start a parameter.
+        let end = rest[1..].find('{').map_or(rest.len(), |i| i + 1);
+        self.0 = &rest[end..];
+        Some(TemplatePart::Literal(&rest[..end]))
+    }
+}
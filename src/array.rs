@@ -0,0 +1,74 @@
+//! Fixed-size arrays of any length, for use with `#[serde(with = "serde_osc::array")]`.
+//!
+//! Arrays of up to 32 elements (e.g. `[f32; 16]`) work out of the box: like
+//! tuples, they become an OSC array ('[' ... ']') when nested within the
+//! arguments, or the arguments themselves when they make up the whole argument
+//! list. serde has no implementations for longer arrays, which this module
+//! provides via const generics. It also insists that the number of elements
+//! received matches the array length exactly.
+//!
+//! ```
+//! #[macro_use]
+//! extern crate serde_derive;
+//! extern crate serde_osc;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Levels {
+//!     bank: i32,
+//!     #[serde(with = "serde_osc::array")]
+//!     channels: [f32; 64],
+//! }
+//!
+//! # fn main() {
+//! let levels = ("/meters".to_owned(), Levels { bank: 1, channels: [0.5; 64] });
+//! let packet = serde_osc::to_vec(&levels).unwrap();
+//! assert_eq!(serde_osc::from_slice::<(String, Levels)>(&packet).unwrap(), levels);
+//! # }
+//! ```
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+use serde::de::{Deserialize, Deserializer, Error, IgnoredAny, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+pub fn serialize<S, T, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer, T: Serialize
+{
+    let mut tuple = serializer.serialize_tuple(N)?;
+    for elem in array {
+        tuple.serialize_element(elem)?;
+    }
+    tuple.end()
+}
+
+pub fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where D: Deserializer<'de>, T: Deserialize<'de>
+{
+    deserializer.deserialize_tuple(N, ArrayVisitor(PhantomData))
+}
+
+struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+    where T: Deserialize<'de>
+{
+    type Value = [T; N];
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of {} elements", N)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[T; N], A::Error> {
+        let mut elems = Vec::with_capacity(N);
+        while elems.len() < N {
+            match seq.next_element()? {
+                Some(elem) => elems.push(elem),
+                None => return Err(A::Error::invalid_length(elems.len(), &self)),
+            }
+        }
+        // Surplus elements are an error too, rather than silently dropped.
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(A::Error::invalid_length(N + 1, &self));
+        }
+        // The length was checked above.
+        Ok(<[T; N]>::try_from(elems).unwrap_or_else(|_| unreachable!()))
+    }
+}
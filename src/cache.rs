@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use serde::ser::Serialize;
+
+use de::{DeserializerOptions, OscReader};
+use error::{Error, ResultE};
+use ser;
+
+/// Remembers the arguments last sent to each address, so that messages which
+/// wouldn't change anything can be left unsent, e.g. when syncing the state
+/// of a GUI to a mixing console at frame rate.
+///
+/// Messages are compared by their encoding (typetag and argument data), so
+/// an `i32` argument of 1 differs from an `f32` argument of 1.0, and floats
+/// only match if their bits do.
+///
+/// ```
+/// use std::net::UdpSocket;
+/// use serde_osc::cache::StateCache;
+///
+/// let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+/// let mut sent = StateCache::new();
+/// let mut datagrams = 0;
+/// for fader in &[0.5f32, 0.5, 0.5, 0.75] {
+///     if let Some(packet) = sent.to_vec_unframed(&("/ch/1/fader", (*fader,))).unwrap() {
+///         socket.send_to(&packet, socket.local_addr().unwrap()).unwrap();
+///         datagrams += 1;
+///     }
+/// }
+/// assert_eq!(datagrams, 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StateCache {
+    /// The typetag and argument data last sent to each address.
+    sent: HashMap<String, Vec<u8>>,
+}
+
+impl StateCache {
+    /// A cache which has seen nothing, so that every message counts as a change.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Record the message `msg` (encoded, without a length prefix), returning
+    /// whether its arguments differ from those last recorded for its address.
+    ///
+    /// Fails with `Error::BadFormat` if `msg` is a bundle.
+    pub fn update(&mut self, msg: &[u8]) -> ResultE<bool> {
+        let mut rd = Cursor::new(msg);
+        let address = rd.parse_address(DeserializerOptions::default())?;
+        if address == "#bundle" {
+            return Err(Error::BadFormat);
+        }
+        let args = &msg[rd.position() as usize..];
+        match self.sent.get_mut(&address) {
+            Some(last) if last[..] == *args => Ok(false),
+            Some(last) => {
+                last.clear();
+                last.extend_from_slice(args);
+                Ok(true)
+            },
+            None => {
+                self.sent.insert(address, args.to_vec());
+                Ok(true)
+            },
+        }
+    }
+    /// Serialize the message `value` into a length-prefixed packet, if its
+    /// arguments differ from those last sent to its address; otherwise return `None`.
+    pub fn to_vec<T: ?Sized + Serialize>(&mut self, value: &T) -> ResultE<Option<Vec<u8>>> {
+        let packet = ser::to_vec(value)?;
+        Ok(if self.update(&packet[4..])? { Some(packet) } else { None })
+    }
+    /// Like `to_vec`, but omits the length prefix.
+    pub fn to_vec_unframed<T: ?Sized + Serialize>(&mut self, value: &T) -> ResultE<Option<Vec<u8>>> {
+        let packet = ser::to_vec_unframed(value)?;
+        Ok(if self.update(&packet)? { Some(packet) } else { None })
+    }
+    /// Forget what was sent to `address`, so that the next message to it
+    /// counts as a change (e.g. once the receiver has been reset).
+    pub fn forget(&mut self, address: &str) {
+        self.sent.remove(address);
+    }
+    /// Forget everything, e.g. after reconnecting, so that the full state is sent again.
+    pub fn clear(&mut self) {
+        self.sent.clear();
+    }
+    /// The number of addresses messages have been recorded for.
+    pub fn len(&self) -> usize {
+        self.sent.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.sent.is_empty()
+    }
+}
@@ -0,0 +1,63 @@
+//! Rewriting packets into a canonical encoding, so that equivalent packets
+//! are byte-for-byte equal, e.g. before hashing or deduplicating them.
+
+use de::{DeserializerOptions, TypetagCompat, UnknownTypes};
+use error::ResultE;
+use framing::Framing;
+use ser;
+use value::Packet;
+
+/// Re-encode a length-prefixed packet in the strict OSC 1.0 form.
+///
+/// The packet is decoded leniently and then encoded again, so that:
+///
+/// - every message has a typetag, starting with ',' (a missing comma, or a
+///   missing typetag, as sent by some older implementations, is added);
+/// - strings, blobs and the packet itself are padded with NUL bytes to a
+///   multiple of 4 bytes;
+/// - booleans are encoded as 'T' and 'F', without data;
+/// - the lengths of bundle elements match their contents.
+///
+/// Arguments of unsupported types are kept as they are (see
+/// `de::UnknownTypes::Capture`). Fails if the packet can't be decoded.
+///
+/// ```
+/// use serde_osc::canonical::canonicalize;
+///
+/// // No ',' before the typetag.
+/// let mut legacy = vec![0, 0, 0, 12];
+/// legacy.extend_from_slice(b"/go\0i\0\0\0\0\0\0\x05");
+/// assert_eq!(canonicalize(&legacy).unwrap(), serde_osc::to_vec(&("/go", (5,))).unwrap());
+/// ```
+pub fn canonicalize(bytes: &[u8]) -> ResultE<Vec<u8>> {
+    canonicalize_with(bytes, Framing::LengthPrefixed, lenient())
+}
+
+/// Re-encode a packet which has no length prefix (e.g. a UDP datagram); see
+/// [`canonicalize`]. The result has no length prefix either.
+///
+/// [`canonicalize`]: fn.canonicalize.html
+pub fn canonicalize_unframed(bytes: &[u8]) -> ResultE<Vec<u8>> {
+    canonicalize_with(bytes, Framing::Unframed, lenient())
+}
+
+/// Re-encode a packet, decoding it with the provided framing and options
+/// (e.g. to also accept little-endian numbers); see [`canonicalize`].
+///
+/// [`canonicalize`]: fn.canonicalize.html
+pub fn canonicalize_with(bytes: &[u8], framing: Framing, options: DeserializerOptions) -> ResultE<Vec<u8>> {
+    let packet = Packet::from_slice_with(bytes, framing, options)?;
+    match framing {
+        Framing::LengthPrefixed => ser::to_vec(&packet),
+        Framing::Unframed => ser::to_vec_unframed(&packet),
+    }
+}
+
+/// The options which accept every deviation that can be canonicalized.
+fn lenient() -> DeserializerOptions {
+    DeserializerOptions {
+        typetags: TypetagCompat::Legacy,
+        unknown_types: UnknownTypes::Capture,
+        ..DeserializerOptions::default()
+    }
+}
@@ -0,0 +1,204 @@
+use std::marker::PhantomData;
+use byteorder::{BigEndian, ByteOrder};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use serde;
+use serde::de::DeserializeOwned;
+use tokio_util::codec;
+
+use de::{self, DeserializerOptions};
+use error::{Error, ResultE};
+use framing::slip;
+use framing::Framing;
+use ser::{self, SerializerOptions};
+
+/// How packets are delimited within the byte stream handled by an [`OscCodec`].
+///
+/// [`OscCodec`]: struct.OscCodec.html
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CodecFraming {
+    /// Each packet is preceded by its length, as an `i32` (OSC 1.0 over TCP).
+    #[default]
+    LengthPrefixed,
+    /// Each packet is enclosed in SLIP delimiters (OSC 1.1 over TCP or serial lines).
+    Slip,
+    /// Each buffer holds exactly one packet, e.g. with `tokio_util::udp::UdpFramed`.
+    Unframed,
+}
+
+/// Encodes and decodes OSC packets for tokio's `Framed` streams and sinks.
+///
+/// Packets are decoded into `T`; anything which implements `Serialize` can
+/// be encoded. Errors are reported as [`serde_osc::error::Error`], which
+/// (as `Framed` requires) can be built from an `io::Error`.
+///
+/// ```
+/// # extern crate bytes;
+/// # extern crate serde_osc;
+/// # extern crate tokio_util;
+/// use bytes::BytesMut;
+/// use serde_osc::codec::OscCodec;
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// # fn main() {
+/// let mut codec = OscCodec::<(String, (i32,))>::slip();
+/// let mut buf = BytesMut::new();
+/// codec.encode(("/m", (1,)), &mut buf).unwrap();
+/// let (address, args) = codec.decode(&mut buf).unwrap().unwrap();
+/// assert_eq!((address.as_str(), args), ("/m", (1,)));
+/// # }
+/// ```
+///
+/// A decoded packet which fails to deserialize is still consumed, so the
+/// stream can carry on with the next one. A length prefix which is invalid
+/// (or exceeds `max_packet_size`) can't be skipped; the buffered data is then
+/// discarded.
+///
+/// [`serde_osc::error::Error`]: ../error/enum.Error.html
+#[derive(Debug)]
+pub struct OscCodec<T> {
+    framing: CodecFraming,
+    de_options: DeserializerOptions,
+    ser_options: SerializerOptions,
+    _packet: PhantomData<T>,
+}
+
+impl<T> Clone for OscCodec<T> {
+    fn clone(&self) -> Self {
+        Self::with_options(self.framing, self.de_options, self.ser_options)
+    }
+}
+
+impl<T> Default for OscCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> OscCodec<T> {
+    /// Codec for length-prefixed packets.
+    pub fn new() -> Self {
+        Self::with_framing(CodecFraming::LengthPrefixed)
+    }
+    /// Codec for SLIP-framed packets.
+    pub fn slip() -> Self {
+        Self::with_framing(CodecFraming::Slip)
+    }
+    /// Codec for datagrams which each hold a single packet.
+    pub fn unframed() -> Self {
+        Self::with_framing(CodecFraming::Unframed)
+    }
+    pub fn with_framing(framing: CodecFraming) -> Self {
+        Self::with_options(framing, DeserializerOptions::default(), SerializerOptions::default())
+    }
+    /// Decode packets according to `de_options`, and encode them according to
+    /// `ser_options`. Setting `max_packet_size` also bounds how much data is
+    /// buffered while waiting for a packet.
+    pub fn with_options(framing: CodecFraming, de_options: DeserializerOptions, ser_options: SerializerOptions) -> Self {
+        Self {
+            framing,
+            de_options,
+            ser_options,
+            _packet: PhantomData,
+        }
+    }
+    pub fn framing(&self) -> CodecFraming {
+        self.framing
+    }
+
+    /// Split the first whole packet (without its framing) off the front of `src`.
+    ///
+    /// If the framing itself is broken (a bad length prefix, or a frame too
+    /// large to ever complete), there's no telling where the next packet
+    /// starts, so everything buffered is dropped. A frame which can be
+    /// delimited but not decoded is removed on its own.
+    fn next_packet(&self, src: &mut BytesMut) -> ResultE<Option<Bytes>> {
+        match self.framing {
+            CodecFraming::LengthPrefixed => {
+                let end = match de::packet_end(src, &self.de_options) {
+                    Ok(Some(end)) => end,
+                    Err(e) => {
+                        src.clear();
+                        return Err(e);
+                    },
+                    Ok(None) => {
+                        // Make room for the rest of the packet, once its length is
+                        // known (and trusted not to be absurd).
+                        if src.len() >= 4 && self.de_options.max_packet_size.is_some() {
+                            let length = BigEndian::read_u32(src) as usize;
+                            src.reserve(4 + length - src.len());
+                        }
+                        return Ok(None);
+                    },
+                };
+                let mut packet = src.split_to(end).freeze();
+                packet.advance(4);
+                Ok(Some(packet))
+            },
+            CodecFraming::Slip => {
+                // Skip the opening delimiter (and any empty frames).
+                let start = src.iter().take_while(|&&byte| byte == slip::END).count();
+                src.advance(start);
+                let end = match src.iter().position(|&byte| byte == slip::END) {
+                    Some(end) => end,
+                    None => {
+                        // Each packet byte takes at most 2 bytes once escaped.
+                        if self.de_options.max_packet_size.is_some_and(|max| src.len() > 2*max) {
+                            src.clear();
+                            return Err(Error::SizeLimitExceeded);
+                        }
+                        return Ok(None);
+                    },
+                };
+                let frame = src.split_to(end + 1);
+                Ok(Some(slip::decode(&frame[..end])?.into()))
+            },
+            CodecFraming::Unframed if src.is_empty() => Ok(None),
+            CodecFraming::Unframed => Ok(Some(src.split().freeze())),
+        }
+    }
+}
+
+impl<T> codec::Decoder for OscCodec<T>
+    where T: DeserializeOwned
+{
+    type Item = T;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> ResultE<Option<T>> {
+        let packet = match self.next_packet(src)? {
+            Some(packet) => packet,
+            None => return Ok(None),
+        };
+        self.de_options.check_size(packet.len())?;
+        de::from_slice_framed(&packet, Framing::Unframed, self.de_options, None).map(Some)
+    }
+}
+
+impl<T, I> codec::Encoder<I> for OscCodec<T>
+    where I: serde::ser::Serialize
+{
+    type Error = Error;
+
+    fn encode(&mut self, item: I, dst: &mut BytesMut) -> ResultE<()> {
+        let framing = match self.framing {
+            CodecFraming::LengthPrefixed => Framing::LengthPrefixed,
+            CodecFraming::Unframed => Framing::Unframed,
+            CodecFraming::Slip => {
+                let mut packet = Vec::new();
+                item.serialize(&mut ser::Serializer::with_options(&mut packet, Framing::Unframed, self.ser_options))?;
+                let mut frame = Vec::with_capacity(packet.len() + 2);
+                slip::encode(&packet, &mut frame);
+                dst.extend_from_slice(&frame);
+                return Ok(());
+            },
+        };
+        let start = dst.len();
+        let mut ser = ser::Serializer::with_options(dst.writer(), framing, self.ser_options);
+        let result = item.serialize(&mut ser);
+        // Don't leave a partial packet behind.
+        if result.is_err() {
+            dst.truncate(start);
+        }
+        result
+    }
+}
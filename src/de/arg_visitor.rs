@@ -1,16 +1,21 @@
-use std::io::{Read, Take};
+use std::io::{BufRead, Read, Take};
 use std::vec;
 use serde::de;
-use serde::de::{DeserializeSeed, SeqAccess, Visitor};
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, VariantAccess, Visitor};
 
 use error::{Error, ResultE};
+use types::is_helper_name;
 use super::osc_reader::OscReader;
-use super::osc_type::OscType;
-use super::maybe_skip_comma::MaybeSkipComma;
+use super::osc_type::{OscArg, OscType};
+use super::read_source::ReadSource;
+use super::options::{DeserializerOptions, TrailingArgs};
+use super::typetag::read_typetag;
 
 #[derive(Debug)]
 pub struct ArgDeserializer<'a, R: Read + 'a> {
-    data: Option<ArgVisitor<'a, R>>,
+    data: ArgVisitor<'a, R>,
+    /// The arguments can only be deserialized once.
+    used: bool,
 }
 
 /// Deserializes the argument data of an OSC message.
@@ -20,44 +25,48 @@ pub struct ArgVisitor<'a, R: Read + 'a> {
     /// calling .next() on this returns the OSC char code of the next argument,
     /// e.g. 'i' for i32, 'f' for f32, etc.
     /// We store this as an iterator to avoid tracking the index of the current arg.
-    arg_types : MaybeSkipComma<vec::IntoIter<u8>>,
+    arg_types : vec::IntoIter<u8>,
+    /// Index of the next top-level argument, for error reporting.
+    arg_index: usize,
+    options: DeserializerOptions,
 }
 
-impl<'a, R: Read + 'a> ArgDeserializer<'a, R> {
-    pub fn new(read: &'a mut Take<R>) -> ResultE<Self> {
+impl<'a, R: BufRead + 'a> ArgDeserializer<'a, R> {
+    pub fn new(read: &'a mut Take<R>, options: DeserializerOptions) -> ResultE<Self> {
         Ok(Self {
-            data: Some(ArgVisitor::new(read)?),
+            data: ArgVisitor::new(read, options)?,
+            used: false,
         })
     }
+    fn take(&mut self) -> ResultE<&mut ArgVisitor<'a, R>> {
+        if self.used {
+            return Err(Error::BadFormat);
+        }
+        self.used = true;
+        Ok(&mut self.data)
+    }
 }
 impl<'de, 'a, R> de::Deserializer<'de> for &'a mut ArgDeserializer<'a, R>
-    where R: Read + 'a
+    where R: ReadSource<'de> + 'a
 {
     type Error = Error;
     fn deserialize_any<V>(self, visitor: V) -> ResultE<V::Value>
         where V: Visitor<'de>
     {
-        match self.data.take() {
-            Some(data) => visitor.visit_seq(data),
-            // The arguments can only be deserialized once.
-            None => Err(Error::BadFormat),
-        }
+        let data = self.take()?;
+        let value = visitor.visit_seq(&mut *data)?;
+        data.finish()?;
+        Ok(value)
     }
     // default serde impls don't equate units to empty sequences.
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where V: Visitor<'de>
     {
-        match self.data.take() {
-            Some(mut data) => {
-                match data.parse_next()? {
-                    // We have no arguments; decoding a unit is ok!
-                    None => visitor.visit_unit(),
-                    // Cannot deserialize a unit from a non-empty sequence!
-                    Some(_) => Err(Error::BadFormat),
-                }
-            },
-            // The arguments can only be deserialized once.
-            None => Err(Error::BadFormat),
+        match self.take()?.parse_next()? {
+            // We have no arguments; decoding a unit is ok!
+            None => visitor.visit_unit(),
+            // Cannot deserialize a unit from a non-empty sequence!
+            Some(_) => Err(Error::BadFormat),
         }
     }
     fn deserialize_unit_struct<V>(
@@ -69,47 +78,345 @@ impl<'de, 'a, R> de::Deserializer<'de> for &'a mut ArgDeserializer<'a, R>
     {
         self.deserialize_unit(visitor)
     }
+    // Maps are encoded as alternating key and value arguments.
+    fn deserialize_map<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let data = self.take()?;
+        let value = visitor.visit_map(&mut *data)?;
+        data.finish()?;
+        Ok(value)
+    }
+    // The argument data is left for the packet deserializer to skip, unless
+    // it's checked in strict mode.
+    fn deserialize_ignored_any<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let data = self.take()?;
+        if data.options.strict {
+            data.skip_rest()?;
+        }
+        visitor.visit_unit()
+    }
+    // e.g. a struct wrapping the tuple of arguments.
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
 
     // This struct only deserializes sequences; ignore all type hints.
     // More info: https://github.com/serde-rs/serde/blob/b7d6c5d9f7b3085a4d40a446eeb95976d2337e07/serde/src/macros.rs#L106
     forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string option
-        seq bytes byte_buf map newtype_struct
-        tuple_struct struct identifier tuple enum ignored_any
+        seq bytes byte_buf
+        tuple_struct struct identifier tuple enum
     }
 }
 
 
-impl<'a, R> ArgVisitor<'a, R>
-    where R: Read + 'a
-{
-    pub fn new(read: &'a mut Take<R>) -> ResultE<Self> {
-        let arg_types = read.read_0term_bytes().map(|bytes| MaybeSkipComma::new(bytes.into_iter()))?;
+/// Deserializes the payload of a newtype enum variant, e.g. `Play(f32)`.
+/// If the payload is itself a sequence-like type (struct, tuple, ...) then it
+/// receives all of the arguments; otherwise the message must have exactly one
+/// argument, which is deserialized into the payload.
+#[derive(Debug)]
+pub struct NewtypeArgDeserializer<'a, R: Read + 'a> {
+    data: ArgVisitor<'a, R>,
+}
+
+/// Deserializes the arguments of a message as the contents of an enum variant.
+#[derive(Debug)]
+pub struct ArgVariantAccess<'a, R: Read + 'a> {
+    read: &'a mut Take<R>,
+    options: DeserializerOptions,
+}
+
+impl<'a, R: BufRead + 'a> ArgVisitor<'a, R> {
+    pub fn new(read: &'a mut Take<R>, options: DeserializerOptions) -> ResultE<Self> {
+        let arg_types = read_typetag(read, options)?;
+        check_nesting(&arg_types, options)?;
         Ok(ArgVisitor {
             read,
-            arg_types,
+            arg_types: arg_types.into_iter(),
+            arg_index: 0,
+            options,
         })
     }
-    fn parse_next(&mut self) -> ResultE<Option<OscType>> {
+    /// Number of bytes of the message left to parse.
+    pub fn remaining(&self) -> u64 {
+        self.read.limit()
+    }
+    /// Number of top-level arguments left to parse.
+    fn remaining_args(&self) -> usize {
+        let mut depth = 0usize;
+        let mut count = 0;
+        for tag in self.arg_types.clone() {
+            match tag {
+                // An array counts as a single argument.
+                b'[' => {
+                    if depth == 0 {
+                        count += 1;
+                    }
+                    depth += 1;
+                },
+                b']' => depth = depth.saturating_sub(1),
+                _ if depth == 0 => count += 1,
+                _ => {},
+            }
+        }
+        count
+    }
+}
+
+impl<'de, 'a, R> ArgVisitor<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    pub fn parse_next(&mut self) -> ResultE<Option<OscType<'de>>> {
         match self.arg_types.next() {
             None => Ok(None),
-            Some(tag) => self.parse_arg(tag).map(|arg| Some(arg)),
+            Some(tag) => {
+                self.arg_index += 1;
+                self.parse_arg(tag).map(Some).map_err(|e| self.locate(e))
+            },
         }
     }
-    fn parse_arg(&mut self, typecode: u8) -> ResultE<OscType> {
-        match typecode {
-            b'i' => self.read.parse_i32().map(|i| { OscType::I32(i) }),
-            b'f' => self.read.parse_f32().map(|f| { OscType::F32(f) }),
-            b's' => self.read.parse_str().map(|s| { OscType::String(s) }),
-            b'b' => self.read.parse_blob().map(|b| { OscType::Blob(b) }),
-            _ => Err(Error::UnsupportedType),
+    /// Prepare a parsed argument for deserialization.
+    fn arg(&self, value: OscType<'de>) -> OscArg<'de> {
+        OscArg::new(value, &self.options)
+    }
+    /// Annotate an error with the index of the most recently parsed argument.
+    fn locate(&self, error: Error) -> Error {
+        let index = self.arg_index.saturating_sub(1);
+        error.with_context(|ctx| { ctx.arg_index.get_or_insert(index); })
+    }
+    fn parse_arg(&mut self, typecode: u8) -> ResultE<OscType<'de>> {
+        parse_arg(self.read, typecode, &mut self.arg_types, self.options)
+    }
+    /// Once the arguments have been deserialized, check for any left over.
+    /// In strict mode, they're skipped over, so that any data after them is
+    /// noticed.
+    fn finish(&mut self) -> ResultE<()> {
+        match (self.options.trailing_args, self.remaining_args()) {
+            (TrailingArgs::Reject, count) if count > 0 => Err(Error::TrailingArguments(count)),
+            _ if self.options.strict => self.skip_rest(),
+            _ => Ok(()),
+        }
+    }
+    /// Skip over the remaining arguments, without decoding them.
+    pub fn skip_rest(&mut self) -> ResultE<()> {
+        while let Some(tag) = self.arg_types.next() {
+            self.arg_index += 1;
+            skip_arg(self.read, tag, &mut self.arg_types, self.options).map_err(|e| self.locate(e))?;
         }
+        Ok(())
+    }
+}
+
+/// The next argument of a message, which is only decoded once the type it's
+/// deserialized into is known; for `IgnoredAny`, its data is skipped instead.
+struct NextArg<'v, 'a: 'v, R: Read + 'a> {
+    args: &'v mut ArgVisitor<'a, R>,
+    tag: u8,
+}
+
+impl<'de, 'v, 'a, R> NextArg<'v, 'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    fn parse(self) -> ResultE<OscArg<'de>> {
+        let value = self.args.parse_arg(self.tag)?;
+        Ok(self.args.arg(value))
+    }
+}
+
+/// Decode the argument, and forward to its deserializer.
+macro_rules! forward_to_parsed_arg {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> ResultE<V::Value>
+                where V: Visitor<'de>
+            {
+                self.parse()?.$func(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'v, 'a, R> de::Deserializer<'de> for NextArg<'v, 'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    type Error = Error;
+    fn deserialize_ignored_any<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        skip_arg(self.args.read, self.tag, &mut self.args.arg_types, self.args.options)?;
+        visitor.visit_unit()
+    }
+
+    forward_to_parsed_arg! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option deserialize_unit
+        deserialize_seq deserialize_map deserialize_identifier
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.parse()?.deserialize_unit_struct(name, visitor)
+    }
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.parse()?.deserialize_newtype_struct(name, visitor)
+    }
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.parse()?.deserialize_tuple(len, visitor)
+    }
+    fn deserialize_tuple_struct<V>(self, name: &'static str, len: usize, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.parse()?.deserialize_tuple_struct(name, len, visitor)
+    }
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.parse()?.deserialize_struct(name, fields, visitor)
+    }
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.parse()?.deserialize_enum(name, variants, visitor)
+    }
+}
+
+/// Check that arrays in the typetag aren't nested too deeply, as they're
+/// parsed recursively.
+pub(crate) fn check_nesting(arg_types: &[u8], options: DeserializerOptions) -> ResultE<()> {
+    let mut depth = 0usize;
+    for &tag in arg_types {
+        match tag {
+            b'[' if options.too_deep(depth) => return Err(Error::DepthLimitExceeded),
+            b'[' => depth += 1,
+            b']' => depth = depth.saturating_sub(1),
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+/// Parse an argument of the given type. The types of any array elements are
+/// taken from `arg_types`, up to and including the closing ']'; the rest of
+/// the tags tell whether an unknown argument is the last one.
+pub(crate) fn parse_arg<'de, R, I>(
+    read: &mut R,
+    typecode: u8,
+    arg_types: &mut I,
+    options: DeserializerOptions
+) -> ResultE<OscType<'de>>
+    where R: ReadSource<'de>, I: Iterator<Item=u8> + Clone
+{
+    match typecode {
+        // Numbers are big-endian, unless the sender is known to get that wrong.
+        b'i' => read.parse_u32_in(options.endianness).map(|i| { OscType::I32(i as i32) }),
+        b'h' => read.parse_u64_in(options.endianness).map(|h| { OscType::I64(h as i64) }),
+        b'f' => read.parse_u32_in(options.endianness).map(|f| { OscType::F32(f32::from_bits(f)) }),
+        b'd' => read.parse_u64_in(options.endianness).map(|d| { OscType::F64(f64::from_bits(d)) }),
+        b's' => read.parse_str_with(options).map(|s| { OscType::String(s) }),
+        b'S' => read.parse_str_with(options).map(|s| { OscType::Symbol(s) }),
+        b'b' => read.read_blob(options.max_blob_size).map(|b| { OscType::Blob(b) }),
+        b'T' => Ok(OscType::Bool(true)),
+        b'F' => Ok(OscType::Bool(false)),
+        b'N' => Ok(OscType::Nil),
+        b'c' => read.parse_char_in(options.endianness).map(|c| { OscType::Char(c) }),
+        b'm' => read.parse_word().map(|m| { OscType::Midi(m) }),
+        b'r' => read.parse_word().map(|c| { OscType::Color(c) }),
+        b'[' => {
+            let mut elems = Vec::new();
+            loop {
+                match arg_types.next() {
+                    // Unterminated array
+                    None => return Err(Error::BadFormat),
+                    Some(b']') => return Ok(OscType::Array(elems)),
+                    Some(tag) => elems.push(parse_arg(read, tag, arg_types, options)?),
+                }
+            }
+        },
+        // Array end without a matching start
+        b']' => Err(Error::BadFormat),
+        tag => {
+            if let Some(ext) = options.extension(tag) {
+                let data = (ext.read)(read)?;
+                if data.len() % 4 != 0 {
+                    return Err(Error::BadPadding);
+                }
+                return Ok(OscType::Unknown(tag, data));
+            }
+            let mut data = Vec::new();
+            match options.unknown_types.capture_size(tag)? {
+                Some(size) => {
+                    data.resize(size, 0);
+                    read.read_exact(&mut data)?;
+                },
+                // Only the last argument's size can be inferred: it's the rest of the message.
+                None if arg_types.clone().all(|tag| tag == b']') => {
+                    read.read_to_end(&mut data)?;
+                    if data.len() % 4 != 0 {
+                        return Err(Error::BadPadding);
+                    }
+                },
+                None => return Err(Error::UnsupportedType),
+            }
+            Ok(OscType::Unknown(tag, data))
+        },
+    }
+}
+
+/// Skip over an argument of the given type, without decoding it. Types whose
+/// extent isn't fixed by the spec are decoded as usual, as `parse_arg` does.
+pub(crate) fn skip_arg<'de, R, I>(
+    read: &mut R,
+    typecode: u8,
+    arg_types: &mut I,
+    options: DeserializerOptions
+) -> ResultE<()>
+    where R: ReadSource<'de>, I: Iterator<Item=u8> + Clone
+{
+    match typecode {
+        b'i' | b'f' | b'c' | b'm' | b'r' => read.skip_exact(4),
+        b'h' | b'd' => read.skip_exact(8),
+        b's' | b'S' => read.skip_0term_bytes(),
+        b'b' => read.skip_blob(options.max_blob_size),
+        b'T' | b'F' | b'N' => Ok(()),
+        b'[' => {
+            loop {
+                match arg_types.next() {
+                    // Unterminated array
+                    None => return Err(Error::BadFormat),
+                    Some(b']') => return Ok(()),
+                    Some(tag) => skip_arg(read, tag, arg_types, options)?,
+                }
+            }
+        },
+        _ => parse_arg(read, typecode, arg_types, options).map(|_| ()),
     }
 }
 
 
 impl<'de, 'a, R> SeqAccess<'de> for ArgVisitor<'a, R>
-    where R: Read + 'a
+    where R: ReadSource<'de> + 'a
 {
     type Error = Error;
     fn next_element_seed<T>(&mut self, seed: T) -> ResultE<Option<T::Value>>
@@ -117,11 +424,171 @@ impl<'de, 'a, R> SeqAccess<'de> for ArgVisitor<'a, R>
     {
         // Return None when the message has been fully parsed,
         // else call seed.deserialize to deserialize the next item.
-        let value = self.parse_next()?;
-        match value {
+        match self.arg_types.next() {
             // end of sequence
             None => Ok(None),
-            Some(osc_arg) => seed.deserialize(osc_arg).map(Some),
+            Some(tag) => {
+                self.arg_index += 1;
+                let result = seed.deserialize(NextArg { args: self, tag });
+                result.map(Some).map_err(|e| self.locate(e))
+            },
+        }
+    }
+    /// The number of remaining arguments, so that e.g. a `Vec` can be allocated up front.
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining_args())
+    }
+}
+
+
+/// Visit the arguments as alternating keys and values.
+impl<'de, 'a, R> MapAccess<'de> for ArgVisitor<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    type Error = Error;
+    fn next_key_seed<K>(&mut self, seed: K) -> ResultE<Option<K::Value>>
+        where K: DeserializeSeed<'de>
+    {
+        self.next_element_seed(seed)
+    }
+    fn next_value_seed<V>(&mut self, seed: V) -> ResultE<V::Value>
+        where V: DeserializeSeed<'de>
+    {
+        // A key without a value
+        self.next_element_seed(seed)?.ok_or(Error::BadFormat)
+    }
+}
+
+
+impl<'a, R: Read + 'a> ArgVariantAccess<'a, R> {
+    pub fn new(read: &'a mut Take<R>, options: DeserializerOptions) -> Self {
+        Self { read, options }
+    }
+}
+
+impl<'de, 'a, R> VariantAccess<'de> for ArgVariantAccess<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    type Error = Error;
+    fn unit_variant(self) -> ResultE<()> {
+        match ArgVisitor::new(self.read, self.options)?.parse_next()? {
+            None => Ok(()),
+            // Unit variants cannot carry arguments.
+            Some(_) => Err(Error::BadFormat),
         }
     }
+    fn newtype_variant_seed<T>(self, seed: T) -> ResultE<T::Value>
+        where T: DeserializeSeed<'de>
+    {
+        seed.deserialize(NewtypeArgDeserializer{ data: ArgVisitor::new(self.read, self.options)? })
+    }
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let mut args = ArgVisitor::new(self.read, self.options)?;
+        let value = visitor.visit_seq(&mut args)?;
+        args.finish()?;
+        Ok(value)
+    }
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.tuple_variant(fields.len(), visitor)
+    }
+}
+
+
+impl<'de, 'a, R> NewtypeArgDeserializer<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    /// Parse the one and only argument.
+    fn single_arg(mut self) -> ResultE<OscArg<'de>> {
+        let arg = self.data.parse_next()?.ok_or(Error::BadFormat)?;
+        match self.data.parse_next()? {
+            None => Ok(self.data.arg(arg)),
+            Some(_) => Err(Error::BadFormat),
+        }
+    }
+}
+
+/// Forward to the deserializer of the single argument in the message.
+macro_rules! forward_to_single_arg {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> ResultE<V::Value>
+                where V: Visitor<'de>
+            {
+                self.single_arg()?.$func(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a, R> de::Deserializer<'de> for NewtypeArgDeserializer<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    type Error = Error;
+    fn deserialize_any<V>(mut self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let value = visitor.visit_seq(&mut self.data)?;
+        self.data.finish()?;
+        Ok(value)
+    }
+    fn deserialize_unit<V>(mut self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        match self.data.parse_next()? {
+            None => visitor.visit_unit(),
+            Some(_) => Err(Error::BadFormat),
+        }
+    }
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.deserialize_unit(visitor)
+    }
+    // The helper types in `serde_osc::types` are a single argument; other
+    // newtype structs are whatever they wrap (e.g. a struct of arguments).
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        if is_helper_name(name) {
+            self.single_arg()?.deserialize_newtype_struct(name, visitor)
+        } else {
+            visitor.visit_newtype_struct(self)
+        }
+    }
+
+    forward_to_single_arg! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option
+    }
+
+    fn deserialize_map<V>(mut self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let value = visitor.visit_map(&mut self.data)?;
+        self.data.finish()?;
+        Ok(value)
+    }
+
+    // Sequence-like payloads receive the whole argument list.
+    forward_to_deserialize_any! {
+        seq tuple_struct struct identifier tuple enum ignored_any
+    }
 }
@@ -0,0 +1,112 @@
+use std::convert::TryInto;
+use std::io::{Cursor, ErrorKind};
+
+use error::{Error, ResultE};
+use framing::Framing;
+use timetag::TimeTag;
+use super::decoder::packet_end;
+use super::options::DeserializerOptions;
+use super::osc_reader::OscReader;
+
+/// Hands out the elements of a bundle one at a time as raw bytes, without
+/// decoding them, e.g. so that a scheduler can queue the elements of a bundle
+/// of future-dated messages and only decode each one when it's due.
+///
+/// Only the time-tag and element lengths are read when the reader is created
+/// (so that a truncated bundle is rejected up front); the elements may still
+/// be malformed. Each element is returned without its length prefix, ready for
+/// `de::from_slice_unframed` (or `BundleReader::new_unframed`, if it's itself a bundle).
+///
+/// ```
+/// use serde_osc::de::{self, BundleReader};
+///
+/// let bundle = ((5u32, 6u32), (("/a", (1,)), ("/b", (2.0f32,))));
+/// let packet = serde_osc::to_vec(&bundle).unwrap();
+/// let mut elements = BundleReader::new(&packet).unwrap();
+/// assert_eq!(elements.len_hint(), 2);
+/// let first = elements.next_element_bytes().unwrap();
+/// let (address, (n,)): (String, (i32,)) = de::from_slice_unframed(first).unwrap();
+/// assert_eq!((address.as_str(), n), ("/a", 1));
+/// assert_eq!(elements.len_hint(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BundleReader<'a> {
+    timetag: TimeTag,
+    /// The elements which haven't been returned yet, each with its length prefix.
+    elements: &'a [u8],
+    remaining: usize,
+}
+
+impl<'a> BundleReader<'a> {
+    /// Read the header of the length-prefixed bundle at the start of `bytes`.
+    /// Any data after the bundle is ignored.
+    pub fn new(bytes: &'a [u8]) -> ResultE<Self> {
+        Self::with_options(bytes, Framing::LengthPrefixed, DeserializerOptions::default())
+    }
+    /// Read the header of a bundle which has no length prefix; it extends to
+    /// the end of `bytes`.
+    pub fn new_unframed(bytes: &'a [u8]) -> ResultE<Self> {
+        Self::with_options(bytes, Framing::Unframed, DeserializerOptions::default())
+    }
+    /// Read the header of a bundle, using the provided framing and decoding options.
+    /// Fails with `Error::BadFormat` if the packet is a message.
+    pub fn with_options(bytes: &'a [u8], framing: Framing, options: DeserializerOptions) -> ResultE<Self> {
+        let contents = match framing {
+            Framing::LengthPrefixed => match packet_end(bytes, &options)? {
+                Some(end) => &bytes[4..end],
+                None => return Err(Error::Io(ErrorKind::UnexpectedEof.into())),
+            },
+            Framing::Unframed => {
+                options.check_size(bytes.len())?;
+                bytes
+            },
+        };
+        let mut rd = Cursor::new(contents);
+        if rd.parse_address(options)? != "#bundle" {
+            return Err(Error::BadFormat);
+        }
+        let timetag = rd.parse_timetag()?.into();
+        let elements = &contents[rd.position() as usize..];
+        let mut remaining = 0;
+        while (rd.position() as usize) < contents.len() {
+            let length: usize = rd.parse_i32()?.try_into()?;
+            options.check_size(length)?;
+            rd.skip_exact(length)?;
+            remaining += 1;
+        }
+        Ok(Self { timetag, elements, remaining })
+    }
+    /// When the bundle's contents are to be handled.
+    pub fn timetag(&self) -> TimeTag {
+        self.timetag
+    }
+    /// The number of elements which haven't been returned yet.
+    pub fn len_hint(&self) -> usize {
+        self.remaining
+    }
+    /// The next element (a message or a bundle), without its length prefix,
+    /// or `None` once all have been returned.
+    pub fn next_element_bytes(&mut self) -> Option<&'a [u8]> {
+        if self.elements.is_empty() {
+            return None;
+        }
+        // The lengths were checked when the bundle was read.
+        let length = i32::from_be_bytes([self.elements[0], self.elements[1], self.elements[2], self.elements[3]]) as usize;
+        let (element, rest) = self.elements[4..].split_at(length);
+        self.elements = rest;
+        self.remaining -= 1;
+        Some(element)
+    }
+}
+
+impl<'a> Iterator for BundleReader<'a> {
+    type Item = &'a [u8];
+    fn next(&mut self) -> Option<&'a [u8]> {
+        self.next_element_bytes()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for BundleReader<'a> {}
@@ -1,12 +1,14 @@
 use std::io::{Read, Take};
-use std::mem;
 use serde::de;
-use serde::de::{DeserializeSeed, SeqAccess, Visitor};
+use serde::de::{DeserializeSeed, IgnoredAny, SeqAccess, Visitor};
 
 use error::{Error, ResultE};
+use timetag::{self, DURATION_NAME, SYSTEM_TIME_NAME};
 use super::iter_visitor::IterVisitor;
+use super::options::DeserializerOptions;
+use super::read_source::ReadSource;
 use super::osc_reader::OscReader;
-use super::pkt_deserializer::PktDeserializer;
+use super::pkt_deserializer::ElementDeserializer;
 use super::prim_deserializer::PrimDeserializer;
 
 /// Deserializes a single bundle, within a packet.
@@ -14,6 +16,9 @@ use super::prim_deserializer::PrimDeserializer;
 pub struct BundleVisitor<'a, R: Read + 'a> {
     read: &'a mut Take<R>,
     state: State,
+    options: DeserializerOptions,
+    /// Number of bundles enclosing the elements, including this one.
+    depth: usize,
 }
 
 /// Which part of the bundle is being parsed
@@ -23,46 +28,75 @@ enum State {
     TimeTag,
     /// Parsing the body of the bundle: OSC Bundle Elements
     Elements,
+    /// The elements have been handed out; nothing is left.
+    Done,
 }
 
 /// Struct to deserialize a single element from the OSC bundle
 enum BundleField<'a, R: Read + 'a> {
     TimeTag((u32, u32)),
-    Elements(&'a mut Take<R>),
+    Elements(&'a mut Take<R>, DeserializerOptions, usize),
 }
 
 /// Deserializes each item (message/bundle) within the bundle element sequence.
 struct ElemAccessor<'a, R: Read + 'a> {
     read: &'a mut Take<R>,
+    options: DeserializerOptions,
+    depth: usize,
 }
 
 impl<'a, R> BundleVisitor<'a, R>
     where R: Read + 'a
 {
-    pub fn new(read: &'a mut Take<R>) -> Self {
+    pub fn new(read: &'a mut Take<R>, options: DeserializerOptions, depth: usize) -> Self {
         Self {
             read: read,
             state: State::TimeTag,
+            options,
+            depth,
         }
     }
 }
 
+impl<'de, 'a, R> BundleVisitor<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    /// Once the bundle has been deserialized, skip over the elements in
+    /// strict mode if they weren't, so that any data after them is noticed.
+    pub fn finish(self) -> ResultE<()> {
+        if !self.options.strict {
+            return Ok(());
+        }
+        if let State::TimeTag = self.state {
+            self.read.parse_timetag()?;
+        }
+        // Anything too short to be an element is left for the caller to report.
+        while self.read.limit() >= 4 {
+            let mut elem = ElementDeserializer::new(self.read, self.options, self.depth);
+            de::Deserializer::deserialize_ignored_any(&mut elem, IgnoredAny)?;
+        }
+        Ok(())
+    }
+}
 
 impl<'de, 'a, R> SeqAccess<'de> for BundleVisitor<'a, R>
-    where R: Read + 'a
+    where R: ReadSource<'de> + 'a
 {
     type Error = Error;
     fn next_element_seed<T>(&mut self, seed: T) -> ResultE<Option<T::Value>>
         where T: DeserializeSeed<'de>
     {
-        if self.read.limit() == 0 {
-            // end of bundle
-            return Ok(None);
-        }
-        let elem = match mem::replace(&mut self.state, State::Elements) {
-            State::TimeTag => BundleField::TimeTag(self.read.parse_timetag()?),
-            State::Elements => BundleField::Elements(self.read),
-            //State::Elements => BundleField::Packet(PktDeserializer::new(self.read)),
+        let elem = match self.state {
+            State::TimeTag => {
+                self.state = State::Elements;
+                BundleField::TimeTag(self.read.parse_timetag()?)
+            },
+            // The elements form a single sequence, even if there are none.
+            State::Elements => {
+                self.state = State::Done;
+                BundleField::Elements(self.read, self.options, self.depth)
+            },
+            State::Done => return Ok(None),
         };
         seed.deserialize(elem).map(Some)
     }
@@ -70,7 +104,7 @@ impl<'de, 'a, R> SeqAccess<'de> for BundleVisitor<'a, R>
 
 
 impl<'de, 'a, R> de::Deserializer<'de> for BundleField<'a, R>
-    where R: Read + 'a
+    where R: ReadSource<'de> + 'a
 {
     type Error = Error;
     // deserializes a single item from the message, consuming self.
@@ -82,29 +116,86 @@ impl<'de, 'a, R> de::Deserializer<'de> for BundleField<'a, R>
             BundleField::TimeTag((sec, frac)) =>
                 visitor.visit_seq(IterVisitor([sec, frac].into_iter().cloned()
                     .map(PrimDeserializer))),
-            BundleField::Elements(mut read) =>
-                visitor.visit_seq(ElemAccessor{ read }),
+            BundleField::Elements(read, options, depth) =>
+                visitor.visit_seq(ElemAccessor{ read, options, depth }),
         }
     }
 
-    // OSC messages are strongly typed, so we don't make use of any type hints.
+    // serde represents `SystemTime` and `Duration` as structs of (seconds, nanoseconds).
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let (secs, nanos) = match (self, name) {
+            (BundleField::TimeTag(tt), SYSTEM_TIME_NAME) => timetag::to_unix_parts(tt)?,
+            (BundleField::TimeTag(tt), DURATION_NAME) => {
+                let since_1900 = timetag::to_duration(tt);
+                (since_1900.as_secs(), since_1900.subsec_nanos())
+            },
+            (field, _) => return field.deserialize_any(visitor),
+        };
+        visitor.visit_seq(IterVisitor([secs, u64::from(nanos)].iter().cloned().map(PrimDeserializer)))
+    }
+
+    // An `Option` time-tag is `None` for "immediately".
+    fn deserialize_option<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        match self {
+            BundleField::TimeTag(timetag::IMMEDIATELY) => visitor.visit_none(),
+            BundleField::TimeTag(_) => visitor.visit_some(self),
+            field => field.deserialize_any(visitor),
+        }
+    }
+
+    // chrono's `DateTime` is deserialized from an RFC 3339 string.
+    fn deserialize_str<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        match self {
+            #[cfg(feature = "chrono")]
+            BundleField::TimeTag(tt) => visitor.visit_string(
+                timetag::to_datetime(tt).to_rfc3339_opts(::chrono::SecondsFormat::Nanos, true)),
+            field => field.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_string<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.deserialize_str(visitor)
+    }
+    // e.g. a struct wrapping the time-tag.
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // OSC messages are strongly typed, so we don't make use of any other type hints.
     // More info: https://github.com/serde-rs/serde/blob/b7d6c5d9f7b3085a4d40a446eeb95976d2337e07/serde/src/macros.rs#L106
     forward_to_deserialize_any! {
-        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
-        seq bytes byte_buf map unit_struct newtype_struct
-        tuple_struct struct identifier tuple enum ignored_any
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char unit
+        seq bytes byte_buf map unit_struct
+        tuple_struct identifier tuple enum ignored_any
     }
 }
 
 
 impl<'de, 'a, R> SeqAccess<'de> for ElemAccessor<'a, R>
-    where R: Read + 'a
+    where R: ReadSource<'de> + 'a
 {
     type Error = Error;
     fn next_element_seed<T>(&mut self, seed: T) -> ResultE<Option<T::Value>>
         where T: DeserializeSeed<'de>
     {
-        // TODO: handle EOF by returning None
-        seed.deserialize(&mut PktDeserializer::new(self.read)).map(Some)
+        if self.read.limit() == 0 {
+            // end of bundle
+            return Ok(None);
+        }
+        seed.deserialize(&mut ElementDeserializer::new(self.read, self.options, self.depth)).map(Some)
     }
 }
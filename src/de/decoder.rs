@@ -0,0 +1,178 @@
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use serde::de::DeserializeOwned;
+
+use error::{Error, ResultE};
+use super::options::DeserializerOptions;
+
+/// Decodes length-prefixed packets from data which arrives in arbitrary
+/// pieces, e.g. from a non-blocking `TcpStream`.
+///
+/// Data is buffered until a whole packet is available, so reads which end
+/// partway through a packet are not an error (unlike with [`PacketStream`]).
+///
+/// ```
+/// use serde_osc::de::Decoder;
+///
+/// let packet = serde_osc::to_vec(&("/m", (1,))).unwrap();
+/// let mut decoder = Decoder::<(String, (i32,))>::new();
+/// assert!(decoder.feed(&packet[..5]).unwrap().is_none());
+/// let (address, args) = decoder.feed(&packet[5..]).unwrap().unwrap();
+/// assert_eq!((address.as_str(), args), ("/m", (1,)));
+/// ```
+///
+/// [`PacketStream`]: struct.PacketStream.html
+#[derive(Debug)]
+pub struct Decoder<T> {
+    buf: Vec<u8>,
+    options: DeserializerOptions,
+    /// Whether to skip to the next plausible packet after a corrupt length prefix.
+    resync: bool,
+    _packet: PhantomData<T>,
+}
+
+impl<T> Default for Decoder<T>
+    where T: DeserializeOwned
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Decoder<T>
+    where T: DeserializeOwned
+{
+    pub fn new() -> Self {
+        Self::with_options(DeserializerOptions::default())
+    }
+    /// Decode each packet according to `options`. Setting `max_packet_size`
+    /// also bounds how much data is buffered while waiting for a packet.
+    pub fn with_options(options: DeserializerOptions) -> Self {
+        Self {
+            buf: Vec::new(),
+            options,
+            resync: false,
+            _packet: PhantomData,
+        }
+    }
+    /// Recover from corrupt length prefixes, e.g. after bytes were lost on a
+    /// TCP stream, by skipping to the next plausible packet (see [`resync`])
+    /// rather than discarding everything buffered.
+    ///
+    /// A packet is then only decoded if its contents start with '/' or
+    /// "#bundle"; others fail with `Error::BadFormat`, and are skipped likewise.
+    ///
+    /// ```
+    /// use serde_osc::de::Decoder;
+    ///
+    /// let mut data = b"\x2f\x00garbage".to_vec();
+    /// data.extend(serde_osc::to_vec(&("/m", (1,))).unwrap());
+    /// let mut decoder = Decoder::<(String, (i32,))>::new().resynchronizing();
+    /// assert!(decoder.feed(&data).is_err());
+    /// let (address, args) = decoder.decode().unwrap().unwrap();
+    /// assert_eq!((address.as_str(), args), ("/m", (1,)));
+    /// ```
+    ///
+    /// [`resync`]: #method.resync
+    pub fn resynchronizing(self) -> Self {
+        Self { resync: true, ..self }
+    }
+    /// Add `data` to the buffer, and decode the first packet if it's now complete.
+    ///
+    /// A single read may contain several packets; call [`decode`] to get at
+    /// the rest.
+    ///
+    /// [`decode`]: #method.decode
+    pub fn feed(&mut self, data: &[u8]) -> ResultE<Option<T>> {
+        self.buf.extend_from_slice(data);
+        self.decode()
+    }
+    /// Decode the first buffered packet, or return `None` if it isn't complete yet.
+    ///
+    /// A packet which fails to decode is still consumed, so the next call
+    /// continues with the packet after it. An invalid length prefix can't be
+    /// skipped, however; all buffered data is then discarded, unless the
+    /// decoder is [`resynchronizing`].
+    ///
+    /// [`resynchronizing`]: #method.resynchronizing
+    pub fn decode(&mut self) -> ResultE<Option<T>> {
+        let end = match self.packet_end() {
+            Ok(Some(end)) => end,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                if self.resync {
+                    self.resync();
+                } else {
+                    self.buf.clear();
+                }
+                return Err(e);
+            },
+        };
+        let packet = super::from_slice_with_options(&self.buf[..end], self.options);
+        self.buf.drain(..end);
+        packet.map(Some)
+    }
+    /// The number of bytes received which haven't been decoded yet.
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+    /// Discard any buffered data, e.g. after reconnecting.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+    /// Discard buffered data up to the next plausible packet boundary after
+    /// the first buffered byte, e.g. once a packet has failed to decode
+    /// because its length prefix was corrupt. Returns the number of bytes
+    /// discarded.
+    ///
+    /// A boundary is plausible if it has a valid length prefix followed by
+    /// '/' or "#bundle", as far as that has arrived. Data which could be the
+    /// start of a packet, but hasn't fully arrived, is kept.
+    pub fn resync(&mut self) -> usize {
+        let skip = (1..self.buf.len())
+            .find(|&start| plausible_start(&self.buf[start..], &self.options))
+            .unwrap_or(self.buf.len());
+        self.buf.drain(..skip);
+        skip
+    }
+    /// The end of the first buffered packet, if all of it has arrived.
+    fn packet_end(&self) -> ResultE<Option<usize>> {
+        let end = packet_end(&self.buf, &self.options)?;
+        if self.resync && !plausible_start(&self.buf, &self.options) {
+            return Err(Error::BadFormat);
+        }
+        Ok(end)
+    }
+}
+
+/// Whether `buf` could start with a packet, judging by as much of its length
+/// prefix and address as is there.
+fn plausible_start(buf: &[u8], options: &DeserializerOptions) -> bool {
+    if buf.len() < 4 {
+        return true;
+    }
+    match packet_end(buf, options) {
+        Ok(_) => {},
+        Err(_) => return false,
+    }
+    let contents = &buf[4..];
+    match contents.first() {
+        None | Some(&b'/') => true,
+        Some(_) => {
+            let len = contents.len().min(8);
+            contents[..len] == b"#bundle\0"[..len]
+        },
+    }
+}
+
+/// The end of the first length-prefixed packet in `buf`, if all of it has arrived.
+pub(crate) fn packet_end(buf: &[u8], options: &DeserializerOptions) -> ResultE<Option<usize>> {
+    let length = match buf.get(..4) {
+        Some(prefix) => i32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]),
+        None => return Ok(None),
+    };
+    let length: usize = length.try_into()?;
+    options.check_size(length)?;
+    let end = 4 + length;
+    Ok(if buf.len() >= end { Some(end) } else { None })
+}
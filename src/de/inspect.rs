@@ -0,0 +1,89 @@
+use std::convert::TryInto;
+use std::io::{Cursor, ErrorKind};
+
+use error::{Error, ResultE};
+use framing::Framing;
+use timetag::TimeTag;
+use super::decoder::packet_end;
+use super::options::DeserializerOptions;
+use super::osc_reader::OscReader;
+use super::typetag::read_typetag;
+
+/// What a packet holds, as returned by [`inspect`].
+///
+/// [`inspect`]: fn.inspect.html
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PacketKind {
+    /// A message; its arguments aren't decoded.
+    Message {
+        /// The message address.
+        address: String,
+        /// The type of each argument, without the leading ','.
+        typetag: String,
+    },
+    /// A bundle; its elements aren't decoded.
+    Bundle {
+        /// When the bundle's contents are to be handled.
+        timetag: TimeTag,
+        /// Number of elements (messages or bundles) directly within the bundle.
+        element_count: usize,
+    },
+}
+
+/// Find out what the length-prefixed packet at the start of `bytes` holds,
+/// reading no more of it than needed, e.g. so that a router can decide where
+/// to forward it. Any data after the packet is ignored.
+///
+/// Only the address and typetag of a message are decoded, and only the
+/// time-tag and element lengths of a bundle; the packet may still be
+/// malformed beyond them (see [`validate`]).
+///
+/// ```
+/// use serde_osc::de::{inspect, PacketKind};
+///
+/// let packet = serde_osc::to_vec(&("/note", (60, 0.5f32))).unwrap();
+/// match inspect(&packet).unwrap() {
+///     PacketKind::Message { address, typetag } => assert_eq!((&*address, &*typetag), ("/note", "if")),
+///     other => panic!("expected a message, got {:?}", other),
+/// }
+/// ```
+///
+/// [`validate`]: fn.validate.html
+pub fn inspect(bytes: &[u8]) -> ResultE<PacketKind> {
+    inspect_with(bytes, Framing::LengthPrefixed, DeserializerOptions::default())
+}
+
+/// Find out what a packet which has no length prefix holds; it extends to
+/// the end of `bytes`.
+pub fn inspect_unframed(bytes: &[u8]) -> ResultE<PacketKind> {
+    inspect_with(bytes, Framing::Unframed, DeserializerOptions::default())
+}
+
+/// Find out what a packet holds, using the provided framing and decoding options.
+pub fn inspect_with(bytes: &[u8], framing: Framing, options: DeserializerOptions) -> ResultE<PacketKind> {
+    let contents = match framing {
+        Framing::LengthPrefixed => match packet_end(bytes, &options)? {
+            Some(end) => &bytes[4..end],
+            None => return Err(Error::Io(ErrorKind::UnexpectedEof.into())),
+        },
+        Framing::Unframed => {
+            options.check_size(bytes.len())?;
+            bytes
+        },
+    };
+    let mut rd = Cursor::new(contents);
+    let address = rd.parse_address(options)?;
+    if address != "#bundle" {
+        let typetag = String::from_utf8(read_typetag(&mut rd, options)?)?;
+        return Ok(PacketKind::Message { address, typetag });
+    }
+    let timetag = rd.parse_timetag()?.into();
+    let mut element_count = 0;
+    while (rd.position() as usize) < contents.len() {
+        let length: usize = rd.parse_i32()?.try_into()?;
+        options.check_size(length)?;
+        rd.skip_exact(length)?;
+        element_count += 1;
+    }
+    Ok(PacketKind::Bundle { timetag, element_count })
+}
@@ -1,4 +1,4 @@
-use serde::de::{Deserializer, DeserializeSeed, SeqAccess};
+use serde::de::{Deserializer, DeserializeSeed, MapAccess, SeqAccess};
 
 use error::{Error, ResultE};
 
@@ -32,3 +32,21 @@ impl<'de, I> SeqAccess<'de> for IterVisitor<I>
     }
 }
 
+
+/// Visit the items as alternating keys and values.
+impl<'de, I> MapAccess<'de> for IterVisitor<I>
+    where I: Iterator, I::Item : Deserializer<'de, Error=Error>
+{
+    type Error = Error;
+    fn next_key_seed<K>(&mut self, seed: K) -> ResultE<Option<K::Value>>
+        where K: DeserializeSeed<'de>
+    {
+        self.next_element_seed(seed)
+    }
+    fn next_value_seed<V>(&mut self, seed: V) -> ResultE<V::Value>
+        where V: DeserializeSeed<'de>
+    {
+        // A key without a value
+        self.next_element_seed(seed)?.ok_or(Error::BadFormat)
+    }
+}
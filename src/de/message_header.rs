@@ -0,0 +1,36 @@
+use std::io::Cursor;
+
+use error::ResultE;
+use super::options::DeserializerOptions;
+use super::osc_reader::OscReader;
+use super::typetag::read_typetag;
+
+/// The address and typetag of a message, as returned by [`Deserializer::peek_header`].
+///
+/// [`Deserializer::peek_header`]: struct.Deserializer.html#method.peek_header
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MessageHeader {
+    /// The message address, or "#bundle" for a bundle.
+    pub address: String,
+    /// The type of each argument, e.g. "if" for an `i32` followed by an `f32`.
+    /// Unlike on the wire, there is no leading ','.
+    pub typetag: String,
+}
+
+impl MessageHeader {
+    /// Parse the header at the start of the packet `contents`.
+    pub(crate) fn parse(contents: &[u8], options: DeserializerOptions) -> ResultE<Self> {
+        let mut rd = Cursor::new(contents);
+        let address = rd.parse_address(options)?;
+        let typetag = if address == "#bundle" {
+            String::new()
+        } else {
+            String::from_utf8(read_typetag(&mut rd, options)?)?
+        };
+        Ok(Self { address, typetag })
+    }
+    /// Whether this is the header of a bundle, rather than a message.
+    pub fn is_bundle(&self) -> bool {
+        self.address == "#bundle"
+    }
+}
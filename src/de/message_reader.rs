@@ -0,0 +1,210 @@
+use std::cmp;
+use std::convert::TryInto;
+use std::fmt;
+use std::io::{self, BufReader, ErrorKind, Read, Take};
+use std::vec;
+use byteorder::{BigEndian, ReadBytesExt};
+
+use error::{Error, ResultE};
+use framing::Framing;
+use value::Value;
+use super::arg_visitor::{check_nesting, parse_arg};
+use super::message_header::MessageHeader;
+use super::options::DeserializerOptions;
+use super::osc_reader::OscReader;
+use super::read_source::{self, IoRead};
+use super::typetag::read_typetag;
+
+/// Reads a message one argument at a time, so that blobs can be streamed
+/// (e.g. into a file) rather than held in memory.
+///
+/// Top-level blob arguments are returned as a [`BlobReader`], which reads the
+/// blob data straight from the underlying reader; all other arguments
+/// (including blobs within arrays) are decoded into a [`Value`].
+/// `max_blob_size` applies to streamed blobs too.
+///
+/// ```
+/// use std::io::{self, Cursor};
+/// use serde_osc::de::{MessageReader, StreamedArg};
+/// use serde_osc::ser::MessageWriter;
+///
+/// let firmware = vec![0xa5; 100_000];
+/// let mut packet = Vec::new();
+/// let mut msg = MessageWriter::new("/update").unwrap();
+/// msg.arg(&3).unwrap();
+/// msg.blob(&firmware[..], firmware.len()).unwrap();
+/// msg.write_to(&mut packet).unwrap();
+///
+/// let mut rd = Cursor::new(packet);
+/// let mut msg = MessageReader::new(&mut rd).unwrap();
+/// assert_eq!(msg.header().typetag, "ib");
+/// let mut received = Vec::new();
+/// while let Some(arg) = msg.next_arg().unwrap() {
+///     match arg {
+///         StreamedArg::Value(version) => assert_eq!(version, 3.into()),
+///         StreamedArg::Blob(mut blob) => { io::copy(&mut blob, &mut received).unwrap(); },
+///     }
+/// }
+/// msg.finish().unwrap();
+/// assert_eq!(received, firmware);
+/// ```
+///
+/// [`BlobReader`]: struct.BlobReader.html
+/// [`Value`]: ../value/enum.Value.html
+#[derive(Debug)]
+pub struct MessageReader<'a, R: Read + 'a> {
+    read: Take<BufReader<Take<&'a mut R>>>,
+    framing: Framing,
+    header: MessageHeader,
+    arg_types: vec::IntoIter<u8>,
+    /// Data of the most recent blob which hasn't been read yet.
+    blob_remaining: u64,
+    /// Padding after the most recent blob.
+    blob_padding: usize,
+    options: DeserializerOptions,
+}
+
+/// An argument read by a [`MessageReader`].
+///
+/// [`MessageReader`]: struct.MessageReader.html
+#[derive(Debug)]
+pub enum StreamedArg<'b> {
+    /// Any argument other than a blob.
+    Value(Value),
+    /// A blob, whose data is yet to be read.
+    Blob(BlobReader<'b>),
+}
+
+/// Reads the data of a blob argument, as it arrives.
+/// Any data which isn't read is skipped once the next argument is requested.
+pub struct BlobReader<'b> {
+    read: &'b mut dyn Read,
+    remaining: &'b mut u64,
+    size: usize,
+}
+
+impl<'a, R> MessageReader<'a, R>
+    where R: Read + 'a
+{
+    /// Read the header of a length-prefixed message.
+    pub fn new(reader: &'a mut R) -> ResultE<Self> {
+        Self::with_options(reader, Framing::LengthPrefixed, DeserializerOptions::default())
+    }
+    /// Read the header of a message which spans the entire reader and has no length prefix.
+    pub fn new_unframed(reader: &'a mut R) -> ResultE<Self> {
+        Self::with_options(reader, Framing::Unframed, DeserializerOptions::default())
+    }
+    /// Read the header of a message, using the provided framing and decoding options.
+    /// Fails with `Error::BadFormat` if the packet is a bundle.
+    pub fn with_options(reader: &'a mut R, framing: Framing, options: DeserializerOptions) -> ResultE<Self> {
+        let length = match framing {
+            Framing::LengthPrefixed => {
+                let length: usize = reader.read_i32::<BigEndian>()?.try_into()?;
+                options.check_size(length)?;
+                length as u64
+            },
+            Framing::Unframed => u64::MAX,
+        };
+        let mut read = read_source::window(reader, length).take(length);
+        let address = read.parse_address(options)?;
+        if address == "#bundle" {
+            return Err(Error::BadFormat);
+        }
+        let arg_types = read_typetag(&mut read, options)?;
+        check_nesting(&arg_types, options)?;
+        let typetag = String::from_utf8(arg_types.clone())?;
+        Ok(Self {
+            read,
+            framing,
+            header: MessageHeader { address, typetag },
+            arg_types: arg_types.into_iter(),
+            blob_remaining: 0,
+            blob_padding: 0,
+            options,
+        })
+    }
+    /// The address and typetag of the message.
+    pub fn header(&self) -> &MessageHeader {
+        &self.header
+    }
+    /// Read the next argument, or return `None` once all have been read.
+    pub fn next_arg(&mut self) -> ResultE<Option<StreamedArg<'_>>> {
+        self.skip_blob()?;
+        match self.arg_types.next() {
+            None => Ok(None),
+            Some(b'b') => {
+                let size = self.read.parse_blob_size(self.options.max_blob_size)?;
+                self.blob_remaining = size as u64;
+                self.blob_padding = (4 - size % 4) % 4;
+                Ok(Some(StreamedArg::Blob(BlobReader {
+                    read: &mut self.read,
+                    remaining: &mut self.blob_remaining,
+                    size,
+                })))
+            },
+            Some(tag) => {
+                let arg = parse_arg(&mut IoRead(&mut self.read), tag, &mut self.arg_types, self.options)?;
+                Ok(Some(StreamedArg::Value(arg.into())))
+            },
+        }
+    }
+    /// Skip any arguments which haven't been read, leaving the reader at the
+    /// start of the next packet.
+    pub fn finish(mut self) -> ResultE<()> {
+        self.skip_blob()?;
+        let size = self.read.limit();
+        let drained = io::copy(&mut self.read, &mut io::sink())?;
+        if self.framing == Framing::LengthPrefixed && drained != size {
+            return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(())
+    }
+    /// Discard the unread data of the most recent blob, and verify its padding.
+    fn skip_blob(&mut self) -> ResultE<()> {
+        let remaining = self.blob_remaining;
+        if io::copy(&mut self.read.by_ref().take(remaining), &mut io::sink())? != remaining {
+            return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+        }
+        self.blob_remaining = 0;
+        let mut padding = [0u8; 3];
+        let padding = &mut padding[..self.blob_padding];
+        self.read.read_exact(padding)?;
+        self.blob_padding = 0;
+        if padding.iter().any(|&c| c != 0) {
+            return Err(Error::BadPadding);
+        }
+        Ok(())
+    }
+}
+
+impl<'b> BlobReader<'b> {
+    /// The length of the blob, in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    /// The number of bytes which haven't been read yet.
+    pub fn remaining(&self) -> usize {
+        *self.remaining as usize
+    }
+}
+
+impl<'b> Read for BlobReader<'b> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let max = cmp::min(buf.len() as u64, *self.remaining) as usize;
+        let read = self.read.read(&mut buf[..max])?;
+        if read == 0 && max != 0 {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        *self.remaining -= read as u64;
+        Ok(read)
+    }
+}
+
+impl<'b> fmt::Debug for BlobReader<'b> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BlobReader")
+            .field("size", &self.size)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
@@ -1,33 +1,182 @@
-use std::io::{Cursor, Read};
+use std::io::Read;
 use serde;
+use address::AddressPattern;
 use error::ResultE;
+use framing::Framing;
 
 mod arg_visitor;
+mod bundle_reader;
 mod bundle_visitor;
+mod decoder;
+mod inspect;
 mod iter_visitor;
-mod maybe_skip_comma;
+mod message_header;
+mod message_reader;
 mod msg_visitor;
-mod osc_reader;
+mod options;
+pub(crate) mod osc_reader;
 mod osc_type;
+pub(crate) mod packet_enum;
+mod packet_reader;
+mod packet_stream;
 mod pkt_deserializer;
 mod prim_deserializer;
+mod typetag;
+mod validate;
+pub(crate) mod read_source;
 
+pub use self::bundle_reader::BundleReader;
+pub use self::decoder::Decoder;
+pub use self::inspect::{inspect, inspect_unframed, inspect_with, PacketKind};
+pub(crate) use self::decoder::packet_end;
+pub use self::message_header::MessageHeader;
+pub use self::message_reader::{BlobReader, MessageReader, StreamedArg};
+pub use self::osc_reader::OscReader;
+pub use self::options::{Coercion, Compat, DeserializerOptions, Endianness, ExtensionType, Narrowing, StringDecoding, TrailingArgs, TypetagCompat, UnknownTypes, DEFAULT_MAX_DEPTH};
+pub(crate) use self::arg_visitor::ArgVisitor;
+pub(crate) use self::packet_reader::read_packet;
+pub use self::packet_stream::PacketStream;
 pub use self::pkt_deserializer::PktDeserializer as Deserializer;
+pub use self::validate::{validate, validate_unframed, validate_with, PacketInfo};
+use self::pkt_deserializer::SourceDeserializer;
+use self::read_source::SliceRead;
 
 /// Deserialize an OSC packet from some readable device.
-pub fn from_read<'de, D, R>(mut rd: R) -> ResultE<D>
+///
+/// `rd` needn't be buffered: the packet is read in chunks of up to 8 KiB,
+/// but never past its end, so `rd` is left at the start of the next packet.
+pub fn from_read<'de, D, R>(rd: R) -> ResultE<D>
     where R: Read, D: serde::de::Deserialize<'de>
 {
-    let mut de = Deserializer::new(&mut rd);
+    from_read_framed(rd, Framing::LengthPrefixed)
+}
+
+/// Deserialize an OSC packet that has no length prefix from some readable device.
+/// The packet is assumed to extend until EOF, as is the case for e.g. a UDP datagram.
+pub fn from_read_unframed<'de, D, R>(rd: R) -> ResultE<D>
+    where R: Read, D: serde::de::Deserialize<'de>
+{
+    from_read_framed(rd, Framing::Unframed)
+}
+
+/// Deserialize successive length-prefixed OSC packets from some readable
+/// device, until EOF. This reads back what [`ser::to_write_all`] wrote.
+///
+/// The result is a [`PacketStream`] over `rd`; see it for how errors are handled.
+///
+/// [`ser::to_write_all`]: ../ser/fn.to_write_all.html
+/// [`PacketStream`]: struct.PacketStream.html
+pub fn from_read_all<D, R>(rd: R) -> PacketStream<R, D>
+    where R: Read, D: serde::de::DeserializeOwned
+{
+    PacketStream::new(rd)
+}
+
+/// Deserialize a length-prefixed OSC packet from some readable device,
+/// decoding it according to `options`.
+pub fn from_read_with_options<'de, D, R>(mut rd: R, options: DeserializerOptions) -> ResultE<D>
+    where R: Read, D: serde::de::Deserialize<'de>
+{
+    let mut de = Deserializer::with_options(&mut rd, Framing::LengthPrefixed, options);
+    D::deserialize(&mut de)
+}
+
+/// Deserialize a length-prefixed OSC message from some readable device, provided
+/// its address is matched by `pattern`.
+///
+/// The address is checked before any arguments are decoded; if it doesn't match
+/// (or the packet is a bundle), this fails with `Error::AddressMismatch`.
+/// Either way, the whole packet is consumed from the reader. To cheaply try
+/// several target types on one packet, use [`from_slice_matching`] instead.
+///
+/// [`from_slice_matching`]: fn.from_slice_matching.html
+pub fn from_read_matching<'de, D, R>(mut rd: R, pattern: &AddressPattern) -> ResultE<D>
+    where R: Read, D: serde::de::Deserialize<'de>
+{
+    let mut de = Deserializer::new(&mut rd).matching(pattern);
+    D::deserialize(&mut de)
+}
+
+fn from_read_framed<'de, D, R>(mut rd: R, framing: Framing) -> ResultE<D>
+    where R: Read, D: serde::de::Deserialize<'de>
+{
+    let mut de = Deserializer::with_framing(&mut rd, framing);
     D::deserialize(&mut de)
 }
 
 
 /// Deserialize an OSC packet from a `&[u8]` type.
-/// This is a wrapper around the `from_read` function.
 /// Pairs nicely with ser::to_vec, as Vec<u8> is coercable to &[u8].
-pub fn from_slice<'de, T>(slice: &[u8]) -> ResultE<T>
+///
+/// Blobs may be borrowed from the slice, e.g. into a `&[u8]` field
+/// (or one marked `#[serde(with = "serde_bytes")]`), rather than copied.
+pub fn from_slice<'de, T>(slice: &'de [u8]) -> ResultE<T>
+    where T: serde::de::Deserialize<'de>
+{
+    from_slice_framed(slice, Framing::LengthPrefixed, DeserializerOptions::default(), None)
+}
+
+/// Deserialize an OSC packet from the `Vec<u8>` it was serialized into, e.g.
+/// with ser::to_vec. Unlike `from_slice`, nothing can be borrowed from the
+/// input, so the result must own its data.
+pub fn from_vec<T>(vec: Vec<u8>) -> ResultE<T>
+    where T: serde::de::DeserializeOwned
+{
+    from_slice(&vec)
+}
+
+/// Deserialize an OSC packet that has no length prefix from a `&[u8]` type.
+/// Pairs with ser::to_vec_unframed.
+pub fn from_slice_unframed<'de, T>(slice: &'de [u8]) -> ResultE<T>
+    where T: serde::de::Deserialize<'de>
+{
+    from_slice_framed(slice, Framing::Unframed, DeserializerOptions::default(), None)
+}
+
+/// Deserialize a length-prefixed OSC packet from a `&[u8]` type,
+/// decoding it according to `options`.
+pub fn from_slice_with_options<'de, T>(slice: &'de [u8], options: DeserializerOptions) -> ResultE<T>
+    where T: serde::de::Deserialize<'de>
+{
+    from_slice_framed(slice, Framing::LengthPrefixed, options, None)
+}
+
+/// Deserialize a length-prefixed OSC message from a `&[u8]` type, provided its
+/// address is matched by `pattern`. See [`from_read_matching`].
+///
+/// ```
+/// # extern crate serde_osc;
+/// use serde_osc::address::AddressPattern;
+/// use serde_osc::de::from_slice_matching;
+/// use serde_osc::error::Error;
+///
+/// # fn main() {
+/// let packet = serde_osc::to_vec(&("/synth/1/freq", (440.0f32,))).unwrap();
+/// let freq: Result<(String, (f32,)), _> = from_slice_matching(&packet, &"/synth/*/freq".into());
+/// assert_eq!(freq.unwrap().1, (440.0,));
+///
+/// let gain: Result<(String, (i32,)), _> = from_slice_matching(&packet, &"/synth/*/gain".into());
+/// match gain {
+///     Err(Error::AddressMismatch(address)) => assert_eq!(address, "/synth/1/freq"),
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// # }
+/// ```
+pub fn from_slice_matching<'de, T>(slice: &'de [u8], pattern: &AddressPattern) -> ResultE<T>
+    where T: serde::de::Deserialize<'de>
+{
+    from_slice_framed(slice, Framing::LengthPrefixed, DeserializerOptions::default(), Some(pattern))
+}
+
+pub(crate) fn from_slice_framed<'de, T>(
+    slice: &'de [u8],
+    framing: Framing,
+    options: DeserializerOptions,
+    pattern: Option<&AddressPattern>
+) -> ResultE<T>
     where T: serde::de::Deserialize<'de>
 {
-    from_read(Cursor::new(slice))
+    let mut rd = SliceRead::new(slice);
+    let mut de = SourceDeserializer::from_slice(&mut rd, framing, options, pattern);
+    T::deserialize(&mut de)
 }
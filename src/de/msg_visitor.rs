@@ -1,16 +1,19 @@
 use std::io::{Read, Take};
 use std::mem;
-use serde::de::{DeserializeSeed, SeqAccess};
+use serde::de::{DeserializeSeed, EnumAccess, SeqAccess};
 
 use error::{Error, ResultE};
-use super::arg_visitor::ArgDeserializer;
-use super::osc_type::OscType;
+use super::arg_visitor::{ArgDeserializer, ArgVariantAccess, ArgVisitor};
+use super::options::DeserializerOptions;
+use super::read_source::ReadSource;
+use super::osc_type::{OscArg, OscType};
 
 /// Deserializes a single message, within a packet.
 #[derive(Debug)]
 pub struct MsgVisitor<'a, R: Read + 'a> {
     read: &'a mut Take<R>,
     state: State,
+    options: DeserializerOptions,
 }
 
 /// Which part of the OSC message is being parsed
@@ -24,21 +27,51 @@ enum State {
     Done,
 }
 
+/// Deserializes a single message as an enum, where the address selects the variant.
+#[derive(Debug)]
+pub struct MsgEnumAccess<'a, R: Read + 'a> {
+    read: &'a mut Take<R>,
+    address: String,
+    options: DeserializerOptions,
+}
+
 impl<'a, R> MsgVisitor<'a, R>
     where R: Read + 'a
 {
-    pub fn new(read: &'a mut Take<R>, address: String) -> Self {
+    pub fn new(read: &'a mut Take<R>, address: String, options: DeserializerOptions) -> Self {
         Self {
             read: read,
             state: State::Address(address),
+            options,
+        }
+    }
+}
+
+impl<'de, 'a, R> MsgVisitor<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    /// Once the message has been deserialized, skip over the arguments in
+    /// strict mode if they weren't, so that any data after them is noticed.
+    pub fn finish(self) -> ResultE<()> {
+        match self.state {
+            State::Done => Ok(()),
+            _ if self.options.strict => ArgVisitor::new(self.read, self.options)?.skip_rest(),
+            _ => Ok(()),
         }
     }
 }
 
+impl<'a, R> MsgEnumAccess<'a, R>
+    where R: Read + 'a
+{
+    pub fn new(read: &'a mut Take<R>, address: String, options: DeserializerOptions) -> Self {
+        Self { read, address, options }
+    }
+}
 
 
 impl<'de, 'a, R> SeqAccess<'de> for MsgVisitor<'a, R>
-    where R: Read + 'a
+    where R: ReadSource<'de> + 'a
 {
     type Error = Error;
     fn next_element_seed<T>(&mut self, seed: T) -> ResultE<Option<T::Value>>
@@ -47,11 +80,11 @@ impl<'de, 'a, R> SeqAccess<'de> for MsgVisitor<'a, R>
         let (new_state, result) = match mem::replace(&mut self.state, State::Done) {
             // parse the address
             State::Address(address) => {
-                (State::Typestring, seed.deserialize(OscType::String(address)).map(Some))
+                (State::Typestring, seed.deserialize(OscArg::new(OscType::String(address), &self.options)).map(Some))
             },
             // parsed the address; now parse the args
             State::Typestring => {
-                (State::Done, seed.deserialize(&mut ArgDeserializer::new(self.read)?).map(Some))
+                (State::Done, seed.deserialize(&mut ArgDeserializer::new(self.read, self.options)?).map(Some))
             },
             // parsed the address and the args; nothing left to do
             State::Done => {
@@ -62,3 +95,16 @@ impl<'de, 'a, R> SeqAccess<'de> for MsgVisitor<'a, R>
         result
     }
 }
+
+impl<'de, 'a, R> EnumAccess<'de> for MsgEnumAccess<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    type Error = Error;
+    type Variant = ArgVariantAccess<'a, R>;
+    fn variant_seed<V>(self, seed: V) -> ResultE<(V::Value, Self::Variant)>
+        where V: DeserializeSeed<'de>
+    {
+        let variant = seed.deserialize(OscArg::new(OscType::String(self.address), &self.options))?;
+        Ok((variant, ArgVariantAccess::new(self.read, self.options)))
+    }
+}
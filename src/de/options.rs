@@ -0,0 +1,444 @@
+use std::io::{self, Read};
+
+use error::{Error, ResultE};
+use types::RawArg;
+
+/// Settings which control how leniently packets are decoded.
+///
+/// The defaults accept only well-formed packets; construct the options with
+/// struct update syntax to change individual settings:
+///
+/// ```
+/// use serde_osc::de::{DeserializerOptions, StringDecoding};
+///
+/// let options = DeserializerOptions {
+///     strings: StringDecoding::Lossy,
+///     max_blob_size: Some(64 * 1024),
+///     ..DeserializerOptions::default()
+/// };
+/// # let _ = options;
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeserializerOptions {
+    /// How to handle strings (and addresses) which are not valid UTF-8.
+    pub strings: StringDecoding,
+    /// Reject packets whose length exceeds this many bytes (excluding the
+    /// length prefix itself) with `Error::SizeLimitExceeded`.
+    /// This bounds the memory used when reading unframed packets, and guards
+    /// against corrupt length prefixes on streams. `None` means no limit.
+    pub max_packet_size: Option<usize>,
+    /// Reject blob arguments longer than this many bytes with
+    /// `Error::SizeLimitExceeded`. `None` means no limit.
+    pub max_blob_size: Option<usize>,
+    /// Reject bundles nested more than this many levels deep (counting the
+    /// outermost bundle as one level) with `Error::DepthLimitExceeded`; the
+    /// same limit applies to arrays nested within a message's arguments.
+    /// Decoding nested bundles and arrays is recursive, so this guards against stack
+    /// exhaustion. Defaults to [`DEFAULT_MAX_DEPTH`]; `None` means no limit.
+    ///
+    /// [`DEFAULT_MAX_DEPTH`]: constant.DEFAULT_MAX_DEPTH.html
+    pub max_depth: Option<usize>,
+    /// Whether numeric arguments may be converted to the type being deserialized into.
+    pub coercion: Coercion,
+    /// What to do with numeric arguments too wide for the type being
+    /// deserialized into, e.g. an 'h' argument for an `i32`.
+    pub narrowing: Narrowing,
+    /// Byte order of numeric arguments. OSC numbers are big-endian, but a few
+    /// broken senders (typically embedded devices) use little-endian ones.
+    pub endianness: Endianness,
+    /// How to handle arguments whose typetag isn't supported.
+    pub unknown_types: UnknownTypes,
+    /// Nonstandard argument types to decode, in addition to those this crate
+    /// supports; see [`ExtensionType`]. These are accepted whatever
+    /// `unknown_types` says.
+    ///
+    /// [`ExtensionType`]: struct.ExtensionType.html
+    pub extensions: &'static [ExtensionType],
+    /// Which deviations from the OSC 1.0 typetag format to accept.
+    pub typetags: TypetagCompat,
+    /// What to do with arguments left over once the type being deserialized
+    /// into is complete, e.g. the third argument of a message decoded into
+    /// `(String, (i32, f32))`.
+    pub trailing_args: TrailingArgs,
+    /// Reject anything the OSC 1.0 spec doesn't allow, e.g. for protocol test
+    /// harnesses. This takes precedence over the more lenient settings:
+    ///
+    /// - addresses must start with '/' (else `Error::BadAddress`);
+    /// - typetags must start with ',' (else `Error::MissingTypetagComma`),
+    ///   whatever `typetags` says;
+    /// - strings must be ASCII (else `Error::NonAsciiString`), whatever
+    ///   `strings` says;
+    /// - packets and bundle elements must be a multiple of 4 bytes long (else
+    ///   `Error::BadPadding`). The padding of the data within them is checked
+    ///   regardless of this setting;
+    /// - the contents of packets and bundle elements must extend to their
+    ///   declared length (else `Error::LengthMismatch`); arguments and bundle
+    ///   elements which aren't deserialized are skipped over to check this.
+    ///   Otherwise, any surplus is skipped, so that a stream of packets stays
+    ///   aligned either way.
+    pub strict: bool,
+}
+
+/// The default limit on the nesting of bundles.
+pub const DEFAULT_MAX_DEPTH: usize = 32;
+
+impl Default for DeserializerOptions {
+    fn default() -> Self {
+        Self {
+            strings: StringDecoding::default(),
+            max_packet_size: None,
+            max_blob_size: None,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            coercion: Coercion::default(),
+            narrowing: Narrowing::default(),
+            endianness: Endianness::default(),
+            unknown_types: UnknownTypes::default(),
+            extensions: &[],
+            typetags: TypetagCompat::default(),
+            trailing_args: TrailingArgs::default(),
+            strict: false,
+        }
+    }
+}
+
+impl DeserializerOptions {
+    /// Settings for talking to the SuperCollider servers, scsynth and
+    /// supernova, without sclang.
+    ///
+    /// Their replies use integers, floats and booleans interchangeably (e.g.
+    /// the "is group" flag of `/n_info`), so numbers and booleans are coerced
+    /// leniently. Messages without a typetag, which the servers accept, are
+    /// accepted too. Symbols, nils and nested arrays need no special
+    /// treatment: they're always supported.
+    pub fn supercollider() -> Self {
+        Self {
+            coercion: Coercion::Lenient,
+            typetags: TypetagCompat::Legacy,
+            ..Self::default()
+        }
+    }
+    /// Settings for talking to applications built on liblo, the reference C
+    /// implementation of OSC (e.g. Ardour, or scripts using pyliblo).
+    ///
+    /// liblo sends every type it supports, including time-tag ('t') and
+    /// infinitum ('I') arguments, which this crate doesn't decode: they're
+    /// kept as raw data (see `UnknownTypes::Capture`), so that the rest of the
+    /// message can still be decoded, and forwarded unchanged. Messages which
+    /// end after their address, with no typetag at all, are taken to have no
+    /// arguments. A message without arguments, which liblo sends with the
+    /// typetag "," alone, and nils ('N', decoded as `None` or `()`) need no
+    /// special treatment.
+    pub fn liblo() -> Self {
+        Self {
+            unknown_types: UnknownTypes::Capture,
+            typetags: TypetagCompat::Legacy,
+            ..Self::default()
+        }
+    }
+    /// Settings for talking to Max/MSP and Pure Data patches (e.g. through
+    /// `[udpsend]`, or `[oscformat]` and `[netsend -u -b]`).
+    ///
+    /// Pd numbers are all floats, and Max sends a number as an integer or
+    /// a float depending on how it was produced (e.g. `[slider]` or
+    /// `[flonum]`), so numbers are coerced to the type of the field they're
+    /// decoded into whenever that's exact, and toggles (1 or 0) can be decoded
+    /// into `bool` fields. Max can be set to send 64-bit floats, which are
+    /// rounded to the nearest `f32` for `f32` fields. Typetags without the
+    /// leading ',', or missing altogether (as older externals send them), are
+    /// accepted.
+    pub fn max_pd() -> Self {
+        Self {
+            coercion: Coercion::Lenient,
+            narrowing: Narrowing::Saturate,
+            typetags: TypetagCompat::Legacy,
+            ..Self::default()
+        }
+    }
+    /// Settings for talking to the TouchOSC and Lemur control surface apps.
+    ///
+    /// Their controls send floats, even for buttons, toggles and the "z"
+    /// messages which report whether a control is being touched (e.g.
+    /// "/1/fader1/z" with 1.0 or 0.0), so numbers are coerced to the type of
+    /// the field they're decoded into whenever that's exact, including 1 and 0
+    /// to `bool` fields. Nothing else needs special treatment: colors ('r',
+    /// as a `types::Color`) and booleans, which newer versions of TouchOSC
+    /// send, are always supported, as are page changes (a message to the
+    /// page's address, e.g. "/2", without arguments) and the bundles Lemur
+    /// sends, timestamped "immediately".
+    pub fn touchosc() -> Self {
+        Self {
+            coercion: Coercion::Lenient,
+            ..Self::default()
+        }
+    }
+    /// Whether a bundle nested within `enclosing` other bundles is too deep.
+    pub(crate) fn too_deep(&self, enclosing: usize) -> bool {
+        self.max_depth.is_some_and(|max| enclosing >= max)
+    }
+    /// Check the length of a packet (or bundle element) against the size
+    /// limit and, in strict mode, its alignment.
+    pub(crate) fn check_size(&self, length: usize) -> ResultE<()> {
+        if self.max_packet_size.is_some_and(|max| length > max) {
+            return Err(Error::SizeLimitExceeded);
+        }
+        if self.strict && !length.is_multiple_of(4) {
+            return Err(Error::BadPadding);
+        }
+        Ok(())
+    }
+    /// In strict mode, check that a message address starts with '/'.
+    pub(crate) fn check_address(&self, address: &[u8]) -> ResultE<()> {
+        if self.strict && address.first() != Some(&b'/') && address != b"#bundle" {
+            return Err(Error::BadAddress(String::from_utf8_lossy(address).into_owned()));
+        }
+        Ok(())
+    }
+    /// In strict mode, check that a string is ASCII.
+    pub(crate) fn check_string(&self, data: &[u8]) -> ResultE<()> {
+        if self.strict && !data.is_ascii() {
+            return Err(Error::NonAsciiString);
+        }
+        Ok(())
+    }
+    /// The extension type registered for `tag`, if any.
+    pub(crate) fn extension(&self, tag: u8) -> Option<&'static ExtensionType> {
+        self.extensions.iter().find(|ext| ext.tag == tag as char)
+    }
+    /// Whether a message may omit its typetag.
+    pub(crate) fn typetag_optional(&self) -> bool {
+        self.typetags == TypetagCompat::Legacy && !self.strict
+    }
+}
+
+/// The implementations (or families of them) with a ready-made set of
+/// [`DeserializerOptions`], for picking the profile of a peer at runtime
+/// (e.g. from a configuration file).
+///
+/// ```
+/// use serde_osc::de::{Compat, DeserializerOptions};
+///
+/// let options: DeserializerOptions = Compat::Liblo.into();
+/// assert_eq!(options, DeserializerOptions::liblo());
+/// ```
+///
+/// [`DeserializerOptions`]: struct.DeserializerOptions.html
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Compat {
+    /// Any OSC 1.0 implementation: `DeserializerOptions::default()`.
+    #[default]
+    Osc10,
+    /// scsynth and supernova: `DeserializerOptions::supercollider()`.
+    SuperCollider,
+    /// liblo: `DeserializerOptions::liblo()`.
+    Liblo,
+    /// Max/MSP and Pure Data: `DeserializerOptions::max_pd()`.
+    MaxPd,
+    /// TouchOSC and Lemur: `DeserializerOptions::touchosc()`.
+    TouchOsc,
+}
+
+impl Compat {
+    /// The options for talking to this implementation.
+    pub fn options(self) -> DeserializerOptions {
+        match self {
+            Compat::Osc10 => DeserializerOptions::default(),
+            Compat::SuperCollider => DeserializerOptions::supercollider(),
+            Compat::Liblo => DeserializerOptions::liblo(),
+            Compat::MaxPd => DeserializerOptions::max_pd(),
+            Compat::TouchOsc => DeserializerOptions::touchosc(),
+        }
+    }
+}
+
+impl From<Compat> for DeserializerOptions {
+    fn from(compat: Compat) -> Self {
+        compat.options()
+    }
+}
+
+/// How to decode the bytes of an OSC string.
+///
+/// The OSC spec only permits ASCII strings, but some legacy hardware sends
+/// 8-bit (typically Latin-1) text.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum StringDecoding {
+    /// Fail with `Error::StrParseError` if the string isn't valid UTF-8.
+    #[default]
+    Strict,
+    /// Replace invalid UTF-8 sequences with U+FFFD REPLACEMENT CHARACTER.
+    Lossy,
+    /// If the string isn't valid UTF-8, decode each byte as a Latin-1
+    /// (ISO 8859-1) character instead.
+    Latin1,
+}
+
+/// How to handle numeric arguments whose type differs from the one they are
+/// deserialized into, e.g. an 'f' argument for an `i32` field.
+///
+/// Integer arguments are always accepted for any integer type they fit in,
+/// and 'f' arguments for an `f64`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Coercion {
+    /// Fail with `Error::TypeMismatch`.
+    #[default]
+    Strict,
+    /// Convert between integer ('i', 'h') and floating-point ('f', 'd')
+    /// arguments, provided the value is represented exactly, e.g. `1.0` for an
+    /// `i32`, or `3` for an `f32`. Other values still fail with `Error::TypeMismatch`.
+    Lossless,
+    /// As `Lossless`, and also between booleans ('T', 'F') and the numbers
+    /// 1 and 0, which some implementations (e.g. SuperCollider) use
+    /// interchangeably for flags.
+    Lenient,
+}
+
+/// How to handle numeric arguments whose value doesn't fit the type they are
+/// deserialized into, e.g. an 'h' argument of 2^40 for an `i32`, or any 'd'
+/// argument for an `f32`.
+///
+/// Widening is always allowed: 'i' arguments are accepted for an `i64`, and
+/// 'f' arguments for an `f64`. Integers which fit are accepted for any integer
+/// type whatever this says.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Narrowing {
+    /// Fail: with `Error::Message` for an integer which doesn't fit, and
+    /// with `Error::TypeMismatch` for a 'd' argument.
+    #[default]
+    Reject,
+    /// Clamp integers to the range of the type, e.g. 2^40 becomes `i32::MAX`
+    /// and -1 becomes 0 for a `u32`. Doubles are rounded to the nearest
+    /// `f32`, clamping finite values to `f32::MAX` in magnitude.
+    Saturate,
+    /// Convert as Rust's `as` operator does: integers are truncated to their
+    /// low bits, and doubles rounded to the nearest `f32`, becoming infinite
+    /// if they're out of its range.
+    Cast,
+}
+
+/// The byte order of numeric arguments ('i', 'h', 'f', 'd' and 'c').
+///
+/// Only argument values are affected: lengths (of packets, bundle elements
+/// and blobs) and time-tags are always big-endian, as senders which get the
+/// byte order of arguments wrong usually still frame packets correctly.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// Most significant byte first, as the spec requires.
+    #[default]
+    Big,
+    /// Least significant byte first, for talking to broken senders.
+    Little,
+}
+
+/// How to handle arguments with a typetag this crate doesn't support.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum UnknownTypes {
+    /// Fail with `Error::UnsupportedType`.
+    #[default]
+    Reject,
+    /// Keep the argument's raw data, provided its size can be inferred:
+    /// either the tag is one of the extended types listed by the OSC 1.0 spec
+    /// ('t': 8 bytes, 'I': none), or the argument is the last one
+    /// in the message, and so extends to its end. The argument is decoded as
+    /// a `value::Value::Unknown`, or as a byte buffer; other unknown
+    /// arguments still fail with `Error::UnsupportedType`.
+    Capture,
+}
+
+/// Which malformed typetags to accept, for compatibility with older OSC
+/// implementations which predate (or only partly follow) OSC 1.0.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TypetagCompat {
+    /// The typetag may omit its leading ',' (e.g. "if" rather than ",if").
+    #[default]
+    CommaOptional,
+    /// As `CommaOptional`, and a message may also omit its typetag entirely,
+    /// ending right after its address; it is then taken to have no arguments.
+    Legacy,
+}
+
+/// What to do with the arguments of a message which the type it's
+/// deserialized into doesn't consume. Only top-level arguments count: the
+/// surplus elements of an array are always skipped.
+///
+/// Conversely, a message may have fewer arguments than the type has fields,
+/// as many controllers send abbreviated messages: fields marked
+/// `#[serde(default)]` then take their default values, while other missing
+/// fields fail with `Error::Message`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TrailingArgs {
+    /// Skip them, so that a type can pick out the leading arguments of a
+    /// longer message.
+    #[default]
+    Skip,
+    /// Fail with `Error::TrailingArguments`, giving the number left over.
+    /// Arguments deserialized into `IgnoredAny` count as consumed.
+    Reject,
+}
+
+/// A nonstandard argument type, e.g. one used by in-house hardware, and how to
+/// find the extent of its data.
+///
+/// Arguments of the type are decoded as their raw data: into a
+/// `value::Value::Unknown`, or handed to the `Deserialize` implementation of
+/// the field they're decoded into as a byte buffer. They can be encoded from
+/// a `types::RawArg`.
+///
+/// ```
+/// # extern crate serde_osc;
+/// use std::io::{self, Read};
+/// use serde_osc::de::{DeserializerOptions, ExtensionType};
+/// use serde_osc::framing::Framing;
+/// use serde_osc::types::RawArg;
+/// use serde_osc::value::{Message, Packet, Value};
+///
+/// // 'x': a 3D vector, as three big-endian f32s.
+/// fn read_vector(read: &mut dyn Read) -> io::Result<Vec<u8>> {
+///     let mut data = vec![0; 12];
+///     read.read_exact(&mut data)?;
+///     Ok(data)
+/// }
+/// static EXTENSIONS: &[ExtensionType] = &[ExtensionType { tag: 'x', read: read_vector }];
+///
+/// # fn main() {
+/// let msg = Message::new("/pos").with_arg(RawArg::new('x', vec![0; 12])).with_arg(1);
+/// let packet = serde_osc::to_vec(&msg).unwrap();
+///
+/// let options = DeserializerOptions { extensions: EXTENSIONS, ..DeserializerOptions::default() };
+/// let decoded = Packet::from_slice_with(&packet, Framing::LengthPrefixed, options).unwrap();
+/// assert_eq!(decoded, Packet::Message(msg));
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ExtensionType {
+    /// The typetag character; the tags of the types this crate supports
+    /// can't be overridden.
+    pub tag: char,
+    /// Reads the data of one argument of this type from the rest of the
+    /// message, consuming exactly that data (including any padding, so that
+    /// its length is a multiple of 4).
+    pub read: fn(&mut dyn Read) -> io::Result<Vec<u8>>,
+}
+
+/// Extension types are equal if they have the same tag and read function;
+/// the same function may have more than one address, so this is best-effort.
+impl PartialEq for ExtensionType {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag && self.read as usize == other.read as usize
+    }
+}
+
+impl Eq for ExtensionType {}
+
+impl UnknownTypes {
+    /// The size of the data of an argument with the unsupported tag `tag`,
+    /// if it's known; `None` if the argument extends to the end of the message.
+    /// Fails if the tag can't be captured.
+    pub(crate) fn capture_size(self, tag: u8) -> ResultE<Option<usize>> {
+        match (self, tag) {
+            (UnknownTypes::Capture, b't') => Ok(Some(8)),
+            (UnknownTypes::Capture, b'I') => Ok(Some(0)),
+            (UnknownTypes::Capture, tag) if RawArg::valid_tag(tag) => Ok(None),
+            _ => Err(Error::UnsupportedType),
+        }
+    }
+}
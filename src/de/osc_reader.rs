@@ -1,62 +1,284 @@
 use std::convert::TryInto;
-use std::io::Read;
-use byteorder::{BigEndian, ReadBytesExt};
+use std::io::{BufRead, ErrorKind, Read};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
 
 use error::{Error, ResultE};
+use super::options::{DeserializerOptions, Endianness, StringDecoding};
 
-/// auto-implemented trait to parse OSC data from a Read object.
-pub trait OscReader: Read {
+/// Largest buffer reserved for a blob before its contents arrive.
+const BLOB_PREALLOC: usize = 64 * 1024;
+
+/// The position of the first NUL byte in `bytes`, i.e. the end of the string
+/// at its start. Scanning for terminators takes much of the time spent
+/// decoding small messages, so with the `memchr` feature this searches a
+/// word (or vector register) at a time.
+#[cfg(feature = "memchr")]
+pub(crate) fn find_nul(bytes: &[u8]) -> Option<usize> {
+    ::memchr::memchr(0, bytes)
+}
+
+#[cfg(not(feature = "memchr"))]
+pub(crate) fn find_nul(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().position(|&c| c == 0)
+}
+
+/// auto-implemented trait to parse OSC data from a BufRead object.
+///
+/// These are the primitives the deserializer itself is built from; they take
+/// care of byte order, string terminators and padding, and can be reused to
+/// decode formats this crate doesn't handle (e.g. proprietary bundle variants).
+/// Each one consumes exactly the bytes of the field it reads, padding included.
+///
+/// Buffering matters: without it, every field would be a separate (tiny) read
+/// of the underlying source. A `&[u8]` or `io::Cursor` is buffered already;
+/// wrap other readers in an `io::BufReader`.
+///
+/// ```
+/// use serde_osc::de::OscReader;
+///
+/// // "#tagged" followed by an i32 and a string, as sent by some proprietary peer.
+/// let mut data: &[u8] = b"#tagged\0\0\0\0\x07name\0\0\0\0";
+/// assert_eq!(data.parse_str().unwrap(), "#tagged");
+/// assert_eq!(data.parse_i32().unwrap(), 7);
+/// assert_eq!(data.parse_str().unwrap(), "name");
+/// assert!(data.is_empty());
+/// ```
+pub trait OscReader: BufRead {
     /// Read a null-terminated sequence of bytes & verify padding
     fn read_0term_bytes(&mut self) -> ResultE<Vec<u8>> {
+        // Usually the whole string (and its padding) is buffered already,
+        // and is copied out in one go.
+        let buffered = {
+            let available = self.fill_buf()?;
+            match find_nul(available) {
+                // The terminator and padding make up a multiple of 4 bytes.
+                Some(len) if available.len() >= (len + 4) & !0x3 => {
+                    let padded_len = (len + 4) & !0x3;
+                    if available[len..padded_len].iter().any(|&c| c != 0) {
+                        // We had data after the null terminator.
+                        return Err(Error::BadPadding);
+                    }
+                    Some((available[..len].to_vec(), padded_len))
+                },
+                _ => None,
+            }
+        };
+        if let Some((data, padded_len)) = buffered {
+            self.consume(padded_len);
+            return Ok(data);
+        }
+        // Otherwise, scan for the terminator, refilling the buffer as needed.
         let mut data = Vec::new();
-        // Because of the 4-byte required padding, we can process 4 characters at a time
-        let mut buf: [u8; 4] = [0, 0, 0, 0];
-        let mut num_zeros = 0;
-        while num_zeros == 0 {
-            self.read_exact(&mut buf)?;
-            // Copy the NON-NULL characters to the buffer.
-            num_zeros = buf.iter().filter(|c| **c == 0).count();
-            if buf[4-num_zeros..4].iter().any(|c| *c != 0) {
-                // We had data after the null terminator.
-                return Err(Error::BadPadding);
+        loop {
+            let (used, found) = {
+                let available = self.fill_buf()?;
+                if available.is_empty() {
+                    return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+                }
+                match find_nul(available) {
+                    Some(end) => {
+                        data.extend_from_slice(&available[..end]);
+                        (end, true)
+                    },
+                    None => {
+                        data.extend_from_slice(available);
+                        (available.len(), false)
+                    },
+                }
+            };
+            self.consume(used);
+            if found {
+                break;
             }
-            data.extend_from_slice(&buf[0..4-num_zeros]);
         }
+        self.read_padding(4 - data.len() % 4)?;
         Ok(data)
     }
+    /// Skip over a null-terminated sequence of bytes & verify padding,
+    /// without copying it out.
+    fn skip_0term_bytes(&mut self) -> ResultE<()> {
+        let mut len = 0;
+        loop {
+            let (used, found) = {
+                let available = self.fill_buf()?;
+                if available.is_empty() {
+                    return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+                }
+                match find_nul(available) {
+                    Some(end) => (end, true),
+                    None => (available.len(), false),
+                }
+            };
+            self.consume(used);
+            len += used;
+            if found {
+                break;
+            }
+        }
+        self.read_padding(4 - len % 4)
+    }
+    /// Read the `count` bytes which pad some data to a 4-byte boundary (e.g.
+    /// the terminator of a string, and what follows it), which must be NULL.
+    fn read_padding(&mut self, count: usize) -> ResultE<()> {
+        let mut padding = [0u8; 4];
+        let padding = &mut padding[..count];
+        self.read_exact_buffered(padding)?;
+        if padding.iter().any(|&c| c != 0) {
+            // We had data after the null terminator.
+            return Err(Error::BadPadding);
+        }
+        Ok(())
+    }
+    /// Consume `len` bytes without copying them out.
+    fn skip_exact(&mut self, len: usize) -> ResultE<()> {
+        let mut remaining = len;
+        while remaining > 0 {
+            let available = self.fill_buf()?.len();
+            if available == 0 {
+                return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+            }
+            let used = available.min(remaining);
+            self.consume(used);
+            remaining -= used;
+        }
+        Ok(())
+    }
     /// Read a null-terminated UTF-8 string & verify padding
     fn parse_str(&mut self) -> ResultE<String> {
         // Note: although OSC specifies ascii only, we may have data >= 128 in the vector.
         // We can safely assume a UTF-8 encoding, because no byte of any multibyte UTF-8
         // contains a zero; the only zero possible in a UTF-8 string is the ASCII zero.
         // See the UTF-8 table here: https://en.wikipedia.org/wiki/UTF-8#History
+        self.parse_str_with(DeserializerOptions::default())
+    }
+    /// Read a null-terminated string & verify padding, handling non-UTF-8
+    /// (or, in strict mode, non-ASCII) data as specified by `options`.
+    fn parse_str_with(&mut self, options: DeserializerOptions) -> ResultE<String> {
         let bytes = self.read_0term_bytes()?;
-        Ok(String::from_utf8(bytes)?)
+        options.check_string(&bytes)?;
+        match (String::from_utf8(bytes), options.strings) {
+            (Ok(s), _) => Ok(s),
+            (Err(e), StringDecoding::Strict) => Err(e.into()),
+            (Err(e), StringDecoding::Lossy) => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+            (Err(e), StringDecoding::Latin1) => Ok(e.as_bytes().iter().map(|&b| b as char).collect()),
+        }
     }
+    /// Read the address of a message (or "#bundle") & verify padding.
+    fn parse_address(&mut self, options: DeserializerOptions) -> ResultE<String> {
+        let address = self.parse_str_with(options)?;
+        options.check_address(address.as_bytes())?;
+        Ok(address)
+    }
+    /// Fill `buf`, copying straight out of the buffer if it holds enough data
+    /// (which is cheaper than `read_exact` through a chain of `Take`s).
+    fn read_exact_buffered(&mut self, buf: &mut [u8]) -> ResultE<()> {
+        let len = buf.len();
+        let buffered = match self.fill_buf()? {
+            available if available.len() >= len => {
+                buf.copy_from_slice(&available[..len]);
+                true
+            },
+            _ => false,
+        };
+        if buffered {
+            self.consume(len);
+        } else {
+            self.read_exact(buf)?;
+        }
+        Ok(())
+    }
+    /// Read a big-endian 'i' argument (or a length).
     fn parse_i32(&mut self) -> ResultE<i32> {
-       Ok( self.read_i32::<BigEndian>()?)
+        let mut data = [0u8; 4];
+        self.read_exact_buffered(&mut data)?;
+        Ok(BigEndian::read_i32(&data))
     }
+    /// Read a big-endian 'h' argument.
+    fn parse_i64(&mut self) -> ResultE<i64> {
+        let mut data = [0u8; 8];
+        self.read_exact_buffered(&mut data)?;
+        Ok(BigEndian::read_i64(&data))
+    }
+    /// Read a big-endian 'f' argument.
     fn parse_f32(&mut self) -> ResultE<f32> {
-        Ok(self.read_f32::<BigEndian>()?)
+        let mut data = [0u8; 4];
+        self.read_exact_buffered(&mut data)?;
+        Ok(BigEndian::read_f32(&data))
+    }
+    /// Read a big-endian 'd' argument.
+    fn parse_f64(&mut self) -> ResultE<f64> {
+        let mut data = [0u8; 8];
+        self.read_exact_buffered(&mut data)?;
+        Ok(BigEndian::read_f64(&data))
+    }
+    /// Read the 4 bytes of a numeric argument as a `u32`, in the given byte order.
+    fn parse_u32_in(&mut self, order: Endianness) -> ResultE<u32> {
+        let mut data = [0u8; 4];
+        self.read_exact_buffered(&mut data)?;
+        Ok(match order {
+            Endianness::Big => BigEndian::read_u32(&data),
+            Endianness::Little => LittleEndian::read_u32(&data),
+        })
+    }
+    /// Read the 8 bytes of a numeric argument as a `u64`, in the given byte order.
+    fn parse_u64_in(&mut self, order: Endianness) -> ResultE<u64> {
+        let mut data = [0u8; 8];
+        self.read_exact_buffered(&mut data)?;
+        Ok(match order {
+            Endianness::Big => BigEndian::read_u64(&data),
+            Endianness::Little => LittleEndian::read_u64(&data),
+        })
+    }
+    /// Read a 'c' character, sent as its 32-bit code point.
+    /// Fails with `Error::BadFormat` if that isn't a Unicode scalar value.
+    fn parse_char(&mut self) -> ResultE<char> {
+        self.parse_char_in(Endianness::Big)
+    }
+    /// Read a 'c' character whose code point is in the given byte order.
+    fn parse_char_in(&mut self, order: Endianness) -> ResultE<char> {
+        char::from_u32(self.parse_u32_in(order)?).ok_or(Error::BadFormat)
     }
     /// "Time tags are represented by a 64 bit fixed point number.
     ///  The first 32 bits specify the number of seconds since midnight on January 1, 1900,
     ///  and the last 32 bits specify fractional parts of a second to a precision of about 200 picoseconds.
     ///  This is the representation used by Internet NTP timestamps."
     fn parse_timetag(&mut self) -> ResultE<(u32, u32)> {
-       let sec = self.read_u32::<BigEndian>()?;
-       let frac = self.read_u32::<BigEndian>()?;
-       Ok((sec, frac))
+        let mut data = [0u8; 8];
+        self.read_exact_buffered(&mut data)?;
+        Ok((BigEndian::read_u32(&data[..4]), BigEndian::read_u32(&data[4..])))
+    }
+    /// Read 4 uninterpreted bytes, e.g. a MIDI message ('m') or RGBA color ('r').
+    fn parse_word(&mut self) -> ResultE<[u8; 4]> {
+        let mut data = [0u8; 4];
+        self.read_exact_buffered(&mut data)?;
+        Ok(data)
     }
-    /// Read an OSC blob & verify padding.
-    /// A blob consists of a length + u8 array.
-    fn parse_blob(&mut self) -> ResultE<Vec<u8>> {
+    /// Read the length of an OSC blob.
+    /// Fails with `Error::SizeLimitExceeded` if it's longer than `max_size` bytes.
+    fn parse_blob_size(&mut self, max_size: Option<usize>) -> ResultE<usize> {
         let size: usize = self.parse_i32()?.try_into()?;
+        if max_size.is_some_and(|max| size > max) {
+            return Err(Error::SizeLimitExceeded);
+        }
+        Ok(size)
+    }
+    /// Skip over an OSC blob & verify padding, without copying it out.
+    /// Fails with `Error::SizeLimitExceeded` if it's longer than `max_size` bytes.
+    fn skip_blob(&mut self, max_size: Option<usize>) -> ResultE<()> {
+        let size = self.parse_blob_size(max_size)?;
+        self.skip_exact(size)?;
+        self.read_padding((4 - size % 4) % 4)
+    }
+    /// Read the `size` bytes of data of an OSC blob & verify padding.
+    fn parse_blob_data(&mut self, size: usize) -> ResultE<Vec<u8>> {
         // Blobs are padded to a 4-byte boundary
         let padded_size = (size + 3) & !0x3;
-        // Read EXACTLY this much data:
-        let mut data = vec![0; padded_size];
-        self.read_exact(&mut data)?;
+        // Read EXACTLY this much data. Beyond the first 64 KiB, the buffer
+        // grows as data arrives, so a bogus length can't trigger a huge allocation up front.
+        let mut data = Vec::with_capacity(padded_size.min(BLOB_PREALLOC));
+        if self.take(padded_size as u64).read_to_end(&mut data)? != padded_size {
+            return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+        }
         // Ensure these extra bytes where NULL (sanity check)
         if data.drain(size..padded_size).all(|c| c == 0) {
             Ok(data)
@@ -66,6 +288,6 @@ pub trait OscReader: Read {
     }
 }
 
-/// Provide OSC reading functions to all types implementing Read
-impl<R: Read + ?Sized> OscReader for R {}
+/// Provide OSC reading functions to all types implementing BufRead
+impl<R: BufRead + ?Sized> OscReader for R {}
 
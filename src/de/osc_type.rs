@@ -1,42 +1,376 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
 use serde::de::{Deserializer, Visitor};
 use error::{Error, ResultE};
+use types::{COLOR_NAME, MIDI_NAME, RAW_ARG_NAME, SYMBOL_NAME, UNICODE_CHAR_NAME};
+use super::iter_visitor::IterVisitor;
+use super::options::{Coercion, DeserializerOptions, Narrowing};
+use super::prim_deserializer::PrimDeserializer;
 
-/// Struct to deserialize a single element from the OSC message sequence.
+/// A single element from the OSC message sequence.
 /// (e.g. just the address, or the first argument, etc).
+/// Blobs may be borrowed from the input, if it's a slice.
 #[derive(Debug)]
-pub enum OscType {
+pub enum OscType<'de> {
     I32(i32),
+    I64(i64),
     F32(f32),
+    F64(f64),
     String(String),
-    Blob(Vec<u8>),
+    Symbol(String),
+    Blob(Cow<'de, [u8]>),
+    Bool(bool),
+    Nil,
+    Char(char),
+    Midi([u8; 4]),
+    Color([u8; 4]),
+    Array(Vec<OscType<'de>>),
+    /// An unsupported type, captured as its tag and raw data.
+    Unknown(u8, Vec<u8>),
+}
+
+/// Struct to deserialize a single element from the OSC message sequence,
+/// converting between numeric types as allowed by `coercion` and `narrowing`.
+#[derive(Debug)]
+pub struct OscArg<'de> {
+    value: OscType<'de>,
+    coercion: Coercion,
+    narrowing: Narrowing,
 }
 
+impl<'de> OscType<'de> {
+    /// The typetag character of the argument.
+    pub fn tag(&self) -> u8 {
+        match *self {
+            OscType::I32(_) => b'i',
+            OscType::I64(_) => b'h',
+            OscType::F32(_) => b'f',
+            OscType::F64(_) => b'd',
+            OscType::String(_) => b's',
+            OscType::Symbol(_) => b'S',
+            OscType::Blob(_) => b'b',
+            OscType::Bool(true) => b'T',
+            OscType::Bool(false) => b'F',
+            OscType::Nil => b'N',
+            OscType::Char(_) => b'c',
+            OscType::Midi(_) => b'm',
+            OscType::Color(_) => b'r',
+            OscType::Array(_) => b'[',
+            OscType::Unknown(tag, _) => tag,
+        }
+    }
+    /// Convert a number into the integer (if `expected` is 'i' or 'h') or
+    /// floating-point (if 'f' or 'd') equivalent, provided it is exactly representable.
+    fn coerce(&self, expected: u8) -> Option<OscType<'de>> {
+        // Bounds of i64, which are exact as floats.
+        const MIN: f64 = -9_223_372_036_854_775_808.0;
+        const MAX: f64 = 9_223_372_036_854_775_808.0;
+        let to_int = |f: f64| if f.fract() == 0.0 && (MIN..MAX).contains(&f) {
+            Some(OscType::I64(f as i64))
+        } else {
+            None
+        };
+        // Casting back to i64 saturates, so values which round up to 2^63 are excluded explicitly.
+        let to_f32 = |h: i64| match h as f32 {
+            f if f64::from(f) < MAX && f as i64 == h => Some(OscType::F32(f)),
+            _ => None,
+        };
+        let to_f64 = |h: i64| match h as f64 {
+            d if d < MAX && d as i64 == h => Some(OscType::F64(d)),
+            _ => None,
+        };
+        match (expected, self) {
+            (b'i', &OscType::F32(f)) | (b'h', &OscType::F32(f)) => to_int(f.into()),
+            (b'i', &OscType::F64(d)) | (b'h', &OscType::F64(d)) => to_int(d),
+            (b'f', &OscType::I32(i)) => to_f32(i.into()),
+            (b'f', &OscType::I64(h)) => to_f32(h),
+            (b'd', &OscType::I32(i)) => Some(OscType::F64(i.into())),
+            (b'd', &OscType::I64(h)) => to_f64(h),
+            _ => None,
+        }
+    }
+    /// Convert a boolean into the number 1 or 0 (if `expected` is numeric), or
+    /// the number 1 or 0 into a boolean (if `expected` is 'T').
+    fn coerce_bool(&self, expected: u8) -> Option<OscType<'de>> {
+        let to_bool = |x: f64| match x {
+            0.0 => Some(OscType::Bool(false)),
+            1.0 => Some(OscType::Bool(true)),
+            _ => None,
+        };
+        match (expected, self) {
+            (b'T', &OscType::I32(i)) => to_bool(i.into()),
+            (b'T', &OscType::I64(h)) => to_bool(h as f64),
+            (b'T', &OscType::F32(f)) => to_bool(f.into()),
+            (b'T', &OscType::F64(d)) => to_bool(d),
+            (b'i', &OscType::Bool(b)) => Some(OscType::I32(b.into())),
+            (b'h', &OscType::Bool(b)) => Some(OscType::I64(b.into())),
+            (b'f', &OscType::Bool(b)) => Some(OscType::F32(u8::from(b).into())),
+            (b'd', &OscType::Bool(b)) => Some(OscType::F64(u8::from(b).into())),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> OscArg<'de> {
+    pub fn new(value: OscType<'de>, options: &DeserializerOptions) -> Self {
+        Self { value, coercion: options.coercion, narrowing: options.narrowing }
+    }
+    /// Deserialize a primitive, provided the argument has one of the `accepted`
+    /// tags (or can be coerced to the first of them, which is the tag reported
+    /// as expected otherwise).
+    fn deserialize_tagged<V>(self, accepted: &[u8], visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        let expected = accepted[0];
+        let found = self.value.tag();
+        if accepted.contains(&found) {
+            return self.deserialize_any(visitor);
+        }
+        let coerced = match self.coercion {
+            Coercion::Strict => None,
+            Coercion::Lossless => self.value.coerce(expected),
+            Coercion::Lenient => self.value.coerce(expected).or_else(|| self.value.coerce_bool(expected)),
+        };
+        match coerced {
+            Some(value) => OscArg { value, ..self }.deserialize_any(visitor),
+            None => Err(Error::TypeMismatch { expected: expected as char, found: found as char }),
+        }
+    }
+    /// Deserialize the elements of an array with the same conversion policies.
+    fn elements(
+        elems: Vec<OscType<'de>>,
+        coercion: Coercion,
+        narrowing: Narrowing
+    ) -> IterVisitor<impl Iterator<Item=OscArg<'de>>> {
+        IterVisitor(elems.into_iter().map(move |value| OscArg { value, coercion, narrowing }))
+    }
+}
 
-impl<'de> Deserializer<'de> for OscType {
+/// The typetags accepted interchangeably by each kind of Rust type. Senders
+/// often pick a wider type than needed (e.g. 'h' for a small count) or a
+/// symbol for a string, so a type which can hold either kind of argument
+/// accepts both. The first tag is the one reported as expected on a mismatch.
+mod tags {
+    /// Integers narrower than 64 bits; 'h' arguments are accepted if they fit.
+    pub const INT: &[u8] = b"ih";
+    /// 64-bit integers.
+    pub const LONG: &[u8] = b"hi";
+    /// `f32`; 'd' arguments are only accepted if narrowed (see `Narrowing`).
+    pub const FLOAT: &[u8] = b"f";
+    /// `f64`.
+    pub const DOUBLE: &[u8] = b"df";
+    /// Strings; SuperCollider, for one, sends symbols where others send strings.
+    pub const STRING: &[u8] = b"sS";
+    /// `char`: a 'c' argument, or a string of one character.
+    pub const CHAR: &[u8] = b"csS";
+    pub const BOOL: &[u8] = b"TF";
+    pub const BLOB: &[u8] = b"b";
+    pub const NIL: &[u8] = b"N";
+    /// `MidiMessage`; other 4-byte arguments (e.g. colors or blobs) are rejected.
+    pub const MIDI: &[u8] = b"m";
+    /// `Color`; likewise, other 4-byte arguments are rejected.
+    pub const COLOR: &[u8] = b"r";
+    /// `Symbol`, which accepts strings just as `String` accepts symbols.
+    pub const SYMBOL: &[u8] = b"Ss";
+    /// `UnicodeChar`.
+    pub const UNICODE_CHAR: &[u8] = b"c";
+}
+
+/// Implement primitive type hints by checking the tag of the argument
+/// against one of the sets in `tags`.
+macro_rules! deserialize_tagged {
+    ($($func:ident => $accepted:expr;)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> ResultE<V::Value>
+            where
+                V: Visitor<'de>
+            {
+                self.deserialize_tagged($accepted, visitor)
+            }
+        )*
+    };
+}
+
+/// Implement integer type hints: integer arguments which don't fit the type
+/// are narrowed according to the `Narrowing` policy, while other arguments
+/// are checked as by `deserialize_tagged!`.
+macro_rules! deserialize_int {
+    ($($func:ident: $t:ty => $visit:ident $accepted:expr;)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> ResultE<V::Value>
+            where
+                V: Visitor<'de>
+            {
+                let int = match self.value {
+                    OscType::I32(i) => i64::from(i),
+                    OscType::I64(h) => h,
+                    _ => return self.deserialize_tagged($accepted, visitor),
+                };
+                match (self.narrowing, <$t>::try_from(int)) {
+                    (Narrowing::Saturate, Err(_)) =>
+                        visitor.$visit(i128::from(int).clamp(<$t>::MIN as i128, <$t>::MAX as i128) as $t),
+                    (Narrowing::Cast, Err(_)) => visitor.$visit(int as $t),
+                    _ => self.deserialize_tagged($accepted, visitor),
+                }
+            }
+        )*
+    };
+}
+
+
+impl<'de> Deserializer<'de> for OscArg<'de> {
     type Error = Error;
     // deserializes a single item from the message, consuming self.
     fn deserialize_any<V>(self, visitor: V) -> ResultE<V::Value>
     where
         V: Visitor<'de>
     {
-        match self {
+        match self.value {
             OscType::I32(i) => visitor.visit_i32(i),
+            OscType::I64(h) => visitor.visit_i64(h),
             OscType::F32(f) => visitor.visit_f32(f),
-            OscType::String(s) => visitor.visit_string(s),
-            // TODO: If the user is attempting to deserialize a Vec<u8>, this
-            //   will error! We should make use of the deserialize_seq function
-            //   in this case.
-            OscType::Blob(b) => visitor.visit_byte_buf(b),
+            OscType::F64(d) => visitor.visit_f64(d),
+            OscType::String(s) | OscType::Symbol(s) => visitor.visit_string(s),
+            // The data is handed over as-is, e.g. to a `serde_bytes::ByteBuf`,
+            // or to a `&'de [u8]` if it was borrowed from the input.
+            OscType::Blob(Cow::Borrowed(b)) => visitor.visit_borrowed_bytes(b),
+            OscType::Blob(Cow::Owned(b)) => visitor.visit_byte_buf(b),
+            OscType::Bool(b) => visitor.visit_bool(b),
+            OscType::Nil => visitor.visit_unit(),
+            OscType::Char(c) => visitor.visit_char(c),
+            // Fixed-size byte arrays are exposed as a sequence, so they can
+            // be deserialized into either `[u8; 4]` or a helper type.
+            OscType::Midi(bytes) | OscType::Color(bytes) =>
+                visitor.visit_seq(IterVisitor(bytes.iter().cloned().map(PrimDeserializer))),
+            OscType::Array(elems) => visitor.visit_seq(Self::elements(elems, self.coercion, self.narrowing)),
+            OscType::Unknown(_, data) => visitor.visit_byte_buf(data),
+        }
+    }
+    // Nil is the only argument that maps to `None`; all else is `Some`.
+    fn deserialize_option<V>(self, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        match self.value {
+            OscType::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    // A blob can also be deserialized as a sequence of bytes, e.g. into a
+    // plain `Vec<u8>` (though `serde_bytes` avoids visiting each byte).
+    fn deserialize_seq<V>(self, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        match self.value {
+            OscType::Blob(b) => visitor.visit_seq(IterVisitor(b.iter().cloned().map(PrimDeserializer))),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    // An array can hold the alternating keys and values of a map.
+    fn deserialize_map<V>(self, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        match self.value {
+            OscType::Array(elems) => visitor.visit_map(Self::elements(elems, self.coercion, self.narrowing)),
+            _ => self.deserialize_any(visitor),
         }
     }
 
-    // OSC messages are strongly typed, so we don't make use of any type hints.
+    // The raw data of an unknown argument can also be deserialized as bytes.
+    fn deserialize_bytes<V>(self, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        match self.value {
+            OscType::Unknown(..) => self.deserialize_any(visitor),
+            _ => self.deserialize_tagged(tags::BLOB, visitor),
+        }
+    }
+    fn deserialize_byte_buf<V>(self, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    // Integers may be either 32 or 64 bits wide; serde checks that the value
+    // fits, unless it's narrowed.
+    deserialize_int! {
+        deserialize_i8: i8 => visit_i8 tags::INT;
+        deserialize_i16: i16 => visit_i16 tags::INT;
+        deserialize_i32: i32 => visit_i32 tags::INT;
+        deserialize_i64: i64 => visit_i64 tags::LONG;
+        deserialize_u8: u8 => visit_u8 tags::INT;
+        deserialize_u16: u16 => visit_u16 tags::INT;
+        deserialize_u32: u32 => visit_u32 tags::INT;
+        deserialize_u64: u64 => visit_u64 tags::LONG;
+    }
+
+    // A 'd' argument only fits in an `f32` if it's narrowed.
+    fn deserialize_f32<V>(self, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        match (self.narrowing, &self.value) {
+            (Narrowing::Saturate, &OscType::F64(d)) if d.is_finite() =>
+                visitor.visit_f32(d.clamp(f32::MIN.into(), f32::MAX.into()) as f32),
+            (Narrowing::Saturate, &OscType::F64(d)) | (Narrowing::Cast, &OscType::F64(d)) =>
+                visitor.visit_f32(d as f32),
+            _ => self.deserialize_tagged(tags::FLOAT, visitor),
+        }
+    }
+
+    // An 'f' argument fits in an `f64`.
+    deserialize_tagged! {
+        deserialize_bool => tags::BOOL;
+        deserialize_f64 => tags::DOUBLE;
+        deserialize_char => tags::CHAR;
+        deserialize_str => tags::STRING;
+        deserialize_string => tags::STRING;
+        deserialize_unit => tags::NIL;
+    }
+
+    // The argument is already decoded, so there's nothing left to skip.
+    fn deserialize_ignored_any<V>(self, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        visitor.visit_unit()
+    }
+
+    // Helper types such as `MidiMessage` are checked against their typetag,
+    // except for `RawArg`, which takes any argument; other newtype structs
+    // (e.g. `struct Freq(f32)`) deserialize what they wrap.
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> ResultE<V::Value>
+    where
+        V: Visitor<'de>
+    {
+        match name {
+            MIDI_NAME => self.deserialize_tagged(tags::MIDI, visitor),
+            COLOR_NAME => self.deserialize_tagged(tags::COLOR, visitor),
+            SYMBOL_NAME => self.deserialize_tagged(tags::SYMBOL, visitor),
+            UNICODE_CHAR_NAME => self.deserialize_tagged(tags::UNICODE_CHAR, visitor),
+            RAW_ARG_NAME => self.deserialize_any(visitor),
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    // Compound types check the argument themselves.
     // More info: https://github.com/serde-rs/serde/blob/b7d6c5d9f7b3085a4d40a446eeb95976d2337e07/serde/src/macros.rs#L106
     forward_to_deserialize_any! {
-        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
-        seq bytes byte_buf map unit_struct newtype_struct
-        tuple_struct struct identifier tuple enum ignored_any
+        unit_struct
+        tuple_struct struct identifier enum
     }
 }
-
-
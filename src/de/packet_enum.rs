@@ -0,0 +1,99 @@
+use serde::de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, SeqAccess, VariantAccess, Visitor};
+
+use error::{Error, ResultE};
+
+/// Name of the enum variant which holds a message, in an enum which can hold any packet.
+pub(crate) const MESSAGE_VARIANT: &str = "Message";
+/// Name of the enum variant which holds a bundle, in an enum which can hold any packet.
+pub(crate) const BUNDLE_VARIANT: &str = "Bundle";
+
+/// Whether an enum with these variants holds a message or bundle (selected
+/// by the kind of packet), rather than having a variant for each address.
+/// Neither name can be an address, which always starts with '/'.
+pub(crate) fn is_packet_enum(variants: &[&str]) -> bool {
+    variants.contains(&MESSAGE_VARIANT) && variants.contains(&BUNDLE_VARIANT)
+}
+
+/// Presents a message or bundle as the `Message` or `Bundle` variant of an
+/// enum, whose contents are the `(address, args)` or `(timetag, elements)` sequence.
+#[derive(Debug)]
+pub struct PacketEnumAccess<S> {
+    variant: &'static str,
+    contents: S,
+}
+
+impl<S> PacketEnumAccess<S> {
+    pub fn message(contents: S) -> Self {
+        Self { variant: MESSAGE_VARIANT, contents }
+    }
+    pub fn bundle(contents: S) -> Self {
+        Self { variant: BUNDLE_VARIANT, contents }
+    }
+}
+
+impl<'de, S> EnumAccess<'de> for PacketEnumAccess<S>
+    where S: SeqAccess<'de, Error=Error>
+{
+    type Error = Error;
+    type Variant = Self;
+    fn variant_seed<V>(self, seed: V) -> ResultE<(V::Value, Self)>
+        where V: DeserializeSeed<'de>
+    {
+        let variant = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, S> VariantAccess<'de> for PacketEnumAccess<S>
+    where S: SeqAccess<'de, Error=Error>
+{
+    type Error = Error;
+    // The packet always has contents.
+    fn unit_variant(self) -> ResultE<()> {
+        Err(Error::BadFormat)
+    }
+    // e.g. `Message((String, (f32,)))`, or `Bundle(value::Bundle)`.
+    fn newtype_variant_seed<T>(self, seed: T) -> ResultE<T::Value>
+        where T: DeserializeSeed<'de>
+    {
+        seed.deserialize(ContentsDeserializer(self.contents))
+    }
+    // e.g. `Message(String, (f32,))`.
+    fn tuple_variant<V>(mut self, _len: usize, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_seq(&mut self.contents)
+    }
+    // e.g. `Message { address: String, args: (f32,) }`.
+    fn struct_variant<V>(mut self, _fields: &'static [&'static str], visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_seq(&mut self.contents)
+    }
+}
+
+/// Presents the packet contents as a sequence, whatever type is asked for.
+struct ContentsDeserializer<S>(S);
+
+impl<'de, S> de::Deserializer<'de> for ContentsDeserializer<S>
+    where S: SeqAccess<'de, Error=Error>
+{
+    type Error = Error;
+    fn deserialize_any<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_seq(self.0)
+    }
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // More info: https://github.com/serde-rs/serde/blob/b7d6c5d9f7b3085a4d40a446eeb95976d2337e07/serde/src/macros.rs#L106
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
+        seq bytes byte_buf map unit_struct
+        tuple_struct struct identifier tuple enum ignored_any
+    }
+}
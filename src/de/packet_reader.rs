@@ -0,0 +1,108 @@
+use std::convert::TryInto;
+use std::io::{BufRead, Cursor, Read, Take};
+use byteorder::{BigEndian, ReadBytesExt};
+
+use error::{Error, ResultE};
+use framing::Framing;
+use types::{Color, MidiMessage, RawArg};
+use value::{Bundle, Message, Packet, Value};
+use super::arg_visitor::ArgVisitor;
+use super::options::DeserializerOptions;
+use super::osc_reader::OscReader;
+use super::osc_type::OscType;
+use super::read_source::{self, IoRead};
+
+/// Read an entire packet into its dynamic representation.
+pub fn read_packet<R: Read>(reader: &mut R, framing: Framing, options: DeserializerOptions) -> ResultE<Packet> {
+    match framing {
+        Framing::LengthPrefixed => {
+            let length: usize = reader.read_i32::<BigEndian>()?.try_into()?;
+            options.check_size(length)?;
+            let mut window = read_source::window(reader, length as u64);
+            read_located(&mut IoRead(&mut window as &mut dyn BufRead).take(length as u64), options)
+        },
+        Framing::Unframed => {
+            // Read at most one byte past the limit, to detect oversized packets.
+            let max_read = options.max_packet_size.map_or(u64::MAX, |max| (max as u64).saturating_add(1));
+            let mut data = Vec::new();
+            reader.take(max_read).read_to_end(&mut data)?;
+            options.check_size(data.len())?;
+            let length = data.len() as u64;
+            read_located(&mut IoRead(&mut Cursor::new(data) as &mut dyn BufRead).take(length), options)
+        },
+    }
+}
+
+/// Read the outermost packet, annotating any error with its offset.
+fn read_located(reader: &mut Take<IoRead<&mut dyn BufRead>>, options: DeserializerOptions) -> ResultE<Packet> {
+    let length = reader.limit();
+    read_contents(reader, options, 0).map_err(|e| {
+        // Nested elements stop reading at the error, so this is the offset within the outermost packet.
+        let offset = length - reader.limit();
+        e.with_context(|ctx| ctx.offset = offset)
+    })
+}
+
+/// Read the packet contents, once its extent is known.
+/// The contents must fill the extent exactly.
+/// `depth` is the number of bundles enclosing the packet.
+fn read_contents(reader: &mut Take<IoRead<&mut dyn BufRead>>, options: DeserializerOptions, depth: usize) -> ResultE<Packet> {
+    let length = reader.limit();
+    let address = reader.parse_address(options)?;
+    let packet = if address == "#bundle" {
+        if options.too_deep(depth) {
+            return Err(Error::DepthLimitExceeded);
+        }
+        let mut bundle = Bundle::new(reader.parse_timetag()?);
+        while reader.limit() != 0 {
+            let length: usize = reader.parse_i32()?.try_into()?;
+            options.check_size(length)?;
+            let mut elem = IoRead(&mut *reader as &mut dyn BufRead).take(length as u64);
+            bundle.elements.push(read_contents(&mut elem, options, depth + 1)?);
+        }
+        Packet::Bundle(bundle)
+    } else {
+        let args = read_args(reader, options)
+            .map_err(|e| e.with_context(|ctx| { ctx.address.get_or_insert_with(|| address.clone()); }))?;
+        Packet::Message(Message{ address, args })
+    };
+    match reader.limit() {
+        0 => Ok(packet),
+        // Trailing data after the last argument or element.
+        remaining => Err(Error::LengthMismatch {
+            declared: length as usize,
+            consumed: (length - remaining) as usize,
+        }),
+    }
+}
+
+/// Read the typetag and all the arguments of a message.
+fn read_args(reader: &mut Take<IoRead<&mut dyn BufRead>>, options: DeserializerOptions) -> ResultE<Vec<Value>> {
+    let mut visitor = ArgVisitor::new(reader, options)?;
+    let mut args = Vec::new();
+    while let Some(arg) = visitor.parse_next()? {
+        args.push(arg.into());
+    }
+    Ok(args)
+}
+
+impl<'de> From<OscType<'de>> for Value {
+    fn from(arg: OscType<'de>) -> Self {
+        match arg {
+            OscType::I32(i) => Value::Int(i),
+            OscType::I64(h) => Value::Long(h),
+            OscType::F32(f) => Value::Float(f),
+            OscType::F64(d) => Value::Double(d),
+            OscType::String(s) => Value::String(s),
+            OscType::Symbol(s) => Value::Symbol(s),
+            OscType::Blob(b) => Value::Blob(b.into_owned()),
+            OscType::Bool(b) => Value::Bool(b),
+            OscType::Nil => Value::Nil,
+            OscType::Char(c) => Value::Char(c),
+            OscType::Midi(bytes) => Value::Midi(MidiMessage::from_bytes(bytes)),
+            OscType::Color(bytes) => Value::Color(Color::from_bytes(bytes)),
+            OscType::Array(elems) => Value::Array(elems.into_iter().map(Value::from).collect()),
+            OscType::Unknown(tag, data) => Value::Unknown(RawArg::new(tag as char, data)),
+        }
+    }
+}
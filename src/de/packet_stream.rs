@@ -0,0 +1,111 @@
+use std::io::{Cursor, ErrorKind, Read};
+use std::marker::PhantomData;
+use serde::de::DeserializeOwned;
+
+use error::{Error, ResultE};
+use framing::Framing;
+use super::options::DeserializerOptions;
+use super::pkt_deserializer::PktDeserializer;
+
+/// Iterator which deserializes successive length-prefixed packets from a
+/// long-lived data source, such as a `TcpStream`.
+///
+/// Iteration ends (yields `None`) when the source reaches EOF at a packet
+/// boundary. EOF in the middle of a packet is reported as an error.
+/// After any error the stream position is unreliable, so iteration stops.
+/// For non-blocking sources, whose reads may end partway through a packet,
+/// use a [`Decoder`] instead.
+///
+/// # Example
+///
+/// ```
+/// # extern crate serde_osc;
+/// # use std::io::Cursor;
+/// use serde_osc::de::PacketStream;
+///
+/// # fn main() {
+/// let data = b"\0\0\0\x08/m1\0,\0\0\0\0\0\0\x08/m2\0,\0\0\0";
+/// let addresses: Vec<String> = PacketStream::<_, (String, ())>::new(Cursor::new(&data[..]))
+///     .map(|pkt| pkt.unwrap().0)
+///     .collect();
+/// assert_eq!(addresses, vec!["/m1", "/m2"]);
+/// # }
+/// ```
+///
+/// [`Decoder`]: struct.Decoder.html
+#[derive(Debug)]
+pub struct PacketStream<R: Read, T> {
+    reader: R,
+    options: DeserializerOptions,
+    done: bool,
+    _packet: PhantomData<T>,
+}
+
+impl<R: Read, T> PacketStream<R, T>
+    where T: DeserializeOwned
+{
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, DeserializerOptions::default())
+    }
+    /// Decode each packet according to `options`.
+    pub fn with_options(reader: R, options: DeserializerOptions) -> Self {
+        Self {
+            reader,
+            options,
+            done: false,
+            _packet: PhantomData,
+        }
+    }
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read the next packet, or return `None` on a clean EOF.
+    fn next_packet(&mut self) -> ResultE<Option<T>> {
+        // Read the length prefix ourselves, to distinguish EOF at a packet
+        // boundary from EOF within a packet.
+        let mut length = [0u8; 4];
+        let mut n_read = 0;
+        while n_read < length.len() {
+            match self.reader.read(&mut length[n_read..]) {
+                Ok(0) if n_read == 0 => return Ok(None),
+                Ok(0) => return Err(Error::Io(ErrorKind::UnexpectedEof.into())),
+                Ok(n) => n_read += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {},
+                Err(e) => return Err(e.into()),
+            }
+        }
+        // Hand the prefix back to the deserializer, followed by the rest of the packet.
+        let mut packet = Cursor::new(length).chain(self.reader.by_ref());
+        let mut de = PktDeserializer::with_options(&mut packet, Framing::LengthPrefixed, self.options);
+        T::deserialize(&mut de).map(Some)
+    }
+}
+
+impl<R: Read, T> Iterator for PacketStream<R, T>
+    where T: DeserializeOwned
+{
+    type Item = ResultE<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_packet() {
+            Ok(Some(packet)) => Some(Ok(packet)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            },
+        }
+    }
+}
@@ -1,12 +1,20 @@
-use std::io::Read;
+use std::convert::TryInto;
+use std::io::{self, Cursor, ErrorKind, Read, Take};
 use byteorder::{BigEndian, ReadBytesExt};
 use serde::de;
 use serde::de::Visitor;
 
+use address::AddressPattern;
 use error::{Error, ResultE};
+use framing::Framing;
+use observe::{Observer, PacketSummary};
+use super::message_header::MessageHeader;
+use super::options::DeserializerOptions;
 use super::osc_reader::OscReader;
-use super::msg_visitor::MsgVisitor;
+use super::msg_visitor::{MsgEnumAccess, MsgVisitor};
 use super::bundle_visitor::BundleVisitor;
+use super::packet_enum::{is_packet_enum, PacketEnumAccess};
+use super::read_source::{self, IoRead, ReadSource, SliceRead};
 
 /// Deserializes an entire OSC packet or bundle element (they are syntactically identical).
 /// An OSC packet consists of an `i32` indicating its length, followed by
@@ -21,17 +29,372 @@ use super::bundle_visitor::BundleVisitor;
 /// See [`serde_osc::ser::Serializer`] for more info regarding valid
 /// deserialization targets.
 ///
+/// When the packet is not preceded by its length (e.g. it was received as a
+/// UDP datagram), construct the deserializer with [`new_unframed`]; the entire
+/// remaining contents of the reader are then treated as the packet.
+///
+/// Self-describing types, such as `serde_json::Value`, can hold any packet,
+/// and `serde_transcode` can convert packets straight to other formats:
+/// a message is presented as an `(address, args)` sequence, where the
+/// arguments form a nested sequence, and a bundle as a `(timetag, elements)`
+/// sequence, where each element is a message or bundle in turn.
+///
+/// Enums are deserialized from messages, the address naming the variant;
+/// however, an enum with `Message` and `Bundle` variants can hold either kind
+/// of packet, so that it needn't be known up front:
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # extern crate serde_osc;
+/// # fn main() {
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// enum Packet {
+///     Message(String, (f32,)),
+///     Bundle((u32, u32), Vec<(String, (f32,))>),
+/// }
+///
+/// let bundle = serde_osc::to_vec(&((0u32, 1u32), (("/gain", (0.5f32,)),))).unwrap();
+/// assert_eq!(serde_osc::from_slice::<Packet>(&bundle).unwrap(),
+///     Packet::Bundle((0, 1), vec![("/gain".to_owned(), (0.5,))]));
+/// # }
+/// ```
+///
+/// Use [`with_options`] to adjust how strictly packets are decoded, e.g. to bound their size,
+/// and [`matching`] to only accept messages sent to particular addresses.
+/// [`with_observer`] reports the address, typetag and size of each packet
+/// once it's decoded, e.g. for metrics.
+///
 /// [`serde_osc::ser::Serializer`]: ../ser/struct.Serializer.html
+/// [`new_unframed`]: #method.new_unframed
+/// [`with_options`]: #method.with_options
+/// [`matching`]: #method.matching
+/// [`with_observer`]: #method.with_observer
 #[derive(Debug)]
 pub struct PktDeserializer<'a, R: Read + 'a> {
     reader: &'a mut R,
+    framing: Framing,
+    options: DeserializerOptions,
+    /// Number of bundles enclosing this packet.
+    depth: usize,
+    /// The pattern which the message address must match, if any.
+    pattern: Option<&'a AddressPattern>,
+    /// Contents of the packet, if they were read by `peek_header`.
+    peeked: Option<Vec<u8>>,
+    /// Called with each packet, once it's decoded.
+    observer: Option<Observer>,
 }
 
 impl<'a, R> PktDeserializer<'a, R>
     where R: Read + 'a
 {
+    /// Deserialize a length-prefixed packet.
     pub fn new(reader: &'a mut R) -> Self {
-        Self{ reader }
+        Self::with_framing(reader, Framing::LengthPrefixed)
+    }
+    /// Deserialize a packet which spans the entire reader and has no length prefix.
+    pub fn new_unframed(reader: &'a mut R) -> Self {
+        Self::with_framing(reader, Framing::Unframed)
+    }
+    /// Deserialize a packet, using the provided framing.
+    pub fn with_framing(reader: &'a mut R, framing: Framing) -> Self {
+        Self::with_options(reader, framing, DeserializerOptions::default())
+    }
+    /// Deserialize a packet, using the provided framing and decoding options.
+    pub fn with_options(reader: &'a mut R, framing: Framing, options: DeserializerOptions) -> Self {
+        Self{ reader, framing, options, depth: 0, pattern: None, peeked: None, observer: None }
+    }
+    /// Only accept a message whose address is matched by `pattern`.
+    ///
+    /// The address is checked before any of the arguments are decoded; other
+    /// messages (and bundles) fail with `Error::AddressMismatch`, and are skipped
+    /// just like packets which fail to decode.
+    pub fn matching(self, pattern: &'a AddressPattern) -> Self {
+        Self{ pattern: Some(pattern), ..self }
+    }
+    /// Call `observer` with the address, typetag and size of each packet
+    /// once it's decoded (see `serde_osc::observe`).
+    ///
+    /// Each packet is then read into memory before it's decoded, as by
+    /// `peek_header`, so that its header is at hand.
+    pub fn with_observer(self, observer: Observer) -> Self {
+        Self{ observer: Some(observer), ..self }
+    }
+}
+
+/// Deserializes a packet directly from a `ReadSource`, so that blobs may be
+/// borrowed from the input.
+#[derive(Debug)]
+pub(crate) struct SourceDeserializer<'a, R: Read + 'a> {
+    reader: &'a mut R,
+    /// Extent of the packet, if it's known up front; otherwise the packet is length-prefixed.
+    length: Option<usize>,
+    options: DeserializerOptions,
+    /// Number of bundles enclosing this packet.
+    depth: usize,
+    /// The pattern which the message address must match, if any.
+    pattern: Option<&'a AddressPattern>,
+}
+
+impl<'a, 'de> SourceDeserializer<'a, SliceRead<'de>> {
+    /// Deserialize a packet from a slice, using the provided framing and decoding options.
+    /// An unframed packet spans the rest of the slice.
+    pub fn from_slice(
+        reader: &'a mut SliceRead<'de>,
+        framing: Framing,
+        options: DeserializerOptions,
+        pattern: Option<&'a AddressPattern>
+    ) -> Self {
+        let length = match framing {
+            Framing::LengthPrefixed => None,
+            Framing::Unframed => Some(reader.remaining().len()),
+        };
+        Self{ reader, length, options, depth: 0, pattern }
+    }
+}
+
+/// Deserializes a (length-prefixed) element of a bundle.
+///
+/// The element is read through the same `Take` as the enclosing bundle, with
+/// its limit narrowed to the element for the time being. Wrapping it in
+/// another `Take` instead would give each level of nesting a distinct reader
+/// type, which visitors that accept any packet (e.g. `serde_json::Value`, or
+/// `serde_transcode`) can't be instantiated for.
+#[derive(Debug)]
+pub(crate) struct ElementDeserializer<'a, R: Read + 'a> {
+    reader: &'a mut Take<R>,
+    options: DeserializerOptions,
+    /// Number of bundles enclosing this element.
+    depth: usize,
+}
+
+impl<'a, R: Read + 'a> ElementDeserializer<'a, R> {
+    pub fn new(reader: &'a mut Take<R>, options: DeserializerOptions, depth: usize) -> Self {
+        Self{ reader, options, depth }
+    }
+}
+
+/// What shape the packet contents should be presented to the visitor as.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Kind {
+    /// A `(address, args)` sequence for messages, or `(timetag, elements)` for bundles.
+    Seq,
+    /// An enum whose variant is named by the message address.
+    Enum,
+    /// An enum with `Message` and `Bundle` variants, holding the `Seq` of either.
+    Packet,
+}
+
+impl Kind {
+    /// How to present a packet as an enum with the given variants.
+    fn of_enum(variants: &[&str]) -> Self {
+        if is_packet_enum(variants) { Kind::Packet } else { Kind::Enum }
+    }
+}
+
+/// Read the length prefix of a packet.
+fn read_length<R: Read>(reader: &mut R, options: DeserializerOptions) -> ResultE<usize> {
+    let length: usize = reader.read_i32::<BigEndian>()?.try_into()?;
+    check_size(length, options)
+}
+
+/// Check the extent of a packet against the size limit.
+fn check_size(length: usize, options: DeserializerOptions) -> ResultE<usize> {
+    options.check_size(length)?;
+    Ok(length)
+}
+
+/// Deserialize the packet contents, once its extent is known.
+fn deserialize_contents<'de, V, R>(
+    reader: &mut Take<R>,
+    kind: Kind,
+    options: DeserializerOptions,
+    depth: usize,
+    pattern: Option<&AddressPattern>,
+    visitor: V
+) -> ResultE<V::Value>
+    where V: Visitor<'de>, R: ReadSource<'de>
+{
+    let length = reader.limit();
+    let mut msg_address = None;
+    // See if packet is a bundle or a message.
+    let result = reader.parse_address(options).and_then(|address| {
+        if pattern.is_some_and(|pattern| address == "#bundle" || !pattern.matches(&address)) {
+            return Err(Error::AddressMismatch(address));
+        }
+        match (address.as_str(), kind) {
+            ("#bundle", Kind::Seq) | ("#bundle", Kind::Packet) if options.too_deep(depth) =>
+                return Err(Error::DepthLimitExceeded),
+            ("#bundle", Kind::Seq) => {
+                let mut bundle = BundleVisitor::new(reader, options, depth + 1);
+                let value = visitor.visit_seq(&mut bundle)?;
+                bundle.finish()?;
+                return Ok(value);
+            },
+            ("#bundle", Kind::Packet) => {
+                let mut bundle = BundleVisitor::new(reader, options, depth + 1);
+                let value = visitor.visit_enum(PacketEnumAccess::bundle(&mut bundle))?;
+                bundle.finish()?;
+                return Ok(value);
+            },
+            // A bundle has no address with which to select a variant.
+            ("#bundle", Kind::Enum) => return Err(Error::BadFormat),
+            _ => {},
+        }
+        msg_address = Some(address.clone());
+        match kind {
+            Kind::Seq => {
+                let mut msg = MsgVisitor::new(reader, address, options);
+                let value = visitor.visit_seq(&mut msg)?;
+                msg.finish()?;
+                Ok(value)
+            },
+            Kind::Enum => visitor.visit_enum(MsgEnumAccess::new(reader, address, options)),
+            Kind::Packet => {
+                let mut msg = MsgVisitor::new(reader, address, options);
+                let value = visitor.visit_enum(PacketEnumAccess::message(&mut msg))?;
+                msg.finish()?;
+                Ok(value)
+            },
+        }
+    }).and_then(|value| match reader.limit() {
+        // In strict mode, the contents must extend to the declared length.
+        remaining if remaining > 0 && options.strict => Err(Error::LengthMismatch {
+            declared: length as usize,
+            consumed: (length - remaining) as usize,
+        }),
+        _ => Ok(value),
+    });
+    let result = result.map_err(|e| match e {
+        // Not a decoding failure, so there's nothing to locate.
+        Error::AddressMismatch(_) => e,
+        // Bundle elements aren't drained on error (see below), so this is the
+        // offset within the outermost packet; it overrides that of any element.
+        _ => {
+            let offset = length - reader.limit();
+            e.with_context(|ctx| {
+                ctx.offset = offset;
+                if ctx.address.is_none() {
+                    ctx.address = msg_address;
+                }
+            })
+        },
+    });
+    if depth > 0 && result.is_err() {
+        // The enclosing bundle fails too, and will skip the remainder.
+        return result;
+    }
+    // If the consumer only handled a portion of the sequence, we still
+    // need to advance the reader so as to be ready for any next message.
+    // Discard them rather than buffering, as the length may be bogus.
+    let size = reader.limit();
+    let drained = io::copy(reader, &mut io::sink());
+    let value = result?;
+    if drained? != size {
+        return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(value)
+}
+
+impl<'a, R> PktDeserializer<'a, R>
+    where R: Read + 'a
+{
+    /// Deserialize the packet, and report it to the observer (if any).
+    fn deserialize_packet<'de, V>(&mut self, kind: Kind, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let observer = match self.observer {
+            Some(observer) => observer,
+            None => return self.deserialize_next(kind, visitor),
+        };
+        let header = match self.peek_header() {
+            Ok(header) => Some(header),
+            // A malformed packet is still held, and is reported (and skipped) when decoded.
+            Err(_) if self.peeked.is_some() => None,
+            Err(e) => return Err(e),
+        };
+        let size = self.peeked.as_ref().map_or(0, Vec::len);
+        let value = self.deserialize_next(kind, visitor)?;
+        if let Some(header) = header {
+            observer(&PacketSummary { address: &header.address, typetag: &header.typetag, size });
+        }
+        Ok(value)
+    }
+    /// Determine the extent of the packet and then deserialize its contents.
+    fn deserialize_next<'de, V>(&mut self, kind: Kind, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        if let Some(data) = self.peeked.take() {
+            let length = data.len() as u64;
+            return deserialize_contents(&mut IoRead(Cursor::new(data)).take(length), kind, self.options, self.depth, self.pattern, visitor);
+        }
+        match self.framing {
+            Framing::LengthPrefixed => {
+                let length = read_length(&mut self.reader, self.options)?;
+                let window = read_source::window(&mut *self.reader, length as u64);
+                deserialize_contents(&mut IoRead(window).take(length as u64), kind, self.options, self.depth, self.pattern, visitor)
+            },
+            Framing::Unframed => {
+                let data = self.read_unframed()?;
+                let length = data.len() as u64;
+                deserialize_contents(&mut IoRead(Cursor::new(data)).take(length), kind, self.options, self.depth, self.pattern, visitor)
+            },
+        }
+    }
+    /// Read an unframed packet, which is everything up to EOF.
+    fn read_unframed(&mut self) -> ResultE<Vec<u8>> {
+        // Read at most one byte past the limit, to detect oversized packets.
+        let max_read = self.options.max_packet_size.map_or(u64::MAX, |max| (max as u64).saturating_add(1));
+        let mut data = Vec::new();
+        self.reader.by_ref().take(max_read).read_to_end(&mut data)?;
+        self.options.check_size(data.len())?;
+        Ok(data)
+    }
+    /// Decode the address and typetag of the packet, without consuming it.
+    ///
+    /// The packet is read into memory, and a subsequent deserialization (or
+    /// peek) decodes it from there, so that a receiver can pick the type to
+    /// deserialize into based on the header. For a bundle, the address is
+    /// "#bundle" and the typetag is empty.
+    ///
+    /// ```
+    /// # extern crate serde;
+    /// # extern crate serde_osc;
+    /// use std::io::Cursor;
+    /// use serde::Deserialize;
+    /// use serde_osc::de::Deserializer;
+    ///
+    /// # fn main() {
+    /// let packet = serde_osc::to_vec(&("/note", (60, 0.5f32))).unwrap();
+    /// let mut rd = Cursor::new(packet);
+    /// let mut de = Deserializer::new(&mut rd);
+    /// let header = de.peek_header().unwrap();
+    /// assert_eq!((header.address.as_str(), header.typetag.as_str()), ("/note", "if"));
+    ///
+    /// let (_, (pitch, velocity)) = <(String, (i32, f32))>::deserialize(&mut de).unwrap();
+    /// assert_eq!((pitch, velocity), (60, 0.5));
+    /// # }
+    /// ```
+    pub fn peek_header(&mut self) -> ResultE<MessageHeader> {
+        let data = match self.peeked.take() {
+            Some(data) => data,
+            None => match self.framing {
+                Framing::LengthPrefixed => {
+                    let length = read_length(&mut self.reader, self.options)?;
+                    let mut data = Vec::new();
+                    self.reader.by_ref().take(length as u64).read_to_end(&mut data)?;
+                    if data.len() != length {
+                        return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+                    }
+                    data
+                },
+                Framing::Unframed => self.read_unframed()?,
+            },
+        };
+        let header = MessageHeader::parse(&data, self.options);
+        // Even a malformed packet stays put, so that it is reported (and
+        // skipped) by the next deserialization.
+        self.peeked = Some(data);
+        header
     }
 }
 
@@ -42,31 +405,146 @@ impl<'de, 'a, R> de::Deserializer<'de> for &'a mut PktDeserializer<'a, R>
     fn deserialize_any<V>(self, visitor: V) -> ResultE<V::Value>
         where V: Visitor<'de>
     {
-        // First, extract the length of the packet.
-        let length = self.reader.read_i32::<BigEndian>()?;
-        let mut reader = self.reader.take(length as u64);
-        // See if packet is a bundle or a message.
-        let address = reader.parse_str()?;
-        let result = match address.as_str() {
-            "#bundle" => visitor.visit_seq(BundleVisitor::new(&mut reader)),
-            _ => visitor.visit_seq(MsgVisitor::new(&mut reader, address)),
+        self.deserialize_packet(Kind::Seq, visitor)
+    }
+
+    /// Deserialize a message into the enum variant named by its address.
+    /// The message arguments become the variant's contents.
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.deserialize_packet(Kind::of_enum(variants), visitor)
+    }
+    // e.g. a struct wrapping a message type.
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // Aside from enums, this struct only deserializes sequences; ignore all other type hints.
+    // More info: https://github.com/serde-rs/serde/blob/b7d6c5d9f7b3085a4d40a446eeb95976d2337e07/serde/src/macros.rs#L106
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
+        seq bytes byte_buf map unit_struct
+        tuple_struct struct identifier tuple ignored_any
+    }
+}
+
+impl<'de, 'a, R> SourceDeserializer<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    /// Determine the extent of the packet and then deserialize its contents.
+    fn deserialize_packet<V>(&mut self, kind: Kind, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let length = match self.length {
+            Some(length) => check_size(length, self.options)?,
+            None => check_size(self.reader.parse_i32()?.try_into()?, self.options)?,
         };
-        // If the consumer only handled a portion of the sequence, we still
-        // need to advance the reader so as to be ready for any next message.
-        // TODO: it should be possible to read any extra chars w/o allocating.
-        // Tracking: https://github.com/rust-lang/rust/issues/13989
-        let size = reader.limit() as usize;
-        let mut extra_chars = Vec::with_capacity(size);
-        extra_chars.resize(size, Default::default());
-        reader.read_exact(&mut extra_chars)?;
+        deserialize_contents(&mut (&mut *self.reader).take(length as u64), kind, self.options, self.depth, self.pattern, visitor)
+    }
+}
+
+impl<'de, 'a, R> de::Deserializer<'de> for &mut SourceDeserializer<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    type Error = Error;
+    fn deserialize_any<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.deserialize_packet(Kind::Seq, visitor)
+    }
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.deserialize_packet(Kind::of_enum(variants), visitor)
+    }
+    // e.g. a struct wrapping a message type.
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
+        seq bytes byte_buf map unit_struct
+        tuple_struct struct identifier tuple ignored_any
+    }
+}
+
+impl<'de, 'a, R> ElementDeserializer<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    /// Read the length of the element, and then deserialize its contents.
+    fn deserialize_packet<V>(&mut self, kind: Kind, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let length = check_size(self.reader.parse_i32()?.try_into()?, self.options)? as u64;
+        let bundle_remaining = self.reader.limit();
+        if length > bundle_remaining {
+            return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+        }
+        self.reader.set_limit(length);
+        let result = deserialize_contents(self.reader, kind, self.options, self.depth, None, visitor);
+        // On error, the element is left partly read; the bundle skips the rest.
+        let consumed = length - self.reader.limit();
+        self.reader.set_limit(bundle_remaining - consumed);
         result
     }
+}
+
+impl<'de, 'a, R> de::Deserializer<'de> for &mut ElementDeserializer<'a, R>
+    where R: ReadSource<'de> + 'a
+{
+    type Error = Error;
+    fn deserialize_any<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.deserialize_packet(Kind::Seq, visitor)
+    }
+    /// Skip the element without decoding it.
+    fn deserialize_ignored_any<V>(self, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        let length = check_size(self.reader.parse_i32()?.try_into()?, self.options)?;
+        if length as u64 > self.reader.limit() {
+            return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+        }
+        self.reader.skip_exact(length)?;
+        visitor.visit_unit()
+    }
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V
+    ) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        self.deserialize_packet(Kind::of_enum(variants), visitor)
+    }
+    // e.g. a struct wrapping a message type.
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> ResultE<V::Value>
+        where V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
 
-    // This struct only deserializes sequences; ignore all type hints.
-    // More info: https://github.com/serde-rs/serde/blob/b7d6c5d9f7b3085a4d40a446eeb95976d2337e07/serde/src/macros.rs#L106
     forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
-        seq bytes byte_buf map unit_struct newtype_struct
-        tuple_struct struct identifier tuple enum ignored_any
+        seq bytes byte_buf map unit_struct
+        tuple_struct struct identifier tuple
     }
 }
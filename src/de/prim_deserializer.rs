@@ -4,24 +4,32 @@ use error::{Error, ResultE};
 
 /// Serde gymnastics.
 /// Implements the Deserializer trait for primitive types.
-/// Currently only implemented for the types needed by osc_serde,
-/// but could easily be extended via a macro.
+/// Currently only implemented for the types needed by osc_serde;
+/// extend via the `prim_deserializer!` macro.
 #[derive(Debug)]
 pub struct PrimDeserializer<T>(pub T);
 
-impl<'de> Deserializer<'de> for PrimDeserializer<u32> {
-    type Error = Error;
-    fn deserialize_any<V>(self, visitor: V) -> ResultE<V::Value>
-        where V: Visitor<'de>
-    {
-        visitor.visit_u32(self.0)
-    }
+macro_rules! prim_deserializer {
+    ($t:ty, $visit:ident) => {
+        impl<'de> Deserializer<'de> for PrimDeserializer<$t> {
+            type Error = Error;
+            fn deserialize_any<V>(self, visitor: V) -> ResultE<V::Value>
+                where V: Visitor<'de>
+            {
+                visitor.$visit(self.0)
+            }
 
-    // Ignore type hints
-    // More info: https://github.com/serde-rs/serde/blob/b7d6c5d9f7b3085a4d40a446eeb95976d2337e07/serde/src/macros.rs#L106
-    forward_to_deserialize_any! {
-        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
-        seq bytes byte_buf map unit_struct newtype_struct
-        tuple_struct struct identifier tuple enum ignored_any
-    }
+            // Ignore type hints
+            // More info: https://github.com/serde-rs/serde/blob/b7d6c5d9f7b3085a4d40a446eeb95976d2337e07/serde/src/macros.rs#L106
+            forward_to_deserialize_any! {
+                bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
+                seq bytes byte_buf map unit_struct newtype_struct
+                tuple_struct struct identifier tuple enum ignored_any
+            }
+        }
+    };
 }
+
+prim_deserializer!(u8, visit_u8);
+prim_deserializer!(u32, visit_u32);
+prim_deserializer!(u64, visit_u64);
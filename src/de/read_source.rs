@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+use std::cmp;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Take};
+
+use error::{Error, ResultE};
+use super::osc_reader::OscReader;
+
+/// Input to the deserializer, which may be able to lend out parts of itself
+/// for the `'de` lifetime rather than copying them, as is the case for slices.
+pub trait ReadSource<'de>: BufRead {
+    /// Consume the next `len` bytes without copying them, if the input allows.
+    /// Returns `None` (consuming nothing) if it doesn't.
+    fn borrow_bytes(&mut self, len: usize) -> Option<ResultE<&'de [u8]>>;
+
+    /// Read an OSC blob & verify padding, borrowing it from the input if possible.
+    /// Fails with `Error::SizeLimitExceeded` if it's longer than `max_size` bytes.
+    fn read_blob(&mut self, max_size: Option<usize>) -> ResultE<Cow<'de, [u8]>> {
+        let size = self.parse_blob_size(max_size)?;
+        let padded_size = (size + 3) & !0x3;
+        match self.borrow_bytes(padded_size) {
+            Some(padded) => {
+                let padded = padded?;
+                if padded[size..].iter().all(|&c| c == 0) {
+                    Ok(Cow::Borrowed(&padded[..size]))
+                } else {
+                    Err(Error::BadPadding)
+                }
+            },
+            None => self.parse_blob_data(size).map(Cow::Owned),
+        }
+    }
+}
+
+/// Any buffered reader; its data is always copied.
+#[derive(Debug)]
+pub struct IoRead<R>(pub R);
+
+impl<R: Read> Read for IoRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<R: BufRead> BufRead for IoRead<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.0.fill_buf()
+    }
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
+
+impl<'de, R: BufRead> ReadSource<'de> for IoRead<R> {
+    fn borrow_bytes(&mut self, _len: usize) -> Option<ResultE<&'de [u8]>> {
+        None
+    }
+}
+
+/// Largest buffer used to read a packet from an `io::Read`.
+const WINDOW_SIZE: usize = 8 * 1024;
+
+/// Buffer the next `length` bytes of `reader`, so that they can be parsed
+/// without a `read` call for every field. Nothing beyond those bytes is read,
+/// so once they're consumed, `reader` is left at the start of the next packet.
+pub fn window<R: Read>(reader: R, length: u64) -> BufReader<Take<R>> {
+    let capacity = cmp::min(length, WINDOW_SIZE as u64) as usize;
+    BufReader::with_capacity(capacity, reader.take(length))
+}
+
+/// A slice which the deserialized values may borrow from.
+#[derive(Debug)]
+pub struct SliceRead<'de> {
+    slice: &'de [u8],
+}
+
+impl<'de> SliceRead<'de> {
+    pub fn new(slice: &'de [u8]) -> Self {
+        Self { slice }
+    }
+    /// The input which hasn't been read yet.
+    pub fn remaining(&self) -> &'de [u8] {
+        self.slice
+    }
+}
+
+impl<'de> Read for SliceRead<'de> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = cmp::min(buf.len(), self.slice.len());
+        buf[..len].copy_from_slice(&self.slice[..len]);
+        self.slice = &self.slice[len..];
+        Ok(len)
+    }
+}
+
+impl<'de> BufRead for SliceRead<'de> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.slice)
+    }
+    fn consume(&mut self, amt: usize) {
+        self.slice = &self.slice[amt..];
+    }
+}
+
+impl<'de> ReadSource<'de> for SliceRead<'de> {
+    fn borrow_bytes(&mut self, len: usize) -> Option<ResultE<&'de [u8]>> {
+        if len > self.slice.len() {
+            return Some(Err(Error::Io(ErrorKind::UnexpectedEof.into())));
+        }
+        let (head, tail) = self.slice.split_at(len);
+        self.slice = tail;
+        Some(Ok(head))
+    }
+}
+
+impl<'de, R: ReadSource<'de>> ReadSource<'de> for Take<R> {
+    fn borrow_bytes(&mut self, len: usize) -> Option<ResultE<&'de [u8]>> {
+        let limit = self.limit();
+        if len as u64 > limit {
+            return Some(Err(Error::Io(ErrorKind::UnexpectedEof.into())));
+        }
+        let borrowed = self.get_mut().borrow_bytes(len)?;
+        if borrowed.is_ok() {
+            self.set_limit(limit - len as u64);
+        }
+        Some(borrowed)
+    }
+}
+
+impl<'de, R: ReadSource<'de> + ?Sized> ReadSource<'de> for &mut R {
+    fn borrow_bytes(&mut self, len: usize) -> Option<ResultE<&'de [u8]>> {
+        (**self).borrow_bytes(len)
+    }
+}
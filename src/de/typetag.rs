@@ -0,0 +1,33 @@
+use std::io::BufRead;
+
+use error::{Error, ResultE};
+use super::options::DeserializerOptions;
+use super::osc_reader::OscReader;
+
+/// Read the typetag of a message (which follows its address), without the
+/// leading comma.
+///
+/// Typetags USUALLY start with a comma, but not always (except in strict
+/// mode); with `TypetagCompat::Legacy`, a message may also end before its
+/// typetag, in which case it has no arguments.
+pub fn read_typetag<R>(read: &mut R, options: DeserializerOptions) -> ResultE<Vec<u8>>
+    where R: BufRead + ?Sized
+{
+    if options.typetag_optional() && read.fill_buf()?.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut tags = read.read_0term_bytes()?;
+    let comma = tags.len() - skip_comma(&tags, options)?.len();
+    tags.drain(..comma);
+    Ok(tags)
+}
+
+/// Drop the leading comma of a typetag, if it has one.
+/// In strict mode, it must have one.
+pub fn skip_comma(tags: &[u8], options: DeserializerOptions) -> ResultE<&[u8]> {
+    match tags.split_first() {
+        Some((&b',', rest)) => Ok(rest),
+        _ if options.strict => Err(Error::MissingTypetagComma),
+        _ => Ok(tags),
+    }
+}
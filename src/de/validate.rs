@@ -0,0 +1,242 @@
+use std::convert::TryInto;
+use std::io::{Cursor, ErrorKind};
+use std::mem;
+use std::str;
+
+use error::{Error, ResultE};
+use framing::Framing;
+use super::arg_visitor::check_nesting;
+use super::options::{DeserializerOptions, ExtensionType, StringDecoding};
+use super::osc_reader::find_nul;
+use super::typetag::skip_comma;
+
+/// Summary of a well-formed packet, as returned by [`validate`].
+///
+/// [`validate`]: fn.validate.html
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PacketInfo {
+    /// Size of the packet contents, excluding the length prefix (if any).
+    pub size: usize,
+    /// Number of messages, including those within bundles.
+    pub messages: usize,
+    /// Number of bundles, including the packet itself if it's a bundle.
+    pub bundles: usize,
+    /// How deeply bundles are nested; 0 if the packet is a message.
+    pub depth: usize,
+}
+
+/// Check that `bytes` holds exactly one well-formed, length-prefixed packet,
+/// without decoding it into any Rust values.
+///
+/// Everything which `Packet::from_slice` would reject is reported, with the
+/// same errors: truncated or misaligned data, unsupported typetags, invalid
+/// strings, packets whose contents end before their declared length, and
+/// packets beyond the default limits. Data after the packet is rejected with
+/// `Error::BadFormat`.
+///
+/// ```
+/// use serde_osc::de::validate;
+///
+/// let packet = serde_osc::to_vec(&("/note", (60, 0.5f32))).unwrap();
+/// let info = validate(&packet).unwrap();
+/// assert_eq!((info.size, info.messages, info.bundles), (packet.len() - 4, 1, 0));
+///
+/// assert!(validate(&packet[..packet.len() - 1]).is_err());
+/// ```
+pub fn validate(bytes: &[u8]) -> ResultE<PacketInfo> {
+    validate_with(bytes, Framing::LengthPrefixed, DeserializerOptions::default())
+}
+
+/// Check that `bytes` holds exactly one well-formed packet which has no length prefix.
+pub fn validate_unframed(bytes: &[u8]) -> ResultE<PacketInfo> {
+    validate_with(bytes, Framing::Unframed, DeserializerOptions::default())
+}
+
+/// Check that `bytes` holds exactly one well-formed packet, using the provided
+/// framing and decoding options.
+pub fn validate_with(bytes: &[u8], framing: Framing, options: DeserializerOptions) -> ResultE<PacketInfo> {
+    let mut validator = Validator { bytes, pos: 0, end: bytes.len(), options, info: PacketInfo::default() };
+    let start = match framing {
+        Framing::LengthPrefixed => {
+            let length: usize = validator.i32()?.try_into()?;
+            if bytes.len() - 4 < length {
+                return Err(eof());
+            }
+            if bytes.len() - 4 > length {
+                return Err(Error::BadFormat);
+            }
+            4
+        },
+        Framing::Unframed => 0,
+    };
+    let size = bytes.len() - start;
+    options.check_size(size)?;
+    validator.info.size = size;
+    let result = validator.packet(bytes.len(), 0);
+    // Nested elements stop at the error, so this is the offset within the outermost packet.
+    let offset = (validator.pos - start) as u64;
+    result.map_err(|e| e.with_context(|ctx| ctx.offset = offset))?;
+    Ok(validator.info)
+}
+
+fn eof() -> Error {
+    Error::Io(ErrorKind::UnexpectedEof.into())
+}
+
+/// Walks the packet, advancing `pos` past each item once it has been checked.
+struct Validator<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// End of the packet (or bundle element) being checked.
+    end: usize,
+    options: DeserializerOptions,
+    info: PacketInfo,
+}
+
+impl<'a> Validator<'a> {
+    /// Consume the next `len` bytes.
+    fn take(&mut self, len: usize) -> ResultE<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.end).ok_or_else(eof)?;
+        let data = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(data)
+    }
+    fn i32(&mut self) -> ResultE<i32> {
+        let mut word = [0u8; 4];
+        word.copy_from_slice(self.take(4)?);
+        Ok(i32::from_be_bytes(word))
+    }
+    /// Consume a null-terminated, padded sequence of bytes, returning its contents.
+    fn bytes_0term(&mut self) -> ResultE<&'a [u8]> {
+        let rest = &self.bytes[self.pos..self.end];
+        let len = find_nul(rest).ok_or_else(eof)?;
+        // At least one null, up to the next 4-byte boundary.
+        let padded = self.take((len + 4) & !0x3)?;
+        if padded[len..].iter().any(|&c| c != 0) {
+            return Err(Error::BadPadding);
+        }
+        Ok(&padded[..len])
+    }
+    /// Consume a string, checking its encoding as decoding it would.
+    fn string(&mut self) -> ResultE<&'a [u8]> {
+        let data = self.bytes_0term()?;
+        self.options.check_string(data)?;
+        if self.options.strings == StringDecoding::Strict && str::from_utf8(data).is_err() {
+            // Only allocate to report the error.
+            String::from_utf8(data.to_vec())?;
+        }
+        Ok(data)
+    }
+    /// Check the packet contents, which extend up to `end`.
+    /// `depth` is the number of bundles enclosing the packet.
+    fn packet(&mut self, end: usize, depth: usize) -> ResultE<()> {
+        let outer_end = mem::replace(&mut self.end, end);
+        self.contents(depth)?;
+        self.end = outer_end;
+        Ok(())
+    }
+    fn contents(&mut self, depth: usize) -> ResultE<()> {
+        let start = self.pos;
+        let address = self.string()?;
+        self.options.check_address(address)?;
+        if address == b"#bundle" {
+            if self.options.too_deep(depth) {
+                return Err(Error::DepthLimitExceeded);
+            }
+            self.info.bundles += 1;
+            self.info.depth = self.info.depth.max(depth + 1);
+            self.take(8)?;
+            while self.pos < self.end {
+                let length: usize = self.i32()?.try_into()?;
+                self.options.check_size(length)?;
+                let elem_end = self.pos.checked_add(length).filter(|&elem_end| elem_end <= self.end).ok_or_else(eof)?;
+                self.packet(elem_end, depth + 1)?;
+            }
+        } else {
+            self.info.messages += 1;
+            self.args().map_err(|e| e.with_context(|ctx| {
+                ctx.address.get_or_insert_with(|| String::from_utf8_lossy(address).into_owned());
+            }))?;
+        }
+        match self.pos == self.end {
+            true => Ok(()),
+            // Trailing data after the last argument or element.
+            false => Err(Error::LengthMismatch { declared: self.end - start, consumed: self.pos - start }),
+        }
+    }
+    /// Check the typetag and all the arguments of a message.
+    fn args(&mut self) -> ResultE<()> {
+        let arg_types = match self.pos == self.end {
+            // A message which ends after its address has no arguments.
+            true if self.options.typetag_optional() => &[],
+            _ => skip_comma(self.bytes_0term()?, self.options)?,
+        };
+        check_nesting(arg_types, self.options)?;
+        let mut arg_index = 0;
+        let mut array_depth = 0usize;
+        for (i, &tag) in arg_types.iter().enumerate() {
+            if array_depth == 0 {
+                arg_index += 1;
+            }
+            self.arg(tag, &arg_types[i + 1..], &mut array_depth)
+                .map_err(|e| e.with_context(|ctx| { ctx.arg_index.get_or_insert(arg_index - 1); }))?;
+        }
+        match array_depth {
+            0 => Ok(()),
+            // Unterminated array
+            _ => Err(Error::BadFormat),
+        }
+    }
+    /// Check a single argument (or array delimiter); `rest` are the tags which follow it.
+    fn arg(&mut self, tag: u8, rest: &[u8], array_depth: &mut usize) -> ResultE<()> {
+        match tag {
+            b'i' | b'f' | b'c' | b'm' | b'r' => { self.take(4)?; },
+            b'h' | b'd' => { self.take(8)?; },
+            b's' | b'S' => { self.string()?; },
+            b'b' => {
+                let size: usize = self.i32()?.try_into()?;
+                if self.options.max_blob_size.is_some_and(|max| size > max) {
+                    return Err(Error::SizeLimitExceeded);
+                }
+                let padded = self.take((size + 3) & !0x3)?;
+                if padded[size..].iter().any(|&c| c != 0) {
+                    return Err(Error::BadPadding);
+                }
+            },
+            b'T' | b'F' | b'N' => {},
+            b'[' => *array_depth += 1,
+            // Array end without a matching start
+            b']' if *array_depth == 0 => return Err(Error::BadFormat),
+            b']' => *array_depth -= 1,
+            tag => match self.options.extension(tag) {
+                Some(ext) => self.extension_arg(ext)?,
+                None => self.unknown_arg(tag, rest)?,
+            },
+        }
+        Ok(())
+    }
+    /// Consume an argument of a registered extension type.
+    fn extension_arg(&mut self, ext: &ExtensionType) -> ResultE<()> {
+        let mut data = Cursor::new(&self.bytes[self.pos..self.end]);
+        if (ext.read)(&mut data)?.len() % 4 != 0 {
+            return Err(Error::BadPadding);
+        }
+        self.pos += data.position() as usize;
+        Ok(())
+    }
+    /// Consume an argument of an unsupported type, if it can be captured.
+    fn unknown_arg(&mut self, tag: u8, rest: &[u8]) -> ResultE<()> {
+        match self.options.unknown_types.capture_size(tag)? {
+            Some(size) => { self.take(size)?; },
+            // The last argument extends to the end of the message.
+            None if rest.iter().all(|&tag| tag == b']') => {
+                if !(self.end - self.pos).is_multiple_of(4) {
+                    return Err(Error::BadPadding);
+                }
+                self.pos = self.end;
+            },
+            None => return Err(Error::UnsupportedType),
+        }
+        Ok(())
+    }
+}
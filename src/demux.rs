@@ -0,0 +1,176 @@
+use std::fmt;
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket};
+use serde;
+use serde::de::DeserializeOwned;
+
+use de;
+use error::{Error, ResultE};
+use net::{OscUdpSocket, MAX_DATAGRAM_SIZE};
+use ser;
+
+type Handler<'a> = Box<dyn FnMut(&[u8], &Peer) -> ResultE<()> + 'a>;
+
+/// The peers a handler receives packets from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PeerFilter {
+    Any,
+    /// Any port of one host, for controllers which send from an ephemeral port.
+    Host(IpAddr),
+    Addr(SocketAddr),
+}
+
+impl PeerFilter {
+    fn matches(self, addr: SocketAddr) -> bool {
+        match self {
+            PeerFilter::Any => true,
+            PeerFilter::Host(ip) => addr.ip() == ip,
+            PeerFilter::Addr(peer) => addr == peer,
+        }
+    }
+}
+
+/// The sender of a received packet, through which it can be answered.
+#[derive(Clone, Copy, Debug)]
+pub struct Peer<'s> {
+    addr: SocketAddr,
+    socket: &'s UdpSocket,
+}
+
+impl<'s> Peer<'s> {
+    /// The address the packet came from.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+    /// Serialize `value` as an OSC packet and send it back to the peer,
+    /// from the socket the packet was received on. Returns the number of bytes sent.
+    pub fn reply<T>(&self, value: &T) -> ResultE<usize>
+        where T: ?Sized + serde::ser::Serialize
+    {
+        let packet = ser::to_vec_unframed(value)?;
+        Ok(self.socket.send_to(&packet, self.addr)?)
+    }
+}
+
+/// Receives packets on a UDP socket which several controllers send to, and
+/// hands each one to the handlers registered for its sender, e.g. so that
+/// each of two tablets running a control surface only drives its own deck.
+///
+/// Every handler whose peer matches the sender of a packet is invoked, with
+/// the packet deserialized into that handler's argument type (as with
+/// `OscUdpSocket::recv_from`) and the [`Peer`] it came from, through which
+/// replies find their way back to the right controller.
+///
+/// ```
+/// # extern crate serde_osc;
+/// use serde_osc::demux::Demux;
+/// use serde_osc::net::OscUdpSocket;
+///
+/// # fn main() {
+/// let controller = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+/// let mut demux = Demux::bind("127.0.0.1:0").unwrap();
+/// let server = demux.socket().local_addr().unwrap();
+/// demux.on_any(|(address, ()): (String, ()), peer| {
+///     peer.reply(&(format!("{}.reply", address), ())).unwrap();
+/// });
+///
+/// controller.send_to(&("/ping", ()), server).unwrap();
+/// assert_eq!(demux.recv().unwrap(), (controller.socket().local_addr().unwrap(), 1));
+/// # }
+/// ```
+///
+/// [`Peer`]: struct.Peer.html
+pub struct Demux<'a> {
+    socket: UdpSocket,
+    /// Receive buffer, large enough for any datagram.
+    buf: Vec<u8>,
+    handlers: Vec<(PeerFilter, Handler<'a>)>,
+}
+
+impl<'a> Demux<'a> {
+    /// Receive on a socket bound to the given local address.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> ResultE<Self> {
+        Ok(Self::from_socket(UdpSocket::bind(addr)?))
+    }
+    /// Receive on an already-configured `UdpSocket` (e.g. one with a read timeout).
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            buf: vec![0; MAX_DATAGRAM_SIZE],
+            handlers: Vec::new(),
+        }
+    }
+    /// Access the underlying socket, e.g. to query the local address.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+    /// Unwrap this object, returning the underlying socket.
+    pub fn into_inner(self) -> UdpSocket {
+        self.socket
+    }
+
+    /// Register `handler` to be called for the packets from every peer.
+    pub fn on_any<T, F>(&mut self, handler: F) -> &mut Self
+        where T: DeserializeOwned, F: FnMut(T, &Peer) + 'a
+    {
+        self.on(PeerFilter::Any, handler)
+    }
+    /// Register `handler` to be called for the packets sent from any port of the host `ip`.
+    pub fn on_host<T, F>(&mut self, ip: IpAddr, handler: F) -> &mut Self
+        where T: DeserializeOwned, F: FnMut(T, &Peer) + 'a
+    {
+        self.on(PeerFilter::Host(ip), handler)
+    }
+    /// Register `handler` to be called for the packets sent from `peer`
+    /// (the first address it resolves to).
+    pub fn on_peer<A, T, F>(&mut self, peer: A, handler: F) -> ResultE<&mut Self>
+        where A: ToSocketAddrs, T: DeserializeOwned, F: FnMut(T, &Peer) + 'a
+    {
+        let peer = peer.to_socket_addrs()?.next()
+            .ok_or_else(|| Error::Io(ErrorKind::InvalidInput.into()))?;
+        Ok(self.on(PeerFilter::Addr(peer), handler))
+    }
+    fn on<T, F>(&mut self, filter: PeerFilter, mut handler: F) -> &mut Self
+        where T: DeserializeOwned, F: FnMut(T, &Peer) + 'a
+    {
+        self.handlers.push((filter, Box::new(move |packet, peer| {
+            handler(de::from_slice_unframed(packet)?, peer);
+            Ok(())
+        })));
+        self
+    }
+
+    /// Block until a datagram arrives, and dispatch it to the handlers for
+    /// its sender. Returns the sender, and the number of handlers invoked.
+    ///
+    /// Fails (without invoking the remaining handlers) if the packet can't be
+    /// deserialized into the argument type of a handler it's for.
+    pub fn recv(&mut self) -> ResultE<(SocketAddr, usize)> {
+        let (size, addr) = self.socket.recv_from(&mut self.buf)?;
+        let packet = &self.buf[..size];
+        let peer = Peer { addr, socket: &self.socket };
+        let mut n_handled = 0;
+        for &mut (filter, ref mut handler) in &mut self.handlers {
+            if filter.matches(addr) {
+                handler(packet, &peer)?;
+                n_handled += 1;
+            }
+        }
+        Ok((addr, n_handled))
+    }
+}
+
+impl<'a> From<OscUdpSocket> for Demux<'a> {
+    fn from(socket: OscUdpSocket) -> Self {
+        Self::from_socket(socket.into_inner())
+    }
+}
+
+impl<'a> fmt::Debug for Demux<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Demux")
+            .field("socket", &self.socket)
+            .field("handlers", &self.handlers.iter().map(|h| h.0).collect::<Vec<_>>())
+            .finish()
+    }
+}
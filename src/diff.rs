@@ -0,0 +1,189 @@
+use std::fmt::{self, Display, Formatter};
+use std::mem;
+
+use de::{DeserializerOptions, UnknownTypes};
+use error::ResultE;
+use framing::Framing;
+use value::{Packet, Value};
+
+/// One way in which two packets differ, as reported by [`diff`].
+///
+/// `at` locates the difference within the packets, e.g. `"element 1, arg 2[0]"`
+/// for the first element of the array which is the third argument of the
+/// second element of a bundle; it's empty for the packets themselves.
+///
+/// [`diff`]: fn.diff.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum Difference {
+    /// Different message addresses; a bundle's address is "#bundle".
+    Address { at: String, left: String, right: String },
+    /// Bundles with different time-tags.
+    Timetag { at: String, left: (u32, u32), right: (u32, u32) },
+    /// A different number of arguments (of a message), elements (of a
+    /// bundle) or array elements. The items they have in common are still compared.
+    Count { at: String, left: usize, right: usize },
+    /// Arguments of different types, given as their typetags.
+    Type { at: String, left: String, right: String },
+    /// Arguments of the same type, but with different values.
+    Value { at: String, left: Value, right: Value },
+}
+
+/// Compare two length-prefixed packets, returning their differences (none
+/// if they're equivalent). Floating-point arguments must be equal; see
+/// [`diff_with`] to allow for rounding.
+///
+/// Fails if either packet can't be decoded. Arguments of unsupported types
+/// are compared by their raw data.
+///
+/// ```
+/// use serde_osc::diff::{diff, Difference};
+/// use serde_osc::value::Value;
+///
+/// let sent = serde_osc::to_vec(&("/synth", (440.0f32, "sine"))).unwrap();
+/// let expected = serde_osc::to_vec(&("/synth", (440.0f32, "saw"))).unwrap();
+/// assert_eq!(diff(&sent, &expected).unwrap(), vec![Difference::Value {
+///     at: "arg 1".to_owned(),
+///     left: Value::from("sine"),
+///     right: Value::from("saw"),
+/// }]);
+/// ```
+///
+/// [`diff_with`]: fn.diff_with.html
+pub fn diff(left: &[u8], right: &[u8]) -> ResultE<Vec<Difference>> {
+    diff_with(left, right, Framing::LengthPrefixed, 0.0)
+}
+
+/// Compare two packets, using the provided framing. Floating-point arguments
+/// are considered equal if they differ by at most `tolerance` (NaNs are equal
+/// to each other).
+pub fn diff_with(left: &[u8], right: &[u8], framing: Framing, tolerance: f64) -> ResultE<Vec<Difference>> {
+    let options = DeserializerOptions { unknown_types: UnknownTypes::Capture, ..DeserializerOptions::default() };
+    let left = Packet::from_slice_with(left, framing, options)?;
+    let right = Packet::from_slice_with(right, framing, options)?;
+    let mut differences = Vec::new();
+    Comparison { tolerance, differences: &mut differences }.packets(String::new(), &left, &right);
+    Ok(differences)
+}
+
+/// Assert that two length-prefixed packets are equivalent (see [`diff`]).
+///
+/// # Panics
+///
+/// If they differ, listing each difference, or if either can't be decoded.
+///
+/// [`diff`]: fn.diff.html
+#[track_caller]
+pub fn assert_osc_eq(left: &[u8], right: &[u8]) {
+    assert_osc_near(left, right, 0.0)
+}
+
+/// Assert that two length-prefixed packets are equivalent, allowing their
+/// floating-point arguments to differ by at most `tolerance`.
+///
+/// # Panics
+///
+/// If they differ, listing each difference, or if either can't be decoded.
+#[track_caller]
+pub fn assert_osc_near(left: &[u8], right: &[u8], tolerance: f64) {
+    match diff_with(left, right, Framing::LengthPrefixed, tolerance) {
+        Ok(ref differences) if differences.is_empty() => {},
+        Ok(differences) => {
+            let listed: Vec<String> = differences.iter().map(Difference::to_string).collect();
+            panic!("OSC packets differ:\n  {}", listed.join("\n  "));
+        },
+        Err(e) => panic!("OSC packets can't be compared: {}", e),
+    }
+}
+
+/// Collects the differences between two packets.
+struct Comparison<'a> {
+    tolerance: f64,
+    differences: &'a mut Vec<Difference>,
+}
+
+/// Locate an item within the one at `at`.
+fn within(at: &str, item: &str) -> String {
+    match at {
+        "" => item.to_owned(),
+        at => format!("{}, {}", at, item),
+    }
+}
+
+impl<'a> Comparison<'a> {
+    fn packets(&mut self, at: String, left: &Packet, right: &Packet) {
+        match (left, right) {
+            (Packet::Message(l), Packet::Message(r)) => {
+                if l.address != r.address {
+                    self.differences.push(Difference::Address { at: at.clone(), left: l.address.clone(), right: r.address.clone() });
+                }
+                self.count(&at, l.args.len(), r.args.len());
+                for (i, (l, r)) in l.args.iter().zip(&r.args).enumerate() {
+                    self.values(within(&at, &format!("arg {}", i)), l, r);
+                }
+            },
+            (Packet::Bundle(l), Packet::Bundle(r)) => {
+                if l.timetag != r.timetag {
+                    self.differences.push(Difference::Timetag { at: at.clone(), left: l.timetag, right: r.timetag });
+                }
+                self.count(&at, l.elements.len(), r.elements.len());
+                for (i, (l, r)) in l.elements.iter().zip(&r.elements).enumerate() {
+                    self.packets(within(&at, &format!("element {}", i)), l, r);
+                }
+            },
+            // A message and a bundle have nothing else in common.
+            _ => self.differences.push(Difference::Address {
+                at,
+                left: left.address().unwrap_or("#bundle").to_owned(),
+                right: right.address().unwrap_or("#bundle").to_owned(),
+            }),
+        }
+    }
+    fn count(&mut self, at: &str, left: usize, right: usize) {
+        if left != right {
+            self.differences.push(Difference::Count { at: at.to_owned(), left, right });
+        }
+    }
+    fn values(&mut self, at: String, left: &Value, right: &Value) {
+        let equal = match (left, right) {
+            // 'T' and 'F' are different tags, but the same type.
+            _ if mem::discriminant(left) != mem::discriminant(right) => {
+                self.differences.push(Difference::Type { at, left: left.typetag(), right: right.typetag() });
+                return;
+            },
+            (Value::Array(l), Value::Array(r)) => {
+                self.count(&at, l.len(), r.len());
+                for (i, (l, r)) in l.iter().zip(r).enumerate() {
+                    self.values(format!("{}[{}]", at, i), l, r);
+                }
+                return;
+            },
+            (&Value::Float(l), &Value::Float(r)) => self.floats_equal(l.into(), r.into()),
+            (&Value::Double(l), &Value::Double(r)) => self.floats_equal(l, r),
+            (l, r) => l == r,
+        };
+        if !equal {
+            self.differences.push(Difference::Value { at, left: left.clone(), right: right.clone() });
+        }
+    }
+    fn floats_equal(&self, left: f64, right: f64) -> bool {
+        (left.is_nan() && right.is_nan()) || left == right || (left - right).abs() <= self.tolerance
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let at = |at: &str| if at.is_empty() { "packet".to_owned() } else { at.to_owned() };
+        match *self {
+            Difference::Address { at: ref a, ref left, ref right } =>
+                write!(f, "{}: address {:?} != {:?}", at(a), left, right),
+            Difference::Timetag { at: ref a, left, right } =>
+                write!(f, "{}: time-tag {}:{} != {}:{}", at(a), left.0, left.1, right.0, right.1),
+            Difference::Count { at: ref a, left, right } =>
+                write!(f, "{}: {} items != {}", at(a), left, right),
+            Difference::Type { at: ref a, ref left, ref right } =>
+                write!(f, "{}: type '{}' != '{}'", at(a), left, right),
+            Difference::Value { at: ref a, ref left, ref right } =>
+                write!(f, "{}: {:?} != {:?}", at(a), left, right),
+        }
+    }
+}
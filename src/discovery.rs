@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+
+use error::{Error, ResultE};
+
+/// The mDNS service type under which OSC servers receiving over UDP are advertised.
+pub const SERVICE_TYPE: &str = "_osc._udp.local.";
+
+/// Advertises a local OSC port via mDNS (Bonjour/Zeroconf), so that peers
+/// such as TouchOSC can find it without being told the host's address.
+///
+/// The advertisement lasts until this is dropped (or `stop` is called).
+///
+/// ```no_run
+/// use std::net::UdpSocket;
+/// use serde_osc::discovery::Advertisement;
+///
+/// let socket = UdpSocket::bind("0.0.0.0:9000").unwrap();
+/// let advertised = Advertisement::new("Mixer", 9000).unwrap();
+/// let mut buf = [0u8; 1536];
+/// let (len, _) = socket.recv_from(&mut buf).unwrap();
+/// let (address, ()): (String, ()) = serde_osc::de::from_slice_unframed(&buf[..len]).unwrap();
+/// # let _ = address;
+/// advertised.stop().unwrap();
+/// ```
+pub struct Advertisement {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+/// An OSC service found by [`browse`].
+///
+/// [`browse`]: fn.browse.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Peer {
+    /// The instance name under which the service is advertised, e.g. "Mixer".
+    pub name: String,
+    /// The host's mDNS name, e.g. "studio.local.".
+    pub host: String,
+    /// The addresses the service can be reached at (the host may have several).
+    pub addresses: Vec<SocketAddr>,
+}
+
+impl Advertisement {
+    /// Advertise an OSC server receiving on `port`, under the instance name
+    /// `name`, at every address of this host.
+    pub fn new(name: &str, port: u16) -> ResultE<Self> {
+        Self::with_properties(name, port, HashMap::new())
+    }
+    /// Advertise an OSC server, along with TXT record properties (e.g.
+    /// "txtvers"), which some peers display or use to filter services.
+    pub fn with_properties(name: &str, port: u16, properties: HashMap<String, String>) -> ResultE<Self> {
+        let daemon = ServiceDaemon::new().map_err(mdns_error)?;
+        let info = ServiceInfo::new(SERVICE_TYPE, name, &host_name(name), "", port, properties)
+            .map_err(mdns_error)?
+            .enable_addr_auto();
+        let fullname = info.get_fullname().to_owned();
+        daemon.register(info).map_err(mdns_error)?;
+        Ok(Self { daemon, fullname })
+    }
+    /// The full mDNS name of the service, e.g. "Mixer._osc._udp.local.".
+    pub fn fullname(&self) -> &str {
+        &self.fullname
+    }
+    /// Withdraw the advertisement, telling peers that the service is gone.
+    pub fn stop(self) -> ResultE<()> {
+        self.unregister()
+    }
+    fn unregister(&self) -> ResultE<()> {
+        self.daemon.unregister(&self.fullname).map_err(mdns_error)?;
+        self.daemon.shutdown().map_err(mdns_error)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Advertisement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Advertisement").field("fullname", &self.fullname).finish()
+    }
+}
+
+impl Drop for Advertisement {
+    fn drop(&mut self) {
+        // Already done, if stopped explicitly.
+        let _ = self.unregister();
+    }
+}
+
+/// Look for OSC services on the local network for `timeout`, returning
+/// those which were found (including any advertised by this host).
+pub fn browse(timeout: Duration) -> ResultE<Vec<Peer>> {
+    let daemon = ServiceDaemon::new().map_err(mdns_error)?;
+    let events = daemon.browse(SERVICE_TYPE).map_err(mdns_error)?;
+    let deadline = Instant::now() + timeout;
+    let mut peers: Vec<Peer> = Vec::new();
+    while let Ok(event) = events.recv_deadline(deadline) {
+        let info = match event {
+            ServiceEvent::ServiceResolved(info) => info,
+            _ => continue,
+        };
+        let mut addresses: Vec<SocketAddr> = info.get_addresses().iter()
+            .map(|ip| SocketAddr::new(*ip, info.get_port()))
+            .collect();
+        addresses.sort();
+        let peer = Peer {
+            name: instance_name(info.get_fullname()).to_owned(),
+            host: info.get_hostname().to_owned(),
+            addresses,
+        };
+        // A service is resolved again as its addresses become known.
+        match peers.iter_mut().find(|known| known.name == peer.name) {
+            Some(known) => *known = peer,
+            None => peers.push(peer),
+        }
+    }
+    let _ = daemon.stop_browse(SERVICE_TYPE);
+    let _ = daemon.shutdown();
+    Ok(peers)
+}
+
+/// The mDNS host name to advertise an instance under, e.g. "Mixer-1.local."
+/// for "Mixer 1".
+fn host_name(instance: &str) -> String {
+    let label: String = instance.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("{}.local.", label)
+}
+
+/// The instance name at the start of a service's full name.
+fn instance_name(fullname: &str) -> &str {
+    fullname.strip_suffix(SERVICE_TYPE)
+        .and_then(|name| name.strip_suffix('.'))
+        .unwrap_or(fullname)
+}
+
+fn mdns_error(e: ::mdns_sd::Error) -> Error {
+    Error::Message(format!("mDNS: {}", e))
+}
@@ -0,0 +1,156 @@
+//! Annotated breakdown of raw OSC packets, for protocol debugging.
+//!
+//! Each line of the dump starts with the (hexadecimal) offset of the item it
+//! describes, followed by the item itself, e.g.
+//!
+//! ```norun
+//! 0000  length 24
+//! 0004  address "/audio/play"
+//! 0010  typetag ",if"
+//! 0014  arg 0: i 1
+//! 0018  arg 1: f 440.0
+//! ```
+//!
+//! Malformed data is dumped up to the point where it could no longer be parsed,
+//! followed by a line describing the error.
+
+use std::convert::TryInto;
+use std::fmt::{self, Write};
+use std::io::{Cursor, ErrorKind, Read};
+
+use de::{ArgVisitor, DeserializerOptions};
+use de::osc_reader::OscReader;
+use de::read_source::IoRead;
+use error::Error;
+use framing::Framing;
+use ser;
+use value::{Packet, Value};
+
+/// Dump a length-prefixed packet.
+pub fn dump(bytes: &[u8]) -> String {
+    dump_with(bytes, Framing::LengthPrefixed)
+}
+
+/// Dump a packet which has no length prefix.
+pub fn dump_unframed(bytes: &[u8]) -> String {
+    dump_with(bytes, Framing::Unframed)
+}
+
+/// Dump a packet, using the provided framing.
+pub fn dump_with(bytes: &[u8], framing: Framing) -> String {
+    let mut dumper = Dumper { out: String::new() };
+    if let Err((offset, e)) = dumper.framed(bytes, framing) {
+        dumper.line(offset, 0, format_args!("error: {}", e));
+    }
+    dumper.out
+}
+
+/// Shows the breakdown of the packet's (unframed) encoding.
+impl fmt::Debug for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match ser::to_vec_unframed(self) {
+            Ok(bytes) => f.write_str(dump_unframed(&bytes).trim_end()),
+            Err(e) => write!(f, "<unencodable packet: {}>", e),
+        }
+    }
+}
+
+/// An error, and the offset at which it was encountered.
+type Located<T> = Result<T, (usize, Error)>;
+
+fn eof() -> Error {
+    Error::Io(ErrorKind::UnexpectedEof.into())
+}
+
+struct Dumper {
+    out: String,
+}
+
+impl Dumper {
+    fn line(&mut self, offset: usize, depth: usize, text: fmt::Arguments) {
+        // Writing to a String can't fail.
+        let _ = writeln!(self.out, "{:04x}  {:indent$}{}", offset, "", text, indent = 2*depth);
+    }
+    fn framed(&mut self, bytes: &[u8], framing: Framing) -> Located<()> {
+        let contents = match framing {
+            Framing::Unframed => bytes,
+            Framing::LengthPrefixed => {
+                let length: usize = Cursor::new(bytes).parse_i32()
+                    .and_then(|length| Ok(length.try_into()?))
+                    .map_err(|e| (0, e))?;
+                self.line(0, 0, format_args!("length {}", length));
+                let end = 4 + length;
+                let contents = bytes.get(4..end).ok_or((4, eof()))?;
+                if end < bytes.len() {
+                    self.line(end, 0, format_args!("{} bytes after the packet", bytes.len() - end));
+                }
+                contents
+            },
+        };
+        let base = bytes.len() - contents.len();
+        self.packet(contents, base, 0)
+    }
+    /// Dump the packet contents occupying `bytes`, which start at offset `base`.
+    fn packet(&mut self, bytes: &[u8], base: usize, depth: usize) -> Located<()> {
+        let mut rd = Cursor::new(bytes);
+        let address = rd.parse_str().map_err(|e| (base, e))?;
+        if address == "#bundle" {
+            if DeserializerOptions::default().too_deep(depth) {
+                return Err((base, Error::DepthLimitExceeded));
+            }
+            self.line(base, depth, format_args!("#bundle"));
+            self.bundle(rd, base, depth)
+        } else {
+            self.line(base, depth, format_args!("address {:?}", address));
+            self.message(rd, base, depth)
+        }
+    }
+    fn bundle(&mut self, mut rd: Cursor<&[u8]>, base: usize, depth: usize) -> Located<()> {
+        let bytes = *rd.get_ref();
+        let offset = base + rd.position() as usize;
+        let (sec, frac) = rd.parse_timetag().map_err(|e| (offset, e))?;
+        self.line(offset, depth, format_args!("timetag {}:{}", sec, frac));
+        let mut index = 0;
+        while (rd.position() as usize) < bytes.len() {
+            let offset = base + rd.position() as usize;
+            let length: usize = rd.parse_i32()
+                .and_then(|length| Ok(length.try_into()?))
+                .map_err(|e| (offset, e))?;
+            self.line(offset, depth, format_args!("element {} ({} bytes)", index, length));
+            let start = rd.position() as usize;
+            let elem = bytes.get(start..start + length).ok_or((offset + 4, eof()))?;
+            self.packet(elem, base + start, depth + 1)?;
+            rd.set_position((start + length) as u64);
+            index += 1;
+        }
+        Ok(())
+    }
+    fn message(&mut self, rd: Cursor<&[u8]>, base: usize, depth: usize) -> Located<()> {
+        let len = rd.get_ref().len();
+        let offset = |remaining: u64| base + len - remaining as usize;
+        // Read the typetag separately, as the argument parser consumes it without a trace.
+        let typetag = rd.clone().read_0term_bytes().map(|tag| String::from_utf8_lossy(&tag).into_owned());
+        let remaining = len as u64 - rd.position();
+        let mut rd = IoRead(rd).take(remaining);
+        let typetag_offset = offset(rd.limit());
+        if let Ok(typetag) = typetag {
+            self.line(typetag_offset, depth, format_args!("typetag {:?}", typetag));
+        }
+        let mut args = ArgVisitor::new(&mut rd, DeserializerOptions::default())
+            .map_err(|e| (typetag_offset, e))?;
+        for index in 0.. {
+            let arg_offset = offset(args.remaining());
+            let arg: Value = match args.parse_next().map_err(|e| (arg_offset, e.into_kind()))? {
+                Some(arg) => arg.into(),
+                None => break,
+            };
+            let line = format!("arg {}: {} {}", index, arg.typetag(), arg);
+            self.line(arg_offset, depth, format_args!("{}", line.trim_end()));
+        }
+        let remaining = args.remaining();
+        if remaining != 0 {
+            self.line(offset(remaining), depth, format_args!("{} trailing bytes", remaining));
+        }
+        Ok(())
+    }
+}
@@ -19,12 +19,43 @@ pub enum Error {
     Message(String),
     /// Unknown argument type (i.e. not a 'f'=f32, 'i'=i32, etc)
     UnsupportedType,
+    /// A message address which isn't a valid OSC address (see `ser::AddressPolicy`).
+    BadAddress(String),
+    /// The address of a message (or "#bundle", for a bundle) isn't matched by
+    /// the expected pattern (see `de::from_read_matching`).
+    AddressMismatch(String),
+    /// An argument's typetag doesn't match the type it's deserialized into,
+    /// e.g. expected 'f' (for an `f32`), but found 'i'.
+    TypeMismatch { expected: char, found: char },
     /// Packet doesn't obey correct format; mismatched lengths, or
     /// attempt to read more arguments than were in the typestring (e.g.)
     BadFormat,
+    /// The message has this many more arguments than were deserialized (only
+    /// rejected with `de::TrailingArgs::Reject`).
+    TrailingArguments(usize),
+    /// A packet (or bundle element) whose contents end before its declared
+    /// length: only `consumed` of the `declared` bytes belong to its address,
+    /// typetag and arguments (or time-tag and elements). Only rejected in
+    /// strict mode; otherwise the surplus is skipped.
+    LengthMismatch { declared: usize, consumed: usize },
     /// OSC expects all data to be aligned to 4 bytes lengths.
     /// Likely violators of this are strings, especially those at the end of a packet.
     BadPadding,
+    /// A typetag which doesn't start with ',' (only rejected in strict mode;
+    /// see `de::DeserializerOptions`).
+    MissingTypetagComma,
+    /// A string which isn't ASCII (only rejected in strict mode).
+    NonAsciiString,
+    /// A `char` which isn't ASCII, serialized as a 'c' argument. Use
+    /// `types::UnicodeChar` to send its Unicode code point instead.
+    NonAsciiChar(char),
+    /// A packet (or one of its elements) is larger than the configured limit.
+    SizeLimitExceeded,
+    /// Bundles (or arrays) are nested more deeply than the configured limit.
+    DepthLimitExceeded,
+    /// A packet, bundle element or blob is too long for its `i32` length
+    /// prefix (i.e. longer than `i32::MAX` bytes), so it can't be encoded.
+    TooLarge,
     /// Error encountered due to `std::io::Read`
     Io(io::Error),
     /// Error converting between parsed type and what it represents.
@@ -33,6 +64,70 @@ pub enum Error {
     /// We store ascii strings as UTF-8.
     /// Technically, this is safe, but if we received non-ascii data, we could have invalid UTF-8
     StrParseError(string::FromUtf8Error),
+    /// One of the other errors, annotated with where in the packet it occurred.
+    /// Deserialization errors are reported this way whenever the location is known;
+    /// use [`kind`] to get at the underlying error.
+    ///
+    /// [`kind`]: #method.kind
+    At(Box<ErrorContext>),
+}
+
+/// Location within a packet at which deserialization failed.
+#[derive(Debug)]
+pub struct ErrorContext {
+    /// Byte offset at which decoding stopped, counted from the start of the
+    /// outermost packet's contents (i.e. excluding its length prefix, if any).
+    pub offset: u64,
+    /// Address of the message being decoded, if the error occurred inside a message.
+    pub address: Option<String>,
+    /// Zero-based index of the argument being decoded, if any.
+    pub arg_index: Option<usize>,
+    /// The underlying error.
+    pub error: Error,
+}
+
+impl Error {
+    /// Annotate `error` with the byte offset at which it occurred.
+    pub fn at(offset: u64, error: Error) -> Self {
+        error.with_context(|ctx| ctx.offset = offset)
+    }
+    /// The underlying error, with any location information stripped.
+    pub fn kind(&self) -> &Error {
+        match *self {
+            Error::At(ref ctx) => ctx.error.kind(),
+            ref other => other,
+        }
+    }
+    /// Like [`kind`], but takes ownership of the underlying error.
+    ///
+    /// [`kind`]: #method.kind
+    pub fn into_kind(self) -> Error {
+        match self {
+            Error::At(ctx) => ctx.error.into_kind(),
+            other => other,
+        }
+    }
+    /// Location at which the error occurred, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match *self {
+            Error::At(ref ctx) => Some(ctx),
+            _ => None,
+        }
+    }
+    /// Add to (or update) the location information of this error.
+    pub(crate) fn with_context<F: FnOnce(&mut ErrorContext)>(self, update: F) -> Self {
+        let mut ctx = match self {
+            Error::At(ctx) => ctx,
+            error => Box::new(ErrorContext {
+                offset: 0,
+                address: None,
+                arg_index: None,
+                error,
+            }),
+        };
+        update(&mut ctx);
+        Error::At(ctx)
+    }
 }
 
 
@@ -61,34 +156,54 @@ impl From<string::FromUtf8Error> for Error {
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Error::Message(ref msg) => write!(f, "serde_osc error: {}", msg),
-            Error::UnsupportedType => write!(f, "Unsupported OSC type"),
-            Error::BadFormat => write!(f, "Bad OSC packet format"),
-            Error::BadPadding => write!(f, "OSC data not padded to 4-byte boundary"),
-            Error::Io(ref err) => err.fmt(f),
-            Error::BadCast(ref err) => err.fmt(f),
-            Error::StrParseError(_) => write!(f, "OSC string contains illegal (non-ascii) characters"),
+            Error::Message(ref msg) => write!(f, "{}", msg),
+            Error::UnsupportedType => write!(f, "unsupported OSC argument type"),
+            Error::BadAddress(ref address) => write!(f, "invalid OSC address {:?}", address),
+            Error::AddressMismatch(ref address) => write!(f, "OSC address {:?} doesn't match the expected pattern", address),
+            Error::TypeMismatch { expected, found } => write!(f, "expected '{}', found '{}'", expected, found),
+            Error::BadFormat => write!(f, "malformed OSC packet (e.g. fewer arguments than expected)"),
+            Error::TrailingArguments(count) => write!(f, "{} OSC argument(s) left undeserialized", count),
+            Error::LengthMismatch { declared, consumed } =>
+                write!(f, "OSC packet declared as {} bytes long, but its contents end after {}", declared, consumed),
+            Error::BadPadding => write!(f, "invalid padding after OSC string or blob (data must be aligned to 4 bytes)"),
+            Error::MissingTypetagComma => write!(f, "OSC typetag doesn't start with ','"),
+            Error::NonAsciiString => write!(f, "OSC string contains non-ASCII characters"),
+            Error::NonAsciiChar(c) => write!(f, "non-ASCII character {:?} can't be sent as an OSC 'c' argument", c),
+            Error::SizeLimitExceeded => write!(f, "OSC data exceeds the size limit"),
+            Error::DepthLimitExceeded => write!(f, "OSC bundles or arrays are nested too deeply"),
+            Error::TooLarge => write!(f, "OSC packet or blob is too large for its length prefix (at most {} bytes)", i32::MAX),
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+            Error::BadCast(ref err) => write!(f, "OSC length or value out of range: {}", err),
+            Error::StrParseError(ref err) => write!(f, "OSC string isn't valid UTF-8: {}", err),
+            Error::At(ref ctx) => match (&ctx.address, ctx.arg_index, &ctx.error) {
+                // Name the offending argument up front, as in "argument 2 of /foo: expected 'f', found 'i'".
+                (Some(address), Some(index), Error::TypeMismatch { .. }) =>
+                    write!(f, "argument {} of {}: {} (at byte {})", index, address, ctx.error, ctx.offset),
+                _ => {
+                    write!(f, "{} (at byte {}", ctx.error, ctx.offset)?;
+                    if let Some(ref address) = ctx.address {
+                        write!(f, ", in message {}", address)?;
+                    }
+                    if let Some(index) = ctx.arg_index {
+                        write!(f, ", argument {}", index)?;
+                    }
+                    write!(f, ")")
+                },
+            },
         }
     }
 }
 
+/// The source of an I/O, cast or UTF-8 error is the underlying error from
+/// std. An error annotated with its location has the same source as the
+/// error it annotates (whose message is already part of its own).
 impl std::error::Error for Error {
-    fn description(&self) -> &str {
-        match *self {
-            Error::Message(ref msg) => msg,
-            Error::UnsupportedType => "Unsupported OSC type",
-            Error::BadFormat => "OSC argument count mismatch",
-            Error::BadPadding => "Incorrect OSC data padding",
-            Error::Io(ref io_error) => io_error.description(),
-            Error::BadCast(ref cast_error) => cast_error.description(),
-            Error::StrParseError(ref utf_error) => utf_error.description(),
-        }
-    }
-    fn cause(&self) -> Option<&std::error::Error> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
             Error::Io(ref io_error) => Some(io_error),
             Error::BadCast(ref cast_error) => Some(cast_error),
             Error::StrParseError(ref utf_error) => Some(utf_error),
+            Error::At(ref ctx) => ctx.error.source(),
             _ => None,
         }
     }
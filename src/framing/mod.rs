@@ -0,0 +1,19 @@
+/// SLIP framing for stream transports (OSC 1.1).
+pub mod slip;
+
+/// Describes how the boundaries of an OSC packet are communicated.
+///
+/// Stream-based transports (e.g. TCP in OSC 1.0) prefix each packet with its
+/// length as a big-endian `i32`. Packet-based transports like UDP have no such
+/// prefix: each datagram contains exactly one packet.
+///
+/// Note that this only affects the top-level packet; the elements within a
+/// bundle are always length-prefixed, per the OSC spec.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Framing {
+    /// The packet is preceded by its length, as an `i32`.
+    #[default]
+    LengthPrefixed,
+    /// The packet occupies the entirety of the data source/sink.
+    Unframed,
+}
@@ -0,0 +1,181 @@
+//! SLIP ([RFC 1055]) framing, as specified by OSC 1.1 for stream-based
+//! transports such as serial lines and TCP.
+//!
+//! Each packet is preceded and followed by an `END` byte; occurrences of
+//! `END` and `ESC` within the packet are replaced by two-byte escape sequences.
+//! Since the frame delimits the packet, the packet itself carries no length prefix.
+//!
+//! [RFC 1055]: https://tools.ietf.org/html/rfc1055
+use std::io::{ErrorKind, Read, Write};
+use serde;
+
+use de;
+use error::{Error, ResultE};
+use ser;
+
+/// Marks the start and end of a packet.
+pub const END: u8 = 0xC0;
+/// Marks the start of an escape sequence.
+pub const ESC: u8 = 0xDB;
+/// `ESC ESC_END` encodes a literal `END` byte.
+pub const ESC_END: u8 = 0xDC;
+/// `ESC ESC_ESC` encodes a literal `ESC` byte.
+pub const ESC_ESC: u8 = 0xDD;
+
+/// Append the SLIP encoding of `packet` (including both delimiters) to `output`.
+pub fn encode(packet: &[u8], output: &mut Vec<u8>) {
+    output.push(END);
+    for &byte in packet {
+        match byte {
+            END => output.extend_from_slice(&[ESC, ESC_END]),
+            ESC => output.extend_from_slice(&[ESC, ESC_ESC]),
+            _ => output.push(byte),
+        }
+    }
+    output.push(END);
+}
+
+/// Undo the escaping of a single frame's contents, i.e. the bytes between
+/// two `END` delimiters (exclusive).
+pub fn decode(frame: &[u8]) -> ResultE<Vec<u8>> {
+    let mut packet = Vec::with_capacity(frame.len());
+    let mut bytes = frame.iter();
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            END => return Err(Error::BadFormat),
+            ESC => match bytes.next() {
+                Some(&ESC_END) => packet.push(END),
+                Some(&ESC_ESC) => packet.push(ESC),
+                _ => return Err(Error::BadFormat),
+            },
+            _ => packet.push(byte),
+        }
+    }
+    Ok(packet)
+}
+
+/// Writes SLIP-framed OSC packets to the wrapped `Write` object.
+#[derive(Debug)]
+pub struct SlipWriter<W: Write> {
+    inner: W,
+    /// Scratch space to encode each packet before writing it in one go.
+    buf: Vec<u8>,
+}
+
+impl<W: Write> SlipWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+        }
+    }
+    /// Frame an already-serialized (unframed) OSC packet and write it.
+    pub fn write_packet(&mut self, packet: &[u8]) -> ResultE<()> {
+        self.buf.clear();
+        encode(packet, &mut self.buf);
+        Ok(self.inner.write_all(&self.buf)?)
+    }
+    /// Serialize `value` as an OSC packet and write it as one SLIP frame.
+    pub fn send<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + serde::ser::Serialize
+    {
+        let packet = ser::to_vec_unframed(value)?;
+        self.write_packet(&packet)
+    }
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Reads SLIP-framed OSC packets from the wrapped `Read` object.
+///
+/// Data is consumed one byte at a time, so wrapping an unbuffered source
+/// (e.g. a `TcpStream`) in a `std::io::BufReader` is recommended.
+#[derive(Debug)]
+pub struct SlipReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> SlipReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+    /// Read the next SLIP frame and return its decoded contents.
+    /// Empty frames (e.g. the back-to-back `END` bytes between two packets)
+    /// are skipped. Returns `None` if the stream ends between frames.
+    /// A frame containing an invalid escape sequence fails with `BadFormat`,
+    /// and is discarded, so that the next call returns the following frame.
+    pub fn read_packet(&mut self) -> ResultE<Option<Vec<u8>>> {
+        let mut packet = Vec::new();
+        loop {
+            let byte = match self.read_byte()? {
+                Some(byte) => byte,
+                // EOF is only clean if we're not in the middle of a packet.
+                None if packet.is_empty() => return Ok(None),
+                None => return Err(Error::Io(ErrorKind::UnexpectedEof.into())),
+            };
+            match byte {
+                END if packet.is_empty() => continue,
+                END => return Ok(Some(packet)),
+                ESC => match self.read_byte()? {
+                    Some(ESC_END) => packet.push(END),
+                    Some(ESC_ESC) => packet.push(ESC),
+                    // Drop the whole frame, so that its tail isn't mistaken for the next packet.
+                    Some(END) => return Err(Error::BadFormat),
+                    Some(_) => {
+                        self.skip_frame()?;
+                        return Err(Error::BadFormat);
+                    },
+                    None => return Err(Error::Io(ErrorKind::UnexpectedEof.into())),
+                },
+                _ => packet.push(byte),
+            }
+        }
+    }
+    /// Read the next SLIP frame and deserialize it as an OSC packet.
+    /// Returns `None` if the stream ends between frames.
+    pub fn recv<'de, T>(&mut self) -> ResultE<Option<T>>
+        where T: serde::de::Deserialize<'de>
+    {
+        match self.read_packet()? {
+            Some(packet) => de::from_read_unframed(&packet[..]).map(Some),
+            None => Ok(None),
+        }
+    }
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Discard the rest of the current frame, up to and including its `END`.
+    fn skip_frame(&mut self) -> ResultE<()> {
+        while let Some(byte) = self.read_byte()? {
+            if byte == END {
+                break;
+            }
+        }
+        Ok(())
+    }
+    fn read_byte(&mut self) -> ResultE<Option<u8>> {
+        let mut buf = [0u8; 1];
+        loop {
+            match self.inner.read(&mut buf) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(buf[0])),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {},
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Conversion of packets to and from `serde_json::Value`, for bridges which
+//! relay OSC to web clients (e.g. over a WebSocket).
+//!
+//! The mapping is lossless except for non-finite floats, and stable across
+//! versions of this crate:
+//!
+//! * A message is `{"address": "/synth/1", "args": [...]}`.
+//! * A bundle is `{"timetag": [seconds, fraction], "elements": [...]}`.
+//! * Each argument is `{"type": tag, "value": value}`, where `tag` is its
+//!   typetag character and `value` is:
+//!   * 'i', 'h', 'f', 'd': a number. Non-finite floats (which JSON can't
+//!     hold) become `null`, which converts back to NaN.
+//!   * 's', 'S': a string; 'c': a string of one character.
+//!   * 'b': the bytes, as a base64 string (standard alphabet, with padding).
+//!   * 'T', 'F': `true` or `false`; 'N': `null`.
+//!   * 'm': `[port, status, data1, data2]`; 'r': `[r, g, b, a]`.
+//!   * '[': an array of arguments, each mapped the same way.
+//!   * Any other tag (see `types::RawArg`): its raw data, as base64.
+//!
+//! When converting from JSON, an argument may also be given as a bare value,
+//! whose type is then inferred: integers become 'i' (or 'h' if they don't fit
+//! in 32 bits), other numbers 'f', strings 's', booleans 'T' or 'F', `null`
+//! 'N' and arrays '[' ... ']'.
+//!
+//! ```
+//! extern crate serde_json;
+//! # extern crate serde_osc;
+//! use serde_osc::json::{from_json_value, to_json_value};
+//! use serde_osc::value::Message;
+//!
+//! # fn main() {
+//! let packet = Message::to("/synth/1").arg(440.0f32).arg(vec![1u8, 2, 3]).build().into();
+//! let json = to_json_value(&packet);
+//! assert_eq!(json.to_string(), concat!(r#"{"address":"/synth/1","args":["#,
+//!     r#"{"type":"f","value":440.0},{"type":"b","value":"AQID"}]}"#));
+//!
+//! let from_browser: serde_json::Value = serde_json::from_str(r#"{"address":"/mute","args":[true,3]}"#).unwrap();
+//! let packet = from_json_value(&from_browser).unwrap();
+//! assert_eq!(packet, Message::to("/mute").arg(true).arg(3).build().into());
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use serde_json::{Map, Number};
+use serde_json::Value as Json;
+
+use error::{Error, ResultE};
+use types::{Color, MidiMessage, RawArg};
+use value::{Bundle, Message, Packet, Value};
+
+/// Convert a packet to JSON; see the [module documentation](index.html) for the mapping.
+pub fn to_json_value(packet: &Packet) -> Json {
+    let mut obj = Map::new();
+    match *packet {
+        Packet::Message(ref msg) => {
+            obj.insert("address".to_owned(), Json::from(msg.address.clone()));
+            obj.insert("args".to_owned(), Json::Array(msg.args.iter().map(arg_to_json).collect()));
+        },
+        Packet::Bundle(ref bundle) => {
+            obj.insert("timetag".to_owned(), Json::from(vec![bundle.timetag.0, bundle.timetag.1]));
+            obj.insert("elements".to_owned(), Json::Array(bundle.elements.iter().map(to_json_value).collect()));
+        },
+    }
+    Json::Object(obj)
+}
+
+/// Convert JSON back to a packet; see the [module documentation](index.html) for the mapping.
+///
+/// Fails with `Error::Message` if `json` doesn't follow the mapping (e.g. an
+/// argument whose value doesn't fit its type, or a blob which isn't valid base64).
+pub fn from_json_value(json: &Json) -> ResultE<Packet> {
+    let obj = json.as_object().ok_or_else(|| invalid("a packet must be an object"))?;
+    if let Some(address) = obj.get("address") {
+        let address = address.as_str().ok_or_else(|| invalid("\"address\" must be a string"))?;
+        let args = match obj.get("args") {
+            Some(args) => args.as_array().ok_or_else(|| invalid("\"args\" must be an array"))?
+                .iter().map(arg_from_json).collect::<ResultE<_>>()?,
+            None => Vec::new(),
+        };
+        Ok(Packet::Message(Message { address: address.to_owned(), args }))
+    } else if let Some(timetag) = obj.get("timetag") {
+        let timetag = match timetag.as_array().map(|t| t.as_slice()) {
+            Some([secs, frac]) => (u32_from_json(secs)?, u32_from_json(frac)?),
+            _ => return Err(invalid("\"timetag\" must be [seconds, fraction]")),
+        };
+        let elements = match obj.get("elements") {
+            Some(elements) => elements.as_array().ok_or_else(|| invalid("\"elements\" must be an array"))?
+                .iter().map(from_json_value).collect::<ResultE<_>>()?,
+            None => Vec::new(),
+        };
+        Ok(Packet::Bundle(Bundle { timetag, elements }))
+    } else {
+        Err(invalid("a packet needs an \"address\" or a \"timetag\""))
+    }
+}
+
+fn arg_to_json(arg: &Value) -> Json {
+    let value = match *arg {
+        Value::Int(i) => Json::from(i),
+        Value::Long(h) => Json::from(h),
+        // Via the shortest decimal representation, so that e.g. 0.1 stays 0.1.
+        Value::Float(f) => float_to_json(f.to_string().parse().unwrap_or(f64::NAN)),
+        Value::Double(d) => float_to_json(d),
+        Value::String(ref s) | Value::Symbol(ref s) => Json::from(s.clone()),
+        Value::Blob(ref b) => Json::from(base64_encode(b)),
+        Value::Bool(b) => Json::from(b),
+        Value::Nil => Json::Null,
+        Value::Char(c) => Json::from(c.to_string()),
+        Value::Midi(ref m) => Json::from(m.to_bytes().to_vec()),
+        Value::Color(ref c) => Json::from(c.to_bytes().to_vec()),
+        Value::Array(ref elems) => Json::Array(elems.iter().map(arg_to_json).collect()),
+        Value::Unknown(ref raw) => Json::from(base64_encode(&raw.data)),
+    };
+    let tag = match *arg {
+        Value::Array(_) => '[',
+        Value::Unknown(ref raw) => raw.tag,
+        _ => arg.typetag().chars().next().unwrap_or('N'),
+    };
+    let mut obj = Map::new();
+    obj.insert("type".to_owned(), Json::from(tag.to_string()));
+    obj.insert("value".to_owned(), value);
+    Json::Object(obj)
+}
+
+fn float_to_json(f: f64) -> Json {
+    Number::from_f64(f).map_or(Json::Null, Json::Number)
+}
+
+fn arg_from_json(json: &Json) -> ResultE<Value> {
+    let obj = match *json {
+        Json::Object(ref obj) => obj,
+        ref bare => return bare_arg_from_json(bare),
+    };
+    let tag = match obj.get("type").and_then(Json::as_str).map(|t| (t.chars().next(), t.chars().count())) {
+        Some((Some(tag), 1)) => tag,
+        _ => return Err(invalid("an argument's \"type\" must be a single typetag character")),
+    };
+    let value = obj.get("value").unwrap_or(&Json::Null);
+    let mismatch = || invalid(&format!("value {} doesn't fit type '{}'", value, tag));
+    Ok(match tag {
+        'i' => Value::Int(value.as_i64().and_then(|i| i32::try_from(i).ok()).ok_or_else(mismatch)?),
+        'h' => Value::Long(value.as_i64().ok_or_else(mismatch)?),
+        'f' => Value::Float(float_from_json(value).ok_or_else(mismatch)? as f32),
+        'd' => Value::Double(float_from_json(value).ok_or_else(mismatch)?),
+        's' => Value::String(value.as_str().ok_or_else(mismatch)?.to_owned()),
+        'S' => Value::Symbol(value.as_str().ok_or_else(mismatch)?.to_owned()),
+        'c' => {
+            let mut chars = value.as_str().ok_or_else(mismatch)?.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Value::Char(c),
+                _ => return Err(mismatch()),
+            }
+        },
+        'b' => Value::Blob(base64_decode(value.as_str().ok_or_else(mismatch)?)?),
+        'T' | 'F' => Value::Bool(tag == 'T'),
+        'N' => Value::Nil,
+        'm' => {
+            let [port, status, data1, data2] = four_bytes(value).ok_or_else(mismatch)?;
+            Value::Midi(MidiMessage { port, status, data1, data2 })
+        },
+        'r' => {
+            let [r, g, b, a] = four_bytes(value).ok_or_else(mismatch)?;
+            Value::Color(Color::new(r, g, b, a))
+        },
+        '[' => Value::Array(value.as_array().ok_or_else(mismatch)?
+            .iter().map(arg_from_json).collect::<ResultE<_>>()?),
+        tag if tag.is_ascii() && RawArg::valid_tag(tag as u8) => {
+            let data = base64_decode(value.as_str().ok_or_else(mismatch)?)?;
+            if !data.len().is_multiple_of(4) {
+                return Err(invalid(&format!("the data of type '{}' must be a multiple of 4 bytes", tag)));
+            }
+            Value::Unknown(RawArg::new(tag, data))
+        },
+        tag => return Err(invalid(&format!("unsupported type '{}'", tag))),
+    })
+}
+
+/// An argument given without its type.
+fn bare_arg_from_json(json: &Json) -> ResultE<Value> {
+    Ok(match *json {
+        Json::Null => Value::Nil,
+        Json::Bool(b) => Value::Bool(b),
+        Json::Number(ref n) => match n.as_i64() {
+            Some(i) => i32::try_from(i).map_or(Value::Long(i), Value::Int),
+            None => Value::Float(n.as_f64().ok_or_else(|| invalid(&format!("{} is out of range", n)))? as f32),
+        },
+        Json::String(ref s) => Value::String(s.clone()),
+        Json::Array(ref elems) => Value::Array(elems.iter().map(arg_from_json).collect::<ResultE<_>>()?),
+        Json::Object(_) => unreachable!("typed arguments are handled by arg_from_json"),
+    })
+}
+
+fn float_from_json(json: &Json) -> Option<f64> {
+    match *json {
+        Json::Null => Some(f64::NAN),
+        ref n => n.as_f64(),
+    }
+}
+
+fn u32_from_json(json: &Json) -> ResultE<u32> {
+    json.as_u64().and_then(|n| u32::try_from(n).ok())
+        .ok_or_else(|| invalid("time-tag parts must be 32-bit unsigned integers"))
+}
+
+fn four_bytes(json: &Json) -> Option<[u8; 4]> {
+    let mut bytes = [0; 4];
+    match json.as_array() {
+        Some(elems) if elems.len() == 4 => {
+            for (byte, elem) in bytes.iter_mut().zip(elems) {
+                *byte = elem.as_u64().and_then(|n| u8::try_from(n).ok())?;
+            }
+            Some(bytes)
+        },
+        _ => None,
+    }
+}
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (u32::from(chunk[0]) << 16)
+            | (u32::from(*chunk.get(1).unwrap_or(&0)) << 8)
+            | u32::from(*chunk.get(2).unwrap_or(&0));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> ResultE<Vec<u8>> {
+    let bad = || invalid(&format!("{:?} isn't valid base64", text));
+    let text = text.as_bytes();
+    if !text.len().is_multiple_of(4) {
+        return Err(bad());
+    }
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    for (index, chunk) in text.chunks(4).enumerate() {
+        let last = index + 1 == text.len() / 4;
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return Err(bad());
+        }
+        let mut n = 0u32;
+        for &c in &chunk[..4 - padding] {
+            let digit = BASE64_CHARS.iter().position(|&d| d == c).ok_or_else(bad)?;
+            n = (n << 6) | digit as u32;
+        }
+        n <<= 6 * padding;
+        out.extend_from_slice(&n.to_be_bytes()[1..4 - padding]);
+    }
+    Ok(out)
+}
+
+fn invalid(what: &str) -> Error {
+    Error::Message(format!("invalid OSC JSON: {}", what))
+}
@@ -18,8 +18,28 @@
 //! and one binary "blob" (`u8` array). The rest of the message is the payload,
 //! i.e., the values corresponding to each of these arguments.
 //! The full specification can be found at [http://opensoundcontrol.org/spec-1_0].
-//! Serde_osc implements version 1.0 of the spec (i.e. 'i', 'f', 's' and 'b'
-//! argument types).
+//! Serde_osc implements version 1.0 of the spec, along with the optional
+//! argument types from it that are in common use. Each typetag corresponds to
+//! these Rust types:
+//!
+//! | Typetag     | Rust type                                                         |
+//! |-------------|-------------------------------------------------------------------|
+//! | 'i'         | `i32` (as well as `i8`, `i16`, `u8`, `u16`, and `u32` if it fits) |
+//! | 'h'         | `i64` (and `u64` if it fits)                                      |
+//! | 'f'         | `f32`                                                             |
+//! | 'd'         | `f64`                                                             |
+//! | 's'         | `String` (or `&str` when serializing)                             |
+//! | 'S'         | [`types::Symbol`]                                                 |
+//! | 'b'         | `serde_bytes::ByteBuf` or `serde_bytes::Bytes`                    |
+//! | 'c'         | `char` (ASCII only), or [`types::UnicodeChar`]                    |
+//! | 'm'         | [`types::MidiMessage`]                                            |
+//! | 'r'         | [`types::Color`]                                                  |
+//! | 'T' and 'F' | `bool`                                                            |
+//! | 'N'         | `None` (or `()`)                                                  |
+//! | '[' ... ']' | a sequence, e.g. `Vec<T>` or `[T; N]`, of any of the above        |
+//!
+//! Arguments of any other type can be kept (and forwarded) as a
+//! [`types::RawArg`]; see `de::DeserializerOptions`.
 //!
 //! # Serde_osc usage
 //!
@@ -35,6 +55,11 @@
 //! [`serde_osc::to_vec`]: ser/fn.to_vec.html
 //! [`serde_osc::from_vec`]: de/fn.from_vec.html
 //! [http://opensoundcontrol.org/spec-1_0]: http://opensoundcontrol.org/spec-1_0
+//! [`types::Symbol`]: types/struct.Symbol.html
+//! [`types::UnicodeChar`]: types/struct.UnicodeChar.html
+//! [`types::MidiMessage`]: types/struct.MidiMessage.html
+//! [`types::Color`]: types/struct.Color.html
+//! [`types::RawArg`]: types/struct.RawArg.html
 //!
 //! # Examples
 //!
@@ -52,8 +77,9 @@
 //! use serde_osc::{de, ser};
 //!
 //! /// Struct we'll serialize.
-//! /// This represents a single OSC message with three arguments:
-//! ///   one of type 'i', 'f' and 'b', encoded in the order they appear in the struct.
+//! /// This represents a single OSC message with three arguments, of types
+//! /// 'i' (`i32`), 'f' (`f32`) and 'b' (`ByteBuf`), encoded in the order they
+//! /// appear in the struct. See the table above for the other types.
 //! #[derive(Debug, Deserialize, Serialize)]
 //! struct Message {
 //!     address: String,
@@ -83,13 +109,49 @@
 //!     println!("Received: {:?}", received);
 //! }
 //! ```
+//!
+//! For quick scripts, plain tuples work just as well as a derived struct: a
+//! message is an `(address, (args...))` pair. A message with a single argument
+//! still needs the one-element tuple `(T,)`, and one with no arguments `()`.
+//! A `Vec<u8>` is serialized as an array of integers; use a
+//! `serde_bytes::ByteBuf` to send a blob. Either can be deserialized into a
+//! `Vec<u8>`.
+//!
+//! ```
+//! # extern crate serde_osc;
+//! # fn main() {
+//! let packet = serde_osc::to_vec(&("/audio/play", (1, 44100.0f32, vec![0xdeu8, 0xad]))).unwrap();
+//! let (address, (id, rate, data)) = serde_osc::from_vec::<(String, (i32, f32, Vec<u8>))>(packet).unwrap();
+//! assert_eq!((address.as_str(), id, rate, data), ("/audio/play", 1, 44100.0, vec![0xde, 0xad]));
+//!
+//! let packet = serde_osc::to_vec(&("/volume", (0.5f32,))).unwrap();
+//! let (_, (volume,)): (String, (f32,)) = serde_osc::from_vec(packet).unwrap();
+//! assert_eq!(volume, 0.5);
+//! # }
+//! ```
 
 
 #![feature(try_from)]
 
 extern crate byteorder;
+#[cfg(feature = "chrono")]
+extern crate chrono;
 #[macro_use]
 extern crate serde;
+#[cfg(any(feature = "oscquery", feature = "json"))]
+extern crate serde_json;
+#[cfg(feature = "tokio")]
+extern crate bytes;
+#[cfg(feature = "tokio")]
+extern crate tokio_util;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "discovery")]
+extern crate mdns_sd;
+#[cfg(feature = "websocket")]
+extern crate tungstenite;
+#[cfg(feature = "memchr")]
+extern crate memchr;
 
 /// Errors returned upon serialization/deserialization failure.
 pub mod error;
@@ -97,6 +159,73 @@ pub mod error;
 pub mod de;
 /// OSC packet serialization framework.
 pub mod ser;
+/// Packet framing options.
+pub mod framing;
+/// OSC address patterns, and addresses split into typed segments.
+pub mod address;
+/// Helper types for OSC arguments which have no direct Rust equivalent.
+pub mod types;
+/// Named typetag characters, and the sizes of the arguments they denote.
+pub mod tags;
+/// Fixed-size arrays of any length as message arguments.
+pub mod array;
+/// Binding of argument types to a fixed OSC address, and bundles of uniform messages.
+pub mod message;
+/// Dynamic representation of OSC packets.
+pub mod value;
+/// Plain-text notation for OSC packets.
+pub mod text;
+/// Human-readable breakdown of raw packets.
+pub mod dump;
+/// Comparison of decoded packets, for test assertions.
+pub mod diff;
+/// Re-encoding of packets in strict OSC 1.0 form, so that equivalent packets are byte-for-byte equal.
+pub mod canonical;
+/// Per-argument typetags to encode messages with, for picky receivers.
+pub mod schema;
+/// Conversions between OSC time-tags and Rust time types.
+pub mod timetag;
+/// Releasing the messages of bundles when their time-tag is due.
+pub mod schedule;
+/// Limiting how often messages are sent to each address, keeping only the latest.
+pub mod throttle;
+/// Recording streams of packets to files, and replaying them with their original timing.
+pub mod recording;
+/// Dispatching of messages to handlers based on their address.
+pub mod router;
+/// Rewriting the addresses of packets, for proxies, without decoding their arguments.
+pub mod rewrite;
+/// Suppressing messages whose arguments haven't changed since they were last sent.
+pub mod cache;
+/// Hooks for watching the packets passed through serializers and deserializers.
+pub mod observe;
+/// Helpers for sending and receiving OSC packets over UDP.
+#[cfg(feature = "net")]
+pub mod net;
+/// Request/response exchanges with servers which reply to queries.
+#[cfg(feature = "net")]
+pub mod rpc;
+/// Sending packets over UDP to several named destinations.
+#[cfg(feature = "net")]
+pub mod sender;
+/// Receiving packets from several peers on one UDP socket, by sender.
+#[cfg(feature = "net")]
+pub mod demux;
+/// OSCQuery (JSON) descriptions of the messages a server accepts.
+#[cfg(feature = "oscquery")]
+pub mod oscquery;
+/// Conversion of packets to and from `serde_json::Value`.
+#[cfg(feature = "json")]
+pub mod json;
+/// `Encoder`/`Decoder` implementations for use with tokio's `Framed`.
+#[cfg(feature = "tokio")]
+pub mod codec;
+/// mDNS (Bonjour/Zeroconf) advertisement and browsing of OSC services.
+#[cfg(feature = "discovery")]
+pub mod discovery;
+/// Sending and receiving packets as binary WebSocket messages.
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
-pub use de::{from_read, from_slice};
-pub use ser::{to_write, to_vec};
+pub use de::{from_read, from_slice, from_vec};
+pub use ser::{to_write, to_slice, to_vec};
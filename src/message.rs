@@ -0,0 +1,189 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use serde::de::{Deserialize, Deserializer, Error, SeqAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer, SerializeTuple};
+
+use timetag::TimeTag;
+
+/// Types which represent the arguments of a message sent to a fixed OSC address.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_osc;
+/// use serde_osc::message::{Addressed, Message};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Play {
+///     freq: f32,
+/// }
+/// impl Addressed for Play {
+///     const ADDRESS: &'static str = "/audio/play";
+/// }
+///
+/// # fn main() {
+/// let packet = serde_osc::to_vec(&Message(Play { freq: 440.0 })).unwrap();
+/// let received: Message<Play> = serde_osc::from_slice(&packet).unwrap();
+/// assert_eq!(received.freq, 440.0);
+/// # }
+/// ```
+pub trait Addressed {
+    const ADDRESS: &'static str;
+}
+
+/// Wraps a value whose type implements `Addressed`, so that it (de)serializes
+/// as a full OSC message: the address is taken from `T::ADDRESS`, and the
+/// fields of `T` become the message arguments.
+///
+/// Deserialization fails if the received address differs from `T::ADDRESS`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Message<T>(pub T);
+
+impl<T> Message<T> {
+    pub fn new(args: T) -> Self {
+        Message(args)
+    }
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Addressed> Message<T> {
+    pub fn address(&self) -> &'static str {
+        T::ADDRESS
+    }
+}
+
+impl<T> Deref for Message<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Message<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> Serialize for Message<T>
+    where T: Addressed + Serialize
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tup = serializer.serialize_tuple(2)?;
+        tup.serialize_element(T::ADDRESS)?;
+        tup.serialize_element(&self.0)?;
+        tup.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Message<T>
+    where T: Addressed + Deserialize<'de>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(2, MessageVisitor(PhantomData))
+    }
+}
+
+struct MessageVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for MessageVisitor<T>
+    where T: Addressed + Deserialize<'de>
+{
+    type Value = Message<T>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an OSC message addressed to {}", T::ADDRESS)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let address: String = seq.next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        if address != T::ADDRESS {
+            return Err(A::Error::invalid_value(Unexpected::Str(&address), &T::ADDRESS));
+        }
+        let args = seq.next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        Ok(Message(args))
+    }
+}
+
+/// A bundle of messages which all deserialize into the same type, e.g. the
+/// bulk state updates of a controller, with its time-tag.
+///
+/// The messages may be `Message`s of an `Addressed` type, an enum whose
+/// variants are selected by address, or `(address, args)` pairs. Nested
+/// bundles can't be held, unless `T` can hold them. To ignore the time-tag,
+/// take just the `messages`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_osc;
+/// use serde_osc::message::Bundle;
+/// use serde_osc::timetag::TimeTag;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// enum Fader {
+///     #[serde(rename = "/fader/1")]
+///     One(f32),
+///     #[serde(rename = "/fader/2")]
+///     Two(f32),
+/// }
+///
+/// # fn main() {
+/// let bundle = Bundle::new(TimeTag::Immediate, vec![Fader::One(0.5), Fader::Two(0.25)]);
+/// let packet = serde_osc::to_vec(&bundle).unwrap();
+/// let received: Bundle<Fader> = serde_osc::from_slice(&packet).unwrap();
+/// assert_eq!(received.messages, vec![Fader::One(0.5), Fader::Two(0.25)]);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bundle<T> {
+    /// When the messages are to be handled.
+    pub time: TimeTag,
+    pub messages: Vec<T>,
+}
+
+impl<T> Bundle<T> {
+    pub fn new(time: TimeTag, messages: Vec<T>) -> Self {
+        Bundle { time, messages }
+    }
+}
+
+impl<T> Serialize for Bundle<T>
+    where T: Serialize
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tup = serializer.serialize_tuple(2)?;
+        tup.serialize_element(&self.time)?;
+        tup.serialize_element(&self.messages)?;
+        tup.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Bundle<T>
+    where T: Deserialize<'de>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(2, BundleVisitor(PhantomData))
+    }
+}
+
+struct BundleVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for BundleVisitor<T>
+    where T: Deserialize<'de>
+{
+    type Value = Bundle<T>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an OSC bundle")
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let time = seq.next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let messages = seq.next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        Ok(Bundle { time, messages })
+    }
+}
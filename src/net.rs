@@ -0,0 +1,130 @@
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, ToSocketAddrs, UdpSocket};
+use serde;
+
+use de;
+use error::ResultE;
+use ser;
+
+/// Largest payload a single UDP datagram can carry (over IPv4).
+pub const MAX_DATAGRAM_SIZE: usize = 65507;
+
+/// Thin wrapper over `std::net::UdpSocket` which sends and receives
+/// serde-compatible values as OSC packets.
+///
+/// When OSC is transported over UDP, each datagram holds exactly one packet,
+/// so the datagram size stands in for the 4-byte length prefix used by
+/// stream transports. Packets are therefore sent and received unframed.
+#[derive(Debug)]
+pub struct OscUdpSocket {
+    socket: UdpSocket,
+    /// Receive buffer, large enough for any datagram.
+    buf: Vec<u8>,
+}
+
+impl OscUdpSocket {
+    /// Create a socket bound to the given local address.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> ResultE<Self> {
+        Ok(Self::from_socket(UdpSocket::bind(addr)?))
+    }
+    /// Wrap an already-configured `UdpSocket`.
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            buf: vec![0; MAX_DATAGRAM_SIZE],
+        }
+    }
+    /// Access the underlying socket, e.g. to set timeouts or query the local address.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+    /// Unwrap this object, returning the underlying socket.
+    pub fn into_inner(self) -> UdpSocket {
+        self.socket
+    }
+
+    /// Serialize `value` as an OSC packet and send it to `addr` as a single datagram.
+    /// Returns the number of bytes sent.
+    pub fn send_to<T, A>(&self, value: &T, addr: A) -> ResultE<usize>
+        where T: ?Sized + serde::ser::Serialize, A: ToSocketAddrs
+    {
+        let packet = ser::to_vec_unframed(value)?;
+        Ok(self.socket.send_to(&packet, addr)?)
+    }
+
+    /// Send `value` to every host on the local network listening on `port`
+    /// (the limited broadcast address, 255.255.255.255), enabling broadcast
+    /// on the socket first. Returns the number of bytes sent.
+    pub fn broadcast_to<T>(&self, value: &T, port: u16) -> ResultE<usize>
+        where T: ?Sized + serde::ser::Serialize
+    {
+        self.socket.set_broadcast(true)?;
+        self.send_to(value, SocketAddrV4::new(Ipv4Addr::BROADCAST, port))
+    }
+    /// Allow (or forbid) sending to broadcast addresses, e.g. 192.168.1.255.
+    pub fn set_broadcast(&self, broadcast: bool) -> ResultE<()> {
+        Ok(self.socket.set_broadcast(broadcast)?)
+    }
+
+    /// Create a socket which receives the packets sent to the IPv4 multicast
+    /// `group` on `port`, through the default interface.
+    pub fn bind_multicast_v4(group: Ipv4Addr, port: u16) -> ResultE<Self> {
+        let socket = Self::bind((Ipv4Addr::UNSPECIFIED, port))?;
+        socket.join_multicast_v4(group, Ipv4Addr::UNSPECIFIED)?;
+        Ok(socket)
+    }
+    /// Receive the packets sent to the IPv4 multicast `group`, through the
+    /// interface with the given address (`Ipv4Addr::UNSPECIFIED` for the default).
+    pub fn join_multicast_v4(&self, group: Ipv4Addr, interface: Ipv4Addr) -> ResultE<()> {
+        Ok(self.socket.join_multicast_v4(&group, &interface)?)
+    }
+    /// Stop receiving the packets sent to the IPv4 multicast `group`.
+    pub fn leave_multicast_v4(&self, group: Ipv4Addr, interface: Ipv4Addr) -> ResultE<()> {
+        Ok(self.socket.leave_multicast_v4(&group, &interface)?)
+    }
+    /// Receive the packets sent to the IPv6 multicast `group`, through the
+    /// interface with the given index (0 for the default).
+    pub fn join_multicast_v6(&self, group: Ipv6Addr, interface: u32) -> ResultE<()> {
+        Ok(self.socket.join_multicast_v6(&group, interface)?)
+    }
+    /// Stop receiving the packets sent to the IPv6 multicast `group`.
+    pub fn leave_multicast_v6(&self, group: Ipv6Addr, interface: u32) -> ResultE<()> {
+        Ok(self.socket.leave_multicast_v6(&group, interface)?)
+    }
+    /// Set how many routers IPv4 multicast packets may cross (1, the
+    /// default, keeps them on the local network).
+    pub fn set_multicast_ttl(&self, ttl: u32) -> ResultE<()> {
+        Ok(self.socket.set_multicast_ttl_v4(ttl)?)
+    }
+    /// Set whether IPv4 multicast packets sent from this socket are also
+    /// received by the sending host (they are by default).
+    pub fn set_multicast_loop(&self, multicast_loop: bool) -> ResultE<()> {
+        Ok(self.socket.set_multicast_loop_v4(multicast_loop)?)
+    }
+    /// Set the time-to-live of unicast packets sent from this socket.
+    pub fn set_ttl(&self, ttl: u32) -> ResultE<()> {
+        Ok(self.socket.set_ttl(ttl)?)
+    }
+
+    /// Block until a datagram arrives, and deserialize it as an OSC packet.
+    /// Returns the decoded value along with the address of the sender.
+    pub fn recv_from<'de, T>(&mut self) -> ResultE<(T, SocketAddr)>
+        where T: serde::de::Deserialize<'de>
+    {
+        let (packet, from) = self.recv_datagram()?;
+        let value = de::from_read_unframed(packet)?;
+        Ok((value, from))
+    }
+
+    /// Block until a datagram arrives, and return its (undecoded) contents
+    /// along with the address of the sender.
+    pub(crate) fn recv_datagram(&mut self) -> ResultE<(&[u8], SocketAddr)> {
+        let (size, from) = self.socket.recv_from(&mut self.buf)?;
+        Ok((&self.buf[..size], from))
+    }
+}
+
+impl From<UdpSocket> for OscUdpSocket {
+    fn from(socket: UdpSocket) -> Self {
+        Self::from_socket(socket)
+    }
+}
@@ -0,0 +1,38 @@
+/// Summary of a packet passed through a serializer or deserializer, as handed
+/// to an [`Observer`].
+///
+/// [`Observer`]: type.Observer.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PacketSummary<'a> {
+    /// The message address, or "#bundle" for a bundle.
+    pub address: &'a str,
+    /// The type of each argument, without the leading ','; empty for a bundle.
+    pub typetag: &'a str,
+    /// Size of the packet contents, excluding the length prefix (if any).
+    pub size: usize,
+}
+
+/// A function called once for every packet that is serialized (after it has
+/// been written) or deserialized (after it has been decoded), e.g. to count
+/// traffic per address. Packets which fail aren't reported, and nor are the
+/// elements of a bundle, only the bundle itself.
+///
+/// Set with `ser::Serializer::with_observer` or `de::Deserializer::with_observer`.
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use serde_osc::observe::PacketSummary;
+/// use serde_osc::ser::Serializer;
+///
+/// static BYTES_SENT: AtomicUsize = AtomicUsize::new(0);
+///
+/// fn count(packet: &PacketSummary) {
+///     BYTES_SENT.fetch_add(packet.size, Ordering::Relaxed);
+/// }
+///
+/// let mut output = Vec::new();
+/// let mut ser = Serializer::new(&mut output).with_observer(count);
+/// serde::Serialize::serialize(&("/note", (60, 0.5f32)), &mut ser).unwrap();
+/// assert_eq!(BYTES_SENT.load(Ordering::Relaxed), output.len() - 4);
+/// ```
+pub type Observer = fn(&PacketSummary);
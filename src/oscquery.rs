@@ -0,0 +1,308 @@
+//! Descriptions of an OSC namespace in the [OSCQuery] JSON format, which
+//! hosts such as VDMX and Vezér use to discover the messages a server accepts.
+//!
+//! A [`Namespace`] is a flat list of [`Method`]s; when encoded, their
+//! addresses are arranged into the tree of `CONTENTS` nodes that OSCQuery
+//! expects. Only the attributes describing messages (`TYPE`, `RANGE`,
+//! `ACCESS` and `DESCRIPTION`) are supported; others are ignored when parsing.
+//! Ranges are listed per argument, in typetag order; arrays ('[' and ']')
+//! aren't given ranges of their own.
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_osc;
+//! use serde_osc::message::Addressed;
+//! use serde_osc::oscquery::{Method, Namespace, Range};
+//!
+//! #[derive(Serialize)]
+//! struct Gain {
+//!     level: f32,
+//! }
+//! impl Addressed for Gain {
+//!     const ADDRESS: &'static str = "/mixer/gain";
+//! }
+//!
+//! # fn main() {
+//! let mut gain = Method::of(&Gain { level: 0.0 }).unwrap();
+//! gain.ranges.push(Range { min: Some(0.0), max: Some(1.0), ..Range::default() });
+//! let namespace = Namespace { methods: vec![gain] };
+//!
+//! let json = namespace.to_json();
+//! assert_eq!(Namespace::from_json(&json).unwrap(), namespace);
+//! # }
+//! ```
+//!
+//! [OSCQuery]: https://github.com/Vidvox/OSCQueryProposal
+//! [`Namespace`]: struct.Namespace.html
+//! [`Method`]: struct.Method.html
+
+use std::convert::TryFrom;
+use serde::Serialize;
+use serde_json::{self, Map, Number};
+use serde_json::Value as Json;
+
+use de::{DeserializerOptions, MessageHeader};
+use error::{Error, ResultE};
+use message::Addressed;
+use ser;
+use value::Value;
+
+/// The messages accepted by a server.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Namespace {
+    pub methods: Vec<Method>,
+}
+
+/// A single OSC method: the address messages are sent to, and the arguments they carry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Method {
+    pub address: String,
+    /// The type of each argument, without a leading ',', e.g. "if".
+    pub typetag: String,
+    /// The range of each argument, in order. May be shorter than the typetag,
+    /// if the last arguments are unconstrained.
+    pub ranges: Vec<Range>,
+    pub access: Access,
+    pub description: Option<String>,
+}
+
+/// The values an argument may take.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Range {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// The only values allowed, if the argument is an enumeration.
+    pub values: Vec<Value>,
+}
+
+/// Whether the value of a method may be queried, set (i.e. sent to), or both.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Access {
+    /// Neither; the node only groups other nodes.
+    None,
+    /// The current value can be queried, but not set.
+    ReadOnly,
+    /// Messages can be sent to the method, but its value can't be queried.
+    #[default]
+    WriteOnly,
+    ReadWrite,
+}
+
+/// Name under which the OSCQuery attributes are written, and the child nodes listed.
+const CONTENTS: &str = "CONTENTS";
+
+impl Method {
+    /// A write-only method, without ranges or a description.
+    pub fn new<A: Into<String>, T: Into<String>>(address: A, typetag: T) -> Self {
+        Self {
+            address: address.into(),
+            typetag: typetag.into(),
+            ranges: Vec::new(),
+            access: Access::default(),
+            description: None,
+        }
+    }
+    /// Describe the message `T`, taking the typetag from `example`.
+    pub fn of<T: Addressed + Serialize>(example: &T) -> ResultE<Self> {
+        let packet = ser::to_vec_unframed(&(T::ADDRESS, example))?;
+        let header = MessageHeader::parse(&packet, DeserializerOptions::default())?;
+        Ok(Self::new(header.address, header.typetag))
+    }
+}
+
+impl Access {
+    fn to_code(self) -> u64 {
+        match self {
+            Access::None => 0,
+            Access::ReadOnly => 1,
+            Access::WriteOnly => 2,
+            Access::ReadWrite => 3,
+        }
+    }
+    fn from_code(code: u64) -> ResultE<Self> {
+        match code {
+            0 => Ok(Access::None),
+            1 => Ok(Access::ReadOnly),
+            2 => Ok(Access::WriteOnly),
+            3 => Ok(Access::ReadWrite),
+            _ => Err(invalid(format!("ACCESS {}", code))),
+        }
+    }
+}
+
+/// An error describing a malformed OSCQuery document.
+fn invalid(what: String) -> Error {
+    Error::Message(format!("invalid OSCQuery namespace: {}", what))
+}
+
+/// The tags of the arguments which can be given a range.
+fn arg_tags(typetag: &str) -> impl Iterator<Item=char> + '_ {
+    typetag.chars().filter(|&c| c != '[' && c != ']')
+}
+
+impl Namespace {
+    /// Encode the namespace as the OSCQuery JSON tree, rooted at "/".
+    pub fn to_json(&self) -> String {
+        let mut root = node("/");
+        for method in &self.methods {
+            let mut current = &mut root;
+            let mut path = String::new();
+            for part in method.address.split('/').filter(|part| !part.is_empty()) {
+                path.push('/');
+                path.push_str(part);
+                let contents = current.entry(CONTENTS).or_insert_with(|| Json::Object(Map::new()));
+                current = match *contents.as_object_mut().expect("CONTENTS is always an object")
+                    .entry(part).or_insert_with(|| Json::Object(node(&path))) {
+                    Json::Object(ref mut child) => child,
+                    _ => unreachable!("nodes are always objects"),
+                };
+            }
+            method.describe(current);
+        }
+        Json::Object(root).to_string()
+    }
+    /// Parse an OSCQuery JSON tree. Every node which has a `TYPE` becomes a method.
+    pub fn from_json(json: &str) -> ResultE<Self> {
+        let root: Json = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        let mut methods = Vec::new();
+        collect(&root, "", &mut methods)?;
+        Ok(Self { methods })
+    }
+}
+
+/// An empty node at the given address.
+fn node(path: &str) -> Map<String, Json> {
+    let mut node = Map::new();
+    node.insert("FULL_PATH".to_owned(), Json::String(path.to_owned()));
+    node
+}
+
+impl Method {
+    /// Write the attributes of the method into its node.
+    fn describe(&self, node: &mut Map<String, Json>) {
+        node.insert("TYPE".to_owned(), Json::String(self.typetag.clone()));
+        node.insert("ACCESS".to_owned(), Json::from(self.access.to_code()));
+        if !self.ranges.is_empty() {
+            let ranges = self.ranges.iter().zip(arg_tags(&self.typetag))
+                .map(|(range, tag)| range.to_json(tag))
+                .collect();
+            node.insert("RANGE".to_owned(), Json::Array(ranges));
+        }
+        if let Some(ref description) = self.description {
+            node.insert("DESCRIPTION".to_owned(), Json::String(description.clone()));
+        }
+    }
+    /// Read the method described by a node, given its address.
+    fn parse(node: &Map<String, Json>, address: String) -> ResultE<Self> {
+        let typetag = match node.get("TYPE") {
+            Some(Json::String(typetag)) => typetag.trim_start_matches(',').to_owned(),
+            _ => return Err(invalid(format!("TYPE of {}", address))),
+        };
+        let access = match node.get("ACCESS") {
+            None => Access::default(),
+            Some(access) => access.as_u64().ok_or_else(|| invalid(format!("ACCESS of {}", address)))
+                .and_then(Access::from_code)?,
+        };
+        let ranges = match node.get("RANGE") {
+            None | Some(&Json::Null) => Vec::new(),
+            Some(Json::Array(ranges)) => ranges.iter().zip(arg_tags(&typetag))
+                .map(|(range, tag)| Range::parse(range, tag))
+                .collect::<Option<_>>()
+                .ok_or_else(|| invalid(format!("RANGE of {}", address)))?,
+            Some(_) => return Err(invalid(format!("RANGE of {}", address))),
+        };
+        let description = node.get("DESCRIPTION").and_then(Json::as_str).map(str::to_owned);
+        Ok(Self { address, typetag, ranges, access, description })
+    }
+}
+
+/// Gather the methods within `node` (which is at `path`) and its children.
+fn collect(node: &Json, path: &str, methods: &mut Vec<Method>) -> ResultE<()> {
+    let node = node.as_object().ok_or_else(|| invalid(format!("node {}", path)))?;
+    // Prefer the node's own idea of its path, if present.
+    let path = match node.get("FULL_PATH").and_then(Json::as_str) {
+        Some(full_path) => full_path.to_owned(),
+        None => path.to_owned(),
+    };
+    if node.contains_key("TYPE") {
+        methods.push(Method::parse(node, path.clone())?);
+    }
+    if let Some(contents) = node.get(CONTENTS) {
+        let contents = contents.as_object().ok_or_else(|| invalid(format!("CONTENTS of {}", path)))?;
+        for (name, child) in contents {
+            collect(child, &format!("{}/{}", path.trim_end_matches('/'), name), methods)?;
+        }
+    }
+    Ok(())
+}
+
+impl Range {
+    /// Encode the range of an argument with the given tag.
+    fn to_json(&self, tag: char) -> Json {
+        let mut range = Map::new();
+        // Integer bounds are written as integers.
+        let bound = |x: f64| match tag {
+            'i' | 'h' if x.fract() == 0.0 => Json::from(x as i64),
+            _ => Number::from_f64(x).map_or(Json::Null, Json::Number),
+        };
+        if let Some(min) = self.min {
+            range.insert("MIN".to_owned(), bound(min));
+        }
+        if let Some(max) = self.max {
+            range.insert("MAX".to_owned(), bound(max));
+        }
+        if !self.values.is_empty() {
+            range.insert("VALS".to_owned(), Json::Array(self.values.iter().filter_map(value_to_json).collect()));
+        }
+        Json::Object(range)
+    }
+    /// Parse the range of an argument with the given tag.
+    /// `null` stands for an unconstrained argument.
+    fn parse(range: &Json, tag: char) -> Option<Self> {
+        let range = match *range {
+            Json::Null => return Some(Self::default()),
+            Json::Object(ref range) => range,
+            _ => return None,
+        };
+        let bound = |key| match range.get(key) {
+            None | Some(&Json::Null) => Some(None),
+            Some(bound) => bound.as_f64().map(Some),
+        };
+        let values = match range.get("VALS") {
+            None => Vec::new(),
+            Some(Json::Array(values)) => values.iter()
+                .map(|value| value_from_json(value, tag))
+                .collect::<Option<_>>()?,
+            Some(_) => return None,
+        };
+        Some(Self { min: bound("MIN")?, max: bound("MAX")?, values })
+    }
+}
+
+/// The JSON form of an argument value, if it has one.
+fn value_to_json(value: &Value) -> Option<Json> {
+    match *value {
+        Value::Int(i) => Some(Json::from(i)),
+        Value::Long(h) => Some(Json::from(h)),
+        Value::Float(f) => Number::from_f64(f.into()).map(Json::Number),
+        Value::Double(d) => Number::from_f64(d).map(Json::Number),
+        Value::String(ref s) | Value::Symbol(ref s) => Some(Json::String(s.clone())),
+        Value::Bool(b) => Some(Json::Bool(b)),
+        _ => None,
+    }
+}
+
+/// The argument value of a JSON value, for an argument with the given tag.
+fn value_from_json(json: &Json, tag: char) -> Option<Value> {
+    match (tag, json) {
+        ('i', _) => json.as_i64().and_then(|i| i32::try_from(i).ok()).map(Value::Int),
+        ('h', _) => json.as_i64().map(Value::Long),
+        ('f', _) => json.as_f64().map(|f| Value::Float(f as f32)),
+        ('d', _) => json.as_f64().map(Value::Double),
+        ('s', Json::String(s)) => Some(Value::String(s.clone())),
+        ('S', Json::String(s)) => Some(Value::Symbol(s.clone())),
+        ('T', &Json::Bool(b)) | ('F', &Json::Bool(b)) => Some(Value::Bool(b)),
+        _ => None,
+    }
+}
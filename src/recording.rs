@@ -0,0 +1,242 @@
+//! A recording is a file of timestamped packets: each entry is the time-tag
+//! at which the packet was recorded, followed by the packet itself, with a
+//! length prefix. Like a MIDI file, it lets a session with a live rig be
+//! captured and replayed later, e.g. while debugging.
+//!
+//! A [`Recorder`] stamps each packet with the time from its [`Clock`]; a
+//! [`Player`] releases them again with the same spacing, starting from when
+//! playback begins.
+//!
+//! ```
+//! # extern crate serde_osc;
+//! use std::cell::Cell;
+//! use std::io::Cursor;
+//! use serde_osc::recording::{Player, Recorder};
+//!
+//! # fn main() {
+//! let time = Cell::new((100, 0));
+//! let mut recorder = Recorder::with_clock(Vec::new(), || time.get());
+//! recorder.record(&("/play", ())).unwrap();
+//! time.set((102, 0));
+//! recorder.record(&("/stop", ())).unwrap();
+//!
+//! time.set((500, 0));
+//! let mut player = Player::with_clock(Cursor::new(recorder.into_inner()), || time.get());
+//! let packet = player.pop_due().unwrap().unwrap();
+//! assert_eq!(serde_osc::de::from_slice_unframed::<(String, ())>(&packet).unwrap().0, "/play");
+//! assert_eq!(player.pop_due().unwrap(), None);
+//! time.set((502, 0));
+//! assert!(player.pop_due().unwrap().is_some());
+//! # }
+//! ```
+//!
+//! [`Recorder`]: struct.Recorder.html
+//! [`Player`]: struct.Player.html
+//! [`Clock`]: ../schedule/trait.Clock.html
+
+use std::convert::TryInto;
+use std::io::{ErrorKind, Read, Write};
+use std::thread;
+use std::time::Duration;
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use serde::ser::Serialize;
+
+use error::{Error, ResultE};
+use schedule::{Clock, SystemClock};
+use ser;
+use timetag::{self, from_fixed, to_fixed};
+
+/// A packet of a recording, and the time at which it was recorded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub timetag: (u32, u32),
+    /// The packet, without its length prefix.
+    pub packet: Vec<u8>,
+}
+
+/// Read the next entry of a recording, or return `None` on a clean EOF.
+/// EOF in the middle of an entry is reported as an error.
+pub fn read_entry<R: Read>(read: &mut R) -> ResultE<Option<Entry>> {
+    // Time-tag and length prefix.
+    let mut header = [0u8; 12];
+    let mut n_read = 0;
+    while n_read < header.len() {
+        match read.read(&mut header[n_read..]) {
+            Ok(0) if n_read == 0 => return Ok(None),
+            Ok(0) => return Err(Error::Io(ErrorKind::UnexpectedEof.into())),
+            Ok(n) => n_read += n,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let timetag = (BigEndian::read_u32(&header[0..4]), BigEndian::read_u32(&header[4..8]));
+    let length = BigEndian::read_u32(&header[8..12]);
+    // Don't trust the length for an allocation up front.
+    let mut packet = Vec::new();
+    read.by_ref().take(u64::from(length)).read_to_end(&mut packet)?;
+    if packet.len() != length as usize {
+        return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(Some(Entry { timetag, packet }))
+}
+
+/// Writes packets to a recording, stamped with the time at which they're recorded.
+#[derive(Debug)]
+pub struct Recorder<W: Write, C: Clock = SystemClock> {
+    write: W,
+    clock: C,
+    /// Reused to assemble each entry, so that it's written all at once.
+    buf: Vec<u8>,
+}
+
+impl<W: Write> Recorder<W, SystemClock> {
+    /// Record according to the system clock.
+    pub fn new(write: W) -> Self {
+        Self::with_clock(write, SystemClock)
+    }
+}
+
+impl<W: Write, C: Clock> Recorder<W, C> {
+    pub fn with_clock(write: W, clock: C) -> Self {
+        Self {
+            write,
+            clock,
+            buf: Vec::new(),
+        }
+    }
+    /// Serialize `packet`, and record it.
+    pub fn record<T: ?Sized + Serialize>(&mut self, packet: &T) -> ResultE<()> {
+        self.begin_entry()?;
+        ser::to_write(&mut self.buf, packet)?;
+        self.end_entry()
+    }
+    /// Record a packet which is already serialized (without a length
+    /// prefix), e.g. a datagram as received.
+    pub fn record_raw(&mut self, packet: &[u8]) -> ResultE<()> {
+        self.begin_entry()?;
+        self.buf.write_u32::<BigEndian>(packet.len().try_into()?)?;
+        self.buf.extend_from_slice(packet);
+        self.end_entry()
+    }
+    fn begin_entry(&mut self) -> ResultE<()> {
+        let (sec, frac) = self.clock.now();
+        self.buf.clear();
+        self.buf.write_u32::<BigEndian>(sec)?;
+        self.buf.write_u32::<BigEndian>(frac)?;
+        Ok(())
+    }
+    fn end_entry(&mut self) -> ResultE<()> {
+        self.write.write_all(&self.buf)?;
+        Ok(())
+    }
+    pub fn get_ref(&self) -> &W {
+        &self.write
+    }
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.write
+    }
+    pub fn into_inner(self) -> W {
+        self.write
+    }
+}
+
+/// Replays the packets of a recording with their original timing.
+///
+/// Playback begins when the first entry is read, i.e. upon the first call to
+/// any of the methods below; each later packet is due as long after this as
+/// it was recorded after the first. Like a [`Scheduler`], poll [`pop_due`]
+/// (e.g. after sleeping for [`time_until_next`]), or let [`wait_next`] sleep.
+///
+/// After any error, playback stops.
+///
+/// [`Scheduler`]: ../schedule/struct.Scheduler.html
+/// [`pop_due`]: #method.pop_due
+/// [`time_until_next`]: #method.time_until_next
+/// [`wait_next`]: #method.wait_next
+#[derive(Debug)]
+pub struct Player<R: Read, C: Clock = SystemClock> {
+    read: R,
+    clock: C,
+    /// Playback time minus recorded time, as a fixed point number.
+    offset: Option<u64>,
+    next: Option<Entry>,
+    done: bool,
+}
+
+impl<R: Read> Player<R, SystemClock> {
+    /// Play back according to the system clock.
+    pub fn new(read: R) -> Self {
+        Self::with_clock(read, SystemClock)
+    }
+}
+
+impl<R: Read, C: Clock> Player<R, C> {
+    pub fn with_clock(read: R, clock: C) -> Self {
+        Self {
+            read,
+            clock,
+            offset: None,
+            next: None,
+            done: false,
+        }
+    }
+    /// The time-tag at which the next packet is due, or `None` at the end of the recording.
+    pub fn next_due(&mut self) -> ResultE<Option<(u32, u32)>> {
+        if self.next.is_none() && !self.done {
+            match read_entry(&mut self.read) {
+                Ok(entry) => {
+                    self.done = entry.is_none();
+                    self.next = entry;
+                },
+                Err(e) => {
+                    self.done = true;
+                    return Err(e);
+                },
+            }
+        }
+        let recorded = match self.next {
+            Some(ref entry) => to_fixed(entry.timetag),
+            None => return Ok(None),
+        };
+        let now = to_fixed(self.clock.now());
+        let offset = *self.offset.get_or_insert(now.wrapping_sub(recorded));
+        Ok(Some(from_fixed(recorded.wrapping_add(offset))))
+    }
+    /// How long until the next packet is due.
+    /// This is zero if it's already due, and `None` at the end of the recording.
+    pub fn time_until_next(&mut self) -> ResultE<Option<Duration>> {
+        Ok(self.next_due()?.map(|due| {
+            let diff = to_fixed(due).saturating_sub(to_fixed(self.clock.now()));
+            timetag::to_duration(from_fixed(diff))
+        }))
+    }
+    /// Return the next packet (without its length prefix), if it is due.
+    pub fn pop_due(&mut self) -> ResultE<Option<Vec<u8>>> {
+        match self.next_due()? {
+            Some(due) if to_fixed(due) <= to_fixed(self.clock.now()) =>
+                Ok(self.next.take().map(|entry| entry.packet)),
+            _ => Ok(None),
+        }
+    }
+    /// Sleep until the next packet is due, and return it,
+    /// or return `None` at the end of the recording.
+    ///
+    /// This sleeps in real time, so the clock should keep up with the system's.
+    pub fn wait_next(&mut self) -> ResultE<Option<Vec<u8>>> {
+        loop {
+            if let Some(packet) = self.pop_due()? {
+                return Ok(Some(packet));
+            }
+            match self.time_until_next()? {
+                Some(wait) => thread::sleep(wait),
+                None => return Ok(None),
+            }
+        }
+    }
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+    pub fn into_inner(self) -> R {
+        self.read
+    }
+}
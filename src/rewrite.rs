@@ -0,0 +1,213 @@
+use std::convert::TryInto;
+use std::io::Cursor;
+
+use address::AddressPattern;
+use de::{self, DeserializerOptions};
+use de::osc_reader::OscReader;
+use error::{Error, ResultE};
+use ser::AddressPolicy;
+use ser::osc_writer::{length_prefix, OscWriter};
+
+/// Rewrite the address of a length-prefixed message, or of every message
+/// within a bundle, for proxies which forward packets under a different
+/// address. Everything else (typetags, argument data and time-tags) is
+/// copied verbatim, without being decoded; the length prefix and the lengths
+/// of bundle elements are recomputed.
+///
+/// `rewrite` is called with each address, and returns the new address, or
+/// `None` to leave it as it is. New addresses must be valid OSC addresses
+/// (else `Error::BadAddress`; see `ser::AddressPolicy`).
+///
+/// ```
+/// use serde_osc::rewrite::{rewrite_address, strip_prefix};
+///
+/// let packet = serde_osc::to_vec(&("/bridge/synth/1/freq", (440.0f32,))).unwrap();
+/// let forwarded = rewrite_address(&packet, strip_prefix("/bridge")).unwrap();
+/// let (address, (freq,)): (String, (f32,)) = serde_osc::from_slice(&forwarded).unwrap();
+/// assert_eq!((address.as_str(), freq), ("/synth/1/freq", 440.0));
+/// ```
+pub fn rewrite_address<F>(packet: &[u8], mut rewrite: F) -> ResultE<Vec<u8>>
+    where F: FnMut(&str) -> Option<String>
+{
+    let options = DeserializerOptions::default();
+    let end = de::packet_end(packet, &options)?.ok_or(Error::BadFormat)?;
+    let mut out = Vec::with_capacity(end);
+    out.extend_from_slice(&[0; 4]);
+    rewrite_contents(&packet[4..end], &mut rewrite, &mut out, 0)?;
+    write_length(&mut out, 0)?;
+    Ok(out)
+}
+
+/// Rewrite the addresses of a packet that has no length prefix (e.g. a UDP
+/// datagram). See [`rewrite_address`].
+///
+/// [`rewrite_address`]: fn.rewrite_address.html
+pub fn rewrite_address_unframed<F>(contents: &[u8], mut rewrite: F) -> ResultE<Vec<u8>>
+    where F: FnMut(&str) -> Option<String>
+{
+    DeserializerOptions::default().check_size(contents.len())?;
+    let mut out = Vec::with_capacity(contents.len());
+    rewrite_contents(contents, &mut rewrite, &mut out, 0)?;
+    Ok(out)
+}
+
+/// A rewrite which removes `prefix` from the start of addresses, e.g.
+/// "/bridge/synth" becomes "/synth" for the prefix "/bridge", and "/bridge"
+/// itself becomes "/". Addresses outside the prefix are left as they are.
+pub fn strip_prefix<'p>(prefix: &'p str) -> impl FnMut(&str) -> Option<String> + 'p {
+    move |address| {
+        let rest = address.strip_prefix(prefix)?;
+        match rest {
+            "" => Some("/".to_owned()),
+            rest if rest.starts_with('/') => Some(rest.to_owned()),
+            // e.g. "/bridges" for the prefix "/bridge".
+            _ => None,
+        }
+    }
+}
+
+/// A rewrite which puts `prefix` in front of every address, e.g. "/synth"
+/// becomes "/bridge/synth" for the prefix "/bridge".
+pub fn add_prefix<'p>(prefix: &'p str) -> impl FnMut(&str) -> Option<String> + 'p {
+    move |address| Some(format!("{}{}", prefix, address))
+}
+
+/// Expand a length-prefixed message whose address is a pattern (e.g.
+/// "/track/*/mute") into one message for each address of `namespace` it
+/// matches, for receivers which don't accept patterns. The arguments are
+/// copied verbatim.
+///
+/// A message with a literal address is returned as it is, whether or not
+/// it's in `namespace`; a pattern which matches nothing yields no packets.
+/// A bundle is returned as a single packet, in which each element is
+/// expanded in place (an element which matches nothing is dropped).
+///
+/// ```
+/// use serde_osc::rewrite::fan_out;
+///
+/// let namespace = ["/track/1/mute", "/track/2/mute", "/track/2/solo"];
+/// let packet = serde_osc::to_vec(&("/track/*/mute", (true,))).unwrap();
+/// let packets = fan_out(&packet, &namespace).unwrap();
+/// assert_eq!(packets, vec![
+///     serde_osc::to_vec(&("/track/1/mute", (true,))).unwrap(),
+///     serde_osc::to_vec(&("/track/2/mute", (true,))).unwrap(),
+/// ]);
+/// ```
+pub fn fan_out<S: AsRef<str>>(packet: &[u8], namespace: &[S]) -> ResultE<Vec<Vec<u8>>> {
+    let options = DeserializerOptions::default();
+    let end = de::packet_end(packet, &options)?.ok_or(Error::BadFormat)?;
+    let mut packets = Vec::new();
+    fan_out_contents(&packet[4..end], namespace, 0, &mut |contents| {
+        let mut out = Vec::with_capacity(4 + contents.len());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(contents);
+        write_length(&mut out, 0)?;
+        packets.push(out);
+        Ok(())
+    })?;
+    Ok(packets)
+}
+
+/// Expand a packet that has no length prefix (e.g. a UDP datagram) into one
+/// per matching address. See [`fan_out`].
+///
+/// [`fan_out`]: fn.fan_out.html
+pub fn fan_out_unframed<S: AsRef<str>>(contents: &[u8], namespace: &[S]) -> ResultE<Vec<Vec<u8>>> {
+    DeserializerOptions::default().check_size(contents.len())?;
+    let mut packets = Vec::new();
+    fan_out_contents(contents, namespace, 0, &mut |contents| {
+        packets.push(contents.to_vec());
+        Ok(())
+    })?;
+    Ok(packets)
+}
+
+/// Expand the packet `contents`, passing each resulting packet to `emit`.
+/// `depth` is the number of bundles enclosing the packet.
+fn fan_out_contents<S>(contents: &[u8], namespace: &[S], depth: usize, emit: &mut dyn FnMut(&[u8]) -> ResultE<()>) -> ResultE<()>
+    where S: AsRef<str>
+{
+    let options = DeserializerOptions::default();
+    let mut rd = Cursor::new(contents);
+    let address = rd.parse_address(options)?;
+    if address == "#bundle" {
+        if options.too_deep(depth) {
+            return Err(Error::DepthLimitExceeded);
+        }
+        rd.parse_timetag()?;
+        let mut out = contents[..rd.position() as usize].to_vec();
+        while (rd.position() as usize) < contents.len() {
+            let size: usize = rd.parse_i32()?.try_into()?;
+            let start = rd.position() as usize;
+            let elem = start.checked_add(size)
+                .and_then(|end| contents.get(start..end))
+                .ok_or(Error::BadFormat)?;
+            fan_out_contents(elem, namespace, depth + 1, &mut |expanded| {
+                let length_at = out.len();
+                out.extend_from_slice(&[0; 4]);
+                out.extend_from_slice(expanded);
+                write_length(&mut out, length_at)
+            })?;
+            rd.set_position((start + size) as u64);
+        }
+        emit(&out)
+    } else {
+        let args = &contents[rd.position() as usize..];
+        let pattern = AddressPattern::new(address);
+        if pattern.is_literal() {
+            return emit(contents);
+        }
+        for concrete in namespace.iter().map(AsRef::as_ref).filter(|a| pattern.matches(a)) {
+            let mut out = Vec::with_capacity(concrete.len() + 4 + args.len());
+            out.osc_write_str(&AddressPolicy::Validate.apply(concrete)?)?;
+            out.extend_from_slice(args);
+            emit(&out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Rewrite the packet `contents` into `out`. `depth` is the number of bundles
+/// enclosing the packet.
+fn rewrite_contents<F>(contents: &[u8], rewrite: &mut F, out: &mut Vec<u8>, depth: usize) -> ResultE<()>
+    where F: FnMut(&str) -> Option<String>
+{
+    let options = DeserializerOptions::default();
+    let mut rd = Cursor::new(contents);
+    let address = rd.parse_address(options)?;
+    if address == "#bundle" {
+        if options.too_deep(depth) {
+            return Err(Error::DepthLimitExceeded);
+        }
+        rd.parse_timetag()?;
+        // "#bundle" and the time-tag are copied as they are.
+        out.extend_from_slice(&contents[..rd.position() as usize]);
+        while (rd.position() as usize) < contents.len() {
+            let size: usize = rd.parse_i32()?.try_into()?;
+            let start = rd.position() as usize;
+            let elem = start.checked_add(size)
+                .and_then(|end| contents.get(start..end))
+                .ok_or(Error::BadFormat)?;
+            let length_at = out.len();
+            out.extend_from_slice(&[0; 4]);
+            rewrite_contents(elem, rewrite, out, depth + 1)?;
+            write_length(out, length_at)?;
+            rd.set_position((start + size) as u64);
+        }
+    } else {
+        match rewrite(&address) {
+            Some(new_address) => out.osc_write_str(&AddressPolicy::Validate.apply(&new_address)?)?,
+            None => out.osc_write_str(&address)?,
+        }
+        // The typetag and arguments
+        out.extend_from_slice(&contents[rd.position() as usize..]);
+    }
+    Ok(())
+}
+
+/// Fill in the length prefix at `at` with the length of the data after it.
+fn write_length(out: &mut [u8], at: usize) -> ResultE<()> {
+    let length = length_prefix(out.len() - at - 4)?;
+    out[at..at + 4].copy_from_slice(&length.to_be_bytes());
+    Ok(())
+}
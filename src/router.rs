@@ -0,0 +1,136 @@
+use std::convert::TryInto;
+use std::fmt;
+use std::io::Cursor;
+use serde::de::DeserializeOwned;
+
+use address::AddressPattern;
+use de::{self, DeserializerOptions};
+use de::osc_reader::OscReader;
+use error::{Error, ResultE};
+
+type Handler<'a> = Box<dyn FnMut(&[u8]) -> ResultE<()> + 'a>;
+
+/// Dispatches incoming OSC messages to typed handlers, based on their address.
+///
+/// Each handler is registered for one address, along with a closure taking
+/// the message arguments. When a packet is dispatched, its address is treated
+/// as an [`AddressPattern`] and every handler whose address matches is invoked
+/// with the arguments deserialized into that handler's argument type.
+/// Messages within bundles are dispatched in order (the time-tag is ignored).
+/// A message which doesn't fit one handler's argument type is still passed to
+/// the other handlers, and the rest of its bundle is still dispatched; the
+/// first such error is returned once dispatching is done.
+///
+/// ```
+/// # extern crate serde_osc;
+/// use serde_osc::router::Router;
+///
+/// # fn main() {
+/// let mut played = Vec::new();
+/// {
+///     let mut router = Router::new();
+///     router.at("/audio/play", |args: (i32, f32)| played.push(args));
+///     let packet = serde_osc::to_vec(&("/audio/play", (1, 440.0f32))).unwrap();
+///     assert_eq!(router.dispatch(&packet).unwrap(), 1);
+/// }
+/// assert_eq!(played, vec![(1, 440.0)]);
+/// # }
+/// ```
+///
+/// [`AddressPattern`]: ../address/struct.AddressPattern.html
+pub struct Router<'a> {
+    routes: Vec<(String, Handler<'a>)>,
+}
+
+impl<'a> Router<'a> {
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+    /// Register `handler` to be called for messages addressed to `address`.
+    /// The message arguments are deserialized as the handler's argument type, `A`.
+    pub fn at<A, F>(&mut self, address: &str, mut handler: F) -> &mut Self
+        where A: DeserializeOwned, F: FnMut(A) + 'a
+    {
+        self.routes.push((address.to_owned(), Box::new(move |contents| {
+            let (_address, args): (String, A) = de::from_slice_unframed(contents)?;
+            handler(args);
+            Ok(())
+        })));
+        self
+    }
+    /// Dispatch a length-prefixed packet.
+    /// Returns the number of handlers that were invoked.
+    pub fn dispatch(&mut self, packet: &[u8]) -> ResultE<usize> {
+        let mut rd = Cursor::new(packet);
+        let length: usize = rd.parse_i32()?.try_into()?;
+        match packet.get(4..) {
+            Some(contents) if contents.len() >= length => {
+                self.dispatch_unframed(&contents[..length])
+            },
+            _ => Err(Error::BadFormat),
+        }
+    }
+    /// Dispatch a packet that has no length prefix (e.g. a UDP datagram).
+    /// Returns the number of handlers that were invoked.
+    ///
+    /// Fails with `Error::DepthLimitExceeded` if bundles are nested more
+    /// deeply than `DeserializerOptions` allow by default. Malformed packets
+    /// fail without dispatching any further messages, while a message that
+    /// doesn't fit a handler's argument type fails only after every other
+    /// handler and message has been dispatched.
+    pub fn dispatch_unframed(&mut self, contents: &[u8]) -> ResultE<usize> {
+        let mut failed = None;
+        let n_handled = self.dispatch_contents(contents, 0, &mut failed)?;
+        match failed {
+            Some(err) => Err(err),
+            None => Ok(n_handled),
+        }
+    }
+    /// Dispatch a packet (or bundle element) nested within `depth` bundles.
+    /// The first error returned by a handler is kept in `failed`.
+    fn dispatch_contents(&mut self, contents: &[u8], depth: usize, failed: &mut Option<Error>) -> ResultE<usize> {
+        let mut rd = Cursor::new(contents);
+        let address = rd.parse_str()?;
+        if address == "#bundle" {
+            if DeserializerOptions::default().too_deep(depth) {
+                return Err(Error::DepthLimitExceeded);
+            }
+            rd.parse_timetag()?;
+            let mut n_handled = 0;
+            while (rd.position() as usize) < contents.len() {
+                let size: usize = rd.parse_i32()?.try_into()?;
+                let start = rd.position() as usize;
+                let end = start.checked_add(size).ok_or(Error::BadFormat)?;
+                let elem = contents.get(start..end).ok_or(Error::BadFormat)?;
+                n_handled += self.dispatch_contents(elem, depth + 1, failed)?;
+                rd.set_position(end as u64);
+            }
+            Ok(n_handled)
+        } else {
+            let pattern = AddressPattern::new(address);
+            let mut n_handled = 0;
+            for &mut (ref route, ref mut handler) in &mut self.routes {
+                if pattern.matches(route) {
+                    match handler(contents) {
+                        Ok(()) => n_handled += 1,
+                        Err(err) if failed.is_none() => *failed = Some(err),
+                        Err(_) => {},
+                    }
+                }
+            }
+            Ok(n_handled)
+        }
+    }
+}
+
+impl<'a> Default for Router<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> fmt::Debug for Router<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.routes.iter().map(|r| &r.0)).finish()
+    }
+}
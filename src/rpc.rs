@@ -0,0 +1,132 @@
+use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use serde;
+use serde::de::IgnoredAny;
+
+use de::{self, DeserializerOptions, PacketKind};
+use error::{Error, ResultE};
+use framing::Framing;
+use net::OscUdpSocket;
+
+/// How long to wait for a reply unless told otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The address of the reply to a query sent to `address`, by the common
+/// convention of appending ".reply" (e.g. "/status" is answered by "/status.reply").
+pub fn reply_address(address: &str) -> String {
+    format!("{}.reply", address)
+}
+
+/// Sends query messages to a server over UDP, and waits for the matching reply.
+///
+/// Each query is answered by a message from the server to a known address
+/// (see [`reply_address`]); its arguments are deserialized into the type the
+/// caller expects. Anything else which arrives meanwhile (messages to other
+/// addresses, bundles, or packets from other hosts) is discarded. If no
+/// reply arrives in time, the call fails with an `Error::Io` of kind `TimedOut`.
+///
+/// Queries are answered one at a time, so replies can't be told apart if the
+/// server answers an earlier query late; it's up to the caller to avoid
+/// sending another query to the same address before then.
+///
+/// ```no_run
+/// use serde_osc::net::OscUdpSocket;
+/// use serde_osc::rpc::RpcClient;
+///
+/// let socket = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+/// let mut server = RpcClient::new(socket, "127.0.0.1:57110").unwrap();
+/// // scsynth answers "/status" with "/status.reply".
+/// let (_, ugens, synths, groups, defs, avg_cpu, peak_cpu, nominal_rate, actual_rate):
+///     (i32, i32, i32, i32, i32, f32, f32, f64, f64) = server.call("/status", &()).unwrap();
+/// # let _ = (ugens, synths, groups, defs, avg_cpu, peak_cpu, nominal_rate, actual_rate);
+/// ```
+///
+/// [`reply_address`]: fn.reply_address.html
+#[derive(Debug)]
+pub struct RpcClient {
+    socket: OscUdpSocket,
+    server: SocketAddr,
+    timeout: Duration,
+    options: DeserializerOptions,
+}
+
+impl RpcClient {
+    /// Send queries to `server` through `socket`.
+    pub fn new<A: ToSocketAddrs>(socket: OscUdpSocket, server: A) -> ResultE<Self> {
+        let server = server.to_socket_addrs()?.next()
+            .ok_or_else(|| Error::Io(ErrorKind::InvalidInput.into()))?;
+        Ok(Self {
+            socket,
+            server,
+            timeout: DEFAULT_TIMEOUT,
+            options: DeserializerOptions::default(),
+        })
+    }
+    /// Wait at most `timeout` for each reply (`DEFAULT_TIMEOUT` otherwise).
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+    /// Decode replies according to `options`, e.g. to coerce numbers.
+    pub fn with_options(self, options: DeserializerOptions) -> Self {
+        Self { options, ..self }
+    }
+    /// Access the underlying socket.
+    pub fn socket(&self) -> &OscUdpSocket {
+        &self.socket
+    }
+    /// Unwrap this object, returning the underlying socket.
+    pub fn into_inner(self) -> OscUdpSocket {
+        self.socket
+    }
+
+    /// Send `args` to `address`, and wait for the reply to
+    /// `reply_address(address)`, whose arguments are deserialized into `R`.
+    pub fn call<T, R>(&mut self, address: &str, args: &T) -> ResultE<R>
+        where T: ?Sized + serde::ser::Serialize, R: serde::de::DeserializeOwned
+    {
+        self.call_expecting(address, args, &reply_address(address))
+    }
+    /// Send `args` to `address`, and wait for the reply to `reply`, for
+    /// servers which don't follow the ".reply" convention.
+    pub fn call_expecting<T, R>(&mut self, address: &str, args: &T, reply: &str) -> ResultE<R>
+        where T: ?Sized + serde::ser::Serialize, R: serde::de::DeserializeOwned
+    {
+        // The read timeout is shortened while waiting, so put back the
+        // caller's afterwards, whatever the outcome.
+        let timeout = self.socket.socket().read_timeout()?;
+        let result = self.exchange(address, args, reply);
+        self.socket.socket().set_read_timeout(timeout)?;
+        result
+    }
+    fn exchange<T, R>(&mut self, address: &str, args: &T, reply: &str) -> ResultE<R>
+        where T: ?Sized + serde::ser::Serialize, R: serde::de::DeserializeOwned
+    {
+        let deadline = Instant::now() + self.timeout;
+        self.socket.send_to(&(address, args), self.server)?;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining == Duration::from_secs(0) {
+                return Err(Error::Io(ErrorKind::TimedOut.into()));
+            }
+            self.socket.socket().set_read_timeout(Some(remaining))?;
+            let (packet, from) = match self.socket.recv_datagram() {
+                Ok(received) => received,
+                // Depending on the platform, a timeout is reported either way.
+                Err(Error::Io(ref e)) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut =>
+                    return Err(Error::Io(ErrorKind::TimedOut.into())),
+                Err(e) => return Err(e),
+            };
+            if from != self.server {
+                continue;
+            }
+            // Only the address is decoded until the reply is found.
+            match de::inspect_with(packet, Framing::Unframed, self.options) {
+                Ok(PacketKind::Message { ref address, .. }) if address == reply => {},
+                _ => continue,
+            }
+            let (_, args): (IgnoredAny, R) = de::from_slice_framed(packet, Framing::Unframed, self.options, None)?;
+            return Ok(args);
+        }
+    }
+}
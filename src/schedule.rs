@@ -0,0 +1,194 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::time::{Duration, SystemTime};
+
+use timetag::{self, from_fixed, to_fixed};
+use value::{Bundle, Message, Packet};
+
+pub use timetag::IMMEDIATELY;
+
+/// Source of the current time, as an OSC time-tag.
+///
+/// Any `Fn() -> (u32, u32)` closure is a clock, which makes it easy to drive a
+/// [`Scheduler`] from an audio clock or a simulated one.
+///
+/// [`Scheduler`]: struct.Scheduler.html
+pub trait Clock {
+    /// The current time, as (seconds, fractional seconds) since midnight on January 1, 1900.
+    fn now(&self) -> (u32, u32);
+}
+
+/// The system's wall-clock time.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> (u32, u32) {
+        // Beyond the first NTP era (in 2036), every time-tag is in the past.
+        timetag::from_system_time(SystemTime::now()).unwrap_or((u32::MAX, u32::MAX))
+    }
+}
+
+impl<F: Fn() -> (u32, u32)> Clock for F {
+    fn now(&self) -> (u32, u32) {
+        self()
+    }
+}
+
+/// Holds the messages of received bundles until their time-tag is due.
+///
+/// OSC receivers are supposed to process the messages of a bundle no earlier
+/// than the bundle's time-tag. Push decoded packets into the scheduler as they
+/// arrive, and poll [`pop_due`] (e.g. after sleeping for [`time_until_next`])
+/// to receive their messages at the right time. Messages which are due at the
+/// same time are released in the order in which they were pushed.
+///
+/// Messages outside of any bundle, and bundles tagged [`IMMEDIATELY`], are due
+/// at once. A nested bundle is never due before its enclosing bundle.
+///
+/// ```
+/// # extern crate serde_osc;
+/// use std::cell::Cell;
+/// use serde_osc::schedule::Scheduler;
+/// use serde_osc::value::{Bundle, Message};
+///
+/// # fn main() {
+/// let time = Cell::new((100, 0));
+/// let mut scheduler = Scheduler::with_clock(|| time.get());
+/// let mut bundle = Bundle::new((101, 0));
+/// bundle.elements.push(Message::new("/later").into());
+/// scheduler.push(bundle.into());
+///
+/// assert_eq!(scheduler.pop_due(), None);
+/// time.set((101, 0));
+/// assert_eq!(scheduler.pop_due(), Some(Message::new("/later")));
+/// # }
+/// ```
+///
+/// [`pop_due`]: #method.pop_due
+/// [`time_until_next`]: #method.time_until_next
+/// [`IMMEDIATELY`]: constant.IMMEDIATELY.html
+#[derive(Debug)]
+pub struct Scheduler<C: Clock = SystemClock> {
+    clock: C,
+    queue: BinaryHeap<Reverse<Entry>>,
+    /// Number of messages pushed so far, to keep messages due at the same time in order.
+    pushed: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    /// The time-tag, as a 64-bit fixed point number.
+    due: u64,
+    seq: u64,
+    msg: Message,
+}
+
+impl Scheduler<SystemClock> {
+    /// Create a scheduler which follows the system clock.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+
+impl Default for Scheduler<SystemClock> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Clock> Scheduler<C> {
+    /// Create a scheduler which follows the provided clock.
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            clock,
+            queue: BinaryHeap::new(),
+            pushed: 0,
+        }
+    }
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+    /// Schedule every message in the packet.
+    pub fn push(&mut self, packet: Packet) {
+        match packet {
+            Packet::Message(msg) => self.push_at(msg, 0),
+            Packet::Bundle(bundle) => self.push_bundle(bundle, 0),
+        }
+    }
+    fn push_bundle(&mut self, bundle: Bundle, enclosing_due: u64) {
+        let due = match bundle.timetag {
+            IMMEDIATELY => enclosing_due,
+            timetag => to_fixed(timetag).max(enclosing_due),
+        };
+        for elem in bundle.elements {
+            match elem {
+                Packet::Message(msg) => self.push_at(msg, due),
+                Packet::Bundle(inner) => self.push_bundle(inner, due),
+            }
+        }
+    }
+    fn push_at(&mut self, msg: Message, due: u64) {
+        self.queue.push(Reverse(Entry { due, seq: self.pushed, msg }));
+        self.pushed += 1;
+    }
+    /// Remove and return the next message, if it is due.
+    pub fn pop_due(&mut self) -> Option<Message> {
+        let now = to_fixed(self.clock.now());
+        if self.queue.peek()?.0.due > now {
+            return None;
+        }
+        self.queue.pop().map(|Reverse(entry)| entry.msg)
+    }
+    /// Remove and return all the messages which are due, in order.
+    pub fn drain_due(&mut self) -> Vec<Message> {
+        let mut due = Vec::new();
+        while let Some(msg) = self.pop_due() {
+            due.push(msg);
+        }
+        due
+    }
+    /// The time-tag of the next message to become due, if any.
+    pub fn next_due(&self) -> Option<(u32, u32)> {
+        self.queue.peek().map(|entry| from_fixed(entry.0.due))
+    }
+    /// How long until the next message becomes due.
+    /// This is zero if a message is already due, and `None` if there are no messages.
+    pub fn time_until_next(&self) -> Option<Duration> {
+        let due = self.queue.peek()?.0.due;
+        let diff = due.saturating_sub(to_fixed(self.clock.now()));
+        let nanos = ((diff & 0xffff_ffff) * 1_000_000_000) >> 32;
+        Some(Duration::new(diff >> 32, nanos as u32))
+    }
+    /// Number of messages waiting to be released.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+    /// Discard all waiting messages.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.due, self.seq).cmp(&(other.due, other.seq))
+    }
+}
@@ -0,0 +1,164 @@
+use std::convert::TryFrom;
+use std::mem;
+use serde::ser::Serialize;
+
+use error::{Error, ResultE};
+use framing::Framing;
+use ser;
+use value::{Message, Packet, Value};
+
+/// An OSC argument type to encode a field as; see [`MessageSchema`].
+///
+/// [`MessageSchema`]: struct.MessageSchema.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Tag {
+    /// Leave the argument as it was serialized.
+    Any,
+    /// 'i': 32-bit integer
+    Int,
+    /// 'h': 64-bit integer
+    Long,
+    /// 'f': 32-bit float
+    Float,
+    /// 'd': 64-bit float
+    Double,
+    /// 's': string
+    String,
+    /// 'S': symbol
+    Symbol,
+    /// 'c': character
+    Char,
+}
+
+impl Tag {
+    /// The typetag character of this type ('*' for `Any`).
+    pub fn typetag(&self) -> char {
+        match *self {
+            Tag::Any => '*',
+            Tag::Int => 'i',
+            Tag::Long => 'h',
+            Tag::Float => 'f',
+            Tag::Double => 'd',
+            Tag::String => 's',
+            Tag::Symbol => 'S',
+            Tag::Char => 'c',
+        }
+    }
+    /// Convert `value` to this type.
+    ///
+    /// Numbers convert to any other numeric type (floats to integers by
+    /// truncation; integers which don't fit fail with `Error::BadCast`), and
+    /// strings, symbols and characters to each other (a string only to a
+    /// character if it has exactly one). Anything else fails with `Error::TypeMismatch`.
+    pub fn convert(&self, value: Value) -> ResultE<Value> {
+        let found = value.typetag().chars().next().unwrap_or('?');
+        let mismatch = || Error::TypeMismatch { expected: self.typetag(), found };
+        Ok(match (*self, value) {
+            (Tag::Any, value) => value,
+            (Tag::Int, Value::Int(i)) => Value::Int(i),
+            (Tag::Int, Value::Long(h)) => Value::Int(i32::try_from(h)?),
+            (Tag::Int, Value::Float(f)) => Value::Int(f as i32),
+            (Tag::Int, Value::Double(d)) => Value::Int(d as i32),
+            (Tag::Long, Value::Int(i)) => Value::Long(i.into()),
+            (Tag::Long, Value::Long(h)) => Value::Long(h),
+            (Tag::Long, Value::Float(f)) => Value::Long(f as i64),
+            (Tag::Long, Value::Double(d)) => Value::Long(d as i64),
+            (Tag::Float, Value::Int(i)) => Value::Float(i as f32),
+            (Tag::Float, Value::Long(h)) => Value::Float(h as f32),
+            (Tag::Float, Value::Float(f)) => Value::Float(f),
+            (Tag::Float, Value::Double(d)) => Value::Float(d as f32),
+            (Tag::Double, Value::Int(i)) => Value::Double(i.into()),
+            (Tag::Double, Value::Long(h)) => Value::Double(h as f64),
+            (Tag::Double, Value::Float(f)) => Value::Double(f.into()),
+            (Tag::Double, Value::Double(d)) => Value::Double(d),
+            (Tag::String, Value::String(s)) | (Tag::String, Value::Symbol(s)) => Value::String(s),
+            (Tag::String, Value::Char(c)) => Value::String(c.to_string()),
+            (Tag::Symbol, Value::String(s)) | (Tag::Symbol, Value::Symbol(s)) => Value::Symbol(s),
+            (Tag::Symbol, Value::Char(c)) => Value::Symbol(c.to_string()),
+            (Tag::Char, Value::Char(c)) => Value::Char(c),
+            (Tag::Char, Value::String(s)) | (Tag::Char, Value::Symbol(s)) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Value::Char(c),
+                    _ => return Err(mismatch()),
+                }
+            },
+            _ => return Err(mismatch()),
+        })
+    }
+}
+
+/// The argument types a message must be sent with, for receivers which are
+/// picky about them (e.g. which only accept 'h' for a parameter that Rust
+/// code holds as an `i32`).
+///
+/// Each field is serialized as usual, and then converted to the type given
+/// for its position (see [`Tag::convert`]); fields beyond the end of the
+/// schema, and those given as `Tag::Any`, are left as they are.
+///
+/// ```
+/// use serde_osc::schema::{MessageSchema, Tag};
+///
+/// let schema = MessageSchema::new().arg(Tag::Long).arg(Tag::Double).arg(Tag::Symbol);
+/// let packet = schema.to_vec(&("/track/1", (3, 0.5f32, "mute"))).unwrap();
+/// let (_, (track, gain, state)): (String, (i64, f64, String)) = serde_osc::from_slice(&packet).unwrap();
+/// assert_eq!((track, gain, state.as_str()), (3, 0.5, "mute"));
+/// ```
+///
+/// [`Tag::convert`]: enum.Tag.html#method.convert
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MessageSchema {
+    args: Vec<Tag>,
+}
+
+impl MessageSchema {
+    /// A schema which leaves every argument as it is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Append the type of the next argument.
+    pub fn arg(mut self, tag: Tag) -> Self {
+        self.args.push(tag);
+        self
+    }
+    /// The types given for each argument so far.
+    pub fn args(&self) -> &[Tag] {
+        &self.args
+    }
+    /// Convert the arguments of `msg` to the types of this schema.
+    ///
+    /// Fails with `Error::BadFormat` if the message has fewer arguments than
+    /// the schema has types.
+    pub fn apply(&self, msg: &mut Message) -> ResultE<()> {
+        if msg.args.len() < self.args.len() {
+            return Err(Error::BadFormat);
+        }
+        for (arg, tag) in msg.args.iter_mut().zip(&self.args) {
+            let value = mem::replace(arg, Value::Nil);
+            *arg = tag.convert(value)?;
+        }
+        Ok(())
+    }
+    /// Serialize the message `value` into a length-prefixed packet, with its
+    /// arguments converted to the types of this schema.
+    pub fn to_vec<T: ?Sized + Serialize>(&self, value: &T) -> ResultE<Vec<u8>> {
+        self.to_vec_framed(value, Framing::LengthPrefixed)
+    }
+    /// Like `to_vec`, but omits the length prefix.
+    pub fn to_vec_unframed<T: ?Sized + Serialize>(&self, value: &T) -> ResultE<Vec<u8>> {
+        self.to_vec_framed(value, Framing::Unframed)
+    }
+    fn to_vec_framed<T: ?Sized + Serialize>(&self, value: &T, framing: Framing) -> ResultE<Vec<u8>> {
+        let packet = ser::to_vec_unframed(value)?;
+        let mut msg = match Packet::from_slice_unframed(&packet)? {
+            Packet::Message(msg) => msg,
+            // A schema describes the arguments of a single message.
+            Packet::Bundle(_) => return Err(Error::BadFormat),
+        };
+        self.apply(&mut msg)?;
+        match framing {
+            Framing::LengthPrefixed => ser::to_vec(&msg),
+            Framing::Unframed => ser::to_vec_unframed(&msg),
+        }
+    }
+}
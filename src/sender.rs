@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::{Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use serde;
+
+use error::{Error, ResultE};
+use ser;
+
+/// Sends serde-compatible values as OSC packets over UDP to several named
+/// destinations (e.g. "lights", "mixer" and "video" in a show rig), through a
+/// single socket.
+///
+/// An `OscSender` is `Sync`, so that it can be shared between threads (e.g.
+/// in an `Arc`); each destination has its own serialization buffer, reused
+/// from one packet to the next, so that threads sending to different
+/// destinations don't wait for each other.
+///
+/// ```
+/// use serde_osc::net::OscUdpSocket;
+/// use serde_osc::sender::OscSender;
+///
+/// let mut mixer = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+/// let sender = OscSender::bind("127.0.0.1:0").unwrap();
+/// sender.add_destination("mixer", mixer.socket().local_addr().unwrap()).unwrap();
+/// sender.send("mixer", &("/ch/01/mix/fader", (0.75f32,))).unwrap();
+/// let ((address, (level,)), _): ((String, (f32,)), _) = mixer.recv_from().unwrap();
+/// assert_eq!((address.as_str(), level), ("/ch/01/mix/fader", 0.75));
+/// ```
+#[derive(Debug)]
+pub struct OscSender {
+    socket: UdpSocket,
+    destinations: RwLock<HashMap<String, Destination>>,
+}
+
+#[derive(Debug)]
+struct Destination {
+    addr: SocketAddr,
+    /// The last packet sent there, whose allocation is reused for the next.
+    buf: Mutex<Vec<u8>>,
+}
+
+impl OscSender {
+    /// Create a sender with no destinations, whose socket is bound to the
+    /// given local address (e.g. "0.0.0.0:0" for any port).
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> ResultE<Self> {
+        Ok(Self::from_socket(UdpSocket::bind(addr)?))
+    }
+    /// Send through an already-configured `UdpSocket`.
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            destinations: RwLock::new(HashMap::new()),
+        }
+    }
+    /// Access the underlying socket, e.g. to enable broadcast or query the local address.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Send the packets for `name` to `addr` (the first address it resolves
+    /// to), replacing any previous destination by that name.
+    pub fn add_destination<S, A>(&self, name: S, addr: A) -> ResultE<()>
+        where S: Into<String>, A: ToSocketAddrs
+    {
+        let addr = addr.to_socket_addrs()?.next()
+            .ok_or_else(|| Error::Io(ErrorKind::InvalidInput.into()))?;
+        let destination = Destination { addr, buf: Mutex::new(Vec::new()) };
+        self.write().insert(name.into(), destination);
+        Ok(())
+    }
+    /// Forget the destination `name`, returning its address if there was one.
+    pub fn remove_destination(&self, name: &str) -> Option<SocketAddr> {
+        self.write().remove(name).map(|destination| destination.addr)
+    }
+    /// The address of the destination `name`.
+    pub fn destination(&self, name: &str) -> Option<SocketAddr> {
+        self.read().get(name).map(|destination| destination.addr)
+    }
+    /// The names of all destinations, in no particular order.
+    pub fn destinations(&self) -> Vec<String> {
+        self.read().keys().cloned().collect()
+    }
+
+    /// Serialize `value` as an OSC packet and send it to the destination
+    /// `name` as a single datagram. Returns the number of bytes sent.
+    ///
+    /// Fails with `Error::Message` if there's no such destination.
+    pub fn send<T>(&self, name: &str, value: &T) -> ResultE<usize>
+        where T: ?Sized + serde::ser::Serialize
+    {
+        let destinations = self.read();
+        let destination = destinations.get(name)
+            .ok_or_else(|| Error::Message(format!("no OSC destination named {:?}", name)))?;
+        // The buffer is cleared before each use, so it's fine even if a
+        // thread panicked while holding it.
+        let mut buf = destination.buf.lock().unwrap_or_else(PoisonError::into_inner);
+        ser::to_vec_reuse_unframed(&mut buf, value)?;
+        Ok(self.socket.send_to(&buf, destination.addr)?)
+    }
+    /// Serialize `value` once, and send it to every destination.
+    /// Stops at the first destination which can't be sent to.
+    pub fn send_all<T>(&self, value: &T) -> ResultE<()>
+        where T: ?Sized + serde::ser::Serialize
+    {
+        let packet = ser::to_vec_unframed(value)?;
+        for destination in self.read().values() {
+            self.socket.send_to(&packet, destination.addr)?;
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Destination>> {
+        self.destinations.read().unwrap_or_else(PoisonError::into_inner)
+    }
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Destination>> {
+        self.destinations.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl From<UdpSocket> for OscSender {
+    fn from(socket: UdpSocket) -> Self {
+        Self::from_socket(socket)
+    }
+}
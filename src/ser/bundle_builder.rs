@@ -0,0 +1,94 @@
+use byteorder::{BigEndian, WriteBytesExt};
+use serde::ser::Serialize;
+
+use error::{Error, ResultE};
+use super::to_vec;
+
+/// Size of the "#bundle" address plus the time-tag.
+const HEADER_SIZE: usize = 16;
+
+/// Collects messages into bundles, starting a new bundle whenever the next
+/// message would push the current one past `max_size` bytes.
+///
+/// This is intended for datagram transports, where a packet larger than the
+/// path MTU gets fragmented (or dropped). For UDP over Ethernet, a `max_size`
+/// of 1472 bytes avoids fragmentation. The produced bundles are unframed,
+/// i.e. each one is ready to be sent as a single datagram.
+///
+/// ```
+/// # extern crate serde_osc;
+/// # use serde_osc::ser::BundleBuilder;
+/// # fn main() {
+/// let mut builder = BundleBuilder::new(1472);
+/// for channel in 0..64 {
+///     builder.push(&("/mixer/gain", (channel, 0.5f32))).unwrap();
+/// }
+/// for datagram in builder.finish().unwrap() {
+///     assert!(datagram.len() <= 1472);
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BundleBuilder {
+    max_size: usize,
+    timetag: (u32, u32),
+    /// Bundles which are full.
+    finished: Vec<Vec<u8>>,
+    /// Elements of the bundle being filled, excluding its header.
+    current: Vec<u8>,
+}
+
+impl BundleBuilder {
+    /// Create a builder whose bundles never exceed `max_size` bytes.
+    /// Bundles are given the "immediately" time-tag, `(0, 1)`.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            timetag: (0, 1),
+            finished: Vec::new(),
+            current: Vec::new(),
+        }
+    }
+    /// Set the time-tag applied to every bundle produced by this builder.
+    pub fn with_timetag(mut self, timetag: (u32, u32)) -> Self {
+        self.timetag = timetag;
+        self
+    }
+    /// Serialize `packet` (a message, or a nested bundle) and add it to the
+    /// current bundle, or to a new bundle if it doesn't fit.
+    /// Fails with `Error::SizeLimitExceeded` if the packet can't fit in a
+    /// bundle even on its own.
+    pub fn push<T>(&mut self, packet: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        // Bundle elements keep their length prefix.
+        let element = to_vec(packet)?;
+        if HEADER_SIZE + element.len() > self.max_size {
+            return Err(Error::SizeLimitExceeded);
+        }
+        if HEADER_SIZE + self.current.len() + element.len() > self.max_size {
+            self.flush()?;
+        }
+        self.current.extend_from_slice(&element);
+        Ok(())
+    }
+    /// Return all the bundles built so far, in the order their messages were pushed.
+    pub fn finish(mut self) -> ResultE<Vec<Vec<u8>>> {
+        self.flush()?;
+        Ok(self.finished)
+    }
+
+    /// Wrap the pending elements in a bundle header and move them to `finished`.
+    fn flush(&mut self) -> ResultE<()> {
+        if self.current.is_empty() {
+            return Ok(());
+        }
+        let mut bundle = Vec::with_capacity(HEADER_SIZE + self.current.len());
+        bundle.extend_from_slice(b"#bundle\0");
+        bundle.write_u32::<BigEndian>(self.timetag.0)?;
+        bundle.write_u32::<BigEndian>(self.timetag.1)?;
+        bundle.append(&mut self.current);
+        self.finished.push(bundle);
+        Ok(())
+    }
+}
@@ -1,46 +1,122 @@
-use std::convert::TryInto;
-use std::io::{Cursor, Write};
+use std::io::{self, Cursor, Seek, SeekFrom, Write};
 use serde::ser::{Impossible, Serialize, Serializer, SerializeSeq, SerializeStruct, SerializeTuple};
 
 use error::{Error, ResultE};
-use super::osc_writer::OscWriter;
+use framing::Framing;
+use super::in_place::SliceOutput;
+use super::osc_writer::{length_prefix, OscWriter};
+use super::options::SerializerOptions;
 use super::pkt_serializer::PktSerializer;
 
+/// Serializes the elements of a bundle into `contents`: by default a buffer,
+/// which is written out once the bundle's length is known.
+///
+/// Bundles nested within it are written into the same `contents`, their
+/// length prefixes being filled in once they're complete, rather than each
+/// being assembled in a buffer of its own and then copied into the enclosing one.
 #[derive(Debug)]
-pub struct BundleSerializer {
-    contents: Cursor<Vec<u8>>,
+pub struct BundleSerializer<C: WriteSeek = Cursor<Vec<u8>>> {
+    contents: C,
+    /// Options with which to serialize the elements.
+    options: SerializerOptions,
 }
 #[derive(Debug)]
-pub struct BundleElemSerializer<'a> {
-    bundle: &'a mut BundleSerializer,
+pub struct BundleElemSerializer<'a, C: WriteSeek + 'a> {
+    bundle: &'a mut BundleSerializer<C>,
 }
 
-impl BundleSerializer {
-    pub fn new(contents: Cursor<Vec<u8>>) -> Self {
+/// The contents of a bundle, as seen by its elements.
+/// (Going through `dyn WriteSeek` keeps the serializer types from nesting indefinitely.)
+pub trait WriteSeek: Write + Seek {
+    /// The unwritten space of the contents, if messages can be assembled in it.
+    fn slice_output(&mut self) -> Option<&mut dyn SliceOutput>;
+    /// Whether the elements are only being measured (see `PktSerializer::counting`).
+    fn counting(&self) -> bool {
+        false
+    }
+}
+
+impl WriteSeek for Cursor<Vec<u8>> {
+    fn slice_output(&mut self) -> Option<&mut dyn SliceOutput> {
+        None
+    }
+}
+
+/// An output whose `Seek` implementation is only known at runtime (see
+/// `PktSerializer::with_backpatching`).
+#[derive(Debug)]
+pub struct SeekWith<'a, W: 'a> {
+    pub output: &'a mut W,
+    pub seek: fn(&mut W, SeekFrom) -> io::Result<u64>,
+    /// See `PktSerializer::slice`.
+    pub slice: fn(&mut W) -> Option<&mut dyn SliceOutput>,
+    /// See `PktSerializer::counting`.
+    pub counting: bool,
+}
+
+impl<'a, W: Write> Write for SeekWith<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.output.write_all(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+impl<'a, W> Seek for SeekWith<'a, W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        (self.seek)(self.output, pos)
+    }
+}
+
+impl<'a, W: Write> WriteSeek for SeekWith<'a, W> {
+    fn slice_output(&mut self) -> Option<&mut dyn SliceOutput> {
+        (self.slice)(self.output)
+    }
+    fn counting(&self) -> bool {
+        self.counting
+    }
+}
+
+impl<C: WriteSeek> BundleSerializer<C> {
+    pub fn new(contents: C, options: SerializerOptions) -> Self {
         Self {
-            contents
+            contents,
+            options,
         }
     }
-    pub fn write_into<W: Write>(self, output: &mut W) -> ResultE<()> {
-        let payload = self.contents.into_inner();
+}
+
+impl BundleSerializer {
+    /// Size of the bundle contents, including the "#bundle" address.
+    pub fn payload_size(&self) -> usize {
         // Add 8 because we have yet to write the #bundle address
-        let payload_size = 8 + payload.len();
+        8 + self.contents.get_ref().len()
+    }
+    pub fn write_into<W: Write>(self, output: &mut W, framing: Framing) -> ResultE<()> {
+        let payload_size = self.payload_size();
+        let payload = self.contents.into_inner();
         if payload_size % 4 != 0 {
             // Sanity check; OSC requires packets to be a multiple of 4 bytes.
             return Err(Error::BadFormat);
         }
         // Write the packet length
-        output.osc_write_i32(payload_size.try_into()?)?;
+        if framing == Framing::LengthPrefixed {
+            output.osc_write_i32(length_prefix(payload_size)?)?;
+        }
         // Write the packet payload
         output.osc_write_str("#bundle")?;
         Ok(output.write_all(&payload)?)
     }
 }
 
-impl<'a> Serializer for &'a mut BundleSerializer {
+impl<'a, C: WriteSeek> Serializer for &'a mut BundleSerializer<C> {
     type Ok = ();
     type Error = Error;
-    type SerializeSeq = BundleElemSerializer<'a>;
+    type SerializeSeq = BundleElemSerializer<'a, C>;
     type SerializeTuple = Self::SerializeSeq;
     type SerializeStruct = Self::SerializeSeq;
     type SerializeTupleStruct = Impossible<Self::Ok, Error>;
@@ -69,14 +145,23 @@ impl<'a> Serializer for &'a mut BundleSerializer {
     {
         self.serialize_seq(Some(size))
     }
+    // e.g. a struct wrapping the elements.
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T
+    ) -> ResultE<Self::Ok>
+    {
+        value.serialize(self)
+    }
 
     default_ser!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char bytes
-        str none some unit unit_struct unit_variant newtype_struct newtype_variant
+        str none some unit unit_struct unit_variant newtype_variant
         tuple_struct tuple_variant map struct_variant}
 }
 
 
-impl<'a> SerializeSeq for BundleElemSerializer<'a> {
+impl<'a, C: WriteSeek> SerializeSeq for BundleElemSerializer<'a, C> {
     type Ok = ();
     type Error = Error;
 
@@ -84,7 +169,8 @@ impl<'a> SerializeSeq for BundleElemSerializer<'a> {
         where T: Serialize
     {
         // each bundle element is itself a packet.
-        let mut ser = PktSerializer::new(self.bundle.contents.by_ref());
+        let contents: &mut dyn WriteSeek = &mut self.bundle.contents;
+        let mut ser = PktSerializer::bundle_element(contents, self.bundle.options);
         value.serialize(&mut ser)
     }
     fn end(self) -> ResultE<()> {
@@ -92,7 +178,7 @@ impl<'a> SerializeSeq for BundleElemSerializer<'a> {
     }
 }
 
-impl<'a> SerializeStruct for BundleElemSerializer<'a> {
+impl<'a, C: WriteSeek> SerializeStruct for BundleElemSerializer<'a, C> {
     type Ok = ();
     type Error = Error;
 
@@ -107,7 +193,7 @@ impl<'a> SerializeStruct for BundleElemSerializer<'a> {
     }
 }
 
-impl<'a> SerializeTuple for BundleElemSerializer<'a> {
+impl<'a, C: WriteSeek> SerializeTuple for BundleElemSerializer<'a, C> {
     type Ok = ();
     type Error = Error;
 
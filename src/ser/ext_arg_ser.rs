@@ -0,0 +1,94 @@
+use std::io::Write;
+use serde::ser::{Impossible, Serialize, Serializer};
+
+use error::{Error, ResultE};
+use types::RawArg;
+use super::msg_serializer::MsgBuf;
+use super::osc_writer::OscWriter;
+
+/// Serializes the payload of the argument types which are represented by
+/// helper types (e.g. `MidiMessage`). The caller is responsible for emitting
+/// the typetag; this only writes the argument data.
+#[derive(Debug)]
+pub struct ExtArgSerializer<'a, W: 'a> {
+    args: &'a mut W,
+}
+
+impl<'a, W: Write> ExtArgSerializer<'a, W> {
+    pub fn new(args: &'a mut W) -> Self {
+        Self { args }
+    }
+}
+
+impl<'a, 'b, W: Write> Serializer for &'b mut ExtArgSerializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<Self::Ok, Error>;
+    type SerializeTuple = Self::SerializeSeq;
+    type SerializeStruct = Self::SerializeSeq;
+    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+    /// Fixed-size payloads are copied verbatim; they must keep the data aligned.
+    fn serialize_bytes(self, value: &[u8]) -> ResultE<Self::Ok> {
+        if !value.len().is_multiple_of(4) {
+            return Err(Error::BadPadding);
+        }
+        Ok(self.args.write_all(value)?)
+    }
+    fn serialize_str(self, value: &str) -> ResultE<Self::Ok> {
+        self.args.osc_write_str(value)
+    }
+    // Any character, unlike a plain 'c' argument.
+    fn serialize_char(self, value: char) -> ResultE<Self::Ok> {
+        self.args.osc_write_char(value)
+    }
+
+    default_ser!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64
+        none some unit unit_struct unit_variant newtype_struct newtype_variant
+        seq tuple tuple_struct tuple_variant map struct struct_variant}
+}
+
+/// Serializes a `RawArg`, which is given as its typetag followed by its data.
+/// Unlike `ExtArgSerializer`, this also writes the typetag.
+#[derive(Debug)]
+pub struct RawArgSerializer<'a, B: 'a> {
+    msg: &'a mut B,
+}
+
+impl<'a, B: MsgBuf> RawArgSerializer<'a, B> {
+    pub fn new(msg: &'a mut B) -> Self {
+        Self { msg }
+    }
+}
+
+impl<'a, 'b, B: MsgBuf> Serializer for &'b mut RawArgSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<Self::Ok, Error>;
+    type SerializeTuple = Self::SerializeSeq;
+    type SerializeStruct = Self::SerializeSeq;
+    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+    fn serialize_bytes(self, value: &[u8]) -> ResultE<Self::Ok> {
+        match value.split_first() {
+            Some((&tag, data)) if RawArg::valid_tag(tag) => {
+                if data.len() % 4 != 0 {
+                    return Err(Error::BadPadding);
+                }
+                self.msg.push_tag(tag)?;
+                Ok(self.msg.write_all(data)?)
+            },
+            _ => Err(Error::UnsupportedType),
+        }
+    }
+
+    default_ser!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str
+        none some unit unit_struct unit_variant newtype_struct newtype_variant
+        seq tuple tuple_struct tuple_variant map struct struct_variant}
+}
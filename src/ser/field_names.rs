@@ -0,0 +1,47 @@
+use serde::ser::{Impossible, Serialize, Serializer, SerializeStruct};
+
+use error::{Error, ResultE};
+
+/// The names of the fields of the struct `value`, in the order they're
+/// serialized (skipped fields aren't included).
+pub(crate) fn field_names<T: ?Sized + Serialize>(value: &T) -> ResultE<Vec<&'static str>> {
+    value.serialize(FieldNames(Vec::new()))
+}
+
+/// Collects the names of the fields of a struct, in the order they're serialized.
+struct FieldNames(Vec<&'static str>);
+
+impl Serializer for FieldNames {
+    type Ok = Vec<&'static str>;
+    type Error = Error;
+    type SerializeSeq = Impossible<Self::Ok, Error>;
+    type SerializeTuple = Impossible<Self::Ok, Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> ResultE<Self::SerializeStruct> {
+        Ok(self)
+    }
+    // e.g. a struct wrapping the one with the fields.
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> ResultE<Self::Ok> {
+        value.serialize(self)
+    }
+
+    default_ser!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str bytes
+        none some unit unit_struct unit_variant newtype_variant
+        seq tuple tuple_struct tuple_variant map struct_variant}
+}
+
+impl SerializeStruct for FieldNames {
+    type Ok = Vec<&'static str>;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, _value: &T) -> ResultE<()> {
+        self.0.push(key);
+        Ok(())
+    }
+    fn end(self) -> ResultE<Self::Ok> {
+        Ok(self.0)
+    }
+}
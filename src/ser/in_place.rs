@@ -0,0 +1,166 @@
+use std::borrow::Cow;
+use std::io::{self, Cursor, ErrorKind, Write};
+use byteorder::{BigEndian, ByteOrder};
+
+use error::{Error, ResultE};
+use super::msg_serializer::MsgBuf;
+use super::osc_writer::{length_prefix, OscWriter};
+
+/// An output whose unwritten space can be accessed directly, so that messages
+/// can be assembled in it rather than in scratch buffers (see `to_slice`).
+pub trait SliceOutput {
+    /// The space after the current position.
+    fn remaining(&mut self) -> &mut [u8];
+    /// Move the current position `n` bytes forward.
+    fn advance(&mut self, n: usize);
+}
+
+impl SliceOutput for Cursor<&mut [u8]> {
+    fn remaining(&mut self) -> &mut [u8] {
+        let pos = (self.position() as usize).min(self.get_ref().len());
+        &mut self.get_mut()[pos..]
+    }
+    fn advance(&mut self, n: usize) {
+        let pos = self.position() + n as u64;
+        self.set_position(pos);
+    }
+}
+
+impl<S: SliceOutput + ?Sized> SliceOutput for &mut S {
+    fn remaining(&mut self) -> &mut [u8] {
+        (**self).remaining()
+    }
+    fn advance(&mut self, n: usize) {
+        (**self).advance(n)
+    }
+}
+
+/// The space for the address of a message which begins at the start of `buf`,
+/// after `prefix` bytes reserved for its length.
+pub fn address_space(buf: &mut [u8], prefix: usize) -> ResultE<Cursor<&mut [u8]>> {
+    match buf.get_mut(prefix..) {
+        Some(space) => Ok(Cursor::new(space)),
+        None => Err(Error::Io(ErrorKind::WriteZero.into())),
+    }
+}
+
+/// How far a message assembled in place has got.
+///
+/// The message occupies the space which follows the position of the output:
+/// its length prefix (if any) and address come first, followed by the
+/// argument data. As the typetag must precede the arguments, but its length
+/// isn't known until the last of them, its characters are stored in reverse
+/// at the very end of the space, and moved in front of the arguments once
+/// the message is complete.
+#[derive(Copy, Clone, Debug)]
+pub struct InPlaceState {
+    /// Bytes reserved for the length prefix.
+    prefix: usize,
+    /// End of the padded address, i.e. where the typetag goes.
+    address_end: usize,
+    /// End of the argument data written so far.
+    args_end: usize,
+    /// Number of typetag characters (excluding the leading ',').
+    tags: usize,
+}
+
+impl InPlaceState {
+    /// Continue a message whose address has been written up to `address_end`.
+    pub fn new(prefix: usize, address_end: usize) -> Self {
+        Self { prefix, address_end, args_end: address_end, tags: 0 }
+    }
+    /// Begin a message with the given address at the start of `buf`.
+    pub fn with_address(buf: &mut [u8], prefix: usize, address: &str) -> ResultE<Self> {
+        let mut space = address_space(buf, prefix)?;
+        space.osc_write_str(address)?;
+        Ok(Self::new(prefix, prefix + space.position() as usize))
+    }
+    /// Bytes reserved for the length prefix.
+    pub fn prefix(&self) -> usize {
+        self.prefix
+    }
+    /// Move the typetag in front of the arguments, and fill in the length
+    /// prefix. `buf` must be the same space the message was assembled in.
+    /// Returns the size of the message, including its length prefix.
+    pub fn finish(&self, buf: &mut [u8]) -> ResultE<usize> {
+        let (start, args_end, tags) = (self.address_end, self.args_end, self.tags);
+        let args_len = args_end - start;
+        // ',' followed by the characters, and at least one NUL.
+        let typetag_len = (tags + 5) & !0x3;
+        let end = start + typetag_len + args_len;
+        if end > buf.len() {
+            return Err(Error::Io(ErrorKind::WriteZero.into()));
+        }
+        // Bring the (reversed) characters next to the arguments, swap the two
+        // in place, then make room for the ',' and the padding.
+        let tags_start = buf.len() - tags;
+        buf.copy_within(tags_start.., args_end);
+        buf[start..args_end + tags].rotate_right(tags);
+        buf[start..start + tags].reverse();
+        buf.copy_within(start + tags..args_end + tags, start + typetag_len);
+        buf.copy_within(start..start + tags, start + 1);
+        buf[start] = b',';
+        for pad in &mut buf[start + 1 + tags..start + typetag_len] {
+            *pad = 0;
+        }
+        let payload_size = end - self.prefix;
+        if !payload_size.is_multiple_of(4) {
+            // Sanity check; OSC requires packets to be a multiple of 4 bytes.
+            return Err(Error::BadFormat);
+        }
+        if self.prefix != 0 {
+            BigEndian::write_i32(&mut buf[..4], length_prefix(payload_size)?);
+        }
+        Ok(end)
+    }
+    /// The address and typetag (without the leading ',') of the message,
+    /// once it's been through `finish`.
+    pub fn header<'b>(&self, buf: &'b [u8]) -> (Cow<'b, str>, Cow<'b, str>) {
+        let address = &buf[self.prefix..self.address_end];
+        let address_len = address.iter().position(|&c| c == 0).unwrap_or(address.len());
+        let typetag = &buf[self.address_end + 1..self.address_end + 1 + self.tags];
+        (String::from_utf8_lossy(&address[..address_len]), String::from_utf8_lossy(typetag))
+    }
+}
+
+/// A message being assembled in place (see `InPlaceState`).
+#[derive(Debug)]
+pub struct InPlaceMsg<'a> {
+    buf: &'a mut [u8],
+    state: &'a mut InPlaceState,
+}
+
+impl<'a> InPlaceMsg<'a> {
+    pub fn new(buf: &'a mut [u8], state: &'a mut InPlaceState) -> Self {
+        Self { buf, state }
+    }
+    /// Space left between the arguments and the typetag.
+    fn free(&self) -> usize {
+        self.buf.len() - self.state.tags - self.state.args_end
+    }
+}
+
+impl<'a> Write for InPlaceMsg<'a> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let n = data.len().min(self.free());
+        let args_end = self.state.args_end;
+        self.buf[args_end..args_end + n].copy_from_slice(&data[..n]);
+        self.state.args_end += n;
+        Ok(n)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MsgBuf for InPlaceMsg<'a> {
+    fn push_tag(&mut self, tag: u8) -> io::Result<()> {
+        if self.free() == 0 {
+            return Err(ErrorKind::WriteZero.into());
+        }
+        self.state.tags += 1;
+        let at = self.buf.len() - self.state.tags;
+        self.buf[at] = tag;
+        Ok(())
+    }
+}
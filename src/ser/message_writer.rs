@@ -0,0 +1,107 @@
+use std::fmt;
+use std::io::{Read, Write};
+use serde::ser::Serialize;
+
+use error::ResultE;
+use framing::Framing;
+use super::msg_serializer::{MsgBuf, MsgSerializer};
+use super::options::SerializerOptions;
+
+/// Builds a message whose blob arguments are streamed from readers of known
+/// length, e.g. files, rather than held in memory, or borrowed from slices
+/// rather than copied.
+///
+/// Other arguments are serialized as they're added; blob data is only read
+/// once the message is written. The message is passed to the output with
+/// `write_vectored`, so the data of borrowed blobs goes straight from the
+/// slice to e.g. a socket.
+///
+/// ```
+/// # extern crate serde_osc;
+/// # use serde_osc::ser::MessageWriter;
+/// # fn main() {
+/// let audio = vec![0u8; 48000 * 2];
+/// let mut msg = MessageWriter::new("/buffer/load").unwrap();
+/// msg.arg(&1).unwrap();
+/// msg.blob(&audio[..], audio.len()).unwrap();
+/// let mut packet = Vec::new();
+/// msg.write_to(&mut packet).unwrap();
+/// assert_eq!(packet.len(), 4 + 16 + 4 + 4 + 4 + audio.len());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MessageWriter<'a> {
+    msg: MsgSerializer,
+    blobs: Vec<StreamedBlob<'a>>,
+}
+
+/// Blob data which is only added to the output as the message is written.
+pub(crate) struct StreamedBlob<'a> {
+    /// Position of the data within the encoded arguments.
+    pub offset: usize,
+    pub data: BlobData<'a>,
+    pub len: usize,
+}
+
+/// Where the data of a streamed blob comes from.
+pub(crate) enum BlobData<'a> {
+    /// Copied from the reader.
+    Reader(Box<dyn Read + 'a>),
+    /// Written straight from the slice.
+    Slice(&'a [u8]),
+}
+
+impl<'a> MessageWriter<'a> {
+    /// Begin a message with the given address.
+    pub fn new(address: &str) -> ResultE<Self> {
+        Self::with_options(address, SerializerOptions::default())
+    }
+    /// Begin a message with the given address, encoded according to `options`.
+    pub fn with_options(address: &str, options: SerializerOptions) -> ResultE<Self> {
+        Ok(Self {
+            msg: MsgSerializer::with_address(&options.address_policy.apply(address)?)?,
+            blobs: Vec::new(),
+        })
+    }
+    /// Serialize `value` as the next argument.
+    /// Sequences (other than byte buffers) become OSC arrays.
+    pub fn arg<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        self.msg.serialize_arg(value)
+    }
+    /// Add a blob argument whose `len` bytes of data are read from `reader`.
+    /// Writing the message fails if the reader ends before `len` bytes;
+    /// anything beyond them is left unread.
+    pub fn blob<R>(&mut self, reader: R, len: usize) -> ResultE<()>
+        where R: Read + 'a
+    {
+        let offset = self.msg.begin_streamed_blob(len)?;
+        self.blobs.push(StreamedBlob { offset, data: BlobData::Reader(Box::new(reader)), len });
+        Ok(())
+    }
+    /// Add a blob argument whose data is borrowed from `data` until the
+    /// message is written.
+    pub fn blob_slice(&mut self, data: &'a [u8]) -> ResultE<()> {
+        let offset = self.msg.begin_streamed_blob(data.len())?;
+        self.blobs.push(StreamedBlob { offset, data: BlobData::Slice(data), len: data.len() });
+        Ok(())
+    }
+    /// Write the message as a length-prefixed packet.
+    pub fn write_to<W: Write>(self, write: &mut W) -> ResultE<()> {
+        self.msg.write_streamed(write, Framing::LengthPrefixed, self.blobs)
+    }
+    /// Write the message as a packet without the leading length prefix.
+    pub fn write_to_unframed<W: Write>(self, write: &mut W) -> ResultE<()> {
+        self.msg.write_streamed(write, Framing::Unframed, self.blobs)
+    }
+}
+
+impl<'a> fmt::Debug for StreamedBlob<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamedBlob")
+            .field("offset", &self.offset)
+            .field("len", &self.len)
+            .finish()
+    }
+}
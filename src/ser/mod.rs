@@ -1,18 +1,35 @@
-use std::io::{Cursor, Write};
+use std::convert::TryInto;
+use std::io::{Cursor, ErrorKind, Seek, Write};
 use serde;
-use error::ResultE;
+use error::{Error, ResultE};
+use framing::Framing;
 
 #[macro_use]
 mod serializer_defaults;
 
+mod bundle_builder;
 mod bundle_serializer;
+mod ext_arg_ser;
+mod field_names;
+mod in_place;
+mod message_writer;
 mod pkt_serializer;
 mod pkt_type_decoder;
-mod osc_writer;
+pub(crate) mod osc_writer;
 mod msg_serializer;
+mod options;
+mod size_counter;
 mod timetag_ser;
 
+pub use self::bundle_builder::BundleBuilder;
+pub use self::message_writer::MessageWriter;
+pub use self::options::{AddressPolicy, SerializerOptions};
 pub use self::pkt_serializer::PktSerializer as Serializer;
+use self::size_counter::SizeCounter;
+pub(crate) use self::field_names::field_names;
+
+/// Space first allocated by `to_vec_reuse`, enough for most messages.
+const MIN_REUSED_CAPACITY: usize = 64;
 
 /// Serialize `value` into an OSC packet, and write the contents into `write`.
 /// Note that serialization of structs is done only based on the ordering
@@ -20,7 +37,119 @@ pub use self::pkt_serializer::PktSerializer as Serializer;
 pub fn to_write<S: ?Sized, W: Write>(write: &mut W, value: &S) -> ResultE<()>
     where W: Write, S: serde::ser::Serialize
 {
-    let mut ser = Serializer::new(write.by_ref());
+    to_write_framed(write, value, Framing::LengthPrefixed)
+}
+
+/// Serialize `value` into an OSC packet without the leading length prefix,
+/// and write the contents into `write`. This is the format used by e.g. UDP
+/// transports, where each datagram contains exactly one packet.
+pub fn to_write_unframed<S, W>(write: &mut W, value: &S) -> ResultE<()>
+    where W: Write, S: ?Sized + serde::ser::Serialize
+{
+    to_write_framed(write, value, Framing::Unframed)
+}
+
+fn to_write_framed<S, W>(write: &mut W, value: &S, framing: Framing) -> ResultE<()>
+    where W: Write, S: ?Sized + serde::ser::Serialize
+{
+    let mut ser = Serializer::with_framing(write.by_ref(), framing);
+    value.serialize(&mut ser)
+}
+
+/// Serialize `value` into an OSC packet, and write the contents into the
+/// seekable `write` (e.g. a `File` or `Cursor`), returning the number of bytes
+/// written.
+///
+/// Unlike `to_write`, a bundle isn't assembled in memory first: its elements
+/// are written as they're serialized, and its length prefix is filled in
+/// afterwards. If serialization fails, a partial packet may have been written.
+///
+/// Only the framing of bundles is streamed: each message (on its own or
+/// within a bundle) is still assembled in memory, as its typetag must precede
+/// its arguments. To avoid that too, serialize into a buffer with `to_slice`.
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// let bundle = ((0u32, 1u32), (("/a", (1,)), ("/b", ("two",))));
+/// let mut file = Cursor::new(Vec::new());
+/// let written = serde_osc::ser::to_write_seek(&mut file, &bundle).unwrap();
+/// assert_eq!(written, file.get_ref().len());
+/// assert_eq!(file.into_inner(), serde_osc::to_vec(&bundle).unwrap());
+/// ```
+pub fn to_write_seek<S, W>(write: &mut W, value: &S) -> ResultE<usize>
+    where W: Write + Seek, S: ?Sized + serde::ser::Serialize
+{
+    to_write_seek_framed(write, value, Framing::LengthPrefixed)
+}
+
+/// Like `to_write_seek`, but omits the leading length prefix.
+pub fn to_write_seek_unframed<S, W>(write: &mut W, value: &S) -> ResultE<usize>
+    where W: Write + Seek, S: ?Sized + serde::ser::Serialize
+{
+    to_write_seek_framed(write, value, Framing::Unframed)
+}
+
+fn to_write_seek_framed<S, W>(write: &mut W, value: &S, framing: Framing) -> ResultE<usize>
+    where W: Write + Seek, S: ?Sized + serde::ser::Serialize
+{
+    let start = write.stream_position()?;
+    let mut ser = Serializer::with_backpatching(write.by_ref(), framing, SerializerOptions::default());
+    value.serialize(&mut ser)?;
+    Ok((write.stream_position()? - start).try_into()?)
+}
+
+/// Serialize each of `packets` into a length-prefixed OSC packet, and write
+/// them back-to-back into `write`, e.g. to record a session to a file or to
+/// send a batch over TCP. [`de::from_read_all`] reads them back.
+///
+/// `packets` may be a slice, a `Vec` or any other iterator. The packets must
+/// all be of the same Rust type; to mix messages of different shapes and
+/// bundles, use the dynamic `value::Packet`.
+///
+/// Stops at the first packet which fails to serialize; the ones before it
+/// have already been written.
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// let session = vec![("/play", (1,)), ("/stop", (2,))];
+/// let mut file = Vec::new();
+/// serde_osc::ser::to_write_all(&mut file, &session).unwrap();
+/// let replayed: Vec<(String, (i32,))> = serde_osc::de::from_read_all(Cursor::new(file))
+///     .collect::<Result<_, _>>().unwrap();
+/// assert_eq!(replayed, vec![("/play".to_owned(), (1,)), ("/stop".to_owned(), (2,))]);
+/// ```
+///
+/// Mixed packets:
+///
+/// ```
+/// use serde_osc::value::{Bundle, Message, Packet};
+///
+/// let mut bundle = Bundle::new((0, 1));
+/// bundle.elements.push(Message::to("/b").arg("two").build().into());
+/// let packets: Vec<Packet> = vec![Message::to("/a").arg(1).build().into(), bundle.into()];
+/// let mut stream = Vec::new();
+/// serde_osc::ser::to_write_all(&mut stream, &packets).unwrap();
+/// assert_eq!(Packet::from_slice(&stream).unwrap(), packets[0]);
+/// ```
+///
+/// [`de::from_read_all`]: ../de/fn.from_read_all.html
+pub fn to_write_all<I, W>(write: &mut W, packets: I) -> ResultE<()>
+    where W: Write, I: IntoIterator, I::Item: serde::ser::Serialize
+{
+    for packet in packets {
+        to_write(write, &packet)?;
+    }
+    Ok(())
+}
+
+/// Serialize `value` into a length-prefixed OSC packet, encoded according to
+/// `options`, and write the contents into `write`.
+pub fn to_write_with_options<S, W>(write: &mut W, value: &S, options: SerializerOptions) -> ResultE<()>
+    where W: Write, S: ?Sized + serde::ser::Serialize
+{
+    let mut ser = Serializer::with_options(write.by_ref(), Framing::LengthPrefixed, options);
     value.serialize(&mut ser)
 }
 
@@ -33,3 +162,125 @@ pub fn to_vec<T: ?Sized>(value: &T) -> ResultE<Vec<u8>>
     to_write(&mut output, value)?;
     Ok(output.into_inner())
 }
+
+/// Serializes `value` into a `Vec<u8>` type, omitting the length prefix.
+/// This is a wrapper around the `to_write_unframed` function.
+pub fn to_vec_unframed<T>(value: &T) -> ResultE<Vec<u8>>
+    where T: ?Sized + serde::ser::Serialize
+{
+    let mut output = Cursor::new(Vec::new());
+    to_write_unframed(&mut output, value)?;
+    Ok(output.into_inner())
+}
+
+/// Serializes `value` into a `Vec<u8>` type, encoded according to `options`.
+/// This is a wrapper around the `to_write_with_options` function.
+pub fn to_vec_with_options<T>(value: &T, options: SerializerOptions) -> ResultE<Vec<u8>>
+    where T: ?Sized + serde::ser::Serialize
+{
+    let mut output = Cursor::new(Vec::new());
+    to_write_with_options(&mut output, value, options)?;
+    Ok(output.into_inner())
+}
+
+/// Serializes `value` into `buf`, replacing its previous contents.
+/// The packet is assembled in place, as with `to_slice`, so once `buf` has
+/// grown to fit the largest packet, nothing more is allocated. If
+/// serialization fails, the contents of `buf` are unspecified.
+///
+/// ```
+/// let mut buf = Vec::new();
+/// for i in 0..3 {
+///     serde_osc::ser::to_vec_reuse(&mut buf, &("/count", (i,))).unwrap();
+///     // ... send buf ...
+/// #   assert_eq!(buf, serde_osc::to_vec(&("/count", (i,))).unwrap());
+/// }
+/// ```
+pub fn to_vec_reuse<T>(buf: &mut Vec<u8>, value: &T) -> ResultE<()>
+    where T: ?Sized + serde::ser::Serialize
+{
+    to_vec_reuse_framed(buf, value, Framing::LengthPrefixed)
+}
+
+/// Serializes `value` into `buf`, replacing its previous contents and omitting
+/// the length prefix; see `to_vec_reuse`.
+pub fn to_vec_reuse_unframed<T>(buf: &mut Vec<u8>, value: &T) -> ResultE<()>
+    where T: ?Sized + serde::ser::Serialize
+{
+    to_vec_reuse_framed(buf, value, Framing::Unframed)
+}
+
+fn to_vec_reuse_framed<T>(buf: &mut Vec<u8>, value: &T, framing: Framing) -> ResultE<()>
+    where T: ?Sized + serde::ser::Serialize
+{
+    loop {
+        // Serialize into all of the allocated space; if the packet doesn't
+        // fit, double it and start over.
+        let capacity = buf.capacity().max(MIN_REUSED_CAPACITY);
+        buf.resize(capacity, 0);
+        match to_slice_framed(value, buf, framing) {
+            Ok(len) => {
+                buf.truncate(len);
+                return Ok(());
+            },
+            Err(Error::Io(ref err)) if err.kind() == ErrorKind::WriteZero => buf.reserve(capacity),
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Serializes `value` into the caller-provided `buf`, returning the number of
+/// bytes written. The packet is assembled directly in `buf`, so that nothing
+/// is allocated (e.g. on a real-time audio thread), and the same buffer can be
+/// reused for every packet.
+/// If `buf` is too small to hold the packet, an `Io` error of kind
+/// `WriteZero` is returned and the contents of `buf` are unspecified.
+pub fn to_slice<T>(value: &T, buf: &mut [u8]) -> ResultE<usize>
+    where T: ?Sized + serde::ser::Serialize
+{
+    to_slice_framed(value, buf, Framing::LengthPrefixed)
+}
+
+/// Serializes `value` into the caller-provided `buf`, omitting the length prefix.
+/// Returns the number of bytes written; see `to_slice`.
+pub fn to_slice_unframed<T>(value: &T, buf: &mut [u8]) -> ResultE<usize>
+    where T: ?Sized + serde::ser::Serialize
+{
+    to_slice_framed(value, buf, Framing::Unframed)
+}
+
+fn to_slice_framed<T>(value: &T, buf: &mut [u8], framing: Framing) -> ResultE<usize>
+    where T: ?Sized + serde::ser::Serialize
+{
+    let mut output = Cursor::new(buf);
+    value.serialize(&mut Serializer::in_place(&mut output, framing))?;
+    Ok(output.position() as usize)
+}
+
+/// Computes the number of bytes `value` occupies once serialized by `to_write`
+/// (i.e. including the length prefix), without storing the packet: the sizes
+/// of the address, typetag, arguments and bundle elements are added up, but
+/// nothing is written, so the cost doesn't grow with the size of any blobs.
+/// Useful for sizing buffers passed to `to_slice`, or for checking a packet
+/// against the MTU of a transport before sending it.
+pub fn serialized_size<T>(value: &T) -> ResultE<usize>
+    where T: ?Sized + serde::ser::Serialize
+{
+    serialized_size_framed(value, Framing::LengthPrefixed)
+}
+
+/// Computes the number of bytes `value` occupies once serialized by
+/// `to_write_unframed`, i.e. excluding the length prefix.
+pub fn serialized_size_unframed<T>(value: &T) -> ResultE<usize>
+    where T: ?Sized + serde::ser::Serialize
+{
+    serialized_size_framed(value, Framing::Unframed)
+}
+
+fn serialized_size_framed<T>(value: &T, framing: Framing) -> ResultE<usize>
+    where T: ?Sized + serde::ser::Serialize
+{
+    let mut counter = SizeCounter::new();
+    value.serialize(&mut Serializer::counting(&mut counter, framing))?;
+    Ok(counter.size())
+}
@@ -1,12 +1,60 @@
 use std::convert::TryInto;
-use std::io::{Cursor, Write};
+use std::io::{self, Cursor, ErrorKind, IoSlice, Read, Write};
 use byteorder::WriteBytesExt;
-use serde::ser::{Impossible, Serialize, Serializer, SerializeSeq, SerializeStruct, SerializeTuple};
+use serde::ser::{Impossible, Serialize, Serializer, SerializeMap, SerializeSeq, SerializeStruct,
+    SerializeTuple, SerializeTupleStruct};
 
 use error::{Error, ResultE};
-use super::osc_writer::OscWriter;
+use framing::Framing;
+use types::{is_helper_name, COLOR_NAME, MIDI_NAME, RAW_ARG_NAME, SYMBOL_NAME, UNICODE_CHAR_NAME};
+use super::ext_arg_ser::{ExtArgSerializer, RawArgSerializer};
+use super::message_writer::{BlobData, StreamedBlob};
+use super::osc_writer::{length_prefix, OscWriter};
 
-/// Once we know we're serializing a message, we do so through this struct.
+/// Where the typetag and arguments of a message are assembled as they're
+/// serialized: argument data is appended through `Write`, and typetag
+/// characters through `push_tag`.
+pub trait MsgBuf: Write {
+    /// Append a character to the typetag.
+    fn push_tag(&mut self, tag: u8) -> io::Result<()>;
+    /// The typetag, for use with the `write_*_tag` methods of `OscWriter`.
+    fn tags(&mut self) -> Tags<'_, Self> where Self: Sized {
+        Tags { msg: self }
+    }
+    /// Serialize a sequence (e.g. a tuple or struct) as the argument list.
+    fn serialize_args<T: ?Sized + Serialize>(&mut self, value: &T) -> ResultE<()> where Self: Sized {
+        value.serialize(ArgListSerializer{ msg: self })
+    }
+    /// Serialize one argument of the message.
+    fn serialize_arg<T: ?Sized + Serialize>(&mut self, value: &T) -> ResultE<()> where Self: Sized {
+        value.serialize(&mut ArgSerializer{ msg: self })
+    }
+    /// Serialize the payload of a newtype enum variant as the message arguments.
+    fn serialize_payload<T: ?Sized + Serialize>(&mut self, value: &T) -> ResultE<()> where Self: Sized {
+        value.serialize(PayloadSerializer{ msg: self })
+    }
+}
+
+/// Appends whatever is written to it to the typetag of a message.
+#[derive(Debug)]
+pub struct Tags<'a, B: 'a> {
+    msg: &'a mut B,
+}
+
+impl<'a, B: MsgBuf> Write for Tags<'a, B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &tag in buf {
+            self.msg.push_tag(tag)?;
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Once we know we're serializing a message, we do so through this struct,
+/// unless it can be assembled in place (see `in_place::InPlaceMsg`).
 #[derive(Debug)]
 pub struct MsgSerializer {
     /// Address + typetag, merged into one field
@@ -15,9 +63,30 @@ pub struct MsgSerializer {
     args: Cursor<Vec<u8>>,
 }
 
+/// Serializes the sequence which holds the arguments of a message.
 #[derive(Debug)]
-pub struct ArgSerializer<'a> {
-    msg: &'a mut MsgSerializer,
+pub struct ArgListSerializer<'a, B: 'a> {
+    msg: &'a mut B,
+}
+
+#[derive(Debug)]
+pub struct ArgSerializer<'a, B: 'a> {
+    msg: &'a mut B,
+}
+
+/// Serializes the payload of a newtype enum variant as the message arguments.
+/// Sequence-like payloads (tuples, structs, ...) become the argument list,
+/// while any other payload becomes the message's only argument.
+#[derive(Debug)]
+pub struct PayloadSerializer<'a, B: 'a> {
+    msg: &'a mut B,
+}
+
+/// Serializes a sequence nested within the argument list as an OSC array,
+/// i.e. its elements' typetags are enclosed within '[' and ']'.
+#[derive(Debug)]
+pub struct ArrayArgSerializer<'a, B: 'a> {
+    msg: &'a mut B,
 }
 
 impl MsgSerializer {
@@ -29,36 +98,136 @@ impl MsgSerializer {
             args: Cursor::new(Vec::new()),
         })
     }
-    pub fn write_into<W: Write>(self, output: &mut W) -> ResultE<()> {
+    /// Begin a message with the given address.
+    pub fn with_address(address: &str) -> ResultE<Self> {
+        let mut output = Cursor::new(Vec::new());
+        output.osc_write_str(address)?;
+        Self::new(output)
+    }
+    /// Begin a blob argument of `len` bytes, whose data is supplied to `write_streamed`.
+    /// Returns the offset within the argument data at which the blob data belongs.
+    pub fn begin_streamed_blob(&mut self, len: usize) -> ResultE<usize> {
+        self.addr_typetag.write_blob_tag()?;
+        self.args.osc_write_i32(length_prefix(len)?)?;
+        Ok(self.args.position() as usize)
+    }
+    /// The address and typetag (without the leading ',') of the message so far.
+    pub fn header(&self) -> (String, String) {
+        let addr_typetag = self.addr_typetag.get_ref();
+        let address_len = addr_typetag.iter().position(|&c| c == 0).unwrap_or(0);
+        // The typetag follows the address's padding and the ','.
+        let typetag_start = ((address_len + 4) & !0x3) + 1;
+        (
+            String::from_utf8_lossy(&addr_typetag[..address_len]).into_owned(),
+            String::from_utf8_lossy(&addr_typetag[typetag_start.min(addr_typetag.len())..]).into_owned(),
+        )
+    }
+    /// Size of the message contents (excluding any streamed blobs).
+    pub fn payload_size(&self) -> usize {
+        let typetag_len = self.addr_typetag.get_ref().len();
+        typetag_len + 4 - (typetag_len % 4) + self.args.get_ref().len()
+    }
+    pub fn write_into<W: Write>(self, output: &mut W, framing: Framing) -> ResultE<()> {
+        self.write_streamed(output, framing, Vec::new())
+    }
+    /// Write the message, with the data of each streamed blob in between the
+    /// other arguments.
+    ///
+    /// The parts of the packet (length, address and typetag, arguments, and
+    /// blobs given as slices) are passed to the output together, through
+    /// `write_vectored`, so that blob data isn't copied into a buffer first.
+    /// Blobs given as readers are copied once the parts before them are written.
+    pub(crate) fn write_streamed<W: Write>(self, output: &mut W, framing: Framing, blobs: Vec<StreamedBlob>) -> ResultE<()> {
         let typetag = self.addr_typetag.into_inner();
         let args = self.args.into_inner();
         let tag_pad = 4 - (typetag.len() % 4);
-        let payload_size = typetag.len() + tag_pad + args.len();
+        let blob_size: usize = blobs.iter().map(|blob| (blob.len + 3) & !0x3).sum();
+        let payload_size = typetag.len() + tag_pad + args.len() + blob_size;
         if payload_size % 4 != 0 {
             // Sanity check; OSC requires packets to be a multiple of 4 bytes.
             return Err(Error::BadFormat);
         }
-
-        // Write the packet length
-        output.osc_write_i32(payload_size.try_into()?)?;
-        // Write the address and type tag
-        output.write_all(&typetag)?;
+        let length = length_prefix(payload_size)?.to_be_bytes();
         let zeros = b"\0\0\0\0";
-        output.write_all(&zeros[..tag_pad])?;
-        // Write the arguments
-        Ok(output.write_all(&args)?)
+
+        if blobs.is_empty() {
+            // Without blobs, the parts fit on the stack (empty parts are skipped).
+            let length: &[u8] = if framing == Framing::LengthPrefixed { &length } else { &[] };
+            let mut parts = [IoSlice::new(length), IoSlice::new(&typetag), IoSlice::new(&zeros[..tag_pad]), IoSlice::new(&args)];
+            return Ok(write_all_vectored(output, &mut parts)?);
+        }
+        let mut parts = Vec::with_capacity(4 + 3 * blobs.len());
+        // The packet length
+        if framing == Framing::LengthPrefixed {
+            parts.push(IoSlice::new(&length));
+        }
+        // The address and type tag
+        parts.push(IoSlice::new(&typetag));
+        parts.push(IoSlice::new(&zeros[..tag_pad]));
+        // The arguments, with the blob data in between
+        let mut written = 0;
+        for blob in blobs {
+            parts.push(IoSlice::new(&args[written..blob.offset]));
+            written = blob.offset;
+            match blob.data {
+                BlobData::Slice(data) => parts.push(IoSlice::new(data)),
+                BlobData::Reader(reader) => {
+                    write_all_vectored(output, &mut parts)?;
+                    parts.clear();
+                    let copied = io::copy(&mut reader.take(blob.len as u64), output)?;
+                    if copied != blob.len as u64 {
+                        // The reader ended before the promised length.
+                        return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+                    }
+                },
+            }
+            parts.push(IoSlice::new(&zeros[..(4 - blob.len % 4) % 4]));
+        }
+        parts.push(IoSlice::new(&args[written..]));
+        Ok(write_all_vectored(output, &mut parts)?)
     }
 }
 
-impl<'a> Serializer for &'a mut MsgSerializer {
+/// Argument data is appended to `args`.
+impl Write for MsgSerializer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.args.write(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl MsgBuf for MsgSerializer {
+    fn push_tag(&mut self, tag: u8) -> io::Result<()> {
+        self.addr_typetag.write_u8(tag)
+    }
+}
+
+/// Write all of `parts`, in as few calls to `write_vectored` as the output allows.
+fn write_all_vectored<W: Write + ?Sized>(output: &mut W, mut parts: &mut [IoSlice]) -> io::Result<()> {
+    // Skip empty parts, so that a write of 0 bytes means the output is full.
+    IoSlice::advance_slices(&mut parts, 0);
+    while !parts.is_empty() {
+        match output.write_vectored(parts) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(written) => IoSlice::advance_slices(&mut parts, written),
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {},
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+impl<'a, B: MsgBuf> Serializer for ArgListSerializer<'a, B> {
     type Ok = ();
     type Error = Error;
-    type SerializeSeq = ArgSerializer<'a>;
+    type SerializeSeq = ArgSerializer<'a, B>;
     type SerializeTuple = Self::SerializeSeq;
     type SerializeStruct = Self::SerializeSeq;
     type SerializeTupleStruct = Impossible<Self::Ok, Error>;
     type SerializeTupleVariant = Impossible<Self::Ok, Error>;
-    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeMap = Self::SerializeSeq;
     type SerializeStructVariant = Impossible<Self::Ok, Error>;
 
     fn serialize_seq(
@@ -66,7 +235,15 @@ impl<'a> Serializer for &'a mut MsgSerializer {
         _size: Option<usize>
     ) -> ResultE<Self::SerializeSeq>
     {
-        Ok(ArgSerializer{ msg: self })
+        Ok(ArgSerializer{ msg: self.msg })
+    }
+    // Maps are encoded as alternating key and value arguments.
+    fn serialize_map(
+        self,
+        size: Option<usize>
+    ) -> ResultE<Self::SerializeMap>
+    {
+        self.serialize_seq(size)
     }
     fn serialize_tuple(
         self, 
@@ -94,45 +271,257 @@ impl<'a> Serializer for &'a mut MsgSerializer {
     {
         Ok(())
     }
+    // e.g. a struct wrapping the tuple of arguments.
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T
+    ) -> ResultE<Self::Ok>
+    {
+        value.serialize(self)
+    }
 
     default_ser!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char bytes
-        str none some unit_variant newtype_struct newtype_variant
-        tuple_struct tuple_variant map struct_variant}
+        str none some unit_variant newtype_variant
+        tuple_struct tuple_variant struct_variant}
 }
 
-impl<'a> Serializer for &'a mut ArgSerializer<'a> {
+impl<'a, B: MsgBuf> Serializer for &'a mut ArgSerializer<'a, B> {
     type Ok = ();
     type Error = Error;
-    type SerializeSeq = Impossible<Self::Ok, Error>;
+    type SerializeSeq = ArrayArgSerializer<'a, B>;
     type SerializeTuple = Self::SerializeSeq;
     type SerializeStruct = Self::SerializeSeq;
-    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleStruct = Self::SerializeSeq;
     type SerializeTupleVariant = Impossible<Self::Ok, Error>;
-    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeMap = Self::SerializeSeq;
     type SerializeStructVariant = Impossible<Self::Ok, Error>;
 
     fn serialize_i32(self, value: i32) -> ResultE<Self::Ok> {
-        self.msg.addr_typetag.write_i32_tag()?;
-        Ok(self.msg.args.osc_write_i32(value)?)
+        self.msg.tags().write_i32_tag()?;
+        self.msg.osc_write_i32(value)
+    }
+    // Narrower integers are widened to 'i'; unsigned ones must fit in an i32.
+    fn serialize_i8(self, value: i8) -> ResultE<Self::Ok> {
+        self.serialize_i32(value.into())
+    }
+    fn serialize_i16(self, value: i16) -> ResultE<Self::Ok> {
+        self.serialize_i32(value.into())
+    }
+    fn serialize_u8(self, value: u8) -> ResultE<Self::Ok> {
+        self.serialize_i32(value.into())
+    }
+    fn serialize_u16(self, value: u16) -> ResultE<Self::Ok> {
+        self.serialize_i32(value.into())
+    }
+    fn serialize_u32(self, value: u32) -> ResultE<Self::Ok> {
+        self.serialize_i32(value.try_into()?)
+    }
+    // 64-bit integers are encoded as 'h'; u64 values must fit in an i64.
+    fn serialize_i64(self, value: i64) -> ResultE<Self::Ok> {
+        self.msg.tags().write_i64_tag()?;
+        self.msg.osc_write_i64(value)
+    }
+    fn serialize_u64(self, value: u64) -> ResultE<Self::Ok> {
+        self.serialize_i64(value.try_into()?)
     }
     fn serialize_f32(self, value: f32) -> ResultE<Self::Ok> {
-        self.msg.addr_typetag.write_f32_tag()?;
-        Ok(self.msg.args.osc_write_f32(value)?)
+        self.msg.tags().write_f32_tag()?;
+        self.msg.osc_write_f32(value)
+    }
+    fn serialize_f64(self, value: f64) -> ResultE<Self::Ok> {
+        self.msg.tags().write_f64_tag()?;
+        self.msg.osc_write_f64(value)
     }
     fn serialize_str(self, value: &str) -> ResultE<Self::Ok> {
-        self.msg.addr_typetag.write_str_tag()?;
-        Ok(self.msg.args.osc_write_str(value)?)
+        self.msg.tags().write_str_tag()?;
+        self.msg.osc_write_str(value)
     }
     fn serialize_bytes(self, value: &[u8]) -> ResultE<Self::Ok> {
-        self.msg.addr_typetag.write_blob_tag()?;
-        Ok(self.msg.args.osc_write_blob(value)?)
+        self.msg.tags().write_blob_tag()?;
+        self.msg.osc_write_blob(value)
+    }
+    // The spec defines 'c' as an ASCII character.
+    fn serialize_char(self, value: char) -> ResultE<Self::Ok> {
+        if !value.is_ascii() {
+            return Err(Error::NonAsciiChar(value));
+        }
+        self.msg.tags().write_char_tag()?;
+        self.msg.osc_write_char(value)
+    }
+    fn serialize_bool(self, value: bool) -> ResultE<Self::Ok> {
+        self.msg.tags().write_bool_tag(value)
+    }
+    // Absent optional arguments are encoded as 'N' (nil).
+    fn serialize_none(self) -> ResultE<Self::Ok> {
+        self.msg.tags().write_nil_tag()
+    }
+    // So are units, which is how e.g. JSON's `null` is presented.
+    fn serialize_unit(self) -> ResultE<Self::Ok> {
+        self.serialize_none()
+    }
+    // Present optional arguments are encoded as if they weren't optional.
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> ResultE<Self::Ok> {
+        value.serialize(self)
+    }
+    // The helper types in `serde_osc::types` identify themselves by their name;
+    // other newtype structs (e.g. `struct Freq(f32)`) are encoded as what they wrap.
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T
+    ) -> ResultE<Self::Ok>
+    {
+        match name {
+            MIDI_NAME => self.msg.tags().write_midi_tag()?,
+            COLOR_NAME => self.msg.tags().write_color_tag()?,
+            SYMBOL_NAME => self.msg.tags().write_symbol_tag()?,
+            UNICODE_CHAR_NAME => self.msg.tags().write_char_tag()?,
+            // The typetag is part of the value.
+            RAW_ARG_NAME => return value.serialize(&mut RawArgSerializer::new(self.msg)),
+            _ => return value.serialize(self),
+        }
+        value.serialize(&mut ExtArgSerializer::new(self.msg))
+    }
+    fn serialize_seq(
+        self,
+        _size: Option<usize>
+    ) -> ResultE<Self::SerializeSeq>
+    {
+        self.msg.tags().write_array_begin_tag()?;
+        Ok(ArrayArgSerializer{ msg: self.msg })
+    }
+    fn serialize_tuple(
+        self,
+        size: usize
+    ) -> ResultE<Self::SerializeTuple>
+    {
+        self.serialize_seq(Some(size))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        size: usize
+    ) -> ResultE<Self::SerializeTupleStruct>
+    {
+        self.serialize_seq(Some(size))
+    }
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        size: usize
+    ) -> ResultE<Self::SerializeStruct>
+    {
+        self.serialize_seq(Some(size))
     }
-    default_ser!{bool i8 i16 i64 u8 u16 u32 u64 f64 char
-        none some unit unit_struct unit_variant newtype_struct newtype_variant
-        seq tuple tuple_struct tuple_variant map struct struct_variant}
+    // A nested map becomes an array of alternating keys and values.
+    fn serialize_map(
+        self,
+        size: Option<usize>
+    ) -> ResultE<Self::SerializeMap>
+    {
+        self.serialize_seq(size)
+    }
+    default_ser!{unit_struct unit_variant newtype_variant
+        tuple_variant struct_variant}
 }
 
-impl<'a> SerializeSeq for ArgSerializer<'a> {
+/// Serialize the value as the sole argument of the message.
+macro_rules! forward_to_arg_ser {
+    ($($func:ident($arg:ty))*) => {
+        $(
+            fn $func(self, value: $arg) -> ResultE<Self::Ok> {
+                (&mut ArgSerializer{ msg: self.msg }).$func(value)
+            }
+        )*
+    };
+}
+
+impl<'a, B: MsgBuf> Serializer for PayloadSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = ArgSerializer<'a, B>;
+    type SerializeTuple = Self::SerializeSeq;
+    type SerializeStruct = Self::SerializeSeq;
+    type SerializeTupleStruct = Self::SerializeSeq;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = Self::SerializeSeq;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+    forward_to_arg_ser!{
+        serialize_bool(bool) serialize_i8(i8) serialize_i16(i16) serialize_i32(i32)
+        serialize_i64(i64) serialize_u8(u8) serialize_u16(u16) serialize_u32(u32)
+        serialize_u64(u64) serialize_f32(f32) serialize_f64(f64) serialize_char(char)
+        serialize_str(&str) serialize_bytes(&[u8])
+    }
+    fn serialize_none(self) -> ResultE<Self::Ok> {
+        (&mut ArgSerializer{ msg: self.msg }).serialize_none()
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> ResultE<Self::Ok> {
+        self.msg.serialize_arg(value)
+    }
+    // The helper types in `serde_osc::types` are a single argument; other
+    // newtype structs are whatever they wrap (e.g. a struct of arguments).
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T
+    ) -> ResultE<Self::Ok>
+    {
+        if is_helper_name(name) {
+            (&mut ArgSerializer{ msg: self.msg }).serialize_newtype_struct(name, value)
+        } else {
+            value.serialize(self)
+        }
+    }
+    // A unit payload carries no arguments.
+    fn serialize_unit(self) -> ResultE<Self::Ok> {
+        Ok(())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> ResultE<Self::Ok> {
+        Ok(())
+    }
+    fn serialize_seq(
+        self,
+        _size: Option<usize>
+    ) -> ResultE<Self::SerializeSeq>
+    {
+        Ok(ArgSerializer{ msg: self.msg })
+    }
+    fn serialize_tuple(
+        self,
+        size: usize
+    ) -> ResultE<Self::SerializeTuple>
+    {
+        self.serialize_seq(Some(size))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        size: usize
+    ) -> ResultE<Self::SerializeTupleStruct>
+    {
+        self.serialize_seq(Some(size))
+    }
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        size: usize
+    ) -> ResultE<Self::SerializeStruct>
+    {
+        self.serialize_seq(Some(size))
+    }
+    fn serialize_map(
+        self,
+        size: Option<usize>
+    ) -> ResultE<Self::SerializeMap>
+    {
+        self.serialize_seq(size)
+    }
+    default_ser!{unit_variant newtype_variant tuple_variant struct_variant}
+}
+
+impl<'a, B: MsgBuf> SerializeSeq for ArgSerializer<'a, B> {
     type Ok = ();
     type Error = Error;
 
@@ -147,7 +536,7 @@ impl<'a> SerializeSeq for ArgSerializer<'a> {
     }
 }
 
-impl<'a> SerializeStruct for ArgSerializer<'a> {
+impl<'a, B: MsgBuf> SerializeStruct for ArgSerializer<'a, B> {
     type Ok = ();
     type Error = Error;
 
@@ -162,7 +551,7 @@ impl<'a> SerializeStruct for ArgSerializer<'a> {
     }
 }
 
-impl<'a> SerializeTuple for ArgSerializer<'a> {
+impl<'a, B: MsgBuf> SerializeTuple for ArgSerializer<'a, B> {
     type Ok = ();
     type Error = Error;
 
@@ -176,3 +565,115 @@ impl<'a> SerializeTuple for ArgSerializer<'a> {
         SerializeSeq::end(self)
     }
 }
+
+impl<'a, B: MsgBuf> SerializeMap for ArgSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, key)
+    }
+    fn serialize_value<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> ResultE<()> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a, B: MsgBuf> SerializeTupleStruct for ArgSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> ResultE<()> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a, B: MsgBuf> SerializeSeq for ArrayArgSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        value.serialize(&mut ArgSerializer{ msg: self.msg })
+    }
+    fn end(self) -> ResultE<()> {
+        self.msg.tags().write_array_end_tag()
+    }
+}
+
+impl<'a, B: MsgBuf> SerializeStruct for ArrayArgSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> ResultE<()> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a, B: MsgBuf> SerializeTuple for ArrayArgSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> ResultE<()> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a, B: MsgBuf> SerializeTupleStruct for ArrayArgSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> ResultE<()> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a, B: MsgBuf> SerializeMap for ArrayArgSerializer<'a, B> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, key)
+    }
+    fn serialize_value<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> ResultE<()> {
+        SerializeSeq::end(self)
+    }
+}
@@ -0,0 +1,68 @@
+use std::borrow::Cow;
+
+use error::{Error, ResultE};
+
+/// Settings which control how packets are encoded.
+///
+/// ```
+/// use serde_osc::ser::{AddressPolicy, SerializerOptions};
+///
+/// let options = SerializerOptions {
+///     address_policy: AddressPolicy::Sanitize,
+///     ..SerializerOptions::default()
+/// };
+/// # let _ = options;
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SerializerOptions {
+    /// What to do with message addresses which aren't valid OSC addresses.
+    pub address_policy: AddressPolicy,
+}
+
+/// How to handle invalid message addresses.
+///
+/// A valid address starts with '/', and contains no spaces, '#' or control
+/// characters. (Pattern characters such as '*' and '{' are allowed.)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum AddressPolicy {
+    /// Fail with `Error::BadAddress`.
+    #[default]
+    Validate,
+    /// Fix the address: prepend a '/' if it's missing, percent-escape spaces
+    /// and '#' (as "%20" and "%23") and strip control characters.
+    Sanitize,
+    /// Write the address verbatim.
+    Unchecked,
+}
+
+/// Whether `c` may not appear in an address.
+fn is_invalid(c: char) -> bool {
+    c == ' ' || c == '#' || c.is_control()
+}
+
+impl AddressPolicy {
+    /// Check (or fix) the address of a message according to this policy.
+    pub(crate) fn apply<'a>(&self, address: &'a str) -> ResultE<Cow<'a, str>> {
+        let valid = address.starts_with('/') && !address.contains(is_invalid);
+        match *self {
+            _ if valid => Ok(Cow::Borrowed(address)),
+            AddressPolicy::Unchecked => Ok(Cow::Borrowed(address)),
+            AddressPolicy::Validate => Err(Error::BadAddress(address.to_owned())),
+            AddressPolicy::Sanitize => {
+                let mut sanitized = String::with_capacity(address.len() + 1);
+                if !address.starts_with('/') {
+                    sanitized.push('/');
+                }
+                for c in address.chars() {
+                    match c {
+                        ' ' => sanitized.push_str("%20"),
+                        '#' => sanitized.push_str("%23"),
+                        c if c.is_control() => {},
+                        c => sanitized.push(c),
+                    }
+                }
+                Ok(Cow::Owned(sanitized))
+            },
+        }
+    }
+}
@@ -2,7 +2,13 @@ use std::convert::TryInto;
 use std::io::Write;
 use byteorder::{BigEndian, WriteBytesExt};
 
-use error::ResultE;
+use error::{Error, ResultE};
+
+/// The length prefix of a packet, bundle element or blob of `len` bytes.
+/// Fails with `Error::TooLarge` if it doesn't fit in an `i32`.
+pub(crate) fn length_prefix<N: TryInto<i32>>(len: N) -> ResultE<i32> {
+    len.try_into().map_err(|_| Error::TooLarge)
+}
 
 /// auto-implemented trait to write OSC data to a Write object.
 pub trait OscWriter: Write {
@@ -12,12 +18,24 @@ pub trait OscWriter: Write {
     fn write_i32_tag(&mut self) -> ResultE<()> {
         Ok(self.write_u8(b'i')?)
     }
+    fn osc_write_i64(&mut self, value: i64) -> ResultE<()> {
+        Ok(self.write_i64::<BigEndian>(value)?)
+    }
+    fn write_i64_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b'h')?)
+    }
     fn osc_write_f32(&mut self, value: f32) -> ResultE<()> {
         Ok(self.write_f32::<BigEndian>(value)?)
     }
     fn write_f32_tag(&mut self) -> ResultE<()> {
         Ok(self.write_u8(b'f')?)
     }
+    fn osc_write_f64(&mut self, value: f64) -> ResultE<()> {
+        Ok(self.write_f64::<BigEndian>(value)?)
+    }
+    fn write_f64_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b'd')?)
+    }
     fn osc_write_str(&mut self, value: &str) -> ResultE<()> {
         self.write_all(value.as_bytes())?;
         // pad to 4-byte boundary, PLUS ensure we have at least one null terminator.
@@ -28,9 +46,13 @@ pub trait OscWriter: Write {
     fn write_str_tag(&mut self) -> ResultE<()> {
         Ok(self.write_u8(b's')?)
     }
+    /// Symbols are encoded identically to strings.
+    fn write_symbol_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b'S')?)
+    }
     fn osc_write_blob(&mut self, value: &[u8]) -> ResultE<()> {
         // write the blob length (yes, as an i32)
-        self.write_i32::<BigEndian>(value.len().try_into()?)?;
+        self.write_i32::<BigEndian>(length_prefix(value.len())?)?;
         self.write_all(value)?;
         let pad_bytes = (4 - value.len() % 4) % 4;
         let zeros = b"\0\0\0\0";
@@ -39,6 +61,37 @@ pub trait OscWriter: Write {
     fn write_blob_tag(&mut self) -> ResultE<()> {
         Ok(self.write_u8(b'b')?)
     }
+    /// Characters are written as their 32-bit code point.
+    fn osc_write_char(&mut self, value: char) -> ResultE<()> {
+        Ok(self.write_u32::<BigEndian>(value.into())?)
+    }
+    fn write_char_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b'c')?)
+    }
+    /// 'T' and 'F' carry no payload; the value is encoded in the tag itself.
+    fn write_bool_tag(&mut self, value: bool) -> ResultE<()> {
+        Ok(self.write_u8(if value { b'T' } else { b'F' })?)
+    }
+    /// The 'm' payload is 4 bytes, written verbatim.
+    fn write_midi_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b'm')?)
+    }
+    /// The 'r' payload is 4 bytes (red, green, blue, alpha), written verbatim.
+    fn write_color_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b'r')?)
+    }
+    /// 'N' (nil) carries no payload.
+    fn write_nil_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b'N')?)
+    }
+    /// Arrays have no payload of their own; their elements are written in order
+    /// and the element typetags are placed between '[' and ']'.
+    fn write_array_begin_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b'[')?)
+    }
+    fn write_array_end_tag(&mut self) -> ResultE<()> {
+        Ok(self.write_u8(b']')?)
+    }
     /// Write the OSC timetag, characterized by a (u32, u32) pair.
     /// The first u32 is the seconds, second is fraction of seconds.
     fn osc_write_timetag(&mut self, tag: (u32, u32)) -> ResultE<()> {
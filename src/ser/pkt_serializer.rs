@@ -1,10 +1,19 @@
-use std::io::Write;
-use serde::ser::{Impossible, Serialize, Serializer, SerializeSeq, SerializeStruct, SerializeTuple};
+use std::convert::TryInto;
+use std::io::{self, Cursor, Seek, SeekFrom, Write};
+use serde::ser::{Impossible, Serialize, Serializer, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleVariant};
 
+use de::packet_enum::{BUNDLE_VARIANT, MESSAGE_VARIANT};
 use error::{Error, ResultE};
-use super::bundle_serializer::BundleSerializer;
-use super::msg_serializer::MsgSerializer;
+use framing::Framing;
+use observe::{Observer, PacketSummary};
+use super::bundle_serializer::{BundleSerializer, SeekWith, WriteSeek};
+use super::in_place::{address_space, InPlaceMsg, InPlaceState, SliceOutput};
+use super::msg_serializer::{MsgBuf, MsgSerializer};
+use super::options::SerializerOptions;
+use super::osc_writer::{length_prefix, OscWriter};
 use super::pkt_type_decoder::{PktType, PktTypeDecoder};
+use super::size_counter::{CountedMsg, SizeCounter};
 
 /// Serializes an entire OSC packet, which contains either one message or one
 /// bundle.
@@ -63,10 +72,51 @@ use super::pkt_type_decoder::{PktType, PktTypeDecoder};
 /// Note: the time-tag can also be `[u32; 2]`, a struct containing two `u32` members,
 /// or *anything* that serializes as a flat sequence of two `u32`s.
 ///
+/// By default, the packet is preceded by its length (as is required for stream-based
+/// transports). Use [`new_unframed`] to omit the length, e.g. when the packet
+/// will be sent as a UDP datagram.
+///
+/// Enums are serialized as messages whose address is the variant name (which
+/// can be set with `#[serde(rename = "/some/address")]`). The variant's fields
+/// become the message arguments; a newtype variant whose payload is not itself
+/// a sequence or struct (e.g. `Play(f32)`) is sent with a single argument.
+///
+/// Message addresses are validated; use [`with_options`] to choose another
+/// [`AddressPolicy`].
+///
+/// Data from self-describing formats can be converted to OSC with
+/// `serde_transcode`, provided it has the shape the deserializer produces:
+/// `[address, [args...]]` or `[[seconds, fraction], [elements...]]` (see the
+/// `transcode` example). A unit (e.g. JSON's `null`) is encoded as 'N'.
+///
+/// A bundle is normally assembled in memory, as its length must be written
+/// before it. If the output is seekable, [`with_backpatching`] instead writes
+/// the bundle straight to the output, and fills in its length once done.
+/// (Messages are assembled in memory either way, as the typetag precedes the
+/// arguments.)
+///
+/// To collect metrics or traces of the outgoing traffic, [`with_observer`]
+/// reports the address, typetag and size of each packet once it's written.
+///
 /// [time-tag]: http://opensoundcontrol.org/node/3/#timetags
+/// [`new_unframed`]: #method.new_unframed
+/// [`with_options`]: #method.with_options
+/// [`with_backpatching`]: #method.with_backpatching
+/// [`with_observer`]: #method.with_observer
+/// [`AddressPolicy`]: enum.AddressPolicy.html
 #[derive(Debug)]
 pub struct PktSerializer<W: Write> {
     output: W,
+    framing: Framing,
+    options: SerializerOptions,
+    /// `Seek::seek` of the output, if bundles are to be written to it directly.
+    seek: Option<fn(&mut W, SeekFrom) -> io::Result<u64>>,
+    /// The unwritten space of the output, if messages are to be assembled in it.
+    slice: fn(&mut W) -> Option<&mut dyn SliceOutput>,
+    /// Called with each packet, once it's written.
+    observer: Option<Observer>,
+    /// Whether messages are only measured, and skipped over in the output.
+    counting: bool,
 }
 
 /// After the State receives a serialize_seq call,
@@ -80,17 +130,273 @@ pub struct PktContents<'a, W: Write + 'a> {
     state: State,
 }
 
+/// Serializes the fields of a tuple or struct enum variant as message arguments.
+#[derive(Debug)]
+pub struct VariantContents<'a, W: Write + 'a> {
+    output: &'a mut PktSerializer<W>,
+    msg: Msg,
+}
+
 #[derive(Debug)]
 enum State {
     UnknownType,
-    Msg(MsgSerializer),
+    Msg(Msg),
     Bundle(BundleSerializer),
+    /// A bundle written straight to the output, whose contents begin at the given position.
+    StreamedBundle(u64),
+}
+
+/// A message being serialized.
+#[derive(Debug)]
+enum Msg {
+    /// Assembled in scratch buffers, then written to the output.
+    Buffered(MsgSerializer),
+    /// Assembled in the unwritten space of the output.
+    InPlace(InPlaceState),
+    /// Only measured (see `PktSerializer::counting`).
+    Counted(CountedMsg),
+}
+
+/// The buffer of a message being serialized, whichever kind it is.
+#[derive(Debug)]
+enum AnyMsgBuf<'a> {
+    Buffered(&'a mut MsgSerializer),
+    InPlace(InPlaceMsg<'a>),
+    Counted(&'a mut CountedMsg),
+}
+
+impl<'a> Write for AnyMsgBuf<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            AnyMsgBuf::Buffered(ref mut msg) => msg.write(buf),
+            AnyMsgBuf::InPlace(ref mut msg) => msg.write(buf),
+            AnyMsgBuf::Counted(ref mut msg) => msg.write(buf),
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MsgBuf for AnyMsgBuf<'a> {
+    fn push_tag(&mut self, tag: u8) -> io::Result<()> {
+        match *self {
+            AnyMsgBuf::Buffered(ref mut msg) => msg.push_tag(tag),
+            AnyMsgBuf::InPlace(ref mut msg) => msg.push_tag(tag),
+            AnyMsgBuf::Counted(ref mut msg) => msg.push_tag(tag),
+        }
+    }
+}
+
+/// For outputs which messages can't be assembled in.
+fn no_slice<W>(_output: &mut W) -> Option<&mut dyn SliceOutput> {
+    None
 }
 
 
 impl<W: Write> PktSerializer<W> {
+    /// Serialize a length-prefixed packet.
     pub fn new(output: W) -> Self {
-        Self{ output }
+        Self::with_framing(output, Framing::LengthPrefixed)
+    }
+    /// Serialize a packet without writing its length first.
+    pub fn new_unframed(output: W) -> Self {
+        Self::with_framing(output, Framing::Unframed)
+    }
+    /// Serialize a packet, using the provided framing.
+    pub fn with_framing(output: W, framing: Framing) -> Self {
+        Self::with_options(output, framing, SerializerOptions::default())
+    }
+    /// Serialize a packet, using the provided framing and encoding options.
+    pub fn with_options(output: W, framing: Framing, options: SerializerOptions) -> Self {
+        Self{ output, framing, options, seek: None, slice: no_slice, observer: None, counting: false }
+    }
+    /// Serialize a packet to a seekable output, using the provided framing and
+    /// encoding options. A bundle's elements are written to the output as
+    /// they're serialized, rather than being buffered until the bundle is
+    /// complete; its length prefix is written as a placeholder, then
+    /// overwritten (as are those of the bundles nested within it). Messages
+    /// are still assembled in memory before being written.
+    ///
+    /// If serialization fails, a partial packet may have been written.
+    pub fn with_backpatching(output: W, framing: Framing, options: SerializerOptions) -> Self
+        where W: Seek
+    {
+        Self{ output, framing, options, seek: Some(W::seek), slice: no_slice, observer: None, counting: false }
+    }
+    /// Serialize a packet into the unwritten space of `output`, assembling
+    /// messages in place rather than in scratch buffers, so that nothing is
+    /// allocated. Bundles are backpatched, as with `with_backpatching`.
+    pub(crate) fn in_place(output: W, framing: Framing) -> Self
+        where W: Seek + SliceOutput
+    {
+        fn slice<W: SliceOutput>(output: &mut W) -> Option<&mut dyn SliceOutput> {
+            Some(output)
+        }
+        Self{ output, framing, options: SerializerOptions::default(), seek: Some(W::seek), slice: slice::<W>, observer: None,
+            counting: false }
+    }
+    /// Compute the size of a packet without storing it: the output only
+    /// needs to keep track of its position (see `SizeCounter`). Messages are
+    /// measured rather than assembled, then skipped over by seeking past
+    /// them; bundles are backpatched, as with `with_backpatching`.
+    pub(crate) fn counting(output: W, framing: Framing) -> Self
+        where W: Seek
+    {
+        Self{ counting: true, ..Self::with_backpatching(output, framing, SerializerOptions::default()) }
+    }
+    /// Call `observer` with the address, typetag and size of each packet
+    /// once it's written (see `serde_osc::observe`).
+    pub fn with_observer(self, observer: Observer) -> Self {
+        Self{ observer: Some(observer), ..self }
+    }
+    /// Bytes taken by the length prefix of a packet.
+    fn prefix_len(&self) -> usize {
+        if self.framing == Framing::LengthPrefixed { 4 } else { 0 }
+    }
+    /// Serialize the first element of a packet, which tells a message (of
+    /// which it's the address) from a bundle (of which it's the time-tag).
+    fn begin_packet<T: ?Sized + Serialize>(&mut self, value: &T) -> ResultE<State> {
+        let prefix = self.prefix_len();
+        let options = self.options;
+        let (pkt_type, timetag, msg) = match (self.slice)(&mut self.output) {
+            // The address is written straight to the output.
+            Some(output) => {
+                let mut decoder = PktTypeDecoder::new(address_space(output.remaining(), prefix)?, options);
+                value.serialize(&mut decoder)?;
+                let (pkt_type, timetag) = (decoder.pkt_type(), decoder.timetag());
+                let address_end = prefix + decoder.data().position() as usize;
+                (pkt_type, timetag, Some(Msg::InPlace(InPlaceState::new(prefix, address_end))))
+            },
+            // Only the length of the address is kept.
+            None if self.counting => {
+                let mut decoder = PktTypeDecoder::new(SizeCounter::new(), options);
+                value.serialize(&mut decoder)?;
+                let (pkt_type, timetag) = (decoder.pkt_type(), decoder.timetag());
+                (pkt_type, timetag, Some(Msg::Counted(CountedMsg::new(prefix + decoder.data().size()))))
+            },
+            None => {
+                let mut decoder = PktTypeDecoder::new(Cursor::new(Vec::new()), options);
+                value.serialize(&mut decoder)?;
+                let (pkt_type, timetag) = (decoder.pkt_type(), decoder.timetag());
+                let msg = match pkt_type {
+                    PktType::Msg => Some(Msg::Buffered(MsgSerializer::new(decoder.data())?)),
+                    _ => None,
+                };
+                (pkt_type, timetag, msg)
+            },
+        };
+        match (pkt_type, msg, self.seek) {
+            (PktType::Msg, Some(msg), _) => Ok(State::Msg(msg)),
+            (PktType::Bundle, _, Some(seek)) => Ok(State::StreamedBundle(self.begin_bundle(seek, timetag)?)),
+            (PktType::Bundle, _, None) => {
+                let mut contents = Cursor::new(Vec::new());
+                contents.osc_write_timetag(timetag)?;
+                Ok(State::Bundle(BundleSerializer::new(contents, options)))
+            },
+            _ => Err(Error::BadFormat),
+        }
+    }
+    /// Begin a message with the given (checked) address.
+    fn begin_msg(&mut self, address: &str) -> ResultE<Msg> {
+        let prefix = self.prefix_len();
+        match (self.slice)(&mut self.output) {
+            Some(output) => Ok(Msg::InPlace(InPlaceState::with_address(output.remaining(), prefix, address)?)),
+            None if self.counting => Ok(Msg::Counted(CountedMsg::with_address(prefix, address)?)),
+            None => Ok(Msg::Buffered(MsgSerializer::with_address(address)?)),
+        }
+    }
+    /// The buffer in which to serialize the arguments of `msg`.
+    fn msg_buf<'m>(&'m mut self, msg: &'m mut Msg) -> ResultE<AnyMsgBuf<'m>> {
+        match *msg {
+            Msg::Buffered(ref mut msg) => Ok(AnyMsgBuf::Buffered(msg)),
+            Msg::InPlace(ref mut state) => {
+                let output = (self.slice)(&mut self.output).ok_or(Error::BadFormat)?;
+                Ok(AnyMsgBuf::InPlace(InPlaceMsg::new(output.remaining(), state)))
+            },
+            Msg::Counted(ref mut msg) => Ok(AnyMsgBuf::Counted(msg)),
+        }
+    }
+    /// Write a complete message to the output.
+    fn write_msg(&mut self, msg: Msg) -> ResultE<()> {
+        let observer = self.observer;
+        let msg = match msg {
+            Msg::Buffered(msg) => msg,
+            Msg::InPlace(state) => {
+                let output = (self.slice)(&mut self.output).ok_or(Error::BadFormat)?;
+                let len = state.finish(output.remaining())?;
+                if let Some(observer) = observer {
+                    let (address, typetag) = state.header(output.remaining());
+                    observer(&PacketSummary { address: &address, typetag: &typetag, size: len - state.prefix() });
+                }
+                output.advance(len);
+                return Ok(());
+            },
+            Msg::Counted(msg) => {
+                let len = msg.finish(self.prefix_len())?;
+                let seek = self.seek.ok_or(Error::BadFormat)?;
+                seek(&mut self.output, SeekFrom::Current(len.try_into()?))?;
+                return Ok(());
+            },
+        };
+        let header = observer.map(|_| msg.header());
+        let size = msg.payload_size();
+        msg.write_into(&mut self.output, self.framing)?;
+        if let (Some(observer), Some((address, typetag))) = (observer, header) {
+            observer(&PacketSummary { address: &address, typetag: &typetag, size });
+        }
+        Ok(())
+    }
+    /// Report a bundle of `size` bytes, once it's been written.
+    fn observe_bundle(&self, size: usize) {
+        if let Some(observer) = self.observer {
+            observer(&PacketSummary { address: "#bundle", typetag: "", size });
+        }
+    }
+    /// Write the start of a bundle with the given (encoded) time-tag, leaving
+    /// its length to be filled in by `end_bundle`.
+    /// Returns the position at which the bundle's contents begin.
+    fn begin_bundle(&mut self, seek: fn(&mut W, SeekFrom) -> io::Result<u64>, timetag: (u32, u32)) -> ResultE<u64> {
+        if self.framing == Framing::LengthPrefixed {
+            self.output.osc_write_i32(0)?;
+        }
+        let start = seek(&mut self.output, SeekFrom::Current(0))?;
+        self.output.osc_write_str("#bundle")?;
+        self.output.osc_write_timetag(timetag)?;
+        Ok(start)
+    }
+    /// Backpatch the length of the bundle whose contents begin at `start`.
+    /// Returns the size of its contents.
+    fn end_bundle(&mut self, start: u64) -> ResultE<usize> {
+        let seek = self.seek.ok_or(Error::BadFormat)?;
+        let end = seek(&mut self.output, SeekFrom::Current(0))?;
+        let payload_size = end - start;
+        if !payload_size.is_multiple_of(4) {
+            // Sanity check; OSC requires packets to be a multiple of 4 bytes.
+            return Err(Error::BadFormat);
+        }
+        if self.framing == Framing::LengthPrefixed {
+            seek(&mut self.output, SeekFrom::Start(start - 4))?;
+            self.output.osc_write_i32(length_prefix(payload_size)?)?;
+            seek(&mut self.output, SeekFrom::Start(end))?;
+        }
+        Ok(payload_size.try_into()?)
+    }
+    /// Begin a message addressed to the given enum variant.
+    fn variant_msg(&mut self, variant: &str) -> ResultE<Msg> {
+        let address = self.options.address_policy.apply(variant)?;
+        self.begin_msg(&address)
+    }
+}
+
+impl<'c> PktSerializer<&'c mut dyn WriteSeek> {
+    /// Serialize an element of a bundle into the bundle's contents.
+    pub(crate) fn bundle_element(contents: &'c mut dyn WriteSeek, options: SerializerOptions) -> Self {
+        fn slice<'a>(contents: &'a mut &mut dyn WriteSeek) -> Option<&'a mut dyn SliceOutput> {
+            contents.slice_output()
+        }
+        let counting = contents.counting();
+        Self{ slice, counting, ..Self::with_backpatching(contents, Framing::LengthPrefixed, options) }
     }
 }
 
@@ -101,13 +407,69 @@ impl<'a, W: Write> Serializer for &'a mut PktSerializer<W> {
     type SerializeTuple = Self::SerializeSeq;
     type SerializeStruct = Self::SerializeSeq;
     type SerializeTupleStruct = Impossible<Self::Ok, Error>;
-    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = VariantContents<'a, W>;
     type SerializeMap = Impossible<Self::Ok, Error>;
-    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+    type SerializeStructVariant = Self::SerializeTupleVariant;
 
     default_ser!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char
-        str bytes none some unit unit_struct unit_variant newtype_struct newtype_variant
-        tuple_struct tuple_variant map struct_variant}
+        str bytes none some unit unit_struct
+        tuple_struct map}
+    // e.g. a struct wrapping a message type.
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T
+    ) -> ResultE<Self::Ok>
+    {
+        value.serialize(self)
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str
+    ) -> ResultE<Self::Ok>
+    {
+        let msg = self.variant_msg(variant)?;
+        self.write_msg(msg)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T
+    ) -> ResultE<Self::Ok>
+    {
+        // The variants of an enum which holds any packet, rather than naming an address.
+        if variant == MESSAGE_VARIANT || variant == BUNDLE_VARIANT {
+            return value.serialize(self);
+        }
+        let mut msg = self.variant_msg(variant)?;
+        self.msg_buf(&mut msg)?.serialize_payload(value)?;
+        self.write_msg(msg)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize
+    ) -> ResultE<Self::SerializeTupleVariant>
+    {
+        let msg = self.variant_msg(variant)?;
+        Ok(VariantContents{ output: self, msg })
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize
+    ) -> ResultE<Self::SerializeStructVariant>
+    {
+        self.serialize_tuple_variant(name, index, variant, len)
+    }
     fn serialize_seq(
         self, 
         _size: Option<usize>
@@ -147,31 +509,26 @@ impl<'a, W: Write + 'a> SerializeSeq for PktContents<'a, W> {
                 //   will accept i32, f32, blob, str args.
                 // If the first element we see is a timecode (seq of u32, u32),
                 //   then we become a bundle.
-                let mut decoder = PktTypeDecoder::new();
-                value.serialize(&mut decoder)?;
-
-                match decoder.pkt_type() {
-                    PktType::Unknown => Err(Error::BadFormat),
-                    PktType::Msg => {
-                        self.state = State::Msg(MsgSerializer::new(
-                            decoder.data()
-                        )?);
-                        Ok(())
-                    },
-                    PktType::Bundle => {
-                        self.state = State::Bundle(BundleSerializer::new(
-                            decoder.data()
-                        ));
-                        Ok(())
-                    },
-                }
+                self.state = self.output.begin_packet(value)?;
+                Ok(())
             },
             State::Msg(ref mut msg) => {
-                value.serialize(msg)
+                self.output.msg_buf(msg)?.serialize_args(value)
             },
             State::Bundle(ref mut bundle) => {
                 value.serialize(bundle)
             },
+            State::StreamedBundle(_) => {
+                let options = self.output.options;
+                let seek = self.output.seek.ok_or(Error::BadFormat)?;
+                let contents = SeekWith {
+                    output: &mut self.output.output,
+                    seek,
+                    slice: self.output.slice,
+                    counting: self.output.counting,
+                };
+                value.serialize(&mut BundleSerializer::new(contents, options))
+            },
         }
     }
 
@@ -181,12 +538,21 @@ impl<'a, W: Write + 'a> SerializeSeq for PktContents<'a, W> {
             State::UnknownType => Err(Error::BadFormat),
             // Write the message header & data to the output
             State::Msg(msg) => {
-                msg.write_into(&mut self.output.output)
+                self.output.write_msg(msg)
             },
             // Write the bundle header & data to the output
             State::Bundle(bundle) => {
-                bundle.write_into(&mut self.output.output)
-            }
+                let size = bundle.payload_size();
+                bundle.write_into(&mut self.output.output, self.output.framing)?;
+                self.output.observe_bundle(size);
+                Ok(())
+            },
+            // Fill in the length of the bundle, now that it's been written
+            State::StreamedBundle(start) => {
+                let size = self.output.end_bundle(start)?;
+                self.output.observe_bundle(size);
+                Ok(())
+            },
         }
     }
 }
@@ -221,3 +587,33 @@ impl<'a, W: Write + 'a> SerializeTuple for PktContents<'a, W> {
     }
 }
 
+
+impl<'a, W: Write + 'a> SerializeTupleVariant for VariantContents<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        self.output.msg_buf(&mut self.msg)?.serialize_arg(value)
+    }
+
+    fn end(self) -> ResultE<()> {
+        self.output.write_msg(self.msg)
+    }
+}
+
+impl<'a, W: Write + 'a> SerializeStructVariant for VariantContents<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> ResultE<()>
+        where T: ?Sized + Serialize
+    {
+        SerializeTupleVariant::serialize_field(self, value)
+    }
+
+    fn end(self) -> ResultE<()> {
+        SerializeTupleVariant::end(self)
+    }
+}
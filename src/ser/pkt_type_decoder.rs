@@ -1,8 +1,10 @@
 use std::convert::TryInto;
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 use serde::ser::{Impossible, Serialize, Serializer, SerializeSeq, SerializeStruct, SerializeTuple};
 
 use error::{Error, ResultE};
+use timetag;
+use super::options::SerializerOptions;
 use super::osc_writer::OscWriter;
 use super::timetag_ser::TimetagSer;
 
@@ -11,15 +13,34 @@ use super::timetag_ser::TimetagSer;
 /// argument written:
 ///   * String => the packet is a message, and the string is its address
 ///   * (u32, u32) => the packet is a bundle, and the (u32, u32) is its timetag
+///   * None => the packet is a bundle, to be handled immediately
 ///
 /// This struct serializes the first item & yields the packet type so that
-/// its user can serialize the rest of the packet appropriately.
+/// its user can serialize the rest of the packet appropriately. The address
+/// of a message is written to `output`; the time-tag of a bundle is kept.
 #[derive(Debug)]
-pub struct PktTypeDecoder {
-    output: Cursor<Vec<u8>>,
+pub struct PktTypeDecoder<O> {
+    output: O,
     pkt_type: PktType,
+    timetag: (u32, u32),
+    options: SerializerOptions,
 }
 
+/// Where the address of a message is written.
+pub trait AddressOutput: Write {
+    /// Prepare for an address of `len` bytes.
+    fn reserve(&mut self, _len: usize) {}
+}
+
+impl AddressOutput for Cursor<Vec<u8>> {
+    fn reserve(&mut self, len: usize) {
+        // Room for the padding and the start of the typetag, which follow.
+        self.get_mut().reserve(len + 8);
+    }
+}
+
+impl AddressOutput for Cursor<&mut [u8]> {}
+
 #[derive(Copy, Clone, Debug)]
 pub enum PktType {
     Unknown,
@@ -27,26 +48,33 @@ pub enum PktType {
     Bundle,
 }
 
-impl PktTypeDecoder {
-    pub fn new() -> Self {
+impl<O: AddressOutput> PktTypeDecoder<O> {
+    pub fn new(output: O, options: SerializerOptions) -> Self {
         Self {
-            output: Cursor::new(Vec::new()),
+            output,
             pkt_type: PktType::Unknown,
+            timetag: timetag::IMMEDIATELY,
+            options,
         }
     }
     pub fn pkt_type(&self) -> PktType {
         self.pkt_type
     }
-    pub fn data(self) -> Cursor<Vec<u8>> {
+    /// The time-tag of a bundle.
+    pub fn timetag(&self) -> (u32, u32) {
+        self.timetag
+    }
+    /// The output, holding the address of a message.
+    pub fn data(self) -> O {
         self.output
     }
 }
 
 
-impl<'a> Serializer for &'a mut PktTypeDecoder {
+impl<'a, O: AddressOutput> Serializer for &'a mut PktTypeDecoder<O> {
     type Ok = ();
     type Error = Error;
-    type SerializeSeq = TimetagSeqSer<'a>;
+    type SerializeSeq = TimetagSeqSer<'a, O>;
     type SerializeTuple = Self::SerializeSeq;
     type SerializeStruct = Self::SerializeSeq;
     type SerializeTupleStruct = Impossible<Self::Ok, Error>;
@@ -55,11 +83,28 @@ impl<'a> Serializer for &'a mut PktTypeDecoder {
     type SerializeStructVariant = Impossible<Self::Ok, Error>;
 
     fn serialize_str(self, value: &str) -> ResultE<Self::Ok> {
-        self.output.osc_write_str(value)?;
+        let address = self.options.address_policy.apply(value)?;
+        self.output.reserve(address.len());
+        self.output.osc_write_str(&address)?;
         self.pkt_type = PktType::Msg;
         Ok(())
     }
 
+    fn serialize_none(self) -> ResultE<Self::Ok> {
+        self.timetag = timetag::IMMEDIATELY;
+        self.pkt_type = PktType::Bundle;
+        Ok(())
+    }
+    fn serialize_some<T>(self, value: &T) -> ResultE<Self::Ok>
+        where T: ?Sized + Serialize
+    {
+        value.serialize(self)
+    }
+    // e.g. a struct wrapping the address.
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> ResultE<Self::Ok> {
+        value.serialize(self)
+    }
+
     fn serialize_seq(
         self, 
         _size: Option<usize>
@@ -74,27 +119,29 @@ impl<'a> Serializer for &'a mut PktTypeDecoder {
     {
         self.serialize_seq(Some(size))
     }
+    /// Besides plain structs of two `u32`s, this accepts `SystemTime` and `Duration`
+    /// (see [`serde_osc::timetag`](../timetag/index.html)).
     fn serialize_struct(
         self, 
-        _: &'static str, 
-        size: usize
+        name: &'static str, 
+        _size: usize
     ) -> ResultE<Self::SerializeStruct>
     {
-        self.serialize_seq(Some(size))
+        Ok(TimetagSeqSer{ output: self, ser: TimetagSer::for_struct(name) })
     }
 
     default_ser!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char
-        bytes none some unit unit_struct unit_variant newtype_struct newtype_variant
+        bytes unit unit_struct unit_variant newtype_variant
         tuple_struct tuple_variant map struct_variant}
 }
 
-pub struct TimetagSeqSer<'a> {
-    output: &'a mut PktTypeDecoder,
+pub struct TimetagSeqSer<'a, O: 'a> {
+    output: &'a mut PktTypeDecoder<O>,
     ser: TimetagSer,
 }
 
 
-impl<'a> SerializeSeq for TimetagSeqSer<'a> {
+impl<'a, O: AddressOutput> SerializeSeq for TimetagSeqSer<'a, O> {
     type Ok = ();
     type Error = Error;
 
@@ -104,14 +151,13 @@ impl<'a> SerializeSeq for TimetagSeqSer<'a> {
         value.serialize(&mut self.ser)
     }
     fn end(self) -> ResultE<()> {
-        let timetag = self.ser.try_into()?;
-        self.output.output.osc_write_timetag(timetag)?;
+        self.output.timetag = self.ser.try_into()?;
         self.output.pkt_type = PktType::Bundle;
         Ok(())
     }
 }
 
-impl<'a> SerializeStruct for TimetagSeqSer<'a> {
+impl<'a, O: AddressOutput> SerializeStruct for TimetagSeqSer<'a, O> {
     type Ok = ();
     type Error = Error;
 
@@ -126,7 +172,7 @@ impl<'a> SerializeStruct for TimetagSeqSer<'a> {
     }
 }
 
-impl<'a> SerializeTuple for TimetagSeqSer<'a> {
+impl<'a, O: AddressOutput> SerializeTuple for TimetagSeqSer<'a, O> {
     type Ok = ();
     type Error = Error;
 
@@ -0,0 +1,116 @@
+use std::io::{self, ErrorKind, Seek, SeekFrom, Write};
+
+use error::{Error, ResultE};
+use super::msg_serializer::MsgBuf;
+use super::osc_writer::{length_prefix, OscWriter};
+use super::pkt_type_decoder::AddressOutput;
+
+/// `Write` implementation which discards its input, only keeping track of
+/// how far it extends.
+///
+/// It can also be seeked, so that bundles can be backpatched and messages
+/// skipped over rather than written (see `PktSerializer::counting`); seeking
+/// past the end counts as writing up to there.
+#[derive(Debug, Default)]
+pub struct SizeCounter {
+    pos: u64,
+    size: u64,
+}
+
+impl SizeCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Total number of bytes written (or skipped over) so far.
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+    fn move_to(&mut self, pos: u64) -> u64 {
+        self.pos = pos;
+        self.size = self.size.max(pos);
+        pos
+    }
+}
+
+impl Write for SizeCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let pos = self.pos + buf.len() as u64;
+        self.move_to(pos);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for SizeCounter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+        match pos {
+            Some(pos) => Ok(self.move_to(pos)),
+            None => Err(io::Error::new(ErrorKind::InvalidInput, "seek before the start of the output")),
+        }
+    }
+}
+
+impl AddressOutput for SizeCounter {}
+
+/// A message whose size is being computed: the typetag characters and the
+/// argument data are counted, but not stored.
+#[derive(Copy, Clone, Debug)]
+pub struct CountedMsg {
+    /// End of the padded address, including the length prefix (if any).
+    address_end: usize,
+    /// Bytes of argument data.
+    args: usize,
+    /// Number of typetag characters (excluding the leading ',').
+    tags: usize,
+}
+
+impl CountedMsg {
+    /// Continue a message whose address ends at `address_end`.
+    pub fn new(address_end: usize) -> Self {
+        Self { address_end, args: 0, tags: 0 }
+    }
+    /// Begin a message with the given address, after `prefix` bytes reserved for its length.
+    pub fn with_address(prefix: usize, address: &str) -> ResultE<Self> {
+        let mut counter = SizeCounter::new();
+        counter.osc_write_str(address)?;
+        Ok(Self::new(prefix + counter.size()))
+    }
+    /// The size of the complete message, including its length prefix (if any).
+    /// Fails as writing it would, if the payload is too large for its prefix.
+    pub fn finish(&self, prefix: usize) -> ResultE<usize> {
+        // ',' followed by the characters, and at least one NUL.
+        let typetag_len = (self.tags + 5) & !0x3;
+        let end = self.address_end + typetag_len + self.args;
+        let payload_size = end - prefix;
+        if !payload_size.is_multiple_of(4) {
+            // Sanity check; OSC requires packets to be a multiple of 4 bytes.
+            return Err(Error::BadFormat);
+        }
+        length_prefix(payload_size)?;
+        Ok(end)
+    }
+}
+
+impl Write for CountedMsg {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.args += data.len();
+        Ok(data.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl MsgBuf for CountedMsg {
+    fn push_tag(&mut self, _tag: u8) -> io::Result<()> {
+        self.tags += 1;
+        Ok(())
+    }
+}
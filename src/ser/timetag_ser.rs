@@ -2,20 +2,44 @@ use std::convert::TryInto;
 use serde::ser::{Impossible, Serialize, Serializer};
 
 use error::{Error, ResultE};
+use timetag::{self, DURATION_NAME, SYSTEM_TIME_NAME};
 
+/// Collects the two integers which make up a time-tag.
 #[derive(Debug)]
 pub struct TimetagSer {
+    repr: Repr,
     n_parsed: u8,
-    parsed: [u32; 2],
+    parsed: [u64; 2],
+}
+
+/// What the two integers denote.
+#[derive(Copy, Clone, Debug)]
+enum Repr {
+    /// The (seconds, fraction) of the time-tag itself.
+    Raw,
+    /// The (seconds, nanoseconds) since 1970 of a `SystemTime`.
+    SystemTime,
+    /// The (seconds, nanoseconds) since 1900 of a `Duration`.
+    Duration,
 }
 
 impl TimetagSer {
     pub fn new() -> Self {
         TimetagSer {
+            repr: Repr::Raw,
             n_parsed: 0,
             parsed: [0, 0],
         }
     }
+    /// Collect the fields of a struct, which may be one of the standard time types.
+    pub fn for_struct(name: &str) -> Self {
+        let repr = match name {
+            SYSTEM_TIME_NAME => Repr::SystemTime,
+            DURATION_NAME => Repr::Duration,
+            _ => Repr::Raw,
+        };
+        TimetagSer { repr, ..Self::new() }
+    }
 }
 
 impl TryInto<(u32, u32)> for TimetagSer {
@@ -24,7 +48,12 @@ impl TryInto<(u32, u32)> for TimetagSer {
         if self.n_parsed != 2 {
             return Err(Error::BadFormat);
         }
-        Ok((self.parsed[0], self.parsed[1]))
+        let [first, second] = self.parsed;
+        match self.repr {
+            Repr::Raw => Ok((first.try_into()?, second.try_into()?)),
+            Repr::SystemTime => timetag::from_unix_parts(first, second.try_into()?),
+            Repr::Duration => timetag::from_duration(::std::time::Duration::new(first, second.try_into()?)),
+        }
     }
 }
 
@@ -40,10 +69,13 @@ impl<'a> Serializer for &'a mut TimetagSer {
     type SerializeStructVariant = Impossible<Self::Ok, Error>;
 
     fn serialize_u32(self, value: u32) -> ResultE<Self::Ok> {
+        self.serialize_u64(u64::from(value))
+    }
+    fn serialize_u64(self, value: u64) -> ResultE<Self::Ok> {
         match self.parsed.get_mut(self.n_parsed as usize) {
             // Already serialized all the values needed for a timetag!
             None => Err(Error::BadFormat),
-            Some(mut part) => {
+            Some(part) => {
                 *part = value;
                 self.n_parsed += 1;
                 Ok(())
@@ -52,7 +84,7 @@ impl<'a> Serializer for &'a mut TimetagSer {
     }
 
 
-    default_ser!{bool i8 i16 i32 i64 u8 u16 u64 f32 f64 char
+    default_ser!{bool i8 i16 i32 i64 u8 u16 f32 f64 char
         str bytes none some unit unit_struct unit_variant newtype_struct newtype_variant
         seq tuple tuple_struct tuple_variant map struct struct_variant}
 }
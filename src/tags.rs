@@ -0,0 +1,90 @@
+use std::convert::TryInto;
+
+use de::osc_reader::find_nul;
+
+/// Starts the typetag of a message, e.g. ",if".
+pub const TYPETAG_PREFIX: u8 = b',';
+/// 'i': 32-bit big-endian two's complement integer.
+pub const TAG_INT32: u8 = b'i';
+/// 'f': 32-bit big-endian IEEE 754 float.
+pub const TAG_FLOAT32: u8 = b'f';
+/// 's': NUL-terminated string, padded to a multiple of 4 bytes.
+pub const TAG_STRING: u8 = b's';
+/// 'b': blob; an `i32` byte count, then the bytes, padded to a multiple of 4.
+pub const TAG_BLOB: u8 = b'b';
+/// 'h': 64-bit big-endian two's complement integer.
+pub const TAG_INT64: u8 = b'h';
+/// 't': 64-bit time-tag (not decoded by this crate; see `de::UnknownTypes`).
+pub const TAG_TIMETAG: u8 = b't';
+/// 'd': 64-bit big-endian IEEE 754 float.
+pub const TAG_FLOAT64: u8 = b'd';
+/// 'S': symbol, encoded as a string.
+pub const TAG_SYMBOL: u8 = b'S';
+/// 'c': character, as a 32-bit code point.
+pub const TAG_CHAR: u8 = b'c';
+/// 'r': 32-bit RGBA color.
+pub const TAG_COLOR: u8 = b'r';
+/// 'm': 4-byte MIDI message (port, status, data 1, data 2).
+pub const TAG_MIDI: u8 = b'm';
+/// 'T': true; no data.
+pub const TAG_TRUE: u8 = b'T';
+/// 'F': false; no data.
+pub const TAG_FALSE: u8 = b'F';
+/// 'N': nil; no data.
+pub const TAG_NIL: u8 = b'N';
+/// 'I': infinitum (impulse); no data (not decoded by this crate).
+pub const TAG_INFINITUM: u8 = b'I';
+/// '[': starts an array; no data.
+pub const TAG_ARRAY_BEGIN: u8 = b'[';
+/// ']': ends an array; no data.
+pub const TAG_ARRAY_END: u8 = b']';
+
+/// Whether this crate decodes arguments with the given tag (without
+/// `de::UnknownTypes::Capture` or an `de::ExtensionType`).
+pub fn is_supported(tag: u8) -> bool {
+    matches!(tag, TAG_INT32 | TAG_FLOAT32 | TAG_STRING | TAG_BLOB | TAG_INT64 | TAG_FLOAT64 | TAG_SYMBOL
+        | TAG_CHAR | TAG_COLOR | TAG_MIDI | TAG_TRUE | TAG_FALSE | TAG_NIL | TAG_ARRAY_BEGIN | TAG_ARRAY_END)
+}
+
+/// The size in bytes of the data of an argument with the given tag, if the
+/// tag alone determines it (i.e. not for strings, symbols and blobs, nor for
+/// nonstandard types). Tags without data (e.g. 'T' and '[') have size 0.
+pub fn fixed_size(tag: u8) -> Option<usize> {
+    match tag {
+        TAG_INT32 | TAG_FLOAT32 | TAG_CHAR | TAG_COLOR | TAG_MIDI => Some(4),
+        TAG_INT64 | TAG_FLOAT64 | TAG_TIMETAG => Some(8),
+        TAG_TRUE | TAG_FALSE | TAG_NIL | TAG_INFINITUM | TAG_ARRAY_BEGIN | TAG_ARRAY_END => Some(0),
+        _ => None,
+    }
+}
+
+/// The size in bytes (including padding) of the argument with the given
+/// tag at the start of `payload`, i.e. the offset of the next argument.
+///
+/// Returns `None` if the tag isn't one of the standard types, or if
+/// `payload` is too short to hold the argument (e.g. a string without a NUL
+/// terminator, or a blob whose declared length exceeds the remaining data).
+///
+/// ```
+/// use serde_osc::tags::{arg_size, TAG_BLOB, TAG_INT32, TAG_STRING};
+///
+/// assert_eq!(arg_size(TAG_INT32, &[0, 0, 0, 1]), Some(4));
+/// assert_eq!(arg_size(TAG_STRING, b"abcd\0\0\0\0/next"), Some(8));
+/// assert_eq!(arg_size(TAG_BLOB, &[0, 0, 0, 2, 7, 7, 0, 0]), Some(8));
+/// assert_eq!(arg_size(TAG_STRING, b"abcd"), None);
+/// ```
+pub fn arg_size(tag: u8, payload: &[u8]) -> Option<usize> {
+    let size = match tag {
+        TAG_STRING | TAG_SYMBOL => {
+            let len = find_nul(payload)?;
+            // The terminator, then padding to a multiple of 4.
+            (len + 4) & !0x3
+        },
+        TAG_BLOB => {
+            let len: usize = i32::from_be_bytes(payload.get(..4)?.try_into().ok()?).try_into().ok()?;
+            4 + ((len.checked_add(3)?) & !0x3)
+        },
+        tag => fixed_size(tag)?,
+    };
+    if size <= payload.len() { Some(size) } else { None }
+}
@@ -0,0 +1,365 @@
+//! A plain-text notation for OSC packets, in the style of liblo's `oscdump`.
+//!
+//! A message is written as its address, followed by its typetag (without the
+//! leading comma) and then one token per argument:
+//!
+//! ```norun
+//! /audio/play ifs 1 440.0 "a string"
+//! ```
+//!
+//! Strings, symbols and characters are double-quoted (with `\"`, `\\`, `\n`,
+//! `\r` and `\t` escapes); blobs, MIDI messages, colors and the raw data of unknown types
+//! are written as `#` followed by their bytes in hex. 'T', 'F', 'N' and array
+//! brackets appear only in the typetag. A bundle is written as `#bundle`, its time-tag as `seconds:fraction`,
+//! and then each element enclosed in braces:
+//!
+//! ```norun
+//! #bundle 0:1 { /a i 1 } { /b }
+//! ```
+//!
+//! When parsing, unquoted strings are also accepted, as is a leading comma on the typetag.
+
+use std::fmt::{self, Display, Formatter, Write};
+use std::str::FromStr;
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use de::{self, DeserializerOptions};
+use error::{Error, ResultE};
+use ser;
+use types::{Color, MidiMessage, RawArg};
+use value::{Bundle, Message, Packet, Value};
+
+/// Serialize the value as an OSC packet, in text form.
+pub fn to_string<T>(value: &T) -> ResultE<String>
+    where T: ?Sized + Serialize
+{
+    let packet = Packet::from_slice_unframed(&ser::to_vec_unframed(value)?)?;
+    Ok(packet.to_string())
+}
+
+/// Deserialize an OSC packet from its text form.
+pub fn from_str<T>(text: &str) -> ResultE<T>
+    where T: DeserializeOwned
+{
+    let packet: Packet = text.parse()?;
+    de::from_slice_unframed(&ser::to_vec_unframed(&packet)?)
+}
+
+
+impl Display for Value {
+    /// Write the argument's token, which is empty for arguments that are
+    /// fully described by their typetag ('T', 'F', 'N').
+    /// The elements of an array are separated by spaces.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Long(h) => write!(f, "{}", h),
+            Value::Float(x) => write!(f, "{:?}", x),
+            Value::Double(x) => write!(f, "{:?}", x),
+            Value::String(ref s) | Value::Symbol(ref s) => write_quoted(f, s),
+            Value::Blob(ref b) => write_hex(f, b),
+            Value::Bool(_) | Value::Nil => Ok(()),
+            Value::Char(c) => write_quoted(f, c.encode_utf8(&mut [0; 4])),
+            Value::Midi(ref m) => write_hex(f, &m.to_bytes()),
+            Value::Color(ref c) => write_hex(f, &c.to_bytes()),
+            Value::Array(ref elems) => write_tokens(f, elems, ""),
+            Value::Unknown(ref raw) => write_hex(f, &raw.data),
+        }
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.address)?;
+        if !self.args.is_empty() {
+            write!(f, " {}", self.typetag())?;
+            write_tokens(f, &self.args, " ")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Bundle {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "#bundle {}:{}", self.timetag.0, self.timetag.1)?;
+        for elem in &self.elements {
+            write!(f, " {{ {} }}", elem)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Packet::Message(ref msg) => msg.fmt(f),
+            Packet::Bundle(ref bundle) => bundle.fmt(f),
+        }
+    }
+}
+
+/// Write the non-empty tokens of `values`, each preceded by `sep` (except the first, if `sep` is empty).
+fn write_tokens(f: &mut Formatter, values: &[Value], sep: &str) -> fmt::Result {
+    let mut sep = sep;
+    for value in values {
+        let token = value.to_string();
+        if !token.is_empty() {
+            write!(f, "{}{}", sep, token)?;
+            sep = " ";
+        }
+    }
+    Ok(())
+}
+
+fn write_quoted(f: &mut Formatter, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+fn write_hex(f: &mut Formatter, bytes: &[u8]) -> fmt::Result {
+    f.write_char('#')?;
+    for b in bytes {
+        write!(f, "{:02x}", b)?;
+    }
+    Ok(())
+}
+
+
+impl FromStr for Packet {
+    type Err = Error;
+    fn from_str(text: &str) -> ResultE<Self> {
+        let mut tokens = Tokens::new(text);
+        let packet = tokens.parse_packet(0)?;
+        match tokens.next()? {
+            None => Ok(packet),
+            Some(token) => Err(syntax_error(&format!("unexpected {}", token))),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = Error;
+    fn from_str(text: &str) -> ResultE<Self> {
+        match text.parse()? {
+            Packet::Message(msg) => Ok(msg),
+            Packet::Bundle(_) => Err(syntax_error("expected a message, found a bundle")),
+        }
+    }
+}
+
+fn syntax_error(msg: &str) -> Error {
+    Error::Message(format!("invalid OSC text: {}", msg))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    /// A whitespace-delimited word
+    Word(&'a str),
+    /// A double-quoted string, with escapes resolved
+    Quoted(String),
+}
+
+impl<'a> Display for Token<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Token::Word(w) => write!(f, "'{}'", w),
+            Token::Quoted(ref s) => write_quoted(f, s),
+        }
+    }
+}
+
+/// Splits the text into tokens, and parses packets from them.
+struct Tokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { rest: text }
+    }
+    fn next(&mut self) -> ResultE<Option<Token<'a>>> {
+        self.rest = self.rest.trim_start();
+        if self.rest.is_empty() {
+            return Ok(None);
+        }
+        if self.rest.starts_with('"') {
+            return self.next_quoted().map(Some);
+        }
+        let end = self.rest.find(char::is_whitespace).unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Ok(Some(Token::Word(word)))
+    }
+    fn next_quoted(&mut self) -> ResultE<Token<'a>> {
+        let mut s = String::new();
+        let mut chars = self.rest.char_indices().skip(1);
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[i+1..];
+                    return Ok(Token::Quoted(s));
+                },
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => s.push('\n'),
+                    Some((_, 'r')) => s.push('\r'),
+                    Some((_, 't')) => s.push('\t'),
+                    Some((_, c @ '"')) | Some((_, c @ '\\')) => s.push(c),
+                    Some((_, c)) => return Err(syntax_error(&format!("unknown escape '\\{}'", c))),
+                    None => break,
+                },
+                c => s.push(c),
+            }
+        }
+        Err(syntax_error("unterminated string"))
+    }
+    /// Return the next token, which must exist.
+    fn expect(&mut self, what: &str) -> ResultE<Token<'a>> {
+        self.next()?.ok_or_else(|| syntax_error(&format!("expected {}, found end of text", what)))
+    }
+    /// Return the next unquoted word, which must exist.
+    fn expect_word(&mut self, what: &str) -> ResultE<&'a str> {
+        match self.expect(what)? {
+            Token::Word(w) => Ok(w),
+            token => Err(syntax_error(&format!("expected {}, found {}", what, token))),
+        }
+    }
+    /// Whether the next token is the given word, without consuming it.
+    fn peek_is(&self, word: &str) -> bool {
+        let rest = self.rest.trim_start();
+        rest.starts_with(word) && rest[word.len()..].chars().next().is_none_or(char::is_whitespace)
+    }
+
+    /// Parse a packet (or bundle element) nested within `depth` bundles.
+    fn parse_packet(&mut self, depth: usize) -> ResultE<Packet> {
+        let address = self.expect_word("an address or #bundle")?;
+        if address == "#bundle" {
+            // Bundles are parsed recursively, so their nesting is limited
+            // just as it is when decoding.
+            if DeserializerOptions::default().too_deep(depth) {
+                return Err(Error::DepthLimitExceeded);
+            }
+            self.parse_bundle(depth).map(Packet::Bundle)
+        } else {
+            self.parse_args(address).map(Packet::Message)
+        }
+    }
+    fn parse_bundle(&mut self, depth: usize) -> ResultE<Bundle> {
+        let timetag = self.expect_word("a time-tag")?;
+        let mut bundle = Bundle::new(parse_timetag(timetag)?);
+        while self.peek_is("{") {
+            self.next()?;
+            bundle.elements.push(self.parse_packet(depth + 1)?);
+            if self.expect_word("'}'")? != "}" {
+                return Err(syntax_error("expected '}' after bundle element"));
+            }
+        }
+        Ok(bundle)
+    }
+    fn parse_args(&mut self, address: &str) -> ResultE<Message> {
+        let mut msg = Message::new(address);
+        // The typetag is absent if there are no arguments.
+        if self.rest.trim_start().is_empty() || self.peek_is("}") {
+            return Ok(msg);
+        }
+        let typetag = self.expect_word("a typetag")?;
+        let typetag = typetag.trim_start_matches(',');
+        // Arguments of the arrays currently open, innermost last.
+        let mut arrays: Vec<Vec<Value>> = Vec::new();
+        for tag in typetag.chars() {
+            let value = match tag {
+                '[' => {
+                    arrays.push(Vec::new());
+                    continue;
+                },
+                ']' => Value::Array(arrays.pop().ok_or_else(|| syntax_error("unmatched ']' in typetag"))?),
+                'T' => Value::Bool(true),
+                'F' => Value::Bool(false),
+                'N' => Value::Nil,
+                tag => self.parse_arg(tag)?,
+            };
+            match arrays.last_mut() {
+                Some(array) => array.push(value),
+                None => msg.args.push(value),
+            }
+        }
+        if !arrays.is_empty() {
+            return Err(syntax_error("unmatched '[' in typetag"));
+        }
+        Ok(msg)
+    }
+    /// Parse the token of an argument with the given typetag.
+    fn parse_arg(&mut self, tag: char) -> ResultE<Value> {
+        let what = format!("a '{}' argument", tag);
+        let token = self.expect(&what)?;
+        let description = format!("invalid '{}' argument {}", tag, token);
+        let invalid = || syntax_error(&description);
+        let value = match (tag, token) {
+            ('s', Token::Quoted(s)) => Value::String(s),
+            ('s', Token::Word(w)) => Value::String(w.to_owned()),
+            ('S', Token::Quoted(s)) => Value::Symbol(s),
+            ('S', Token::Word(w)) => Value::Symbol(w.to_owned()),
+            ('c', Token::Quoted(s)) => Value::Char(single_char(&s).ok_or_else(invalid)?),
+            ('c', Token::Word(w)) => Value::Char(single_char(w).ok_or_else(invalid)?),
+            (_, Token::Quoted(_)) => return Err(invalid()),
+            ('i', Token::Word(w)) => Value::Int(w.parse().map_err(|_| invalid())?),
+            ('h', Token::Word(w)) => Value::Long(w.parse().map_err(|_| invalid())?),
+            ('f', Token::Word(w)) => Value::Float(w.parse().map_err(|_| invalid())?),
+            ('d', Token::Word(w)) => Value::Double(w.parse().map_err(|_| invalid())?),
+            ('b', Token::Word(w)) => Value::Blob(parse_hex(w).ok_or_else(invalid)?),
+            ('m', Token::Word(w)) => Value::Midi(MidiMessage::from_bytes(parse_word(w).ok_or_else(invalid)?)),
+            ('r', Token::Word(w)) => Value::Color(Color::from_bytes(parse_word(w).ok_or_else(invalid)?)),
+            // Any other type is kept as raw data.
+            (tag, Token::Word(w)) if tag.is_ascii() && RawArg::valid_tag(tag as u8) =>
+                Value::Unknown(RawArg::new(tag, parse_hex(w).ok_or_else(invalid)?)),
+            (tag, _) => return Err(syntax_error(&format!("unsupported typetag '{}'", tag))),
+        };
+        Ok(value)
+    }
+}
+
+/// The only character of `s`.
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+fn parse_timetag(word: &str) -> ResultE<(u32, u32)> {
+    let mut parts = word.splitn(2, ':');
+    match (parts.next().map(str::parse), parts.next().map(str::parse)) {
+        (Some(Ok(sec)), Some(Ok(frac))) => Ok((sec, frac)),
+        _ => Err(syntax_error(&format!("invalid time-tag '{}'", word))),
+    }
+}
+
+/// Parse `#` followed by pairs of hex digits.
+fn parse_hex(word: &str) -> Option<Vec<u8>> {
+    let digits = word.strip_prefix('#')?;
+    if digits.len() % 2 != 0 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..digits.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i+2], 16).ok())
+        .collect()
+}
+
+/// Parse exactly 4 bytes in hex.
+fn parse_word(word: &str) -> Option<[u8; 4]> {
+    match parse_hex(word)?.as_slice() {
+        &[a, b, c, d] => Some([a, b, c, d]),
+        _ => None,
+    }
+}
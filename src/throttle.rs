@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use schedule::{Clock, SystemClock};
+use timetag::{from_duration, from_fixed, to_duration, to_fixed};
+use value::Message;
+
+/// Limits how often messages are sent to each address, coalescing those
+/// which come in faster: only the latest message pushed within the window is
+/// kept. This stops e.g. a fader being dragged from flooding hardware with
+/// one message per mouse movement, while still delivering its final position.
+///
+/// Push messages as they're produced, and poll [`pop_due`] (e.g. after
+/// sleeping for [`time_until_next`]) for those which may be sent now. The
+/// first message to an address is due at once; after that, at most one
+/// message per address is released per window.
+///
+/// Like a `Scheduler`, a throttle follows a [`Clock`], the system's by default.
+///
+/// ```
+/// # extern crate serde_osc;
+/// use std::cell::Cell;
+/// use std::time::Duration;
+/// use serde_osc::throttle::Throttle;
+/// use serde_osc::value::Message;
+///
+/// # fn main() {
+/// let time = Cell::new((100, 0));
+/// let mut throttle = Throttle::with_clock(Duration::from_millis(50), || time.get());
+/// let mut sent = Vec::new();
+/// for position in 0..10 {
+///     throttle.push(Message::to("/fader").arg(position as f32 / 10.0).build());
+///     sent.extend(throttle.drain_due());
+/// }
+/// // The first position goes out at once, and the latest one once the window has passed.
+/// assert_eq!(sent, vec![Message::to("/fader").arg(0.0f32).build()]);
+/// assert_eq!(throttle.len(), 1);
+/// time.set((100, 1 << 30)); // 250ms later
+/// assert_eq!(throttle.drain_due(), vec![Message::to("/fader").arg(0.9f32).build()]);
+/// # }
+/// ```
+///
+/// [`pop_due`]: #method.pop_due
+/// [`time_until_next`]: #method.time_until_next
+/// [`Clock`]: ../schedule/trait.Clock.html
+#[derive(Debug)]
+pub struct Throttle<C: Clock = SystemClock> {
+    clock: C,
+    /// The window, as a 64-bit fixed point number like a time-tag.
+    window: u64,
+    /// The addresses with a message waiting, or released within the window.
+    addresses: HashMap<String, Slot>,
+    /// Number of messages pushed so far, to release due messages in order.
+    pushed: u64,
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    /// When a message to the address was last released.
+    last_sent: Option<u64>,
+    /// The latest message waiting to be released, with its push number.
+    pending: Option<(u64, Message)>,
+}
+
+impl Throttle<SystemClock> {
+    /// Create a throttle which lets through at most one message per address
+    /// every `window`, following the system clock.
+    pub fn new(window: Duration) -> Self {
+        Self::with_clock(window, SystemClock)
+    }
+}
+
+impl<C: Clock> Throttle<C> {
+    /// Create a throttle which follows the provided clock.
+    pub fn with_clock(window: Duration, clock: C) -> Self {
+        Self {
+            clock,
+            // Windows beyond the range of a time-tag never end.
+            window: from_duration(window).map_or(u64::MAX, to_fixed),
+            addresses: HashMap::new(),
+            pushed: 0,
+        }
+    }
+    /// The clock the throttle follows.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+    /// The minimum time between messages to the same address.
+    pub fn window(&self) -> Duration {
+        to_duration(from_fixed(self.window))
+    }
+    /// Queue `msg`, replacing any message to the same address which is still waiting.
+    pub fn push(&mut self, msg: Message) {
+        let seq = self.pushed;
+        self.pushed += 1;
+        let slot = self.addresses.entry(msg.address.clone()).or_default();
+        slot.pending = Some((seq, msg));
+    }
+    /// Remove and return the message which was pushed first, among those
+    /// which may be sent now.
+    pub fn pop_due(&mut self) -> Option<Message> {
+        let now = to_fixed(self.clock.now());
+        let window = self.window;
+        // Forget the addresses which have nothing waiting, and whose window
+        // has passed: their next message is due at once either way.
+        self.addresses.retain(|_, slot| slot.pending.is_some() || Self::due(slot, window) > now);
+        let slot = self.addresses.values_mut()
+            .filter(|slot| slot.pending.is_some() && Self::due(slot, window) <= now)
+            .min_by_key(|slot| slot.pending.as_ref().map(|&(seq, _)| seq))?;
+        slot.last_sent = Some(now);
+        slot.pending.take().map(|(_, msg)| msg)
+    }
+    /// Remove and return all the messages which may be sent now, in the
+    /// order in which they were pushed.
+    pub fn drain_due(&mut self) -> Vec<Message> {
+        let mut due = Vec::new();
+        while let Some(msg) = self.pop_due() {
+            due.push(msg);
+        }
+        due
+    }
+    /// How long until the next waiting message may be sent.
+    /// This is zero if one is already due, and `None` if none are waiting.
+    pub fn time_until_next(&self) -> Option<Duration> {
+        let due = self.addresses.values()
+            .filter(|slot| slot.pending.is_some())
+            .map(|slot| Self::due(slot, self.window))
+            .min()?;
+        let diff = due.saturating_sub(to_fixed(self.clock.now()));
+        Some(to_duration(from_fixed(diff)))
+    }
+    /// Number of messages waiting to be released (at most one per address).
+    pub fn len(&self) -> usize {
+        self.addresses.values().filter(|slot| slot.pending.is_some()).count()
+    }
+    /// Whether no messages are waiting to be released.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Discard all waiting messages, and forget when messages were last
+    /// released, so that the next message to each address is due at once.
+    pub fn clear(&mut self) {
+        self.addresses.clear();
+    }
+    /// When the waiting message of `slot` may be released.
+    fn due(slot: &Slot, window: u64) -> u64 {
+        slot.last_sent.map_or(0, |sent| sent.saturating_add(window))
+    }
+}
@@ -0,0 +1,273 @@
+//! A time-tag is a `(seconds, fraction)` pair, counting seconds (and
+//! 1/2^32ths of a second) since midnight on January 1, 1900, as in NTP.
+//! Only the first NTP era is supported, i.e. times from 1900 until early 2036.
+//! Rust's time types count whole nanoseconds, so the fraction is rounded when
+//! converting to them.
+//!
+//! Besides `(u32, u32)`, the time-tag of a bundle can be deserialized directly
+//! into a `SystemTime`, a `Duration` (the time since 1900), or, with the
+//! `chrono` feature enabled, a `chrono::DateTime<Utc>`. `SystemTime` and
+//! `Duration` time-tags can also be serialized. Note that serde's encoding of
+//! `SystemTime` can't represent times before 1970, which includes the special
+//! [`IMMEDIATELY`] time-tag; prefer `Duration` when these may be received.
+//!
+//! To handle "immediately" explicitly, deserialize the time-tag as an
+//! `Option` of any of the above, which is `None` for [`IMMEDIATELY`], or as a
+//! [`TimeTag`]. Serializing `None` likewise produces [`IMMEDIATELY`].
+//!
+//! A [`TimeTag`] can also be shifted by a `Duration`, and a [`Compensation`]
+//! does so for the clock offset of, and latency to, a receiver.
+//!
+//! [`IMMEDIATELY`]: constant.IMMEDIATELY.html
+//! [`TimeTag`]: enum.TimeTag.html
+//! [`Compensation`]: struct.Compensation.html
+
+use std::convert::TryInto;
+use std::ops::{Add, Sub};
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use error::{Error, ResultE};
+
+/// The time-tag with the special meaning "immediately".
+pub const IMMEDIATELY: (u32, u32) = (0, 1);
+
+/// A time-tag, distinguishing "immediately" from the points in time.
+///
+/// ```
+/// # extern crate serde_osc;
+/// use serde_osc::timetag::{self, TimeTag};
+///
+/// # fn main() {
+/// let bundle = (timetag::IMMEDIATELY, (("/go".to_owned(), ()),));
+/// let packet = serde_osc::to_vec(&bundle).unwrap();
+/// let (when, _): (TimeTag, ((String, ()),)) = serde_osc::from_slice(&packet).unwrap();
+/// assert_eq!(when, TimeTag::Immediate);
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TimeTag {
+    /// The special time-tag `(0, 1)`: handle the bundle as soon as it's received.
+    Immediate,
+    /// Handle the bundle at the `(seconds, fraction)` since 1900.
+    At(u32, u32),
+}
+
+impl From<(u32, u32)> for TimeTag {
+    fn from(timetag: (u32, u32)) -> Self {
+        match timetag {
+            IMMEDIATELY => TimeTag::Immediate,
+            (sec, frac) => TimeTag::At(sec, frac),
+        }
+    }
+}
+
+impl From<TimeTag> for (u32, u32) {
+    fn from(timetag: TimeTag) -> Self {
+        match timetag {
+            TimeTag::Immediate => IMMEDIATELY,
+            TimeTag::At(sec, frac) => (sec, frac),
+        }
+    }
+}
+
+impl TimeTag {
+    /// The time-tag denoting a point in time.
+    /// Fails if the time is before 1900 or beyond the first NTP era.
+    pub fn from_system_time(time: SystemTime) -> ResultE<Self> {
+        from_system_time(time).map(TimeTag::from)
+    }
+    /// The time-tag `offset` after a point in time, e.g. to schedule a bundle
+    /// ahead of the time it's sent. Fails as `from_system_time` does.
+    pub fn from_system_time_plus(time: SystemTime, offset: Duration) -> ResultE<Self> {
+        Self::from_system_time(time)?.checked_add(offset).ok_or(Error::BadFormat)
+    }
+    /// The time-tag `duration` later, or `None` if this is beyond the first
+    /// NTP era. `Immediate` is unaffected.
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        let duration = to_fixed(from_duration(duration).ok()?);
+        self.map_fixed(|fixed| fixed.checked_add(duration))
+    }
+    /// The time-tag `duration` earlier, or `None` if this is before 1900.
+    /// `Immediate` is unaffected.
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        let duration = to_fixed(from_duration(duration).ok()?);
+        self.map_fixed(|fixed| fixed.checked_sub(duration))
+    }
+    fn map_fixed<F: FnOnce(u64) -> Option<u64>>(self, f: F) -> Option<Self> {
+        match self {
+            TimeTag::Immediate => Some(self),
+            TimeTag::At(sec, frac) => f(to_fixed((sec, frac))).map(|fixed| from_fixed(fixed).into()),
+        }
+    }
+}
+
+/// Panics if the result is beyond the first NTP era; see `TimeTag::checked_add`.
+impl Add<Duration> for TimeTag {
+    type Output = TimeTag;
+    fn add(self, duration: Duration) -> TimeTag {
+        self.checked_add(duration).expect("overflow when adding duration to time-tag")
+    }
+}
+
+/// Panics if the result is before 1900; see `TimeTag::checked_sub`.
+impl Sub<Duration> for TimeTag {
+    type Output = TimeTag;
+    fn sub(self, duration: Duration) -> TimeTag {
+        self.checked_sub(duration).expect("overflow when subtracting duration from time-tag")
+    }
+}
+
+impl Serialize for TimeTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        <(u32, u32)>::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeTag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <(u32, u32)>::deserialize(deserializer).map(TimeTag::from)
+    }
+}
+
+/// Adjusts the time-tags of outgoing bundles for a receiver, so that they're
+/// handled at the intended time (much as Ableton Link does): the receiver's
+/// clock may be offset from the local one, and packets take a while to reach it.
+///
+/// ```
+/// # extern crate serde_osc;
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use serde_osc::timetag::{Compensation, TimeTag};
+///
+/// # fn main() {
+/// // The receiver's clock is 1.5s behind ours; allow 20ms for the network.
+/// let compensation = Compensation { clock_offset: -1.5, latency: Duration::from_millis(20) };
+/// let sent = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+/// assert_eq!(compensation.timetag(sent).unwrap(),
+///            TimeTag::from_system_time(sent).unwrap() - Duration::from_millis(1480));
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Compensation {
+    /// How many seconds the receiver's clock is ahead of the local one
+    /// (negative if it's behind), e.g. as measured with NTP.
+    pub clock_offset: f64,
+    /// How long packets take to reach the receiver. Bundles are scheduled
+    /// this long after they're sent, so they arrive in time.
+    pub latency: Duration,
+}
+
+impl Compensation {
+    /// The time-tag, by the receiver's clock, for a bundle sent at the local `time`.
+    /// Fails if the offset isn't a finite number, or the result isn't within
+    /// the first NTP era.
+    pub fn timetag(&self, time: SystemTime) -> ResultE<TimeTag> {
+        let due = TimeTag::from_system_time_plus(time, self.latency)?;
+        let offset = Duration::try_from_secs_f64(self.clock_offset.abs()).map_err(|_| Error::BadFormat)?;
+        let adjusted = if self.clock_offset < 0.0 {
+            due.checked_sub(offset)
+        } else {
+            due.checked_add(offset)
+        };
+        adjusted.ok_or(Error::BadFormat)
+    }
+    /// The time-tag for a bundle sent now.
+    pub fn now(&self) -> ResultE<TimeTag> {
+        self.timetag(SystemTime::now())
+    }
+}
+
+/// Seconds from the OSC (NTP) epoch, 1900-01-01, to the Unix epoch.
+pub(crate) const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// Names under which serde (de)serializes the standard time types.
+pub(crate) const SYSTEM_TIME_NAME: &str = "SystemTime";
+pub(crate) const DURATION_NAME: &str = "Duration";
+
+/// The time elapsed since 1900 at the given time-tag.
+pub fn to_duration((sec, frac): (u32, u32)) -> Duration {
+    // Round to the nearest nanosecond, so that nanoseconds survive a round trip.
+    let nanos = (u64::from(frac) * 1_000_000_000 + (1 << 31)) >> 32;
+    Duration::new(u64::from(sec), 0) + Duration::from_nanos(nanos)
+}
+
+/// The seconds elapsed since 1900 at the given time-tag.
+/// An `f64` resolves these to within a microsecond.
+pub fn to_secs_f64((sec, frac): (u32, u32)) -> f64 {
+    f64::from(sec) + f64::from(frac) / 4_294_967_296.0
+}
+
+/// The time-tag at the given number of seconds since 1900.
+/// Fails if this is negative, not a number, or beyond the first NTP era.
+pub fn from_secs_f64(since_1900: f64) -> ResultE<(u32, u32)> {
+    if !(0.0..4_294_967_296.0).contains(&since_1900) {
+        return Err(Error::BadFormat);
+    }
+    let fixed = (since_1900 * 4_294_967_296.0).round() as u64;
+    Ok(((fixed >> 32).try_into()?, fixed as u32))
+}
+
+/// The time-tag at the given time since 1900.
+/// Fails if this is beyond the first NTP era.
+pub fn from_duration(since_1900: Duration) -> ResultE<(u32, u32)> {
+    let frac = ((u64::from(since_1900.subsec_nanos()) << 32) + 500_000_000) / 1_000_000_000;
+    Ok((since_1900.as_secs().try_into()?, frac as u32))
+}
+
+/// The point in time denoted by a time-tag.
+pub fn to_system_time(timetag: (u32, u32)) -> SystemTime {
+    let since_1900 = to_duration(timetag);
+    let unix_offset = Duration::from_secs(NTP_UNIX_OFFSET);
+    match since_1900.checked_sub(unix_offset) {
+        Some(since_unix) => UNIX_EPOCH + since_unix,
+        None => UNIX_EPOCH - (unix_offset - since_1900),
+    }
+}
+
+/// The time-tag denoting a point in time.
+/// Fails if the time is before 1900 or beyond the first NTP era.
+pub fn from_system_time(time: SystemTime) -> ResultE<(u32, u32)> {
+    let unix_offset = Duration::from_secs(NTP_UNIX_OFFSET);
+    let since_1900 = match time.duration_since(UNIX_EPOCH) {
+        Ok(since_unix) => since_unix + unix_offset,
+        Err(e) => unix_offset.checked_sub(e.duration()).ok_or(Error::BadFormat)?,
+    };
+    from_duration(since_1900)
+}
+
+/// The time-tag as a 64-bit fixed point number, which orders like the time.
+pub(crate) fn to_fixed((sec, frac): (u32, u32)) -> u64 {
+    (u64::from(sec) << 32) | u64::from(frac)
+}
+
+pub(crate) fn from_fixed(fixed: u64) -> (u32, u32) {
+    ((fixed >> 32) as u32, fixed as u32)
+}
+
+/// The time elapsed since 1970 at the given time-tag, as (seconds, nanoseconds).
+/// This is how serde represents a `SystemTime`.
+pub(crate) fn to_unix_parts(timetag: (u32, u32)) -> ResultE<(u64, u32)> {
+    let since_unix = to_system_time(timetag).duration_since(UNIX_EPOCH)
+        .map_err(|_| Error::Message("time-tag is before 1970, and can't be a SystemTime".to_owned()))?;
+    Ok((since_unix.as_secs(), since_unix.subsec_nanos()))
+}
+
+/// The time-tag at the given time since 1970, as (seconds, nanoseconds).
+pub(crate) fn from_unix_parts(secs: u64, nanos: u32) -> ResultE<(u32, u32)> {
+    let since_unix = Duration::new(secs, nanos);
+    from_duration(since_unix + Duration::from_secs(NTP_UNIX_OFFSET))
+}
+
+/// The point in time denoted by a time-tag.
+#[cfg(feature = "chrono")]
+pub fn to_datetime(timetag: (u32, u32)) -> ::chrono::DateTime<::chrono::Utc> {
+    to_system_time(timetag).into()
+}
+
+/// The time-tag denoting a point in time.
+/// Fails if the time is before 1900 or beyond the first NTP era.
+#[cfg(feature = "chrono")]
+pub fn from_datetime(time: ::chrono::DateTime<::chrono::Utc>) -> ResultE<(u32, u32)> {
+    from_system_time(time.into())
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+use serde::de::{Deserialize, Deserializer, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// Newtype-struct name through which `MidiMessage` identifies itself to the serializer.
+pub(crate) const MIDI_NAME: &str = "__osc_midi";
+/// Newtype-struct name through which `Color` identifies itself to the serializer.
+pub(crate) const COLOR_NAME: &str = "__osc_color";
+/// Newtype-struct name through which `Symbol` identifies itself to the serializer.
+pub(crate) const SYMBOL_NAME: &str = "__osc_symbol";
+/// Newtype-struct name through which `UnicodeChar` identifies itself to the serializer.
+pub(crate) const UNICODE_CHAR_NAME: &str = "__osc_unicode_char";
+/// Newtype-struct name through which `RawArg` identifies itself to the serializer.
+pub(crate) const RAW_ARG_NAME: &str = "__osc_raw_arg";
+
+/// Whether a newtype struct is one of the helper types above. Any other
+/// newtype struct is encoded as the value it wraps.
+pub(crate) fn is_helper_name(name: &str) -> bool {
+    [MIDI_NAME, COLOR_NAME, SYMBOL_NAME, UNICODE_CHAR_NAME, RAW_ARG_NAME].contains(&name)
+}
+
+/// A MIDI message, transmitted as an OSC 'm' argument.
+///
+/// When used with a non-OSC data format, this serializes as a sequence of 4 bytes:
+/// `[port, status, data1, data2]`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MidiMessage {
+    /// Port id
+    pub port: u8,
+    /// Status byte, e.g. 0x90 for Note On (channel 1)
+    pub status: u8,
+    pub data1: u8,
+    pub data2: u8,
+}
+
+impl MidiMessage {
+    pub fn new(port: u8, status: u8, data1: u8, data2: u8) -> Self {
+        Self { port, status, data1, data2 }
+    }
+    pub fn from_bytes(bytes: [u8; 4]) -> Self {
+        Self::new(bytes[0], bytes[1], bytes[2], bytes[3])
+    }
+    pub fn to_bytes(&self) -> [u8; 4] {
+        [self.port, self.status, self.data1, self.data2]
+    }
+}
+
+impl Serialize for MidiMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(MIDI_NAME, &FourBytes(self.to_bytes()))
+    }
+}
+
+impl<'de> Deserialize<'de> for MidiMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(MIDI_NAME, FourBytesVisitor)
+            .map(MidiMessage::from_bytes)
+    }
+}
+
+/// A 32-bit RGBA color, transmitted as an OSC 'r' argument.
+///
+/// When used with a non-OSC data format, this serializes as a sequence of 4 bytes:
+/// `[r, g, b, a]`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+    pub fn from_bytes(bytes: [u8; 4]) -> Self {
+        Self::new(bytes[0], bytes[1], bytes[2], bytes[3])
+    }
+    pub fn to_bytes(&self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(COLOR_NAME, &FourBytes(self.to_bytes()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(COLOR_NAME, FourBytesVisitor)
+            .map(Color::from_bytes)
+    }
+}
+
+/// A string transmitted with the 'S' (symbol) typetag rather than 's'.
+///
+/// Some applications (notably SuperCollider) treat symbols and strings
+/// differently, despite them having identical encodings.
+/// When deserializing, both 's' and 'S' arguments are accepted as a `Symbol`
+/// (and likewise for `String`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(pub String);
+
+impl Symbol {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> From<&'a str> for Symbol {
+    fn from(s: &'a str) -> Self {
+        Symbol(s.to_owned())
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(s: String) -> Self {
+        Symbol(s)
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(SYMBOL_NAME, &self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(SYMBOL_NAME, SymbolVisitor)
+    }
+}
+
+struct SymbolVisitor;
+
+impl<'de> Visitor<'de> for SymbolVisitor {
+    type Value = Symbol;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a string")
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        String::deserialize(deserializer).map(Symbol)
+    }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Symbol::from(v))
+    }
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Symbol(v))
+    }
+}
+
+/// A character transmitted as a 'c' argument, even if it isn't ASCII.
+///
+/// The spec only allows ASCII characters, so a plain `char` outside that range
+/// fails to serialize (with `Error::NonAsciiChar`); this sends its Unicode code
+/// point instead, for peers which accept it. When deserializing, any 'c'
+/// argument is accepted as either a `UnicodeChar` or a `char`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnicodeChar(pub char);
+
+impl From<char> for UnicodeChar {
+    fn from(c: char) -> Self {
+        UnicodeChar(c)
+    }
+}
+
+impl Serialize for UnicodeChar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(UNICODE_CHAR_NAME, &self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for UnicodeChar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(UNICODE_CHAR_NAME, UnicodeCharVisitor)
+    }
+}
+
+struct UnicodeCharVisitor;
+
+impl<'de> Visitor<'de> for UnicodeCharVisitor {
+    type Value = UnicodeChar;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a character")
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        char::deserialize(deserializer).map(UnicodeChar)
+    }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> {
+        Ok(UnicodeChar(v))
+    }
+}
+
+/// An argument of a type this crate doesn't understand, kept as its typetag
+/// and encoded data so that it can be forwarded unchanged.
+///
+/// These are produced when decoding with `UnknownTypes::Capture` (see
+/// `serde_osc::de::DeserializerOptions`), and are written back verbatim.
+/// When used with a non-OSC data format, this serializes as a byte array:
+/// the tag, followed by the data.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawArg {
+    /// The typetag character; must be printable ASCII, other than ',', '[' or ']'.
+    pub tag: char,
+    /// The argument data, including any padding; its length must be a multiple of 4.
+    pub data: Vec<u8>,
+}
+
+impl RawArg {
+    pub fn new<D: Into<Vec<u8>>>(tag: char, data: D) -> Self {
+        Self { tag, data: data.into() }
+    }
+    /// Whether `tag` can be used for a raw argument.
+    pub(crate) fn valid_tag(tag: u8) -> bool {
+        tag.is_ascii_graphic() && !b",[]".contains(&tag)
+    }
+}
+
+impl Serialize for RawArg {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::with_capacity(1 + self.data.len());
+        // Non-ASCII tags are replaced with NUL, which the OSC serializer rejects.
+        bytes.push(if self.tag.is_ascii() { self.tag as u8 } else { 0 });
+        bytes.extend_from_slice(&self.data);
+        serializer.serialize_newtype_struct(RAW_ARG_NAME, &TaggedBytes(bytes))
+    }
+}
+
+/// Serializes a typetag and its data as a single byte array.
+struct TaggedBytes(Vec<u8>);
+
+impl Serialize for TaggedBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+/// Serializes as a byte array, so that the OSC serializer can copy the bytes verbatim.
+struct FourBytes([u8; 4]);
+
+impl Serialize for FourBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+/// Deserializes exactly 4 bytes, given either as a byte array or a sequence.
+struct FourBytesVisitor;
+
+impl<'de> Visitor<'de> for FourBytesVisitor {
+    type Value = [u8; 4];
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of 4 bytes")
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_bytes(self)
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        if v.len() != 4 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        Ok([v[0], v[1], v[2], v[3]])
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; 4];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(5, &self));
+        }
+        Ok(bytes)
+    }
+}
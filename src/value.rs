@@ -0,0 +1,585 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::slice;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::ser::{Serialize, Serializer, SerializeTuple};
+
+use de::{self, DeserializerOptions};
+use error::{Error, ResultE};
+use framing::Framing;
+use ser;
+use types::{Color, MidiMessage, RawArg, Symbol, UnicodeChar, SYMBOL_NAME};
+
+/// A single OSC argument, of any supported type.
+///
+/// Arguments are equal if they have the same type and the same value, with
+/// floats compared by their bits: NaN equals itself (given the same bits),
+/// while 0.0 and -0.0 differ. So values (and messages, bundles and packets)
+/// can be hashed, e.g. to deduplicate them, or to look up the last value
+/// sent to each address. They're ordered by type (in the order of the
+/// variants below), then by value, with floats in IEEE 754 total order.
+#[derive(Clone, Debug)]
+pub enum Value {
+    /// 'i': 32-bit integer
+    Int(i32),
+    /// 'h': 64-bit integer
+    Long(i64),
+    /// 'f': 32-bit float
+    Float(f32),
+    /// 'd': 64-bit float
+    Double(f64),
+    /// 's': string
+    String(String),
+    /// 'S': symbol
+    Symbol(String),
+    /// 'b': blob
+    Blob(Vec<u8>),
+    /// 'T' or 'F'
+    Bool(bool),
+    /// 'N': nil
+    Nil,
+    /// 'c': character
+    Char(char),
+    /// 'm': MIDI message
+    Midi(MidiMessage),
+    /// 'r': RGBA color
+    Color(Color),
+    /// '[' ... ']': array of arguments
+    Array(Vec<Value>),
+    /// Any other type, whose data was captured without being decoded
+    /// (see `serde_osc::de::UnknownTypes`).
+    Unknown(RawArg),
+}
+
+/// An OSC message whose arguments aren't known until runtime.
+///
+/// Unlike [`serde_osc::message::Message`], which binds a Rust type to a fixed
+/// address, this can hold any message.
+///
+/// [`serde_osc::message::Message`]: ../message/struct.Message.html
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Message {
+    pub address: String,
+    pub args: Vec<Value>,
+}
+
+/// An OSC bundle: a time-tag, and the packets to be processed at that time.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bundle {
+    /// (seconds, fractional seconds) since midnight on January 1, 1900.
+    pub timetag: (u32, u32),
+    pub elements: Vec<Packet>,
+}
+
+/// Any OSC packet: either a message, or a bundle.
+///
+/// Packets can be decoded from bytes with [`from_slice`], and encoded with
+/// `serde_osc::to_vec` (or any other serialization function of this crate).
+///
+/// Its `Debug` output is an annotated breakdown of its encoding; see [`serde_osc::dump`].
+///
+/// [`from_slice`]: #method.from_slice
+/// [`serde_osc::dump`]: ../dump/index.html
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Packet {
+    Message(Message),
+    Bundle(Bundle),
+}
+
+impl Value {
+    /// The typetag character(s) of this argument, e.g. `"i"` or `"[if]"`.
+    pub fn typetag(&self) -> String {
+        let mut tag = String::new();
+        self.push_typetag(&mut tag);
+        tag
+    }
+    fn push_typetag(&self, tag: &mut String) {
+        match *self {
+            Value::Int(_) => tag.push('i'),
+            Value::Long(_) => tag.push('h'),
+            Value::Float(_) => tag.push('f'),
+            Value::Double(_) => tag.push('d'),
+            Value::String(_) => tag.push('s'),
+            Value::Symbol(_) => tag.push('S'),
+            Value::Blob(_) => tag.push('b'),
+            Value::Bool(true) => tag.push('T'),
+            Value::Bool(false) => tag.push('F'),
+            Value::Nil => tag.push('N'),
+            Value::Char(_) => tag.push('c'),
+            Value::Midi(_) => tag.push('m'),
+            Value::Color(_) => tag.push('r'),
+            Value::Array(ref elems) => {
+                tag.push('[');
+                for elem in elems {
+                    elem.push_typetag(tag);
+                }
+                tag.push(']');
+            },
+            Value::Unknown(ref raw) => tag.push(raw.tag),
+        }
+    }
+    /// The position of the variant, which orders arguments of different types.
+    fn rank(&self) -> u8 {
+        match *self {
+            Value::Int(_) => 0,
+            Value::Long(_) => 1,
+            Value::Float(_) => 2,
+            Value::Double(_) => 3,
+            Value::String(_) => 4,
+            Value::Symbol(_) => 5,
+            Value::Blob(_) => 6,
+            Value::Bool(_) => 7,
+            Value::Nil => 8,
+            Value::Char(_) => 9,
+            Value::Midi(_) => 10,
+            Value::Color(_) => 11,
+            Value::Array(_) => 12,
+            Value::Unknown(_) => 13,
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Int(l), Value::Int(r)) => l.cmp(r),
+            (Value::Long(l), Value::Long(r)) => l.cmp(r),
+            // Only floats with the same bits compare equal.
+            (Value::Float(l), Value::Float(r)) => l.total_cmp(r),
+            (Value::Double(l), Value::Double(r)) => l.total_cmp(r),
+            (Value::String(l), Value::String(r)) | (Value::Symbol(l), Value::Symbol(r)) => l.cmp(r),
+            (Value::Blob(l), Value::Blob(r)) => l.cmp(r),
+            (Value::Bool(l), Value::Bool(r)) => l.cmp(r),
+            (Value::Char(l), Value::Char(r)) => l.cmp(r),
+            (Value::Midi(l), Value::Midi(r)) => l.cmp(r),
+            (Value::Color(l), Value::Color(r)) => l.cmp(r),
+            (Value::Array(l), Value::Array(r)) => l.cmp(r),
+            (Value::Unknown(l), Value::Unknown(r)) => l.cmp(r),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match *self {
+            Value::Int(i) => i.hash(state),
+            Value::Long(h) => h.hash(state),
+            Value::Float(f) => f.to_bits().hash(state),
+            Value::Double(d) => d.to_bits().hash(state),
+            Value::String(ref s) | Value::Symbol(ref s) => s.hash(state),
+            Value::Blob(ref b) => b.hash(state),
+            Value::Bool(b) => b.hash(state),
+            Value::Nil => {},
+            Value::Char(c) => c.hash(state),
+            Value::Midi(ref m) => m.hash(state),
+            Value::Color(ref c) => c.hash(state),
+            Value::Array(ref elems) => elems.hash(state),
+            Value::Unknown(ref raw) => raw.hash(state),
+        }
+    }
+}
+
+impl Message {
+    /// Create a message with no arguments.
+    pub fn new<S: Into<String>>(address: S) -> Self {
+        Self {
+            address: address.into(),
+            args: Vec::new(),
+        }
+    }
+    /// Start building a message to `address`, adding its arguments one call at
+    /// a time.
+    ///
+    /// ```
+    /// # extern crate serde_osc;
+    /// # use serde_osc::value::{Message, Value};
+    /// # fn main() {
+    /// let msg = Message::to("/synth/1/note")
+    ///     .arg(440.0f32)
+    ///     .arg("sine")
+    ///     .begin_array().arg(0.1f32).arg(0.5f32).end_array()
+    ///     .build();
+    /// assert_eq!(msg.typetag(), "fs[ff]");
+    /// assert_eq!(msg.args[1], Value::from("sine"));
+    /// # }
+    /// ```
+    pub fn to<S: Into<String>>(address: S) -> MessageBuilder {
+        MessageBuilder { msg: Self::new(address) }
+    }
+    /// Append an argument (builder-style).
+    pub fn with_arg<V: Into<Value>>(mut self, arg: V) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+    /// The typetag of the message, without the leading comma.
+    pub fn typetag(&self) -> String {
+        let mut tag = String::new();
+        for arg in &self.args {
+            arg.push_typetag(&mut tag);
+        }
+        tag
+    }
+    /// Deserialize the argument at `index` into a `T`, with the same type
+    /// checks (and coercions) as when deserializing a received packet.
+    ///
+    /// ```
+    /// use serde_osc::value::Message;
+    ///
+    /// let msg = Message::to("/synth/1").arg(440.0f32).arg("sine").build();
+    /// assert_eq!(msg.arg::<f32>(0).unwrap(), 440.0);
+    /// assert_eq!(msg.arg::<String>(1).unwrap(), "sine");
+    /// assert!(msg.arg::<i32>(1).is_err());
+    /// assert!(msg.arg::<f32>(2).is_err());
+    /// ```
+    ///
+    /// Fails with `Error::Message` if there's no such argument.
+    pub fn arg<T: DeserializeOwned>(&self, index: usize) -> ResultE<T> {
+        let arg = self.args.get(index).ok_or_else(|| {
+            Error::Message(format!("no argument {} (the message has {})", index, self.args.len()))
+        })?;
+        let (_, (value,)) = Self::deserialize_args::<(T,)>(slice::from_ref(arg))?;
+        Ok(value)
+    }
+    /// Deserialize all the arguments into a `T`, such as a tuple or a struct,
+    /// as if the message had been received.
+    ///
+    /// ```
+    /// use serde_osc::value::Message;
+    ///
+    /// let msg = Message::to("/note").arg(60).arg("piano").build();
+    /// let (note, instrument): (i32, String) = msg.args_as().unwrap();
+    /// assert_eq!((note, instrument.as_str()), (60, "piano"));
+    /// ```
+    pub fn args_as<T: DeserializeOwned>(&self) -> ResultE<T> {
+        Self::deserialize_args::<T>(&self.args).map(|(_, args)| args)
+    }
+    /// Encode `args` under a placeholder address (the message's own may not
+    /// be valid), and decode them again as a `T`.
+    fn deserialize_args<T: DeserializeOwned>(args: &[Value]) -> ResultE<(IgnoredAny, T)> {
+        let packet = ser::to_vec_unframed(&("/", args))?;
+        de::from_slice_unframed(&packet)
+    }
+}
+
+/// Builds a [`Message`] one argument at a time; see [`Message::to`].
+///
+/// The address is given up front, and every array opened with `begin_array`
+/// must be closed before `build` becomes available, so the builder can't
+/// produce an incomplete message.
+///
+/// [`Message`]: struct.Message.html
+/// [`Message::to`]: struct.Message.html#method.to
+#[derive(Clone, Debug)]
+pub struct MessageBuilder {
+    msg: Message,
+}
+
+/// Builds an array ('[' ... ']') argument within a [`MessageBuilder`] (or
+/// within an enclosing array). `end_array` returns to the enclosing builder.
+///
+/// [`MessageBuilder`]: struct.MessageBuilder.html
+#[derive(Clone, Debug)]
+pub struct ArrayBuilder<P> {
+    parent: P,
+    elems: Vec<Value>,
+    /// Appends the finished array to `parent`.
+    close: fn(&mut P, Value),
+}
+
+impl MessageBuilder {
+    /// Append an argument.
+    pub fn arg<V: Into<Value>>(mut self, arg: V) -> Self {
+        self.msg.args.push(arg.into());
+        self
+    }
+    /// Append each of `args` in turn.
+    pub fn args<I>(mut self, args: I) -> Self
+        where I: IntoIterator, I::Item: Into<Value>
+    {
+        self.msg.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+    /// Open an array argument; the following arguments become its elements.
+    pub fn begin_array(self) -> ArrayBuilder<Self> {
+        ArrayBuilder::new(self, |builder, array| builder.msg.args.push(array))
+    }
+    pub fn build(self) -> Message {
+        self.msg
+    }
+}
+
+impl<P> ArrayBuilder<P> {
+    fn new(parent: P, close: fn(&mut P, Value)) -> Self {
+        Self {
+            parent,
+            elems: Vec::new(),
+            close,
+        }
+    }
+    /// Append an element to the array.
+    pub fn arg<V: Into<Value>>(mut self, arg: V) -> Self {
+        self.elems.push(arg.into());
+        self
+    }
+    /// Append each of `args` to the array in turn.
+    pub fn args<I>(mut self, args: I) -> Self
+        where I: IntoIterator, I::Item: Into<Value>
+    {
+        self.elems.extend(args.into_iter().map(Into::into));
+        self
+    }
+    /// Open an array nested within this one.
+    pub fn begin_array(self) -> ArrayBuilder<Self> {
+        ArrayBuilder::new(self, |builder, array| builder.elems.push(array))
+    }
+    /// Close the array, and carry on with the enclosing builder.
+    pub fn end_array(mut self) -> P {
+        (self.close)(&mut self.parent, Value::Array(self.elems));
+        self.parent
+    }
+}
+
+impl Bundle {
+    /// Create an empty bundle with the given time-tag.
+    pub fn new(timetag: (u32, u32)) -> Self {
+        Self {
+            timetag,
+            elements: Vec::new(),
+        }
+    }
+}
+
+impl Packet {
+    /// Decode a length-prefixed packet.
+    pub fn from_slice(slice: &[u8]) -> ResultE<Self> {
+        Self::from_slice_with(slice, Framing::LengthPrefixed, DeserializerOptions::default())
+    }
+    /// Decode a packet which has no length prefix.
+    pub fn from_slice_unframed(slice: &[u8]) -> ResultE<Self> {
+        Self::from_slice_with(slice, Framing::Unframed, DeserializerOptions::default())
+    }
+    /// Decode a packet, using the provided framing and decoding options.
+    pub fn from_slice_with(slice: &[u8], framing: Framing, options: DeserializerOptions) -> ResultE<Self> {
+        de::read_packet(&mut Cursor::new(slice), framing, options)
+    }
+    /// The address of the message, or `None` for a bundle.
+    pub fn address(&self) -> Option<&str> {
+        match *self {
+            Packet::Message(ref msg) => Some(&msg.address),
+            Packet::Bundle(_) => None,
+        }
+    }
+}
+
+
+macro_rules! value_from {
+    ($($t:ty => $variant:ident),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Value::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+value_from!(i32 => Int, i64 => Long, f32 => Float, f64 => Double, String => String,
+    Vec<u8> => Blob, bool => Bool, char => Char, MidiMessage => Midi, Color => Color, Vec<Value> => Array,
+    RawArg => Unknown);
+
+impl<'a> From<&'a str> for Value {
+    fn from(value: &'a str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl From<Symbol> for Value {
+    fn from(value: Symbol) -> Self {
+        Value::Symbol(value.0)
+    }
+}
+
+impl From<Message> for Packet {
+    fn from(msg: Message) -> Self {
+        Packet::Message(msg)
+    }
+}
+
+impl From<Bundle> for Packet {
+    fn from(bundle: Bundle) -> Self {
+        Packet::Bundle(bundle)
+    }
+}
+
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Value::Int(i) => serializer.serialize_i32(i),
+            Value::Long(h) => serializer.serialize_i64(h),
+            Value::Float(f) => serializer.serialize_f32(f),
+            Value::Double(d) => serializer.serialize_f64(d),
+            Value::String(ref s) => serializer.serialize_str(s),
+            Value::Symbol(ref s) => serializer.serialize_newtype_struct(SYMBOL_NAME, s),
+            Value::Blob(ref b) => serializer.serialize_bytes(b),
+            Value::Bool(b) => serializer.serialize_bool(b),
+            Value::Nil => serializer.serialize_none(),
+            // Decoded characters may not be ASCII; they're sent back as they were received.
+            Value::Char(c) => UnicodeChar(c).serialize(serializer),
+            Value::Midi(ref m) => m.serialize(serializer),
+            Value::Color(ref c) => c.serialize(serializer),
+            Value::Array(ref elems) => serializer.collect_seq(elems),
+            Value::Unknown(ref raw) => raw.serialize(serializer),
+        }
+    }
+}
+
+/// Serializes as `(address, args)`.
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tup = serializer.serialize_tuple(2)?;
+        tup.serialize_element(&self.address)?;
+        tup.serialize_element(&self.args)?;
+        tup.end()
+    }
+}
+
+/// Serializes as `(timetag, elements)`.
+impl Serialize for Bundle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tup = serializer.serialize_tuple(2)?;
+        tup.serialize_element(&self.timetag)?;
+        tup.serialize_element(&self.elements)?;
+        tup.end()
+    }
+}
+
+impl Serialize for Packet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Packet::Message(ref msg) => msg.serialize(serializer),
+            Packet::Bundle(ref bundle) => bundle.serialize(serializer),
+        }
+    }
+}
+
+/// Generation of random, well-formed packets for property tests and fuzzing.
+///
+/// Generated packets can always be encoded, and decode back to the same value
+/// (floats are compared by their bits, so even NaNs are equal): addresses are
+/// valid, strings hold no NUL characters, nesting is kept shallow and
+/// `Value::Unknown` isn't generated.
+#[cfg(feature = "arbitrary")]
+mod generate {
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    use types::{Color, MidiMessage};
+    use super::{Bundle, Message, Packet, Value};
+
+    /// How deeply arrays, and bundles, may be nested.
+    const MAX_DEPTH: usize = 2;
+    /// The most arguments of a message (or elements of an array or bundle).
+    const MAX_LEN: usize = 8;
+    /// Characters of address parts, avoiding those reserved for patterns.
+    const ADDRESS_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-.";
+
+    fn len(u: &mut Unstructured) -> Result<usize> {
+        u.int_in_range(0..=MAX_LEN)
+    }
+
+    fn address(u: &mut Unstructured) -> Result<String> {
+        let mut address = String::new();
+        for _ in 0..u.int_in_range(1..=3)? {
+            address.push('/');
+            for _ in 0..u.int_in_range(1..=8)? {
+                address.push(*u.choose(ADDRESS_CHARS)? as char);
+            }
+        }
+        Ok(address)
+    }
+
+    /// An OSC string can't hold a NUL, which would end it.
+    fn string(u: &mut Unstructured) -> Result<String> {
+        Ok(String::arbitrary(u)?.replace('\0', ""))
+    }
+
+    fn value(u: &mut Unstructured, depth: usize) -> Result<Value> {
+        // Arrays are the last kind, left out once deep enough.
+        let last_kind = if depth < MAX_DEPTH { 13 } else { 12 };
+        Ok(match u.int_in_range(0..=last_kind)? {
+            0 => Value::Int(u.arbitrary()?),
+            1 => Value::Long(u.arbitrary()?),
+            2 => Value::Float(u.arbitrary()?),
+            3 => Value::Double(u.arbitrary()?),
+            4 => Value::String(string(u)?),
+            5 => Value::Symbol(string(u)?),
+            6 => Value::Blob(u.arbitrary()?),
+            7 => Value::Bool(true),
+            8 => Value::Bool(false),
+            9 => Value::Nil,
+            10 => Value::Char(u.arbitrary()?),
+            11 => Value::Midi(MidiMessage { port: u.arbitrary()?, status: u.arbitrary()?, data1: u.arbitrary()?, data2: u.arbitrary()? }),
+            12 => Value::Color(Color { r: u.arbitrary()?, g: u.arbitrary()?, b: u.arbitrary()?, a: u.arbitrary()? }),
+            _ => Value::Array((0..len(u)?).map(|_| value(u, depth + 1)).collect::<Result<_>>()?),
+        })
+    }
+
+    fn packet(u: &mut Unstructured, depth: usize) -> Result<Packet> {
+        Ok(if depth < MAX_DEPTH && u.ratio(1, 4)? {
+            Packet::Bundle(Bundle {
+                timetag: u.arbitrary()?,
+                elements: (0..len(u)?).map(|_| packet(u, depth + 1)).collect::<Result<_>>()?,
+            })
+        } else {
+            Packet::Message(Message::arbitrary(u)?)
+        })
+    }
+
+    impl<'a> Arbitrary<'a> for Value {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            value(u, 0)
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for Message {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(Message {
+                address: address(u)?,
+                args: (0..len(u)?).map(|_| value(u, 0)).collect::<Result<_>>()?,
+            })
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for Bundle {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(Bundle {
+                timetag: u.arbitrary()?,
+                elements: (0..len(u)?).map(|_| packet(u, 1)).collect::<Result<_>>()?,
+            })
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for Packet {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            packet(u, 0)
+        }
+    }
+}
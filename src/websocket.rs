@@ -0,0 +1,120 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use serde;
+use tungstenite::{self, Message, WebSocket};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::stream::MaybeTlsStream;
+
+use de;
+use error::{Error, ResultE};
+use ser;
+
+/// Sends and receives serde-compatible values as OSC packets over a
+/// WebSocket, for browser-based control surfaces (e.g. those built with osc-js).
+///
+/// Each packet travels in a single binary WebSocket message, whose length
+/// stands in for the 4-byte length prefix used by stream transports (as with
+/// UDP datagrams); packets are therefore sent and received unframed. Pings
+/// are answered automatically while receiving.
+///
+/// ```no_run
+/// use std::net::TcpListener;
+/// use serde_osc::websocket::OscWebSocket;
+///
+/// let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
+/// let (stream, _) = listener.accept().unwrap();
+/// let mut socket = OscWebSocket::accept(stream).unwrap();
+/// while let Some((address, (value,))) = socket.recv::<(String, (f32,))>().unwrap() {
+///     socket.send(&(format!("{}/ack", address), (value,))).unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct OscWebSocket<S> {
+    socket: WebSocket<S>,
+}
+
+impl OscWebSocket<MaybeTlsStream<TcpStream>> {
+    /// Connect to the WebSocket server at `url`, e.g. "ws://127.0.0.1:8080".
+    pub fn connect<R: IntoClientRequest>(url: R) -> ResultE<Self> {
+        let (socket, _response) = tungstenite::connect(url).map_err(ws_error)?;
+        Ok(Self::from_websocket(socket))
+    }
+}
+
+impl<S: Read + Write> OscWebSocket<S> {
+    /// Perform the server side of the WebSocket handshake over `stream`
+    /// (e.g. a `TcpStream` accepted from a `TcpListener`).
+    pub fn accept(stream: S) -> ResultE<Self> {
+        let socket = tungstenite::accept(stream)
+            .map_err(|e| Error::Message(format!("WebSocket handshake: {}", e)))?;
+        Ok(Self::from_websocket(socket))
+    }
+    /// Wrap a WebSocket whose handshake is already done, e.g. one accepted
+    /// with custom headers.
+    pub fn from_websocket(socket: WebSocket<S>) -> Self {
+        Self { socket }
+    }
+    /// Access the underlying WebSocket, e.g. to query the stream's addresses.
+    pub fn websocket(&self) -> &WebSocket<S> {
+        &self.socket
+    }
+    /// Unwrap this object, returning the underlying WebSocket.
+    pub fn into_inner(self) -> WebSocket<S> {
+        self.socket
+    }
+
+    /// Serialize `value` as an OSC packet and send it as a binary message.
+    pub fn send<T>(&mut self, value: &T) -> ResultE<()>
+        where T: ?Sized + serde::ser::Serialize
+    {
+        let packet = ser::to_vec_unframed(value)?;
+        self.socket.send(Message::binary(packet)).map_err(ws_error)
+    }
+
+    /// Block until a binary message arrives, and deserialize it as an OSC
+    /// packet. Returns `None` once the peer has closed the connection.
+    ///
+    /// Text messages aren't OSC, and fail with `Error::Message`.
+    pub fn recv<T>(&mut self) -> ResultE<Option<T>>
+        where T: serde::de::DeserializeOwned
+    {
+        loop {
+            let message = match self.socket.read() {
+                Ok(message) => message,
+                Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => return Ok(None),
+                Err(e) => return Err(ws_error(e)),
+            };
+            match message {
+                Message::Binary(packet) => return de::from_slice_unframed(&packet).map(Some),
+                Message::Text(_) => return Err(Error::Message("WebSocket: expected a binary message holding an OSC packet, got text".to_owned())),
+                // Control frames, which tungstenite replies to itself.
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {},
+                // The reply to the close is sent by the next read, which
+                // then reports the connection as closed.
+                Message::Close(_) => {},
+            }
+        }
+    }
+
+    /// Start closing the connection; keep calling `recv` until it returns
+    /// `None` for the close to complete.
+    pub fn close(&mut self) -> ResultE<()> {
+        match self.socket.close(None) {
+            Ok(()) | Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => Ok(()),
+            Err(e) => Err(ws_error(e)),
+        }
+    }
+}
+
+impl<S: Read + Write> From<WebSocket<S>> for OscWebSocket<S> {
+    fn from(socket: WebSocket<S>) -> Self {
+        Self::from_websocket(socket)
+    }
+}
+
+fn ws_error(e: tungstenite::Error) -> Error {
+    match e {
+        tungstenite::Error::Io(e) => Error::Io(e),
+        e => Error::Message(format!("WebSocket: {}", e)),
+    }
+}
@@ -0,0 +1,3 @@
+mod pattern;
+mod segments;
+mod template;
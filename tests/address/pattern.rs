@@ -0,0 +1,39 @@
+use serde_osc::address::AddressPattern;
+
+#[test]
+fn literal() {
+    let pat = AddressPattern::new("/synth/1/freq");
+    assert!(pat.is_literal());
+    assert!(pat.matches("/synth/1/freq"));
+    assert!(!pat.matches("/synth/1/fre"));
+    assert!(!pat.matches("/synth/1/freq/x"));
+}
+
+#[test]
+fn wildcards() {
+    assert!(AddressPattern::new("/synth/?/freq").matches("/synth/1/freq"));
+    assert!(!AddressPattern::new("/synth/?/freq").matches("/synth/12/freq"));
+    assert!(AddressPattern::new("/synth/*").matches("/synth/"));
+    assert!(AddressPattern::new("/synth/*q").matches("/synth/freq"));
+    // '*' doesn't match across parts.
+    assert!(!AddressPattern::new("/synth/*").matches("/synth/1/freq"));
+    assert!(AddressPattern::new("/synth/[0-9a]/freq").matches("/synth/a/freq"));
+    assert!(!AddressPattern::new("/synth/[!0-9]/freq").matches("/synth/5/freq"));
+    assert!(AddressPattern::new("/synth/1/{freq,amp}").matches("/synth/1/amp"));
+    assert!(!AddressPattern::new("/synth/1/{freq,amp}").matches("/synth/1/pan"));
+    // Malformed patterns match nothing.
+    assert!(!AddressPattern::new("/synth/[1").matches("/synth/1"));
+}
+
+#[test]
+fn many_stars() {
+    use std::time::{Duration, Instant};
+    // Exponential with a backtracking matcher; the pattern comes from the network.
+    let pattern = format!("/{}b", "*a".repeat(200));
+    let address = format!("/{}", "a".repeat(1000));
+    let start = Instant::now();
+    assert!(!AddressPattern::new(pattern).matches(&address));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(AddressPattern::new("/*a*a*a*b").matches("/xaayaab"));
+    assert!(AddressPattern::new("/*{ab,b}*").matches("/aab"));
+}
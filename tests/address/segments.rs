@@ -0,0 +1,60 @@
+use serde_osc::address::{from_segments, Address, Segments};
+use serde_osc::error::Error;
+
+#[test]
+fn typed_segments() {
+    let address = Address::from("/mixer/track/12/gain");
+    assert_eq!(address.segments().collect::<Vec<_>>(), vec!["mixer", "track", "12", "gain"]);
+    assert_eq!(address.len(), 4);
+    assert_eq!(address.segment::<u8>(2).unwrap(), 12);
+    assert_eq!(address.segment::<String>(3).unwrap(), "gain");
+    assert!(matches!(address.segment::<u32>(4), Err(Error::AddressMismatch(_))));
+    assert!(matches!(address.segment::<u32>(1), Err(Error::Message(_))));
+    assert!(Address::from("/").is_empty());
+}
+
+#[test]
+fn into_tuple() {
+    let (prefix, track, param): (String, u16, String) = from_segments("/track/7/mute").unwrap();
+    assert_eq!((prefix.as_str(), track, param.as_str()), ("track", 7, "mute"));
+    let (on, level): (bool, f32) = from_segments("/true/0.25").unwrap();
+    assert_eq!((on, level), (true, 0.25));
+    // Too many, or too few, segments.
+    assert!(matches!(from_segments::<(String, u16)>("/track/7/mute"), Err(Error::AddressMismatch(_))));
+    assert!(from_segments::<(String, u16, String)>("/track/7").is_err());
+    assert!(from_segments::<(String, u16)>("/track/seven").is_err());
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Param {
+    Freq,
+    Gain,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct VoiceParam {
+    kind: String,
+    voice: u32,
+    param: Param,
+}
+
+#[test]
+fn in_message() {
+    let packet = serde_osc::to_vec(&("/voice/3/freq", (220.0f32,))).unwrap();
+    let (Segments(path), (value,)): (Segments<VoiceParam>, (f32,)) = serde_osc::from_slice(&packet).unwrap();
+    assert_eq!(path, VoiceParam { kind: "voice".to_owned(), voice: 3, param: Param::Freq });
+    assert_eq!(value, 220.0);
+
+    let packet = serde_osc::to_vec(&("/voice/3/pan", (0.0f32,))).unwrap();
+    assert!(serde_osc::from_slice::<(Segments<VoiceParam>, (f32,))>(&packet).is_err());
+}
+
+#[test]
+fn address_round_trip() {
+    let msg = (Address::from("/synth/1/freq"), (440.0f32,));
+    let packet = serde_osc::to_vec(&msg).unwrap();
+    let decoded: (Address, (f32,)) = serde_osc::from_slice(&packet).unwrap();
+    assert_eq!(decoded, msg);
+    assert_eq!(decoded.0.to_string(), "/synth/1/freq");
+}
@@ -0,0 +1,62 @@
+use serde_osc::address::AddressTemplate;
+use serde_osc::error::Error;
+use serde_osc::value::Message;
+
+#[derive(Serialize)]
+struct Channel {
+    bank: char,
+    level: f32,
+    id: u16,
+    name: String,
+}
+
+#[test]
+fn fills_params() {
+    let template = AddressTemplate::new("/mixer/{bank}/ch{id}/level");
+    assert_eq!(template.params(), vec!["bank", "id"]);
+    let msg = template.message(&Channel { bank: 'a', level: 0.5, id: 12, name: "vox".to_owned() }).unwrap();
+    assert_eq!(msg, Message::to("/mixer/a/ch12/level").arg(0.5f32).arg("vox").build());
+}
+
+#[test]
+fn matches_hand_written() {
+    #[derive(Serialize)]
+    struct Note {
+        voice: i64,
+        pitch: i32,
+        on: bool,
+    }
+    let template = AddressTemplate::new("/voice/{voice}/note");
+    let packet = template.to_vec(&Note { voice: 2, pitch: 60, on: true }).unwrap();
+    assert_eq!(packet, serde_osc::to_vec(&("/voice/2/note", (60, true))).unwrap());
+    let unframed = template.to_vec_unframed(&Note { voice: 2, pitch: 60, on: true }).unwrap();
+    assert_eq!(&unframed[..], &packet[4..]);
+}
+
+#[test]
+fn other_braces_verbatim() {
+    // Pattern alternatives aren't parameters.
+    let template = AddressTemplate::new("/{left,right}/{id}");
+    assert_eq!(template.params(), vec!["id"]);
+    #[derive(Serialize)]
+    struct Pan {
+        id: u8,
+    }
+    assert_eq!(template.message(&Pan { id: 1 }).unwrap().address, "/{left,right}/1");
+}
+
+#[test]
+fn bad_params() {
+    #[derive(Serialize)]
+    struct Blob {
+        id: Vec<u8>,
+    }
+    let missing = AddressTemplate::new("/synth/{voice}");
+    assert!(matches!(missing.message(&Channel { bank: 'a', level: 0.0, id: 1, name: String::new() }),
+        Err(Error::Message(_))));
+    // Arrays can't be written into an address.
+    let array = AddressTemplate::new("/synth/{id}");
+    assert!(matches!(array.message(&Blob { id: vec![1, 2] }), Err(Error::Message(_))));
+    // The value must be a struct, so that its fields have names.
+    assert!(matches!(array.message(&(1, 2)), Err(Error::UnsupportedType)));
+}
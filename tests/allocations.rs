@@ -0,0 +1,74 @@
+//! Checks that the allocation-free serializers live up to their name.
+//! (This is a test of its own, as it replaces the global allocator.)
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_bytes;
+extern crate serde_osc;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use serde_bytes::Bytes;
+use serde_osc::ser;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// The number of allocations made by `f`.
+fn allocations<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[derive(Serialize)]
+enum Outgoing {
+    #[serde(rename = "/pan")]
+    Pan(i32, i32),
+    #[serde(rename = "/move")]
+    Move { x: f32, y: f32 },
+}
+
+// A single test, so that nothing else allocates while it runs.
+#[test]
+fn no_allocations() {
+    let mut buf = [0u8; 256];
+    let message = ("/synth/1/freq", (440.0f32, 1, "sine", Bytes::new(b"\x01\x02\x03"), [0.5f32, 0.25]));
+    let bundle = ((0u32, 1u32), (message, Outgoing::Pan(-1, 1), (None::<(u32, u32)>, (Outgoing::Move { x: 0.5, y: 0.5 },))));
+    assert_eq!(allocations(|| { ser::to_slice(&message, &mut buf).unwrap(); }), 0);
+    assert_eq!(allocations(|| { ser::to_slice_unframed(&message, &mut buf).unwrap(); }), 0);
+    assert_eq!(allocations(|| { ser::to_slice(&Outgoing::Pan(1, 2), &mut buf).unwrap(); }), 0);
+    assert_eq!(allocations(|| { ser::to_slice(&bundle, &mut buf).unwrap(); }), 0);
+    // Once the buffer has grown to fit, reusing it doesn't allocate either.
+    let mut reused = Vec::new();
+    ser::to_vec_reuse(&mut reused, &bundle).unwrap();
+    assert_eq!(allocations(|| { ser::to_vec_reuse(&mut reused, &bundle).unwrap(); }), 0);
+    assert_eq!(allocations(|| { ser::to_vec_reuse_unframed(&mut reused, &message).unwrap(); }), 0);
+    // Nor does computing the size of a packet, however large its blobs.
+    let blob = vec![0u8; 10_000_000];
+    let large = ("/blob", (Bytes::new(&blob), "tail"));
+    assert_eq!(allocations(|| { ser::serialized_size(&large).unwrap(); }), 0);
+    assert_eq!(allocations(|| { ser::serialized_size(&bundle).unwrap(); }), 0);
+    assert_eq!(allocations(|| { ser::serialized_size_unframed(&Outgoing::Move { x: 0.5, y: 0.5 }).unwrap(); }), 0);
+    // Whereas `to_vec` does, of course.
+    assert!(allocations(|| { ser::to_vec(&message).unwrap(); }) > 0);
+    let len = ser::to_slice(&bundle, &mut buf).unwrap();
+    assert_eq!(&buf[..len], &ser::to_vec(&bundle).unwrap()[..]);
+}
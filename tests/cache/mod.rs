@@ -0,0 +1,55 @@
+use serde_osc::cache::StateCache;
+use serde_osc::error::Error;
+use serde_osc::ser;
+use serde_osc::value::Bundle;
+
+#[test]
+fn duplicates_suppressed() {
+    let mut cache = StateCache::new();
+    assert!(cache.is_empty());
+    assert_eq!(cache.to_vec(&("/ch/1/fader", (0.5f32,))).unwrap(), Some(ser::to_vec(&("/ch/1/fader", (0.5f32,))).unwrap()));
+    assert_eq!(cache.to_vec(&("/ch/1/fader", (0.5f32,))).unwrap(), None);
+    // Another address has its own state.
+    assert!(cache.to_vec(&("/ch/2/fader", (0.5f32,))).unwrap().is_some());
+    assert!(cache.to_vec(&("/ch/1/fader", (0.75f32,))).unwrap().is_some());
+    assert_eq!(cache.to_vec(&("/ch/1/fader", (0.75f32,))).unwrap(), None);
+    // Back to an earlier value is a change too.
+    assert!(cache.to_vec(&("/ch/1/fader", (0.5f32,))).unwrap().is_some());
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn compared_by_encoding() {
+    let mut cache = StateCache::new();
+    assert!(cache.to_vec_unframed(&("/mute", (1,))).unwrap().is_some());
+    // Same number, different type.
+    assert!(cache.to_vec_unframed(&("/mute", (1.0f32,))).unwrap().is_some());
+    assert!(cache.to_vec_unframed(&("/mute", (1.0f32, "extra"))).unwrap().is_some());
+    assert!(cache.to_vec_unframed(&("/mute", ())).unwrap().is_some());
+    assert!(cache.to_vec_unframed(&("/mute", ())).unwrap().is_none());
+    assert!(cache.to_vec_unframed(&("/level", (f32::NAN,))).unwrap().is_some());
+    assert!(cache.to_vec_unframed(&("/level", (f32::NAN,))).unwrap().is_none());
+}
+
+#[test]
+fn forget_and_clear() {
+    let mut cache = StateCache::new();
+    let a = ser::to_vec_unframed(&("/a", (1,))).unwrap();
+    let b = ser::to_vec_unframed(&("/b", (1,))).unwrap();
+    assert!(cache.update(&a).unwrap());
+    assert!(cache.update(&b).unwrap());
+    assert!(!cache.update(&a).unwrap());
+    cache.forget("/a");
+    assert!(cache.update(&a).unwrap());
+    assert!(!cache.update(&b).unwrap());
+    cache.clear();
+    assert!(cache.is_empty());
+    assert!(cache.update(&b).unwrap());
+}
+
+#[test]
+fn bundles_rejected() {
+    let mut cache = StateCache::new();
+    assert!(matches!(cache.to_vec(&Bundle::new((0, 1))), Err(Error::BadFormat)));
+    assert!(cache.update(b"/a\0").is_err());
+}
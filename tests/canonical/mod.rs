@@ -0,0 +1,63 @@
+use serde_osc::canonical::{canonicalize, canonicalize_unframed, canonicalize_with};
+use serde_osc::de::{DeserializerOptions, Endianness};
+use serde_osc::framing::Framing;
+use serde_osc::ser;
+use serde_osc::types::RawArg;
+use serde_osc::value::{Bundle, Message};
+
+fn prefixed(contents: &[u8]) -> Vec<u8> {
+    let mut packet = (contents.len() as i32).to_be_bytes().to_vec();
+    packet.extend_from_slice(contents);
+    packet
+}
+
+#[test]
+fn already_canonical() {
+    let msg = Message::to("/synth").arg(1).arg("sine").arg(vec![1u8, 2, 3]).arg(true).arg(false)
+        .begin_array().arg(0.5f32).end_array()
+        .build();
+    let packet = ser::to_vec(&msg).unwrap();
+    assert_eq!(canonicalize(&packet).unwrap(), packet);
+    let unframed = ser::to_vec_unframed(&msg).unwrap();
+    assert_eq!(canonicalize_unframed(&unframed).unwrap(), unframed);
+}
+
+#[test]
+fn typetag_comma_added() {
+    let packet = prefixed(b"/go\0sT\0\0abc\0");
+    assert_eq!(canonicalize(&packet).unwrap(), ser::to_vec(&("/go", ("abc", true))).unwrap());
+}
+
+#[test]
+fn missing_typetag_added() {
+    assert_eq!(canonicalize_unframed(b"/status\0").unwrap(), ser::to_vec_unframed(&Message::new("/status")).unwrap());
+}
+
+#[test]
+fn bundle_elements() {
+    let mut contents = b"#bundle\0\0\0\0\0\0\0\0\x01".to_vec();
+    contents.extend_from_slice(&prefixed(b"/a\0\0i\0\0\0\0\0\0\x07"));
+    let mut expected = Bundle::new((0, 1));
+    expected.elements.push(Message::to("/a").arg(7).build().into());
+    assert_eq!(canonicalize(&prefixed(&contents)).unwrap(), ser::to_vec(&expected).unwrap());
+}
+
+#[test]
+fn unknown_types_kept() {
+    let msg = Message::to("/at").arg(RawArg::new('t', vec![0, 0, 0, 1, 0, 0, 0, 2])).build();
+    let packet = ser::to_vec(&msg).unwrap();
+    assert_eq!(canonicalize(&packet).unwrap(), packet);
+}
+
+#[test]
+fn with_options() {
+    let options = DeserializerOptions { endianness: Endianness::Little, ..DeserializerOptions::default() };
+    let packet = canonicalize_with(b"/n\0\0,i\0\0\x05\0\0\0", Framing::Unframed, options).unwrap();
+    assert_eq!(packet, ser::to_vec_unframed(&("/n", (5,))).unwrap());
+}
+
+#[test]
+fn malformed() {
+    assert!(canonicalize(&prefixed(b"/a\0\0,i\0\0\0\0")).is_err());
+    assert!(canonicalize_unframed(b"/a\0\0,s\0\0x\0\0\x01").is_err());
+}
@@ -0,0 +1 @@
+mod osc_codec;
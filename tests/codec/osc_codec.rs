@@ -0,0 +1,140 @@
+use bytes::BytesMut;
+use serde_osc::codec::{CodecFraming, OscCodec};
+use serde_osc::de::DeserializerOptions;
+use serde_osc::error::Error;
+use serde_osc::framing::slip;
+use serde_osc::ser::{self, SerializerOptions};
+use tokio_util::codec::{Decoder, Encoder};
+
+type Msg = (String, (i32,));
+
+fn msg(address: &str, arg: i32) -> Msg {
+    (address.to_owned(), (arg,))
+}
+
+#[test]
+fn length_prefixed() {
+    let mut codec = OscCodec::<Msg>::new();
+    let mut buf = BytesMut::new();
+    codec.encode(("/a", (1,)), &mut buf).unwrap();
+    codec.encode(&msg("/b", 2), &mut buf).unwrap();
+    let expected = [ser::to_vec(&("/a", (1,))).unwrap(), ser::to_vec(&("/b", (2,))).unwrap()].concat();
+    assert_eq!(&buf[..], &expected[..]);
+
+    // Feed the data a byte at a time.
+    let mut input = BytesMut::new();
+    let mut decoded = Vec::new();
+    for &byte in &expected {
+        input.extend_from_slice(&[byte]);
+        if let Some(msg) = codec.decode(&mut input).unwrap() {
+            decoded.push(msg);
+        }
+    }
+    assert_eq!(decoded, vec![msg("/a", 1), msg("/b", 2)]);
+    assert!(input.is_empty());
+}
+
+#[test]
+fn slip() {
+    let mut codec = OscCodec::<(String, (i32, f32))>::slip();
+    assert_eq!(codec.framing(), CodecFraming::Slip);
+    let mut buf = BytesMut::new();
+    // 0xC0 and 0xDB in the i32 must be escaped.
+    codec.encode(("/m1", (0xC0DBi32, 440.0f32)), &mut buf).unwrap();
+    let mut expected = Vec::new();
+    slip::encode(&ser::to_vec_unframed(&("/m1", (0xC0DBi32, 440.0f32))).unwrap(), &mut expected);
+    assert_eq!(&buf[..], &expected[..]);
+
+    // A second frame arrives partially.
+    codec.encode(("/m2", (-1, 0.0f32)), &mut buf).unwrap();
+    let mut rest = buf.split_off(buf.len() - 3);
+    let (address, args) = codec.decode(&mut buf).unwrap().unwrap();
+    assert_eq!((address.as_str(), args), ("/m1", (0xC0DB, 440.0)));
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+    buf.unsplit(rest.split());
+    let (address, args) = codec.decode(&mut buf).unwrap().unwrap();
+    assert_eq!((address.as_str(), args), ("/m2", (-1, 0.0)));
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+}
+
+#[test]
+fn slip_without_leading_end() {
+    // RFC 1055 senders only terminate each frame.
+    let packet = ser::to_vec_unframed(&("/a", (1,))).unwrap();
+    let mut buf = BytesMut::from(&[&packet[..], &[slip::END]].concat()[..]);
+    let mut codec = OscCodec::<Msg>::slip();
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(msg("/a", 1)));
+}
+
+#[test]
+fn slip_bad_escape() {
+    let mut codec = OscCodec::<Msg>::slip();
+    let mut buf = BytesMut::from(&b"\xC0/a\0\0\xDB\x01\xC0"[..]);
+    match codec.decode(&mut buf) {
+        Err(Error::BadFormat) => {},
+        other => panic!("expected BadFormat, got {:?}", other),
+    }
+}
+
+#[test]
+fn slip_bad_escape_keeps_later_frames() {
+    let mut codec = OscCodec::<Msg>::slip();
+    let mut buf = BytesMut::from(&b"\xC0/a\0\0\xDB\x01\xC0"[..]);
+    codec.encode(("/b", (2,)), &mut buf).unwrap();
+    codec.encode(("/c", (3,)), &mut buf).unwrap();
+    assert!(matches!(codec.decode(&mut buf), Err(Error::BadFormat)));
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(msg("/b", 2)));
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(msg("/c", 3)));
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+}
+
+#[test]
+fn unframed() {
+    let mut codec = OscCodec::<Msg>::unframed();
+    let mut buf = BytesMut::new();
+    codec.encode(("/a", (1,)), &mut buf).unwrap();
+    assert_eq!(&buf[..], &ser::to_vec_unframed(&("/a", (1,))).unwrap()[..]);
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(msg("/a", 1)));
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+}
+
+#[test]
+fn bad_packet_is_skipped() {
+    let mut codec = OscCodec::<Msg>::new();
+    let mut buf = BytesMut::new();
+    codec.encode(("/a", ("x",)), &mut buf).unwrap();
+    codec.encode(("/b", (2,)), &mut buf).unwrap();
+    assert!(codec.decode(&mut buf).is_err());
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(msg("/b", 2)));
+}
+
+#[test]
+fn size_limit() {
+    let options = DeserializerOptions {
+        max_packet_size: Some(16),
+        ..Default::default()
+    };
+    let mut codec = OscCodec::<Msg>::with_options(CodecFraming::LengthPrefixed, options, SerializerOptions::default());
+    let mut buf = BytesMut::from(&b"\0\0\x10\0/a\0\0"[..]);
+    match codec.decode(&mut buf) {
+        Err(Error::SizeLimitExceeded) => {},
+        other => panic!("expected SizeLimitExceeded, got {:?}", other),
+    }
+    // The unusable data is discarded.
+    assert!(buf.is_empty());
+
+    // A SLIP frame can't be bounded until it ends.
+    let mut codec = OscCodec::<Msg>::with_options(CodecFraming::Slip, options, SerializerOptions::default());
+    let mut buf = BytesMut::from(&[b'/'; 40][..]);
+    assert!(codec.decode(&mut buf).is_err());
+}
+
+#[test]
+fn failed_encode_leaves_no_output() {
+    let mut codec = OscCodec::<Msg>::new();
+    let mut buf = BytesMut::new();
+    codec.encode(("/a", (1,)), &mut buf).unwrap();
+    let len = buf.len();
+    assert!(codec.encode(("bad address", (1,)), &mut buf).is_err());
+    assert_eq!(buf.len(), len);
+}
@@ -0,0 +1,53 @@
+use serde_osc::de::{self, DeserializerOptions, TrailingArgs};
+use serde_osc::ser;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Fader {
+    level: f32,
+    #[serde(default)]
+    channel: i32,
+    #[serde(default = "default_curve")]
+    curve: String,
+}
+
+fn default_curve() -> String {
+    "linear".to_owned()
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Msg {
+    #[serde(rename = "/fader")]
+    Fader { level: f32, #[serde(default)] channel: i32 },
+}
+
+#[test]
+fn defaults_fill_missing_args() {
+    let packet = ser::to_vec(&("/fader", (0.5f32,))).unwrap();
+    let (_, fader): (String, Fader) = de::from_slice(&packet).unwrap();
+    assert_eq!(fader, Fader { level: 0.5, channel: 0, curve: "linear".to_owned() });
+
+    let packet = ser::to_vec(&("/fader", (0.5f32, 3))).unwrap();
+    let (_, fader): (String, Fader) = de::from_slice(&packet).unwrap();
+    assert_eq!(fader, Fader { level: 0.5, channel: 3, curve: "linear".to_owned() });
+
+    assert_eq!(de::from_slice::<Msg>(&packet).unwrap(), Msg::Fader { level: 0.5, channel: 3 });
+    let packet = ser::to_vec(&("/fader", (0.5f32,))).unwrap();
+    assert_eq!(de::from_slice::<Msg>(&packet).unwrap(), Msg::Fader { level: 0.5, channel: 0 });
+}
+
+#[test]
+fn required_args_still_missing() {
+    let packet = ser::to_vec(&("/fader", ())).unwrap();
+    assert!(de::from_slice::<(String, Fader)>(&packet).is_err());
+}
+
+#[test]
+fn defaults_leave_nothing_trailing() {
+    let options = DeserializerOptions {
+        trailing_args: TrailingArgs::Reject,
+        ..DeserializerOptions::default()
+    };
+    let packet = ser::to_vec(&("/fader", (0.5f32,))).unwrap();
+    let (_, fader): (String, Fader) = de::from_slice_with_options(&packet, options).unwrap();
+    assert_eq!(fader.channel, 0);
+}
@@ -0,0 +1,56 @@
+use serde_osc::{de, ser};
+
+type Args = (i32, Vec<f32>, ((i32, String), Vec<i32>));
+
+#[test]
+fn array_de() {
+    let test_input = b"\x00\x00\x00\x2C/arr\0\0\0\0,i[ff][[is][]]\0\0\0\0\0\x01\x40\0\0\0\x40\x40\0\0\0\0\0\x04x\0\0\0";
+    let expected = ("/arr".to_owned(), (1, vec![2.0, 3.0], ((4, "x".to_owned()), Vec::new())));
+    let deserialized: (String, Args) = de::from_slice(test_input).unwrap();
+    assert_eq!(deserialized, expected);
+}
+
+#[test]
+fn array_unterminated() {
+    let test_input = b"\x00\x00\x00\x0C/arr\0\0\0\0,[i\0";
+    assert!(de::from_slice::<(String, (Vec<i32>,))>(test_input).is_err());
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Mixer {
+    levels: [f32; 2],
+    #[serde(with = "serde_osc::array")]
+    mutes: [i32; 3],
+}
+
+#[test]
+fn fixed_size_arrays() {
+    let test_input = b"\0\0\0\x28/mix\0\0\0\0,[ff][iii]\0\0\x3f\x80\0\0\x3f\0\0\0\0\0\0\0\0\0\0\x01\0\0\0\0";
+    let (_, mixer): (String, Mixer) = de::from_slice(test_input).unwrap();
+    assert_eq!(mixer, Mixer { levels: [1.0, 0.5], mutes: [0, 1, 0] });
+}
+
+#[test]
+fn long_arrays() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Matrix {
+        #[serde(with = "serde_osc::array")]
+        gains: [f32; 64],
+    }
+    let mut gains = [0.0; 64];
+    for (i, gain) in gains.iter_mut().enumerate() {
+        *gain = i as f32;
+    }
+    let packet = ser::to_vec(&("/matrix", Matrix { gains })).unwrap();
+    let (_, matrix): (String, Matrix) = de::from_slice(&packet).unwrap();
+    assert_eq!(&matrix.gains[..], &gains[..]);
+}
+
+#[test]
+fn fixed_size_array_length_mismatch() {
+    // Two and four elements where `mutes` expects three.
+    let short = ser::to_vec(&("/mix", ([1.0f32, 0.5], [0, 1]))).unwrap();
+    let long = ser::to_vec(&("/mix", ([1.0f32, 0.5], [0, 1, 0, 1]))).unwrap();
+    assert!(de::from_slice::<(String, Mixer)>(&short).is_err());
+    assert!(de::from_slice::<(String, Mixer)>(&long).is_err());
+}
@@ -0,0 +1,48 @@
+use serde_osc::{de, ser};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sample {
+    address: String,
+    args: SampleArgs,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct SampleArgs {
+    rate: i32,
+    #[serde(with = "serde_bytes")]
+    frames: Vec<u8>,
+}
+
+#[test]
+fn serde_bytes_field() {
+    let sample = Sample {
+        address: "/sample".to_owned(),
+        args: SampleArgs { rate: 44100, frames: vec![1, 2, 3, 4, 5] },
+    };
+    let packet = ser::to_vec(&sample).unwrap();
+    // Encoded as a single 'b' argument, rather than an array of integers.
+    assert_eq!(&packet[12..16], b",ib\0");
+    assert_eq!(de::from_slice::<Sample>(&packet).unwrap(), sample);
+}
+
+#[test]
+fn large_blob() {
+    let frames: Vec<u8> = (0..1_000_003u32).map(|i| i as u8).collect();
+    let sample = Sample {
+        address: "/sample".to_owned(),
+        args: SampleArgs { rate: 48000, frames },
+    };
+    let packet = ser::to_vec(&sample).unwrap();
+    assert_eq!(packet.len(), 4 + 8 + 4 + 4 + 4 + 1_000_004);
+    assert_eq!(de::from_slice::<Sample>(&packet).unwrap(), sample);
+}
+
+#[test]
+fn blob_as_byte_seq() {
+    // /b ,b [1, 2, 3]
+    let test_input = b"\0\0\0\x10/b\0\0,b\0\0\0\0\0\x03\x01\x02\x03\0";
+    let (_, (bytes,)): (String, (Vec<u8>,)) = de::from_slice(test_input).unwrap();
+    assert_eq!(bytes, vec![1, 2, 3]);
+    let (_, (bytes,)): (String, ([u8; 3],)) = de::from_slice(test_input).unwrap();
+    assert_eq!(bytes, [1, 2, 3]);
+}
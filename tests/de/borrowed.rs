@@ -0,0 +1,66 @@
+use serde_osc::de;
+use serde_osc::error::Error;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Frame<'a> {
+    address: String,
+    #[serde(borrow)]
+    args: FrameArgs<'a>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct FrameArgs<'a> {
+    channel: i32,
+    data: &'a [u8],
+}
+
+/// Whether `inner` lies within `outer`, i.e. was borrowed rather than copied.
+fn within(inner: &[u8], outer: &[u8]) -> bool {
+    let range = outer.as_ptr_range();
+    range.start <= inner.as_ptr() && inner.as_ptr() < range.end
+}
+
+#[test]
+fn borrowed_blob_field() {
+    // /b ,ib 7 [1, 2, 3]
+    let test_input = b"\0\0\0\x14/b\0\0,ib\0\0\0\0\x07\0\0\0\x03\x01\x02\x03\0";
+    let frame: Frame = de::from_slice(test_input).unwrap();
+    assert_eq!(frame, Frame {
+        address: "/b".to_owned(),
+        args: FrameArgs { channel: 7, data: &[1, 2, 3] },
+    });
+    assert!(within(frame.args.data, test_input));
+}
+
+#[test]
+fn borrowed_blob_unframed() {
+    let test_input = b"/b\0\0,b\0\0\0\0\0\x03\x01\x02\x03\0";
+    let (_, (data,)): (String, (&[u8],)) = de::from_slice_unframed(test_input).unwrap();
+    assert_eq!(data, &[1, 2, 3]);
+    assert!(within(data, test_input));
+}
+
+#[test]
+fn borrowed_blob_in_bundle() {
+    // #bundle (0, 1) [/b ,b [9, 8]]
+    let test_input = b"\0\0\0$#bundle\0\0\0\0\0\0\0\0\x01\0\0\0\x10/b\0\0,b\0\0\0\0\0\x02\x09\x08\0\0";
+    type Elements<'a> = ((String, (&'a [u8],)),);
+    let (_, ((_, (data,)),)): ((u32, u32), Elements) = de::from_slice(test_input).unwrap();
+    assert_eq!(data, &[9, 8]);
+    assert!(within(data, test_input));
+}
+
+#[test]
+fn borrowed_blob_bad_padding() {
+    let test_input = b"/b\0\0,b\0\0\0\0\0\x03\x01\x02\x03\x04";
+    let result: Result<(String, (&[u8],)), _> = de::from_slice_unframed(test_input);
+    assert!(matches!(result.map_err(Error::into_kind), Err(Error::BadPadding)));
+}
+
+#[test]
+fn truncated_borrowed_blob() {
+    // The blob claims more data than the packet holds.
+    let test_input = b"/b\0\0,b\0\0\0\0\0\x08\x01\x02\x03\0";
+    let result: Result<(String, (&[u8],)), _> = de::from_slice_unframed(test_input);
+    assert!(result.is_err());
+}
@@ -0,0 +1,83 @@
+use serde_osc::de::{self, BundleReader};
+use serde_osc::error::Error;
+use serde_osc::ser;
+use serde_osc::timetag::TimeTag;
+use serde_osc::value::{Bundle, Message};
+
+fn bundle() -> Bundle {
+    let mut nested = Bundle::new((7, 8));
+    nested.elements.push(Message::new("/b").into());
+    let mut bundle = Bundle::new((5, 6));
+    bundle.elements.push(Message::to("/a").arg(1).build().into());
+    bundle.elements.push(nested.into());
+    bundle.elements.push(Message::to("/c").arg("x").build().into());
+    bundle
+}
+
+#[test]
+fn elements_in_order() {
+    let packet = ser::to_vec(&bundle()).unwrap();
+    let mut rd = BundleReader::new(&packet).unwrap();
+    assert_eq!(rd.timetag(), TimeTag::At(5, 6));
+    assert_eq!(rd.len_hint(), 3);
+
+    let first = rd.next_element_bytes().unwrap();
+    assert_eq!(first, &ser::to_vec_unframed(&("/a", (1,))).unwrap()[..]);
+
+    // A nested bundle is handed out whole, and can be read the same way.
+    let mut nested = BundleReader::new_unframed(rd.next_element_bytes().unwrap()).unwrap();
+    assert_eq!(nested.timetag(), TimeTag::At(7, 8));
+    let (address, ()): (String, ()) = de::from_slice_unframed(nested.next_element_bytes().unwrap()).unwrap();
+    assert_eq!(address, "/b");
+    assert!(nested.next_element_bytes().is_none());
+
+    assert_eq!(rd.len_hint(), 1);
+    let (address, (x,)): (String, (String,)) = de::from_slice_unframed(rd.next_element_bytes().unwrap()).unwrap();
+    assert_eq!((address.as_str(), x.as_str()), ("/c", "x"));
+    assert_eq!(rd.len_hint(), 0);
+    assert!(rd.next_element_bytes().is_none());
+}
+
+#[test]
+fn iterator() {
+    let packet = ser::to_vec_unframed(&bundle()).unwrap();
+    let rd = BundleReader::new_unframed(&packet).unwrap();
+    assert_eq!(rd.len(), 3);
+    let kinds: Vec<_> = rd.map(|element| de::inspect_unframed(element).unwrap()).collect();
+    assert_eq!(kinds.len(), 3);
+    assert_eq!(kinds[0], de::PacketKind::Message { address: "/a".to_owned(), typetag: "i".to_owned() });
+}
+
+#[test]
+fn empty_bundle() {
+    let packet = ser::to_vec(&Bundle::new((0, 1))).unwrap();
+    let mut rd = BundleReader::new(&packet).unwrap();
+    assert_eq!(rd.timetag(), TimeTag::Immediate);
+    assert_eq!(rd.len_hint(), 0);
+    assert!(rd.next_element_bytes().is_none());
+}
+
+#[test]
+fn elements_not_decoded() {
+    // The element's typetag is nonsense, but only its length is read.
+    let mut packet = ser::to_vec_unframed(&((5u32, 6u32), (("/a", (1,)),))).unwrap();
+    let typetag_at = packet.len() - 8;
+    packet[typetag_at + 1] = b'?';
+    let mut rd = BundleReader::new_unframed(&packet).unwrap();
+    let element = rd.next_element_bytes().unwrap();
+    assert!(de::from_slice_unframed::<(String, (i32,))>(element).is_err());
+}
+
+#[test]
+fn invalid() {
+    let message = ser::to_vec(&("/a", (1,))).unwrap();
+    assert!(matches!(BundleReader::new(&message), Err(Error::BadFormat)));
+
+    let packet = ser::to_vec(&bundle()).unwrap();
+    // Truncated packet
+    assert!(BundleReader::new(&packet[..packet.len() - 4]).is_err());
+    // Element longer than the bundle
+    let mut unframed = ser::to_vec_unframed(&bundle()).unwrap();
+    unframed.truncate(unframed.len() - 4);
+    assert!(BundleReader::new_unframed(&unframed).is_err());
+}
@@ -0,0 +1,51 @@
+use serde_osc::de;
+use serde_osc::error::Error;
+use serde_osc::types::UnicodeChar;
+use serde_osc::value::{Message, Packet, Value};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct KeyPress {
+    key: char,
+    down: bool,
+}
+
+#[test]
+fn char_arg() {
+    // /key ,cT 'q'
+    let test_input = b"\x00\x00\x00\x10/key\0\0\0\0,cT\0\x00\x00\x00\x71";
+    let (address, press): (String, KeyPress) = de::from_slice(test_input).unwrap();
+    assert_eq!(address, "/key");
+    assert_eq!(press, KeyPress { key: 'q', down: true });
+}
+
+#[test]
+fn non_ascii_code_point() {
+    // Decoded as it is, whether as a `char` or a `UnicodeChar`.
+    let test_input = b"\x00\x00\x00\x10/key\0\0\0\0,c\0\0\x00\x00\x00\xE9";
+    let (_, (c,)): (String, (char,)) = de::from_slice(test_input).unwrap();
+    assert_eq!(c, 'é');
+    let (_, (c,)): (String, (UnicodeChar,)) = de::from_slice(test_input).unwrap();
+    assert_eq!(c, UnicodeChar('é'));
+    // A value forwards it unchanged.
+    let packet = Packet::from_slice(test_input).unwrap();
+    assert_eq!(packet, Packet::Message(Message::new("/key").with_arg(Value::Char('é'))));
+    assert_eq!(serde_osc::to_vec(&packet).unwrap(), &test_input[..]);
+}
+
+#[test]
+fn invalid_code_point() {
+    // A surrogate isn't a `char`.
+    let test_input = b"\x00\x00\x00\x10/key\0\0\0\0,c\0\0\x00\x00\xD8\x00";
+    match de::from_slice::<(String, (char,))>(test_input).map_err(Error::into_kind) {
+        Err(Error::BadFormat) => {},
+        other => panic!("expected BadFormat, got {:?}", other),
+    }
+}
+
+#[test]
+fn text_form() {
+    let msg = Message::new("/key").with_arg('"').with_arg('q');
+    assert_eq!(msg.to_string(), r#"/key cc "\"" "q""#);
+    let packet: Packet = r#"/key cc "\"" q"#.parse().unwrap();
+    assert_eq!(packet, Packet::Message(msg));
+}
@@ -0,0 +1,70 @@
+use serde_osc::de::{self, Coercion, DeserializerOptions};
+use serde_osc::error::Error;
+use serde_osc::value::{Message, Packet, Value};
+
+fn lossless() -> DeserializerOptions {
+    DeserializerOptions {
+        coercion: Coercion::Lossless,
+        ..DeserializerOptions::default()
+    }
+}
+
+fn mismatch(err: Error) -> (char, char) {
+    match err.into_kind() {
+        Error::TypeMismatch { expected, found } => (expected, found),
+        other => panic!("expected a type mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn strict_by_default() {
+    // /n ,ifhd 3 2.0 5 -4.0
+    let test_input = b"\0\0\0$/n\0\0,ifhd\0\0\0\0\0\0\x03@\0\0\0\0\0\0\0\0\0\0\x05\xC0\x10\0\0\0\0\0\0";
+    let err = de::from_slice::<(String, (f32, i32, f64, i64))>(&test_input[..]).unwrap_err();
+    assert_eq!(mismatch(err), ('f', 'i'));
+    // Integers fit any wide enough integer type, and 'f' fits an f64, without coercion.
+    let (_, args): (String, (i64, f64, i64, f64)) = de::from_slice(&test_input[..]).unwrap();
+    assert_eq!(args, (3, 2.0, 5, -4.0));
+}
+
+#[test]
+fn lossless_coercion() {
+    let test_input = b"\0\0\0$/n\0\0,ifhd\0\0\0\0\0\0\x03@\0\0\0\0\0\0\0\0\0\0\x05\xC0\x10\0\0\0\0\0\0";
+    let (_, args): (String, (f32, i32, f64, i64)) =
+        de::from_slice_with_options(&test_input[..], lossless()).unwrap();
+    assert_eq!(args, (3.0, 2, 5.0, -4));
+    let (_, args): (String, (f64, u8, f32, i32)) =
+        de::from_slice_with_options(&test_input[..], lossless()).unwrap();
+    assert_eq!(args, (3.0, 2, 5.0, -4));
+}
+
+#[test]
+fn inexact_values() {
+    // /n ,fi 2.5 16777217 (which an f32 can't represent)
+    let test_input = b"\0\0\0\x10/n\0\0,fi\0@ \0\0\x01\0\0\x01";
+    let err = de::from_slice_with_options::<(String, (i32, i32))>(&test_input[..], lossless()).unwrap_err();
+    assert_eq!(mismatch(err), ('i', 'f'));
+    let err = de::from_slice_with_options::<(String, (f32, f32))>(&test_input[..], lossless()).unwrap_err();
+    assert_eq!(mismatch(err), ('f', 'i'));
+    // An f64 holds it exactly, though.
+    let (_, (_, big)): (String, (f32, f64)) =
+        de::from_slice_with_options(&test_input[..], lossless()).unwrap();
+    assert_eq!(big, 16777217.0);
+}
+
+#[test]
+fn coercion_within_arrays() {
+    let msg = Message::new("/a").with_arg(vec![Value::Float(1.0), Value::Int(2)]);
+    let packet = serde_osc::to_vec(&Packet::from(msg)).unwrap();
+    let (_, (arr,)): (String, (Vec<i32>,)) = de::from_slice_with_options(&packet, lossless()).unwrap();
+    assert_eq!(arr, vec![1, 2]);
+}
+
+#[test]
+fn double_args() {
+    let msg = Message::new("/d").with_arg(0.25f64).with_arg(-1i64);
+    let packet = serde_osc::to_vec(&Packet::from(msg.clone())).unwrap();
+    assert_eq!(Packet::from_slice(&packet).unwrap(), Packet::from(msg));
+    let (_, args): (String, (f64, i64)) = de::from_slice(&packet).unwrap();
+    assert_eq!(args, (0.25, -1));
+}
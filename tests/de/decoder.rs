@@ -0,0 +1,109 @@
+use serde_osc::{de, ser};
+use serde_osc::de::{Decoder, DeserializerOptions};
+use serde_osc::error::Error;
+
+type Msg = (String, (i32,));
+
+fn msg(address: &str, arg: i32) -> Vec<u8> {
+    ser::to_vec(&(address, (arg,))).unwrap()
+}
+
+#[test]
+fn byte_at_a_time() {
+    let packet = msg("/a", 1);
+    let mut decoder = Decoder::<Msg>::new();
+    for &byte in &packet[..packet.len() - 1] {
+        assert!(decoder.feed(&[byte]).unwrap().is_none());
+    }
+    assert_eq!(decoder.buffered(), packet.len() - 1);
+    let decoded = decoder.feed(&packet[packet.len() - 1..]).unwrap();
+    assert_eq!(decoded, Some(("/a".to_owned(), (1,))));
+    assert_eq!(decoder.buffered(), 0);
+}
+
+#[test]
+fn several_packets_per_read() {
+    let data = [msg("/a", 1), msg("/b", 2), msg("/c", 3)].concat();
+    let mut decoder = Decoder::<Msg>::new();
+    // Everything but the last byte arrives at once.
+    let first = decoder.feed(&data[..data.len() - 1]).unwrap().unwrap();
+    let second = decoder.decode().unwrap().unwrap();
+    assert_eq!((first.0.as_str(), second.0.as_str()), ("/a", "/b"));
+    assert!(decoder.decode().unwrap().is_none());
+    let third = decoder.feed(&data[data.len() - 1..]).unwrap().unwrap();
+    assert_eq!(third, ("/c".to_owned(), (3,)));
+}
+
+#[test]
+fn bundles() {
+    let data = ser::to_vec(&((0u32, 1u32), (("/a", (1,)), ("/b", (2,))))).unwrap();
+    let mut decoder = Decoder::<((u32, u32), (Msg, Msg))>::new();
+    assert!(decoder.feed(&data[..10]).unwrap().is_none());
+    let (timetag, (a, b)) = decoder.feed(&data[10..]).unwrap().unwrap();
+    assert_eq!((timetag, a.0.as_str(), b.0.as_str()), ((0, 1), "/a", "/b"));
+}
+
+#[test]
+fn bad_packet_is_skipped() {
+    // The first packet has an argument of the wrong type.
+    let data = [ser::to_vec(&("/a", ("x",))).unwrap(), msg("/b", 2)].concat();
+    let mut decoder = Decoder::<Msg>::new();
+    assert!(decoder.feed(&data).is_err());
+    assert_eq!(decoder.decode().unwrap(), Some(("/b".to_owned(), (2,))));
+}
+
+#[test]
+fn bad_length_prefix() {
+    let mut decoder = Decoder::<Msg>::new();
+    assert!(decoder.feed(b"\xff\xff\xff\xf0/a\0\0").is_err());
+    // The unusable data is discarded.
+    assert_eq!(decoder.buffered(), 0);
+    assert_eq!(decoder.feed(&msg("/b", 2)).unwrap(), Some(("/b".to_owned(), (2,))));
+
+    let options = DeserializerOptions { max_packet_size: Some(64), ..DeserializerOptions::default() };
+    let mut decoder = Decoder::<Msg>::with_options(options);
+    match decoder.feed(b"\0\x10\0\0").map_err(Error::into_kind) {
+        Err(Error::SizeLimitExceeded) => {},
+        other => panic!("expected SizeLimitExceeded, got {:?}", other),
+    }
+}
+
+#[test]
+fn matches_from_slice() {
+    let packet = msg("/a", 7);
+    let mut decoder = Decoder::<Msg>::new();
+    assert_eq!(decoder.feed(&packet).unwrap().unwrap(), de::from_slice::<Msg>(&packet).unwrap());
+}
+
+#[test]
+fn resync_after_corrupt_prefix() {
+    // The first packet lost its first two bytes.
+    let data = [&msg("/a", 1)[2..], &msg("/b", 2), &msg("/c", 3)].concat();
+    let mut decoder = Decoder::<Msg>::new().resynchronizing();
+    assert!(decoder.feed(&data).is_err());
+    assert_eq!(decoder.decode().unwrap(), Some(("/b".to_owned(), (2,))));
+    assert_eq!(decoder.decode().unwrap(), Some(("/c".to_owned(), (3,))));
+    assert_eq!(decoder.buffered(), 0);
+}
+
+#[test]
+fn resync_keeps_partial_packet() {
+    let packet = msg("/b", 2);
+    let mut decoder = Decoder::<Msg>::new().resynchronizing();
+    let mut data = b"\xff\xff\xff\xff".to_vec();
+    data.extend_from_slice(&packet[..6]);
+    assert!(decoder.feed(&data).is_err());
+    // The start of the next packet is kept until the rest arrives.
+    assert_eq!(decoder.buffered(), 6);
+    assert_eq!(decoder.feed(&packet[6..]).unwrap(), Some(("/b".to_owned(), (2,))));
+}
+
+#[test]
+fn manual_resync() {
+    let mut decoder = Decoder::<Msg>::new();
+    let data = [&b"junk"[..], &msg("/b", 2)].concat();
+    // Without a size limit, the "length" is waited for; skip it explicitly.
+    assert_eq!(decoder.feed(&data).unwrap(), None);
+    assert_eq!(decoder.resync(), 4);
+    assert_eq!(decoder.decode().unwrap(), Some(("/b".to_owned(), (2,))));
+}
@@ -0,0 +1,36 @@
+use serde_osc::de::{self, DeserializerOptions, Endianness};
+use serde_osc::framing::Framing;
+use serde_osc::value::{Message, Packet};
+
+fn little() -> DeserializerOptions {
+    DeserializerOptions { endianness: Endianness::Little, ..DeserializerOptions::default() }
+}
+
+// /m ,ihfdc 1 -2 1.5 0.25 'A', with each number least significant byte first.
+const LITTLE: &[u8] = b"\x00\x00\x00\x28/m\0\0,ihfdc\0\0\
+    \x01\x00\x00\x00\
+    \xFE\xFF\xFF\xFF\xFF\xFF\xFF\xFF\
+    \x00\x00\xC0\x3F\
+    \x00\x00\x00\x00\x00\x00\xD0\x3F\
+    \x41\x00\x00\x00";
+
+#[test]
+fn little_endian_args() {
+    let (address, args): (String, (i32, i64, f32, f64, char)) = de::from_slice_with_options(LITTLE, little()).unwrap();
+    assert_eq!(address, "/m");
+    assert_eq!(args, (1, -2, 1.5, 0.25, 'A'));
+    let packet = Packet::from_slice_with(LITTLE, Framing::LengthPrefixed, little()).unwrap();
+    let expected = Message::to("/m").arg(1).arg(-2i64).arg(1.5f32).arg(0.25f64).arg('A').build();
+    assert_eq!(packet, Packet::Message(expected));
+}
+
+#[test]
+fn lengths_stay_big_endian() {
+    // The blob's length, like the packet's, is big-endian either way.
+    let test_input = b"\x00\x00\x00\x14/m\0\0,ib\0\x02\x00\x00\x00\x00\x00\x00\x02\xAB\xCD\0\0";
+    let (_, (i, blob)): (String, (i32, serde_bytes::ByteBuf)) = de::from_slice_with_options(test_input, little()).unwrap();
+    assert_eq!((i, Vec::from(blob)), (2, vec![0xAB, 0xCD]));
+    // The default reads the same data as big-endian.
+    let (_, (i, _)): (String, (i32, serde_bytes::ByteBuf)) = de::from_slice(test_input).unwrap();
+    assert_eq!(i, 0x02000000);
+}
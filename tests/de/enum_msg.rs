@@ -0,0 +1,40 @@
+use serde_osc::de;
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Incoming {
+    #[serde(rename = "/play")]
+    Play(f32),
+    #[serde(rename = "/stop")]
+    Stop,
+    #[serde(rename = "/pan")]
+    Pan(i32, i32),
+    #[serde(rename = "/move")]
+    Move { x: f32, y: f32 },
+}
+
+#[test]
+fn variant_by_address() {
+    let play = b"\x00\x00\x00\x10/play\0\0\0,f\0\0\x3F\x00\x00\x00";
+    let stop = b"\x00\x00\x00\x0C/stop\0\0\0,\0\0\0";
+    let pan = b"\x00\x00\x00\x14/pan\0\0\0\0,ii\0\x00\x00\x00\x01\x00\x00\x00\x02";
+    let mv = b"\x00\x00\x00\x14/move\0\0\0,ff\0\x3F\x80\x00\x00\x40\x00\x00\x00";
+    assert_eq!(de::from_slice::<Incoming>(play).unwrap(), Incoming::Play(0.5));
+    assert_eq!(de::from_slice::<Incoming>(stop).unwrap(), Incoming::Stop);
+    assert_eq!(de::from_slice::<Incoming>(pan).unwrap(), Incoming::Pan(1, 2));
+    assert_eq!(de::from_slice::<Incoming>(mv).unwrap(), Incoming::Move { x: 1.0, y: 2.0 });
+}
+
+#[test]
+fn unknown_address() {
+    let test_input = b"\x00\x00\x00\x0C/seek\0\0\0,\0\0\0";
+    assert!(de::from_slice::<Incoming>(test_input).is_err());
+}
+
+#[test]
+fn newtype_arg_count() {
+    // `Play` takes exactly one argument.
+    let none = b"\x00\x00\x00\x0C/play\0\0\0,\0\0\0";
+    let two = b"\x00\x00\x00\x14/play\0\0\0,ff\0\x3F\x00\x00\x00\x3F\x00\x00\x00";
+    assert!(de::from_slice::<Incoming>(none).is_err());
+    assert!(de::from_slice::<Incoming>(two).is_err());
+}
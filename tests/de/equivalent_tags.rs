@@ -0,0 +1,65 @@
+use serde_osc::{de, ser};
+use serde_osc::error::Error;
+use serde_osc::types::{Color, MidiMessage, Symbol, UnicodeChar};
+use serde_osc::value::Message;
+
+fn encode(msg: Message) -> Vec<u8> {
+    ser::to_vec(&msg).unwrap()
+}
+
+#[test]
+fn strings_accept_symbols() {
+    let packet = encode(Message::to("/a").arg("str").arg(Symbol::from("sym")).build());
+    let (_, args): (String, (String, String)) = de::from_slice(&packet).unwrap();
+    assert_eq!(args, ("str".to_owned(), "sym".to_owned()));
+    let (_, args): (String, (Symbol, Symbol)) = de::from_slice(&packet).unwrap();
+    assert_eq!(args, (Symbol::from("str"), Symbol::from("sym")));
+}
+
+#[test]
+fn longs_accept_ints() {
+    let packet = encode(Message::to("/a").arg(-3).arg(1i64 << 40).build());
+    let (_, args): (String, (i64, i64)) = de::from_slice(&packet).unwrap();
+    assert_eq!(args, (-3, 1 << 40));
+    let (_, args): (String, (u64, u64)) = de::from_slice(&encode(Message::to("/a").arg(3).arg(4i64).build())).unwrap();
+    assert_eq!(args, (3, 4));
+}
+
+#[test]
+fn doubles_accept_floats() {
+    let packet = encode(Message::to("/a").arg(0.5f32).arg(0.25f64).build());
+    let (_, args): (String, (f64, f64)) = de::from_slice(&packet).unwrap();
+    assert_eq!(args, (0.5, 0.25));
+}
+
+#[test]
+fn within_arrays() {
+    let packet = encode(Message::to("/a").begin_array().arg(1).arg(2i64).end_array()
+        .begin_array().arg("x").arg(Symbol::from("y")).end_array().build());
+    let (_, args): (String, (Vec<i64>, Vec<String>)) = de::from_slice(&packet).unwrap();
+    assert_eq!(args, (vec![1, 2], vec!["x".to_owned(), "y".to_owned()]));
+}
+
+#[test]
+fn narrower_types_still_checked() {
+    // A 'd' argument needs narrowing to become an `f32`.
+    let packet = encode(Message::to("/a").arg(0.5f64).build());
+    assert!(matches!(de::from_slice::<(String, (f32,))>(&packet).unwrap_err().kind(),
+        Error::TypeMismatch { expected: 'f', found: 'd' }));
+    // Strings and numbers aren't interchangeable.
+    let packet = encode(Message::to("/a").arg(Symbol::from("1")).build());
+    assert!(matches!(de::from_slice::<(String, (i64,))>(&packet).unwrap_err().kind(),
+        Error::TypeMismatch { expected: 'h', found: 'S' }));
+}
+
+#[test]
+fn helper_types_checked() {
+    // Each helper type accepts only its own tags, with no coercion between them.
+    let packet = encode(Message::to("/a").arg(MidiMessage::new(0, 0x90, 60, 127)).arg("x").build());
+    assert!(matches!(de::from_slice::<(String, (Color, String))>(&packet).unwrap_err().kind(),
+        Error::TypeMismatch { expected: 'r', found: 'm' }));
+    assert!(matches!(de::from_slice::<(String, (MidiMessage, UnicodeChar))>(&packet).unwrap_err().kind(),
+        Error::TypeMismatch { expected: 'c', found: 's' }));
+    let (_, (_, sym)): (String, (MidiMessage, Symbol)) = de::from_slice(&packet).unwrap();
+    assert_eq!(sym, Symbol::from("x"));
+}
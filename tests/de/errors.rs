@@ -0,0 +1,132 @@
+use serde_osc::de;
+use serde_osc::error::Error;
+use serde_osc::types::{MidiMessage, Symbol};
+
+#[test]
+fn argument_location() {
+    // Second argument is a string, but we ask for an i32.
+    let test_input = b"\x00\x00\x00\x18/m1\0,is\0\0\0\0\x01abc\0\0\0\0\0";
+    let err = de::from_slice::<(String, (i32, i32))>(&test_input[..]).unwrap_err();
+    let ctx = err.context().expect("error has a location");
+    assert_eq!(ctx.address.as_deref(), Some("/m1"));
+    assert_eq!(ctx.arg_index, Some(1));
+    // Decoding stopped after reading the string.
+    assert_eq!(ctx.offset, 16);
+    match *err.kind() {
+        Error::TypeMismatch { expected: 'i', found: 's' } => {},
+        ref other => panic!("expected a type mismatch, got {:?}", other),
+    }
+    assert_eq!(err.to_string(), "argument 1 of /m1: expected 'i', found 's' (at byte 16)");
+}
+
+#[test]
+fn other_error_location() {
+    // A u8 can't hold 300; serde reports this, rather than a tag mismatch.
+    let test_input = b"\x00\x00\x00\x0C/m1\0,i\0\0\x00\x00\x01\x2C";
+    let err = de::from_slice::<(String, (u8,))>(&test_input[..]).unwrap_err();
+    match *err.kind() {
+        Error::Message(_) => {},
+        ref other => panic!("expected a serde error, got {:?}", other),
+    }
+    let message = err.to_string();
+    assert!(message.contains("at byte 12"), "{}", message);
+    assert!(message.contains("in message /m1"), "{}", message);
+    assert!(message.contains("argument 0"), "{}", message);
+}
+
+#[test]
+fn type_mismatch() {
+    let test_input = b"\0\0\0\x1C/foo\0\0\0\0,ffi\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\x01";
+    let err = de::from_slice::<(String, (f32, f32, f32))>(&test_input[..]).unwrap_err();
+    assert!(err.to_string().starts_with("argument 2 of /foo: expected 'f', found 'i'"), "{}", err);
+    // Previously, serde silently converted the integer.
+    let err = de::from_slice::<(String, (f32, f32, f64))>(&test_input[..]).unwrap_err();
+    match *err.kind() {
+        Error::TypeMismatch { expected: 'd', found: 'i' } => {},
+        ref other => panic!("expected a type mismatch, got {:?}", other),
+    }
+    // Mismatches within arrays are reported too.
+    let test_input = b"\0\0\0\x18/arr\0\0\0\0,[if]\0\0\0\0\0\0\0\x01\x3F\x80\0\0";
+    let err = de::from_slice::<(String, (Vec<i32>,))>(&test_input[..]).unwrap_err();
+    assert!(err.to_string().starts_with("argument 0 of /arr: expected 'i', found 'f'"), "{}", err);
+}
+
+#[test]
+fn helper_type_mismatch() {
+    // Helper types report the tag they expected, rather than a visitor's message.
+    let test_input = b"\x00\x00\x00\x0C/m1\0,i\0\0\0\0\0\x01";
+    let err = de::from_slice::<(String, (Symbol,))>(&test_input[..]).unwrap_err();
+    assert!(err.to_string().starts_with("argument 0 of /m1: expected 'S', found 'i'"), "{}", err);
+    let test_input = b"\x00\x00\x00\x0C/m1\0,r\0\0\xff\x80\x00\xff";
+    let err = de::from_slice::<(String, (MidiMessage,))>(&test_input[..]).unwrap_err();
+    match *err.kind() {
+        Error::TypeMismatch { expected: 'm', found: 'r' } => {},
+        ref other => panic!("expected a type mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn unsupported_tag_location() {
+    let test_input = b"\x00\x00\x00\x10/m1\0,iX\0\0\0\0\x01";
+    let err = de::from_slice::<(String, (i32, i32))>(&test_input[..]).unwrap_err();
+    match *err.kind() {
+        Error::UnsupportedType => {},
+        ref other => panic!("expected UnsupportedType, got {:?}", other),
+    }
+    let ctx = err.context().unwrap();
+    assert_eq!((ctx.offset, ctx.arg_index), (12, Some(1)));
+}
+
+#[test]
+fn bundle_element_location() {
+    // The second message of the bundle has a bad argument; offsets count from
+    // the start of the outermost packet contents.
+    type Msg = (String, (i32,));
+    let test_input = b"\x00\x00\x00\x30#bundle\0\x00\x00\x00\x00\x00\x00\x00\x01\
+        \x00\x00\x00\x0C/m1\0,i\0\0\x00\x00\x00\x01\
+        \x00\x00\x00\x0C/m2\0,f\0\0\x3F\x80\x00\x00";
+    let err = de::from_slice::<((u32, u32), (Msg, Msg))>(&test_input[..]).unwrap_err();
+    let ctx = err.context().unwrap();
+    assert_eq!(ctx.address.as_deref(), Some("/m2"));
+    assert_eq!(ctx.arg_index, Some(0));
+    assert_eq!(ctx.offset, 48);
+}
+
+#[test]
+fn padding_display() {
+    // The address "/m1" is followed by only one NUL, and then the typetag.
+    let test_input = b"\x00\x00\x00\x08/m1\0,\0\0X";
+    let err = de::from_slice::<(String, ())>(&test_input[..]).unwrap_err();
+    assert!(err.to_string().starts_with("invalid padding after OSC string"), "{}", err);
+    assert!(err.to_string().contains("(at byte "), "{}", err);
+}
+
+#[test]
+fn utf8_source() {
+    use std::error::Error as StdError;
+    let test_input = b"\x00\x00\x00\x10/m1\0,s\0\0\xFF\xFE\0\0";
+    let err = de::from_slice::<(String, (String,))>(&test_input[..]).unwrap_err();
+    match *err.kind() {
+        Error::StrParseError(_) => {},
+        ref other => panic!("expected a UTF-8 error, got {:?}", other),
+    }
+    assert!(err.to_string().starts_with("OSC string isn't valid UTF-8: invalid utf-8"), "{}", err);
+    // The location annotation doesn't add a link to the chain.
+    let source = err.source().expect("UTF-8 errors have a source");
+    assert!(source.is::<std::string::FromUtf8Error>());
+    assert!(source.source().is_none());
+}
+
+#[test]
+fn io_source() {
+    use std::error::Error as StdError;
+    let err = de::from_read::<(String, (i32,)), _>(&b"\x00\x00\x00\x0C/m1\0"[..]).unwrap_err();
+    match *err.kind() {
+        Error::Io(ref io_error) => assert_eq!(io_error.kind(), std::io::ErrorKind::UnexpectedEof),
+        ref other => panic!("expected an I/O error, got {:?}", other),
+    }
+    assert!(err.to_string().starts_with("I/O error: "), "{}", err);
+    assert!(err.source().expect("I/O errors have a source").is::<std::io::Error>());
+    // Errors without an underlying cause have no source.
+    assert!(Error::BadFormat.source().is_none());
+}
@@ -0,0 +1,127 @@
+use std::fmt;
+use std::io::{self, Cursor, Read};
+use byteorder::{BigEndian, ByteOrder};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use serde_osc::de::{validate_with, DeserializerOptions, ExtensionType, MessageReader, StreamedArg};
+use serde_osc::error::Error;
+use serde_osc::framing::Framing;
+use serde_osc::types::RawArg;
+use serde_osc::value::{Message, Packet, Value};
+
+/// A proprietary 'x' argument: a 3D vector, as three f32s.
+#[derive(Debug, PartialEq)]
+struct Vector(f32, f32, f32);
+
+impl Serialize for Vector {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut data = vec![0; 12];
+        BigEndian::write_f32_into(&[self.0, self.1, self.2], &mut data);
+        RawArg::new('x', data).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Vector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(VectorVisitor)
+    }
+}
+
+struct VectorVisitor;
+
+impl<'de> Visitor<'de> for VectorVisitor {
+    type Value = Vector;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "12 bytes of vector data")
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vector, E> {
+        if v.len() != 12 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        let mut xyz = [0.0; 3];
+        BigEndian::read_f32_into(v, &mut xyz);
+        Ok(Vector(xyz[0], xyz[1], xyz[2]))
+    }
+}
+
+fn read_vector(read: &mut dyn Read) -> io::Result<Vec<u8>> {
+    let mut data = vec![0; 12];
+    read.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// 'v': a variable number of words, preceded by their count.
+fn read_words(read: &mut dyn Read) -> io::Result<Vec<u8>> {
+    let mut count = [0; 4];
+    read.read_exact(&mut count)?;
+    let mut data = count.to_vec();
+    read.take(4 * u64::from(BigEndian::read_u32(&count))).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// A broken reader, which doesn't stop at a 4-byte boundary.
+fn read_3_bytes(read: &mut dyn Read) -> io::Result<Vec<u8>> {
+    let mut data = vec![0; 3];
+    read.read_exact(&mut data)?;
+    Ok(data)
+}
+
+static EXTENSIONS: &[ExtensionType] = &[
+    ExtensionType { tag: 'x', read: read_vector },
+    ExtensionType { tag: 'v', read: read_words },
+    ExtensionType { tag: 'e', read: read_3_bytes },
+];
+
+fn extensions() -> DeserializerOptions {
+    DeserializerOptions { extensions: EXTENSIONS, ..DeserializerOptions::default() }
+}
+
+#[test]
+fn custom_type_round_trip() {
+    let msg = ("/obj/pos", (1, Vector(1.0, -2.0, 0.5), "name"));
+    let packet = serde_osc::to_vec(&msg).unwrap();
+    assert_eq!(&packet[16..24], b",ixs\0\0\0\0");
+    let decoded: (String, (i32, Vector, String)) = serde_osc::de::from_slice_with_options(&packet, extensions()).unwrap();
+    assert_eq!(decoded, ("/obj/pos".to_owned(), (1, Vector(1.0, -2.0, 0.5), "name".to_owned())));
+    assert!(validate_with(&packet, Framing::LengthPrefixed, extensions()).is_ok());
+}
+
+#[test]
+fn unregistered_by_default() {
+    let packet = serde_osc::to_vec(&("/obj/pos", (Vector(1.0, 2.0, 3.0), 1))).unwrap();
+    match serde_osc::de::from_slice::<(String, (Vector, i32))>(&packet).map_err(Error::into_kind) {
+        Err(Error::UnsupportedType) => {},
+        other => panic!("expected UnsupportedType, got {:?}", other),
+    }
+}
+
+#[test]
+fn variable_length_type() {
+    let words = RawArg::new('v', vec![0, 0, 0, 2, 1, 2, 3, 4, 5, 6, 7, 8]);
+    let msg = Message::new("/w").with_arg(words.clone()).with_arg(RawArg::new('v', vec![0; 4])).with_arg(9);
+    let packet = serde_osc::to_vec(&msg).unwrap();
+    let decoded = Packet::from_slice_with(&packet, Framing::LengthPrefixed, extensions()).unwrap();
+    assert_eq!(decoded, Packet::Message(msg));
+    assert!(validate_with(&packet, Framing::LengthPrefixed, extensions()).is_ok());
+
+    let mut rd = Cursor::new(packet);
+    let mut reader = MessageReader::with_options(&mut rd, Framing::LengthPrefixed, extensions()).unwrap();
+    match reader.next_arg().unwrap() {
+        Some(StreamedArg::Value(value)) => assert_eq!(value, Value::Unknown(words)),
+        _ => panic!("expected a value"),
+    }
+}
+
+#[test]
+fn misaligned_data() {
+    let packet = serde_osc::to_vec(&Message::new("/e").with_arg(RawArg::new('e', vec![0; 4]))).unwrap();
+    for result in [
+        Packet::from_slice_with(&packet, Framing::LengthPrefixed, extensions()).map(|_| ()),
+        validate_with(&packet, Framing::LengthPrefixed, extensions()).map(|_| ()),
+    ] {
+        match result.map_err(Error::into_kind) {
+            Err(Error::BadPadding) => {},
+            other => panic!("expected BadPadding, got {:?}", other),
+        }
+    }
+}
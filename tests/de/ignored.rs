@@ -0,0 +1,66 @@
+use std::io::Cursor;
+use serde::de::IgnoredAny;
+use serde_bytes::ByteBuf;
+use serde_osc::{de, ser};
+use serde_osc::de::DeserializerOptions;
+
+fn long_message() -> Vec<u8> {
+    ser::to_vec(&("/m", (1, 2.0f32, "three", ByteBuf::from(vec![4u8; 5]), (5, "six"), 7))).unwrap()
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct FirstTwo {
+    a: i32,
+    b: f32,
+    #[serde(skip)]
+    rest: Option<String>,
+}
+
+#[test]
+fn leading_args_only() {
+    let mut packets = long_message();
+    packets.extend(ser::to_vec(&("/next", (8,))).unwrap());
+    let mut rd = Cursor::new(packets);
+    let (_, first_two): (String, FirstTwo) = de::from_read(&mut rd).unwrap();
+    assert_eq!(first_two, FirstTwo { a: 1, b: 2.0, rest: None });
+    // The rest of the message was skipped.
+    let (address, (next,)): (String, (i32,)) = de::from_read(&mut rd).unwrap();
+    assert_eq!((address.as_str(), next), ("/next", 8));
+}
+
+#[test]
+fn ignored_args() {
+    type Skipping = (String, (IgnoredAny, IgnoredAny, IgnoredAny, IgnoredAny, IgnoredAny, i32));
+    let (_, (.., last)): Skipping = de::from_slice(&long_message()).unwrap();
+    assert_eq!(last, 7);
+    let (_, (first, _)): (String, (i32, IgnoredAny)) = de::from_slice(&long_message()).unwrap();
+    assert_eq!(first, 1);
+    let (address, _): (String, IgnoredAny) = de::from_slice(&long_message()).unwrap();
+    assert_eq!(address, "/m");
+}
+
+#[test]
+fn ignored_args_are_checked() {
+    // The string "three" has bad padding.
+    let mut packet = long_message();
+    packet[34] = b'!';
+    type Skipping = (String, (IgnoredAny, IgnoredAny, IgnoredAny, i32));
+    assert!(de::from_slice::<Skipping>(&packet).is_err());
+    // A skipped blob is still subject to the size limit.
+    let options = DeserializerOptions { max_blob_size: Some(4), ..DeserializerOptions::default() };
+    assert!(de::from_slice_with_options::<Skipping>(&long_message(), options).is_err());
+}
+
+#[test]
+fn ignored_bundle_elements() {
+    let bundle = ((0u32, 1u32), (("/a", ("skipped", 1)), ("/b", (2,))));
+    let packet = ser::to_vec(&bundle).unwrap();
+    type Elements = (IgnoredAny, (String, (i32,)));
+    let (_, (_, (address, (b,)))): ((u32, u32), Elements) = de::from_slice(&packet).unwrap();
+    assert_eq!((address.as_str(), b), ("/b", 2));
+    // A truncated element is still an error.
+    let mut truncated = packet.clone();
+    truncated.truncate(packet.len() - 8);
+    truncated[3] -= 8;
+    assert!(de::from_slice::<((u32, u32), (IgnoredAny, IgnoredAny))>(&truncated).is_err());
+}
@@ -0,0 +1,53 @@
+use serde_osc::de::{self, PacketKind};
+use serde_osc::error::Error;
+use serde_osc::framing::Framing;
+use serde_osc::ser;
+use serde_osc::timetag::TimeTag;
+
+#[test]
+fn message() {
+    let packet = ser::to_vec(&("/synth/1/freq", (440.0f32, "sine", (1, 2)))).unwrap();
+    let expected = PacketKind::Message { address: "/synth/1/freq".to_owned(), typetag: "fs[ii]".to_owned() };
+    assert_eq!(de::inspect(&packet).unwrap(), expected);
+    let unframed = ser::to_vec_unframed(&("/synth/1/freq", (440.0f32, "sine", (1, 2)))).unwrap();
+    assert_eq!(de::inspect_unframed(&unframed).unwrap(), expected);
+}
+
+#[test]
+fn bundle() {
+    let bundle = ((5u32, 6u32), (("/a", (1,)), ((0u32, 1u32), (("/b", ()), ("/c", ()))), ("/d", ("x",))));
+    let packet = ser::to_vec(&bundle).unwrap();
+    // Only the elements directly within the bundle count.
+    assert_eq!(de::inspect(&packet).unwrap(), PacketKind::Bundle { timetag: TimeTag::At(5, 6), element_count: 3 });
+}
+
+#[test]
+fn arguments_not_decoded() {
+    // The blob's length overruns the message, which only decoding would notice.
+    let packet = b"\x00\x00\x00\x0c/m\0\0,b\0\0\x00\x00\x01\x00";
+    assert!(de::from_slice::<(String, (Vec<u8>,))>(packet).is_err());
+    assert_eq!(de::inspect(packet).unwrap(), PacketKind::Message { address: "/m".to_owned(), typetag: "b".to_owned() });
+}
+
+#[test]
+fn trailing_data_ignored() {
+    let mut stream = ser::to_vec(&("/a", ())).unwrap();
+    stream.extend(ser::to_vec(&("/b", ())).unwrap());
+    assert_eq!(de::inspect(&stream).unwrap(), PacketKind::Message { address: "/a".to_owned(), typetag: String::new() });
+}
+
+#[test]
+fn malformed() {
+    let packet = ser::to_vec(&("/a", (1,))).unwrap();
+    assert!(de::inspect(&packet[..packet.len() - 4]).is_err());
+    // A bundle element which overruns the bundle.
+    let mut bundle = ser::to_vec(&((0u32, 1u32), (("/a", ()),))).unwrap();
+    bundle[23] += 4;
+    assert!(de::inspect(&bundle).is_err());
+    // Limits still apply.
+    let options = de::DeserializerOptions { max_packet_size: Some(4), ..de::DeserializerOptions::default() };
+    match de::inspect_with(&packet, Framing::LengthPrefixed, options).unwrap_err().into_kind() {
+        Error::SizeLimitExceeded => {},
+        other => panic!("expected SizeLimitExceeded, got {:?}", other),
+    }
+}
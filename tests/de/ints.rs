@@ -0,0 +1,21 @@
+use serde_osc::de;
+
+#[test]
+fn i32_into_unsigned() {
+    let test_input = b"\x00\x00\x00\x18/u\0\0,iii\0\0\0\0\x00\x00\x00\x07\x00\x00\x01\x2C\x7F\xFF\xFF\xFF";
+    let (_, args): (String, (u8, u16, u32)) = de::from_slice(test_input).unwrap();
+    assert_eq!(args, (7, 300, 0x7fffffff));
+    // 300 doesn't fit in a u8.
+    assert!(de::from_slice::<(String, (u8, u8, u32))>(test_input).is_err());
+    // Negative values don't fit in any unsigned type.
+    let negative = b"\x00\x00\x00\x0C/u\0\0,i\0\0\xFF\xFF\xFF\xFF";
+    assert!(de::from_slice::<(String, (u32,))>(negative).is_err());
+}
+
+#[test]
+fn i64_args() {
+    let test_input = b"\x00\x00\x00\x18/h\0\0,hh\0\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFE\x01\x02\x03\x04\x05\x06\x07\x08";
+    let (_, args): (String, (i64, u64)) = de::from_slice(test_input).unwrap();
+    assert_eq!(args, (-2, 0x0102030405060708));
+    assert!(de::from_slice::<(String, (u64, u64))>(test_input).is_err());
+}
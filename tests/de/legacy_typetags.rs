@@ -0,0 +1,65 @@
+use std::io::Cursor;
+use serde::Deserialize;
+use serde_osc::de;
+use serde_osc::de::{validate_with, Deserializer, DeserializerOptions, MessageReader, TypetagCompat};
+use serde_osc::framing::Framing;
+use serde_osc::value::{Message, Packet};
+
+fn legacy() -> DeserializerOptions {
+    DeserializerOptions { typetags: TypetagCompat::Legacy, ..DeserializerOptions::default() }
+}
+
+// /m ,i 5, with the typetag's comma missing
+const NO_COMMA: &[u8] = b"/m\0\0i\0\0\0\0\0\0\x05";
+// /ping, with no typetag at all
+const NO_TYPETAG: &[u8] = b"\0\0\0\x08/ping\0\0\0";
+
+#[test]
+fn comma_optional_by_default() {
+    let msg: (String, (i32,)) = de::from_slice_unframed(NO_COMMA).unwrap();
+    assert_eq!(msg, ("/m".to_owned(), (5,)));
+    let packet = Packet::from_slice_with(NO_COMMA, Framing::Unframed, legacy()).unwrap();
+    assert_eq!(packet, Packet::Message(Message::new("/m").with_arg(5)));
+}
+
+#[test]
+fn missing_typetag_rejected_by_default() {
+    assert!(de::from_slice::<(String, ())>(NO_TYPETAG).is_err());
+    assert!(Packet::from_slice(NO_TYPETAG).is_err());
+    assert!(de::validate(NO_TYPETAG).is_err());
+}
+
+#[test]
+fn missing_typetag_means_no_args() {
+    let msg: (String, ()) = de::from_slice_with_options(NO_TYPETAG, legacy()).unwrap();
+    assert_eq!(msg, ("/ping".to_owned(), ()));
+    let packet = Packet::from_slice_with(NO_TYPETAG, Framing::LengthPrefixed, legacy()).unwrap();
+    assert_eq!(packet, Packet::Message(Message::new("/ping")));
+    assert_eq!(validate_with(NO_TYPETAG, Framing::LengthPrefixed, legacy()).unwrap().messages, 1);
+
+    let mut rd = Cursor::new(NO_TYPETAG);
+    let reader = MessageReader::with_options(&mut rd, Framing::LengthPrefixed, legacy()).unwrap();
+    assert_eq!(reader.header().typetag, "");
+    reader.finish().unwrap();
+}
+
+#[test]
+fn missing_typetag_peek_and_read() {
+    let mut rd = Cursor::new(NO_TYPETAG);
+    let mut de = Deserializer::with_options(&mut rd, Framing::LengthPrefixed, legacy());
+    let header = de.peek_header().unwrap();
+    assert_eq!((header.address.as_str(), header.typetag.as_str()), ("/ping", ""));
+    let msg = <(String, ())>::deserialize(&mut de).unwrap();
+    assert_eq!(msg.0, "/ping");
+}
+
+#[test]
+fn missing_typetag_in_bundle() {
+    // A bundle holding "/ping" without a typetag, then "/m ,i 5".
+    let mut bundle = b"\0\0\0\x2c#bundle\0\0\0\0\0\0\0\0\x01".to_vec();
+    bundle.extend_from_slice(NO_TYPETAG);
+    bundle.extend_from_slice(b"\0\0\0\x0c/m\0\0,i\0\0\0\0\0\x05");
+    type Elements = ((String, ()), (String, (i32,)));
+    let (_, (ping, m)): ((u32, u32), Elements) = de::from_slice_with_options(&bundle, legacy()).unwrap();
+    assert_eq!((ping.0.as_str(), m), ("/ping", ("/m".to_owned(), (5,))));
+}
@@ -0,0 +1,68 @@
+use std::io::Cursor;
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+use serde_osc::de::{self, Deserializer, DeserializerOptions};
+use serde_osc::error::Error;
+use serde_osc::framing::Framing;
+
+fn strict() -> DeserializerOptions {
+    DeserializerOptions { strict: true, ..DeserializerOptions::default() }
+}
+
+/// A message declared 4 bytes longer than its contents, followed by another packet.
+const PADDED: &[u8] = b"\0\0\0\x14/m\0\0,ii\0\0\0\0\x01\0\0\0\x02\0\0\0\0\0\0\0\x08/n\0\0,\0\0\0";
+
+fn assert_mismatch(error: Error, declared: usize, consumed: usize) {
+    match error.into_kind() {
+        Error::LengthMismatch { declared: d, consumed: c } => assert_eq!((d, c), (declared, consumed)),
+        other => panic!("expected LengthMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn skipped_when_lenient() {
+    let mut rd = Cursor::new(PADDED);
+    let (_, args) = <(String, (i32, i32))>::deserialize(&mut Deserializer::new(&mut rd)).unwrap();
+    assert_eq!(args, (1, 2));
+    // The surplus is skipped, so the next packet is decoded.
+    let (address, ()) = <(String, ())>::deserialize(&mut Deserializer::new(&mut rd)).unwrap();
+    assert_eq!(address, "/n");
+}
+
+#[test]
+fn rejected_when_strict() {
+    let mut rd = Cursor::new(PADDED);
+    let error = <(String, (i32, i32))>::deserialize(&mut Deserializer::with_options(&mut rd, Framing::LengthPrefixed, strict()))
+        .unwrap_err();
+    assert_eq!(error.context().unwrap().address.as_ref().unwrap(), "/m");
+    assert_mismatch(error, 20, 16);
+    // The stream stays aligned.
+    let (address, ()) = <(String, ())>::deserialize(&mut Deserializer::new(&mut rd)).unwrap();
+    assert_eq!(address, "/n");
+}
+
+#[test]
+fn unconsumed_args_checked_when_strict() {
+    // Arguments which aren't deserialized don't count as surplus, but are checked.
+    let packet = &PADDED[..24];
+    assert_mismatch(de::from_slice_with_options::<(String, (i32,))>(packet, strict()).unwrap_err(), 20, 16);
+    assert_mismatch(de::from_slice_with_options::<(String, IgnoredAny)>(packet, strict()).unwrap_err(), 20, 16);
+    assert_mismatch(de::from_slice_with_options::<(String,)>(packet, strict()).unwrap_err(), 20, 16);
+    let exact = serde_osc::to_vec(&("/m", (1, 2))).unwrap();
+    assert!(de::from_slice_with_options::<(String, (i32,))>(&exact, strict()).is_ok());
+    assert!(de::from_slice_with_options::<(String,)>(&exact, strict()).is_ok());
+}
+
+#[test]
+fn bundle_elements() {
+    let elem: &[u8] = b"\0\0\0\x0c/m\0\0,\0\0\0\0\0\0\0";
+    let mut packet = b"#bundle\0\0\0\0\0\0\0\0\x01".to_vec();
+    packet.extend_from_slice(elem);
+    packet.extend_from_slice(elem);
+    type Elements = ((u32, u32), ((String, ()),));
+    // Unconsumed elements are skipped (and checked) in strict mode.
+    assert!(de::from_slice_unframed::<Elements>(&packet).is_ok());
+    let mut rd = Cursor::new(&packet);
+    let mut de = Deserializer::with_options(&mut rd, Framing::Unframed, strict());
+    assert_mismatch(Elements::deserialize(&mut de).unwrap_err(), 12, 8);
+}
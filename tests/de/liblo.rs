@@ -0,0 +1,95 @@
+//! Packets as liblo sends them (e.g. with `lo_send`), byte for byte.
+
+use serde_bytes::ByteBuf;
+use serde_osc::de::{self, Compat, DeserializerOptions};
+use serde_osc::framing::Framing;
+use serde_osc::timetag::IMMEDIATELY;
+use serde_osc::types::{MidiMessage, RawArg};
+use serde_osc::value::{Bundle, Message, Packet, Value};
+
+/// `lo_send(t, "/transport_play", "")`: no arguments, so the typetag is "," alone.
+const NO_ARGS: &[u8] = b"/transport_play\0,\0\0\0";
+/// `lo_send(t, "/reset", "NI")`: a nil and an infinitum, neither with any data.
+const NIL_INFINITUM: &[u8] = b"/reset\0\0,NI\0";
+/// `lo_send(t, "/all", "ihtdScmTF", 7, 1LL << 40, tt, 0.5, "sym", 'x', midi)`,
+/// with the time-tag `{1, 0x80000000}` and the MIDI message `{0, 0x90, 60, 100}`.
+const ALL_TYPES: &[u8] = b"/all\0\0\0\0,ihtdScmTF\0\0\
+    \0\0\0\x07\
+    \0\0\x01\0\0\0\0\0\
+    \0\0\0\x01\x80\0\0\0\
+    ?\xE0\0\0\0\0\0\0\
+    sym\0\
+    \0\0\0x\
+    \0\x90\x3C\x64";
+/// A message which ends after its address, with no typetag at all.
+const NO_TYPETAG: &[u8] = b"/ping\0\0\0";
+/// `lo_send_bundle` of a bundle timestamped `LO_TT_IMMEDIATE`, holding `NIL_INFINITUM`.
+const BUNDLE: &[u8] = b"#bundle\0\0\0\0\0\0\0\0\x01\0\0\0\x0C/reset\0\0,NI\0";
+
+fn decode(packet: &[u8]) -> Packet {
+    Packet::from_slice_with(packet, Framing::Unframed, DeserializerOptions::liblo()).unwrap()
+}
+
+#[test]
+fn empty_typetag() {
+    assert_eq!(decode(NO_ARGS), Packet::Message(Message::new("/transport_play")));
+    // This is plain OSC 1.0, and needs no special options.
+    let (address, ()): (String, ()) = de::from_slice_unframed(NO_ARGS).unwrap();
+    assert_eq!(address, "/transport_play");
+}
+
+#[test]
+fn missing_typetag() {
+    assert_eq!(decode(NO_TYPETAG), Packet::Message(Message::new("/ping")));
+    assert!(de::from_slice_unframed::<(String, ())>(NO_TYPETAG).is_err());
+}
+
+#[test]
+fn nil_and_infinitum() {
+    let expected = Message::to("/reset").arg(Value::Nil).arg(RawArg::new('I', vec![])).build();
+    assert_eq!(decode(NIL_INFINITUM), Packet::Message(expected));
+    // Typed, the nil is `None` and the infinitum its (empty) raw data.
+    let mut rd = NIL_INFINITUM;
+    let mut de = de::Deserializer::with_options(&mut rd, Framing::Unframed, DeserializerOptions::liblo());
+    let (_, (nil, infinitum)): (String, (Option<i32>, ByteBuf)) = serde::Deserialize::deserialize(&mut de).unwrap();
+    assert_eq!((nil, Vec::from(infinitum)), (None, vec![]));
+    let mut rd = NIL_INFINITUM;
+    let mut de = de::Deserializer::with_options(&mut rd, Framing::Unframed, Compat::Liblo.into());
+    let (_, (unit, _)): (String, ((), ByteBuf)) = serde::Deserialize::deserialize(&mut de).unwrap();
+    assert_eq!(unit, ());
+    // By default, the infinitum is rejected.
+    assert!(de::from_slice_unframed::<(String, (Option<i32>, ByteBuf))>(NIL_INFINITUM).is_err());
+}
+
+#[test]
+fn all_types() {
+    let expected = Message::to("/all")
+        .arg(7)
+        .arg(1i64 << 40)
+        .arg(RawArg::new('t', vec![0, 0, 0, 1, 0x80, 0, 0, 0]))
+        .arg(0.5f64)
+        .arg(Value::Symbol("sym".to_owned()))
+        .arg('x')
+        .arg(MidiMessage::new(0, 0x90, 60, 100))
+        .arg(true)
+        .arg(false)
+        .build();
+    assert_eq!(decode(ALL_TYPES), Packet::Message(expected));
+    // The captured time-tag is forwarded unchanged.
+    assert_eq!(serde_osc::ser::to_vec_unframed(&decode(ALL_TYPES)).unwrap(), ALL_TYPES);
+}
+
+#[test]
+fn immediate_bundle() {
+    let reset = Message::to("/reset").arg(Value::Nil).arg(RawArg::new('I', vec![])).build();
+    let mut expected = Bundle::new(IMMEDIATELY);
+    expected.elements.push(Packet::Message(reset));
+    assert_eq!(decode(BUNDLE), Packet::Bundle(expected));
+}
+
+#[test]
+fn profiles() {
+    assert_eq!(DeserializerOptions::from(Compat::Liblo), DeserializerOptions::liblo());
+    assert_eq!(Compat::SuperCollider.options(), DeserializerOptions::supercollider());
+    assert_eq!(Compat::default().options(), DeserializerOptions::default());
+}
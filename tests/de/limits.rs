@@ -0,0 +1,106 @@
+use serde_bytes::ByteBuf;
+use serde_osc::{de, ser};
+use serde_osc::de::DeserializerOptions;
+use serde_osc::error::Error;
+use serde_osc::framing::Framing;
+use serde_osc::value::{Bundle, Message, Packet};
+
+type BlobMsg = (String, (ByteBuf,));
+
+// "/b" message with a single 5-byte blob.
+const BLOB_MSG: &[u8] = b"\x00\x00\x00\x14/b\0\0,b\0\0\x00\x00\x00\x05\x01\x02\x03\x04\x05\0\0\0";
+
+fn assert_too_large<T: ::std::fmt::Debug>(result: Result<T, Error>) {
+    match result.as_ref().map_err(Error::kind) {
+        Err(&Error::SizeLimitExceeded) => {},
+        other => panic!("expected SizeLimitExceeded, got {:?}", other),
+    }
+}
+
+#[test]
+fn max_packet_size() {
+    let options = DeserializerOptions { max_packet_size: Some(19), ..DeserializerOptions::default() };
+    assert_too_large(de::from_slice_with_options::<BlobMsg>(BLOB_MSG, options));
+
+    let options = DeserializerOptions { max_packet_size: Some(20), ..DeserializerOptions::default() };
+    let (_, (blob,)) = de::from_slice_with_options::<BlobMsg>(BLOB_MSG, options).unwrap();
+    assert_eq!(&blob[..], &[1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn max_packet_size_unframed() {
+    let options = DeserializerOptions { max_packet_size: Some(19), ..DeserializerOptions::default() };
+    let mut unframed = &BLOB_MSG[4..];
+    let mut de = de::Deserializer::with_options(&mut unframed, ::serde_osc::framing::Framing::Unframed, options);
+    assert_too_large(<BlobMsg as ::serde::Deserialize>::deserialize(&mut de));
+}
+
+#[test]
+fn max_blob_size() {
+    let options = DeserializerOptions { max_blob_size: Some(4), ..DeserializerOptions::default() };
+    assert_too_large(de::from_slice_with_options::<BlobMsg>(BLOB_MSG, options));
+}
+
+#[test]
+fn bogus_lengths() {
+    // Blob claims to be ~2GB long, but the packet ends after 8 bytes.
+    let blob = b"\x00\x00\x00\x10/b\0\0,b\0\0\x7F\xFF\xFF\xF0\0\0\0\0";
+    assert!(de::from_slice::<BlobMsg>(blob).is_err());
+    // Packet claims to be ~2GB long.
+    let packet = b"\x7F\xFF\xFF\xF0/b\0\0,\0\0\0";
+    assert!(de::from_slice::<(String, ())>(packet).is_err());
+    // Negative length
+    let packet = b"\xFF\xFF\xFF\xF0/b\0\0,\0\0\0";
+    assert!(de::from_slice::<(String, ())>(packet).is_err());
+}
+
+/// Bundle nested `depth` levels deep, around a "/m" message.
+fn nested_bundle(depth: usize) -> Packet {
+    let mut packet = Packet::from(Message::new("/m"));
+    for _ in 0..depth {
+        let mut bundle = Bundle::new((0, 1));
+        bundle.elements.push(packet);
+        packet = bundle.into();
+    }
+    packet
+}
+
+fn assert_too_deep<T: ::std::fmt::Debug>(result: Result<T, Error>) {
+    match result.as_ref().map_err(Error::kind) {
+        Err(&Error::DepthLimitExceeded) => {},
+        other => panic!("expected DepthLimitExceeded, got {:?}", other),
+    }
+}
+
+#[test]
+fn max_depth() {
+    type Inner = ((u32, u32), ((String, ()),));
+    type Outer = ((u32, u32), (((u32, u32), (Inner,)),));
+    let packet = ser::to_vec(&nested_bundle(3)).unwrap();
+    assert!(de::from_slice::<Outer>(&packet).is_ok());
+
+    let options = DeserializerOptions { max_depth: Some(2), ..DeserializerOptions::default() };
+    assert_too_deep(de::from_slice_with_options::<Outer>(&packet, options));
+    assert_too_deep(Packet::from_slice_with(&packet, Framing::LengthPrefixed, options));
+}
+
+#[test]
+fn default_max_depth() {
+    let packet = ser::to_vec(&nested_bundle(de::DEFAULT_MAX_DEPTH)).unwrap();
+    assert_eq!(Packet::from_slice(&packet).unwrap(), nested_bundle(de::DEFAULT_MAX_DEPTH));
+    // Deep enough to exhaust the stack, if unchecked.
+    // Each level is "#bundle", a time-tag and the element length: 20 bytes.
+    let levels = 100_000i32;
+    let mut packet = Vec::new();
+    for level in (0..levels + 1).rev() {
+        packet.extend_from_slice(&(20*level + 8).to_be_bytes());
+        if level != 0 {
+            packet.extend_from_slice(b"#bundle\0\0\0\0\0\0\0\0\x01");
+        }
+    }
+    packet.extend_from_slice(b"/m\0\0,\0\0\0");
+    assert_too_deep(Packet::from_slice(&packet));
+    let options = DeserializerOptions { max_depth: None, ..DeserializerOptions::default() };
+    let packet = ser::to_vec(&nested_bundle(100)).unwrap();
+    assert!(Packet::from_slice_with(&packet, Framing::LengthPrefixed, options).is_ok());
+}
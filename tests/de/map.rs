@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+use serde_osc::de;
+
+#[test]
+fn args_as_map() {
+    let test_input = b"\x00\x00\x00\x1C/p\0\0,sfsf\0\0\0a\0\0\0\x3F\x80\x00\x00b\0\0\0\x40\x00\x00\x00";
+    let (address, map): (String, HashMap<String, f32>) = de::from_slice(test_input).unwrap();
+    assert_eq!(address, "/p");
+    assert_eq!(map.len(), 2);
+    assert_eq!((map["a"], map["b"]), (1.0, 2.0));
+}
+
+#[test]
+fn array_as_map() {
+    let test_input = b"\x00\x00\x00\x24/p\0\0,i[sfsf]\0\0\0\0\0\0\0\x07a\0\0\0\x3F\x80\x00\x00b\0\0\0\x40\x00\x00\x00";
+    let (_, (id, map)): (String, (i32, HashMap<String, f32>)) = de::from_slice(test_input).unwrap();
+    assert_eq!(id, 7);
+    assert_eq!((map["a"], map["b"]), (1.0, 2.0));
+}
+
+#[test]
+fn key_without_value() {
+    let test_input = b"\x00\x00\x00\x14/p\0\0,sfs\0a\0\0\0\x3F\x80\x00\x00b\0\0\0";
+    assert!(de::from_slice::<(String, HashMap<String, f32>)>(test_input).is_err());
+}
@@ -0,0 +1,71 @@
+use std::io::Cursor;
+use serde_osc::{de, ser};
+use serde_osc::address::AddressPattern;
+use serde_osc::error::Error;
+
+type FreqMsg = (String, (f32,));
+
+#[test]
+fn matching_address() {
+    let packet = ser::to_vec(&("/synth/2/freq", (220.0f32,))).unwrap();
+    let pattern = AddressPattern::new("/synth/[1-4]/freq");
+    let msg: FreqMsg = de::from_slice_matching(&packet, &pattern).unwrap();
+    assert_eq!(msg, ("/synth/2/freq".to_owned(), (220.0,)));
+}
+
+#[test]
+fn mismatch_skips_packet() {
+    let mut stream = ser::to_vec(&("/synth/1/gain", (1i32,))).unwrap();
+    stream.extend(ser::to_vec(&("/synth/1/freq", (440.0f32,))).unwrap());
+    let pattern = AddressPattern::new("/synth/*/freq");
+    let mut rd = Cursor::new(stream);
+    match de::from_read_matching::<FreqMsg, _>(&mut rd, &pattern) {
+        // Not annotated with a location, unlike decoding errors.
+        Err(Error::AddressMismatch(address)) => assert_eq!(address, "/synth/1/gain"),
+        other => panic!("expected AddressMismatch, got {:?}", other),
+    }
+    // The mismatched packet was consumed, leaving the reader at the next one.
+    let msg: FreqMsg = de::from_read_matching(&mut rd, &pattern).unwrap();
+    assert_eq!((msg.1).0, 440.0);
+}
+
+#[test]
+fn mismatch_before_args() {
+    // The arguments have an unsupported type, but are never looked at.
+    let test_input = b"\x00\x00\x00\x10/m1\0,iX\0\0\0\0\x01";
+    let err = de::from_slice_matching::<(String, (i32, i32))>(&test_input[..], &"/m2".into()).unwrap_err();
+    match err {
+        Error::AddressMismatch(ref address) => assert_eq!(address, "/m1"),
+        ref other => panic!("expected AddressMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn bundle_mismatch() {
+    let bundle = ((0u32, 1u32), (("/m", (1i32,)),));
+    let packet = ser::to_vec(&bundle).unwrap();
+    // Not even a catch-all pattern matches a bundle.
+    match de::from_slice_matching::<((u32, u32), ((String, (i32,)),))>(&packet, &"*".into()) {
+        Err(Error::AddressMismatch(address)) => assert_eq!(address, "#bundle"),
+        other => panic!("expected AddressMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn enum_target() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Control {
+        #[serde(rename = "/volume")]
+        Volume(f32),
+        #[serde(rename = "/mute")]
+        Mute(i32),
+    }
+    let packet = ser::to_vec(&("/volume", (0.5f32,))).unwrap();
+    let volume: Control = de::from_slice_matching(&packet, &"/volume".into()).unwrap();
+    assert_eq!(volume, Control::Volume(0.5));
+    let err = de::from_slice_matching::<Control>(&packet, &"/mute".into()).unwrap_err();
+    match err {
+        Error::AddressMismatch(_) => {},
+        ref other => panic!("expected AddressMismatch, got {:?}", other),
+    }
+}
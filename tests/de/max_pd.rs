@@ -0,0 +1,68 @@
+//! Packets as sent by Max/MSP and Pure Data patches, byte for byte.
+
+use serde::de::DeserializeOwned;
+use serde_osc::de::{self, Compat, DeserializerOptions};
+use serde_osc::error::ResultE;
+use serde_osc::framing::Framing;
+
+/// Max `[slider]` → `[prepend /fader]` → `[udpsend]`: an integer.
+const FADER_INT: &[u8] = b"/fader\0\0,i\0\0\0\0\0\x40";
+/// Max `[flonum]` → `[prepend /fader]` → `[udpsend]`: a float.
+const FADER_FLOAT: &[u8] = b"/fader\0\0,f\0\0B\x80\x80\0";
+/// Max `[udpsend]` with 64-bit floats enabled.
+const FADER_DOUBLE: &[u8] = b"/fader\0\0,d\0\0@P\x10\0\0\0\0\0";
+/// Pd `[tgl]` → `[oscformat toggle]`: every Pd number is a float.
+const TOGGLE: &[u8] = b"/toggle\0,f\0\0?\x80\0\0";
+/// Pd `[oscformat channel]` with the float 3, for an integer field.
+const CHANNEL: &[u8] = b"/channel\0\0\0\0,f\0\0@@\0\0";
+/// An older external which leaves out the ',' of the typetag.
+const NO_COMMA: &[u8] = b"/note\0\0\0ii\0\0\0\0\0\x3C\0\0\0\x64";
+/// A bang, from an older external which sends no typetag at all.
+const BANG: &[u8] = b"/bang\0\0\0";
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Note {
+    pitch: u8,
+    velocity: u8,
+}
+
+fn from_datagram<T: DeserializeOwned>(mut packet: &[u8]) -> ResultE<T> {
+    let mut de = de::Deserializer::with_options(&mut packet, Framing::Unframed, DeserializerOptions::max_pd());
+    T::deserialize(&mut de)
+}
+
+#[test]
+fn loosely_typed_numbers() {
+    for &(packet, expected) in &[(FADER_INT, 64.0), (FADER_FLOAT, 64.25), (FADER_DOUBLE, 64.25)] {
+        let (address, (level,)): (String, (f32,)) = from_datagram(packet).unwrap();
+        assert_eq!((address.as_str(), level), ("/fader", expected));
+    }
+    let (_, (channel,)): (String, (i32,)) = from_datagram(CHANNEL).unwrap();
+    assert_eq!(channel, 3);
+    // The default options insist on the exact type.
+    assert!(de::from_slice_unframed::<(String, (f32,))>(FADER_INT).is_err());
+    assert!(de::from_slice_unframed::<(String, (i32,))>(CHANNEL).is_err());
+    // Integers are only taken from floats which are whole numbers.
+    assert!(from_datagram::<(String, (i32,))>(FADER_FLOAT).is_err());
+}
+
+#[test]
+fn toggles() {
+    let (_, (on,)): (String, (bool,)) = from_datagram(TOGGLE).unwrap();
+    assert!(on);
+    assert!(de::from_slice_unframed::<(String, (bool,))>(TOGGLE).is_err());
+}
+
+#[test]
+fn legacy_typetags() {
+    let (_, note): (String, Note) = from_datagram(NO_COMMA).unwrap();
+    assert_eq!(note, Note { pitch: 60, velocity: 100 });
+    let (address, ()): (String, ()) = from_datagram(BANG).unwrap();
+    assert_eq!(address, "/bang");
+    assert!(de::from_slice_unframed::<(String, ())>(BANG).is_err());
+}
+
+#[test]
+fn profile() {
+    assert_eq!(DeserializerOptions::from(Compat::MaxPd), DeserializerOptions::max_pd());
+}
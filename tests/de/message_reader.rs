@@ -0,0 +1,141 @@
+use std::io::{self, Cursor, Read};
+use serde_bytes::ByteBuf;
+use serde_osc::de::{DeserializerOptions, MessageReader, StreamedArg};
+use serde_osc::error::Error;
+use serde_osc::framing::Framing;
+use serde_osc::ser;
+use serde_osc::value::Value;
+
+#[test]
+fn stream_args() {
+    let blob: Vec<u8> = (0..100_001u32).map(|i| i as u8).collect();
+    let packet = ser::to_vec(&("/load", (7, ByteBuf::from(blob.clone()), "done"))).unwrap();
+    let mut rd = Cursor::new(packet);
+    let mut msg = MessageReader::new(&mut rd).unwrap();
+    assert_eq!(msg.header().address, "/load");
+    assert_eq!(msg.header().typetag, "ibs");
+
+    match msg.next_arg().unwrap() {
+        Some(StreamedArg::Value(value)) => assert_eq!(value, Value::Int(7)),
+        other => panic!("unexpected arg: {:?}", other),
+    }
+    match msg.next_arg().unwrap() {
+        Some(StreamedArg::Blob(mut reader)) => {
+            assert_eq!(reader.size(), blob.len());
+            let mut received = Vec::new();
+            reader.read_to_end(&mut received).unwrap();
+            assert_eq!(received, blob);
+            assert_eq!(reader.remaining(), 0);
+        },
+        other => panic!("unexpected arg: {:?}", other),
+    }
+    match msg.next_arg().unwrap() {
+        Some(StreamedArg::Value(value)) => assert_eq!(value, Value::String("done".to_owned())),
+        other => panic!("unexpected arg: {:?}", other),
+    }
+    assert!(msg.next_arg().unwrap().is_none());
+    msg.finish().unwrap();
+    assert_eq!(rd.position() as usize, rd.get_ref().len());
+}
+
+#[test]
+fn skip_unread_blob_data() {
+    let first = ser::to_vec(&("/b", (ByteBuf::from(vec![1u8, 2, 3, 4, 5]), 6))).unwrap();
+    let second = ser::to_vec(&("/next", ())).unwrap();
+    let mut rd = Cursor::new([first, second].concat());
+    {
+        let mut msg = MessageReader::new(&mut rd).unwrap();
+        match msg.next_arg().unwrap() {
+            Some(StreamedArg::Blob(mut reader)) => {
+                let mut start = [0u8; 2];
+                reader.read_exact(&mut start).unwrap();
+                assert_eq!(start, [1, 2]);
+            },
+            other => panic!("unexpected arg: {:?}", other),
+        }
+        match msg.next_arg().unwrap() {
+            Some(StreamedArg::Value(value)) => assert_eq!(value, Value::Int(6)),
+            other => panic!("unexpected arg: {:?}", other),
+        }
+        msg.finish().unwrap();
+    }
+    // Skipping the rest of the packet leaves the reader at the next one.
+    let mut msg = MessageReader::new(&mut rd).unwrap();
+    assert_eq!(msg.header().address, "/next");
+    assert!(msg.next_arg().unwrap().is_none());
+}
+
+#[test]
+fn finish_early() {
+    let packet = ser::to_vec(&("/b", (ByteBuf::from(vec![1u8; 9]), 6))).unwrap();
+    let mut rd = Cursor::new(packet);
+    let msg = MessageReader::new(&mut rd).unwrap();
+    msg.finish().unwrap();
+    assert_eq!(rd.position() as usize, rd.get_ref().len());
+}
+
+#[test]
+fn unframed() {
+    let packet = ser::to_vec_unframed(&("/b", (ByteBuf::from(vec![1u8, 2, 3]),))).unwrap();
+    let mut rd = Cursor::new(packet);
+    let mut msg = MessageReader::new_unframed(&mut rd).unwrap();
+    match msg.next_arg().unwrap() {
+        Some(StreamedArg::Blob(mut reader)) => {
+            let mut received = Vec::new();
+            io::copy(&mut reader, &mut received).unwrap();
+            assert_eq!(received, [1, 2, 3]);
+        },
+        other => panic!("unexpected arg: {:?}", other),
+    }
+    assert!(msg.next_arg().unwrap().is_none());
+}
+
+#[test]
+fn bad_blob_padding() {
+    // /b ,b [1, 2, 3] with non-zero padding
+    let test_input = b"\0\0\0\x10/b\0\0,b\0\0\0\0\0\x03\x01\x02\x03\x04";
+    let mut rd = Cursor::new(&test_input[..]);
+    let mut msg = MessageReader::new(&mut rd).unwrap();
+    assert!(msg.next_arg().unwrap().is_some());
+    match msg.next_arg() {
+        Err(Error::BadPadding) => {},
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn truncated_blob() {
+    // The blob claims more data than the stream holds.
+    let test_input = b"\0\0\0\x10/b\0\0,b\0\0\0\0\0\x08\x01\x02\x03\0";
+    let mut rd = Cursor::new(&test_input[..]);
+    let mut msg = MessageReader::new(&mut rd).unwrap();
+    match msg.next_arg().unwrap() {
+        Some(StreamedArg::Blob(mut reader)) => {
+            let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        },
+        other => panic!("unexpected arg: {:?}", other),
+    }
+}
+
+#[test]
+fn blob_size_limit() {
+    let packet = ser::to_vec(&("/b", (ByteBuf::from(vec![1u8; 9]),))).unwrap();
+    let options = DeserializerOptions { max_blob_size: Some(8), ..DeserializerOptions::default() };
+    let mut rd = Cursor::new(packet);
+    let mut msg = MessageReader::with_options(&mut rd, Framing::LengthPrefixed, options).unwrap();
+    match msg.next_arg() {
+        Err(Error::SizeLimitExceeded) => {},
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn bundle_rejected() {
+    let packet = ser::to_vec(&((0u32, 1u32), (("/m", (1,)),))).unwrap();
+    let mut rd = Cursor::new(packet);
+    match MessageReader::new(&mut rd) {
+        Err(Error::BadFormat) => {},
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -2,3 +2,46 @@ mod auto_derive;
 mod bundle;
 mod manual;
 
+mod unframed;
+mod stream;
+mod option;
+mod types;
+mod array;
+mod enum_msg;
+mod strings;
+mod limits;
+mod errors;
+mod map;
+mod ints;
+mod timetags;
+mod matching;
+mod peek;
+mod coercion;
+mod blobs;
+mod borrowed;
+mod message_reader;
+mod validate;
+mod unknown;
+mod decoder;
+mod legacy_typetags;
+mod strict;
+mod extensions;
+mod liblo;
+mod max_pd;
+mod supercollider;
+mod touchosc;
+mod tuples;
+mod ignored;
+mod trailing;
+mod abbreviated;
+mod inspect;
+mod length;
+mod narrowing;
+mod chars;
+mod osc_reader;
+mod endianness;
+mod newtypes;
+mod unit;
+mod packet_enum;
+mod equivalent_tags;
+mod bundle_reader;
@@ -0,0 +1,65 @@
+use serde_osc::de::{self, Coercion, DeserializerOptions, Narrowing};
+use serde_osc::error::Error;
+use serde_osc::ser;
+
+fn narrowing(narrowing: Narrowing) -> DeserializerOptions {
+    DeserializerOptions { narrowing, ..DeserializerOptions::default() }
+}
+
+/// /n ,hhd 2^40+7 -1 1e300
+fn wide() -> Vec<u8> {
+    ser::to_vec(&("/n", ((1i64 << 40) + 7, -1i64, 1e300f64))).unwrap()
+}
+
+#[test]
+fn rejected_by_default() {
+    assert!(de::from_slice::<(String, (i32, i64, f64))>(&wide()).is_err());
+    match de::from_slice::<(String, (i64, i64, f32))>(&wide()).unwrap_err().into_kind() {
+        Error::TypeMismatch { expected: 'f', found: 'd' } => {},
+        other => panic!("expected a type mismatch, got {:?}", other),
+    }
+    // Values which fit are accepted.
+    let packet = ser::to_vec(&("/n", (7i64, -1i64))).unwrap();
+    let (_, args): (String, (u8, i16)) = de::from_slice(&packet).unwrap();
+    assert_eq!(args, (7, -1));
+}
+
+#[test]
+fn saturate() {
+    let options = narrowing(Narrowing::Saturate);
+    let (_, args): (String, (i32, u32, f32)) = de::from_slice_with_options(&wide(), options).unwrap();
+    assert_eq!(args, (i32::MAX, 0, f32::MAX));
+    let (_, args): (String, (i8, i8, f64)) = de::from_slice_with_options(&wide(), options).unwrap();
+    assert_eq!(args, (i8::MAX, -1, 1e300));
+
+    let packet = ser::to_vec(&("/n", (0.1f64, f64::NEG_INFINITY, -1e300f64, i64::MIN))).unwrap();
+    let (_, (a, b, c, d)): (String, (f32, f32, f32, u64)) = de::from_slice_with_options(&packet, options).unwrap();
+    assert_eq!((a, b, c, d), (0.1, f32::NEG_INFINITY, f32::MIN, 0));
+}
+
+#[test]
+fn cast() {
+    let options = narrowing(Narrowing::Cast);
+    let (_, args): (String, (i32, u32, f32)) = de::from_slice_with_options(&wide(), options).unwrap();
+    assert_eq!(args, (7, u32::MAX, f32::INFINITY));
+    let (_, args): (String, (u8, u64, f64)) = de::from_slice_with_options(&wide(), options).unwrap();
+    assert_eq!(args, (7, u64::MAX, 1e300));
+}
+
+#[test]
+fn within_arrays() {
+    let packet = ser::to_vec(&("/n", (vec![300i32, -5],))).unwrap();
+    let options = narrowing(Narrowing::Saturate);
+    let (_, (arr,)): (String, (Vec<u8>,)) = de::from_slice_with_options(&packet, options).unwrap();
+    assert_eq!(arr, vec![255, 0]);
+}
+
+#[test]
+fn with_coercion() {
+    // Coerced floats are checked as before; narrowing only applies to integer arguments.
+    let packet = ser::to_vec(&("/n", (3e9f64,))).unwrap();
+    let options = DeserializerOptions { coercion: Coercion::Lossless, ..narrowing(Narrowing::Saturate) };
+    assert!(de::from_slice_with_options::<(String, (i32,))>(&packet, options).is_err());
+    let (_, (big,)): (String, (i64,)) = de::from_slice_with_options(&packet, options).unwrap();
+    assert_eq!(big, 3_000_000_000);
+}
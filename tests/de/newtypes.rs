@@ -0,0 +1,59 @@
+use serde_osc::{de, ser};
+use serde_osc::types::Symbol;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Freq(f32);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Address(String);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Args((Freq, Symbol));
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Note((Address, Args));
+
+#[test]
+fn transparent_arg() {
+    let packet = ser::to_vec(&("/freq", (440.0f32,))).unwrap();
+    let (_, (freq,)): (String, (Freq,)) = de::from_slice(&packet).unwrap();
+    assert_eq!(freq, Freq(440.0));
+    // The wrapped type still checks the argument.
+    let packet = ser::to_vec(&("/freq", ("440",))).unwrap();
+    assert!(de::from_slice::<(String, (Freq,))>(&packet).is_err());
+}
+
+#[test]
+fn transparent_address_and_payload() {
+    let packet = ser::to_vec(&("/note", (440.0f32, Symbol::from("sine")))).unwrap();
+    let expected = (Address("/note".to_owned()), Args((Freq(440.0), Symbol::from("sine"))));
+    assert_eq!(de::from_slice::<(Address, Args)>(&packet).unwrap(), expected);
+    assert_eq!(de::from_slice::<Note>(&packet).unwrap(), Note(expected));
+}
+
+#[test]
+fn transparent_newtype_variant_payload() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Cmd {
+        #[serde(rename = "/note")]
+        Note(Args),
+        #[serde(rename = "/freq")]
+        Freq(Freq),
+    }
+    let packet = ser::to_vec(&("/note", (440.0f32, Symbol::from("sine")))).unwrap();
+    assert_eq!(de::from_slice::<Cmd>(&packet).unwrap(), Cmd::Note(Args((Freq(440.0), Symbol::from("sine")))));
+    let packet = ser::to_vec(&("/freq", (220.0f32,))).unwrap();
+    assert_eq!(de::from_slice::<Cmd>(&packet).unwrap(), Cmd::Freq(Freq(220.0)));
+}
+
+#[test]
+fn transparent_bundle_parts() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct When((u32, u32));
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Elements(Vec<(String, (i32,))>);
+    let packet = ser::to_vec(&((1u32, 2u32), vec![("/a", (1,))])).unwrap();
+    let (when, elements): (When, Elements) = de::from_slice(&packet).unwrap();
+    assert_eq!(when, When((1, 2)));
+    assert_eq!(elements, Elements(vec![("/a".to_owned(), (1,))]));
+}
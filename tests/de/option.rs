@@ -0,0 +1,22 @@
+use serde_osc::de;
+
+#[test]
+fn option_de() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Args {
+        gain: Option<f32>,
+        channel: Option<i32>,
+        muted: bool,
+    }
+    let test_input = b"\x00\x00\x00\x14/mix\0\0\0\0,NiT\0\0\0\0\0\0\0\x03";
+    let expected = ("/mix".to_owned(), Args { gain: None, channel: Some(3), muted: true });
+    let deserialized: (String, Args) = de::from_slice(test_input).unwrap();
+    assert_eq!(deserialized, expected);
+}
+
+#[test]
+fn nil_to_unit() {
+    let test_input = b"\x00\x00\x00\x0C/nil\0\0\0\0,N\0\0";
+    let deserialized: (String, ((),)) = de::from_slice(test_input).unwrap();
+    assert_eq!(deserialized, ("/nil".to_owned(), ((),)));
+}
@@ -0,0 +1,48 @@
+use std::io::{BufReader, Cursor};
+use serde_osc::de::OscReader;
+use serde_osc::error::Error;
+
+/// A proprietary "#frame" packet: a time-tag, a count and that many blobs.
+type Frame = ((u32, u32), Vec<Vec<u8>>);
+
+fn read_frame<R: OscReader>(rd: &mut R) -> Result<Frame, Error> {
+    if rd.parse_str()? != "#frame" {
+        return Err(Error::BadFormat);
+    }
+    let timetag = rd.parse_timetag()?;
+    let count = rd.parse_i32()?;
+    let mut blobs = Vec::new();
+    for _ in 0..count {
+        let size = rd.parse_blob_size(Some(16))?;
+        blobs.push(rd.parse_blob_data(size)?);
+    }
+    Ok((timetag, blobs))
+}
+
+const FRAME: &[u8] = b"#frame\0\0\0\0\0\x01\0\0\0\x02\0\0\0\x02\0\0\0\x03abc\0\0\0\0\x01z\0\0\0";
+
+#[test]
+fn custom_format() {
+    let expected = ((1, 2), vec![b"abc".to_vec(), b"z".to_vec()]);
+    let mut cursor = Cursor::new(FRAME);
+    assert_eq!(read_frame(&mut cursor).unwrap(), expected);
+    assert_eq!(cursor.position() as usize, FRAME.len());
+    // Reading through a small buffer gives the same result.
+    let mut reader = BufReader::with_capacity(3, FRAME);
+    assert_eq!(read_frame(&mut reader).unwrap(), expected);
+}
+
+#[test]
+fn padding_is_checked() {
+    let mut corrupt = FRAME.to_vec();
+    // The padding after "abc"
+    corrupt[27] = b'!';
+    match read_frame(&mut &corrupt[..]) {
+        Err(Error::BadPadding) => {},
+        other => panic!("expected BadPadding, got {:?}", other),
+    }
+    match read_frame(&mut &FRAME[..FRAME.len() - 4]) {
+        Err(Error::Io(_)) => {},
+        other => panic!("expected an I/O error, got {:?}", other),
+    }
+}
@@ -0,0 +1,92 @@
+use std::io::Cursor;
+use serde::Deserialize;
+use serde_osc::de::{self, Deserializer};
+use serde_osc::error::Error;
+use serde_osc::ser;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Packet {
+    Message((String, (i32,))),
+    Bundle(((u32, u32), Vec<Packet>)),
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Spread {
+    Message(String, (i32,)),
+    Bundle { timetag: (u32, u32), elements: Vec<(String, (i32,))> },
+}
+
+#[test]
+fn message_or_bundle() {
+    let msg = ser::to_vec(&("/a", (1,))).unwrap();
+    let bundle = ser::to_vec(&((2u32, 3u32), (("/b", (4,)),))).unwrap();
+    assert_eq!(de::from_slice::<Packet>(&msg).unwrap(), Packet::Message(("/a".to_owned(), (1,))));
+    assert_eq!(de::from_slice::<Packet>(&bundle).unwrap(),
+        Packet::Bundle(((2, 3), vec![Packet::Message(("/b".to_owned(), (4,)))])));
+}
+
+#[test]
+fn tuple_and_struct_variants() {
+    let msg = ser::to_vec(&("/a", (1,))).unwrap();
+    let bundle = ser::to_vec(&((2u32, 3u32), (("/b", (4,)),))).unwrap();
+    assert_eq!(de::from_slice::<Spread>(&msg).unwrap(), Spread::Message("/a".to_owned(), (1,)));
+    assert_eq!(de::from_slice::<Spread>(&bundle).unwrap(),
+        Spread::Bundle { timetag: (2, 3), elements: vec![("/b".to_owned(), (4,))] });
+}
+
+#[test]
+fn nested_bundles() {
+    let inner = ((5u32, 6u32), (("/c", (7,)),));
+    let outer = ser::to_vec(&((2u32, 3u32), (("/b", (4,)), inner))).unwrap();
+    assert_eq!(de::from_slice::<Packet>(&outer).unwrap(), Packet::Bundle(((2, 3), vec![
+        Packet::Message(("/b".to_owned(), (4,))),
+        Packet::Bundle(((5, 6), vec![Packet::Message(("/c".to_owned(), (7,)))])),
+    ])));
+}
+
+#[test]
+fn stream_of_mixed_packets() {
+    let mut stream = ser::to_vec(&("/a", (1,))).unwrap();
+    stream.extend(ser::to_vec(&((0u32, 1u32), (("/b", (2,)),))).unwrap());
+    let mut rd = Cursor::new(stream);
+    let first = Packet::deserialize(&mut Deserializer::new(&mut rd)).unwrap();
+    let second = Packet::deserialize(&mut Deserializer::new(&mut rd)).unwrap();
+    assert_eq!(first, Packet::Message(("/a".to_owned(), (1,))));
+    assert_eq!(second, Packet::Bundle(((0, 1), vec![Packet::Message(("/b".to_owned(), (2,)))])));
+}
+
+#[test]
+fn wrong_contents() {
+    let msg = ser::to_vec(&("/a", ("x",))).unwrap();
+    assert!(de::from_slice::<Packet>(&msg).is_err());
+}
+
+#[test]
+fn address_enums_unaffected() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Cmd {
+        #[serde(rename = "/a")]
+        A(i32),
+        Message(i32),
+    }
+    let msg = ser::to_vec(&("/a", (1,))).unwrap();
+    assert_eq!(de::from_slice::<Cmd>(&msg).unwrap(), Cmd::A(1));
+    // Without a `Bundle` variant, bundles still can't be held.
+    let bundle = ser::to_vec(&((0u32, 1u32), (("/a", (1,)),))).unwrap();
+    match de::from_slice::<Cmd>(&bundle) {
+        Err(Error::At(ref ctx)) => assert!(matches!(ctx.error, Error::BadFormat)),
+        other => panic!("expected BadFormat, got {:?}", other),
+    }
+}
+
+#[test]
+fn round_trip() {
+    let packet = Packet::Bundle(((0, 1), vec![
+        Packet::Message(("/a".to_owned(), (1,))),
+        Packet::Bundle(((2, 3), vec![])),
+    ]));
+    let bytes = ser::to_vec(&packet).unwrap();
+    let empty: Vec<(String, ())> = Vec::new();
+    assert_eq!(bytes, ser::to_vec(&((0u32, 1u32), (("/a", (1,)), ((2u32, 3u32), empty)))).unwrap());
+    assert_eq!(de::from_slice::<Packet>(&bytes).unwrap(), packet);
+}
@@ -0,0 +1,77 @@
+use std::io::Cursor;
+use serde::Deserialize;
+use serde_osc::{de, ser};
+use serde_osc::de::Deserializer;
+use serde_osc::error::Error;
+
+#[test]
+fn peek_then_deserialize() {
+    let mut stream = ser::to_vec(&("/a", (1i32, "x"))).unwrap();
+    stream.extend(ser::to_vec(&("/b", ())).unwrap());
+    let mut rd = Cursor::new(stream);
+    {
+        let mut de = Deserializer::new(&mut rd);
+        let header = de.peek_header().unwrap();
+        assert_eq!(header.address, "/a");
+        assert_eq!(header.typetag, "is");
+        assert!(!header.is_bundle());
+        // Peeking again doesn't consume anything either.
+        assert_eq!(de.peek_header().unwrap(), header);
+        let msg = <(String, (i32, String))>::deserialize(&mut de).unwrap();
+        assert_eq!(msg, ("/a".to_owned(), (1, "x".to_owned())));
+    }
+    // The reader is left at the next packet.
+    let header = Deserializer::new(&mut rd).peek_header().unwrap();
+    assert_eq!((header.address.as_str(), header.typetag.as_str()), ("/b", ""));
+}
+
+#[test]
+fn peek_bundle() {
+    let bundle = ((5u32, 6u32), (("/m", (1i32,)),));
+    let packet = ser::to_vec(&bundle).unwrap();
+    let mut rd = Cursor::new(packet);
+    let mut de = Deserializer::new(&mut rd);
+    let header = de.peek_header().unwrap();
+    assert!(header.is_bundle());
+    assert_eq!(header.typetag, "");
+    let decoded = <((u32, u32), ((String, (i32,)),))>::deserialize(&mut de).unwrap();
+    assert_eq!(decoded.0, (5, 6));
+}
+
+#[test]
+fn peek_unframed() {
+    let packet = ser::to_vec_unframed(&("/f", (1.5f32,))).unwrap();
+    let mut rd = Cursor::new(packet);
+    let mut de = Deserializer::new_unframed(&mut rd);
+    assert_eq!(de.peek_header().unwrap().typetag, "f");
+    let (_, (arg,)) = <(String, (f32,))>::deserialize(&mut de).unwrap();
+    assert_eq!(arg, 1.5);
+}
+
+#[test]
+fn peek_malformed() {
+    // The typetag isn't terminated before the end of the packet.
+    let test_input = b"\x00\x00\x00\x08/m1\0,iii\x00\x00\x00\x08/m2\0,\0\0\0";
+    let mut rd = Cursor::new(&test_input[..]);
+    {
+        let mut de = Deserializer::new(&mut rd);
+        assert!(de.peek_header().is_err());
+        // The malformed packet is skipped when deserializing it.
+        assert!(<(String, ())>::deserialize(&mut de).is_err());
+    }
+    let msg: (String, ()) = de::from_read(&mut rd).unwrap();
+    assert_eq!(msg.0, "/m2");
+}
+
+#[test]
+fn peek_then_match() {
+    let packet = ser::to_vec(&("/a", (1i32,))).unwrap();
+    let pattern = "/b".into();
+    let mut rd = Cursor::new(packet);
+    let mut de = Deserializer::new(&mut rd).matching(&pattern);
+    assert_eq!(de.peek_header().unwrap().address, "/a");
+    match <(String, (i32,))>::deserialize(&mut de) {
+        Err(Error::AddressMismatch(_)) => {},
+        other => panic!("expected AddressMismatch, got {:?}", other),
+    }
+}
@@ -0,0 +1,41 @@
+use std::io::Cursor;
+use serde_osc::{de, ser};
+use serde_osc::de::PacketStream;
+
+#[test]
+fn stream() {
+    let test_input = b"\0\0\0\x0C/m1\0,i\0\0\0\0\0\x01\0\0\0\x0C/m2\0,i\0\0\0\0\0\x02";
+    let mut stream = PacketStream::<_, (String, (i32,))>::new(Cursor::new(&test_input[..]));
+    assert_eq!(stream.next().unwrap().unwrap(), ("/m1".to_owned(), (1,)));
+    assert_eq!(stream.next().unwrap().unwrap(), ("/m2".to_owned(), (2,)));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn write_all_read_all() {
+    let bundle = ((0u32, 1u32), (("/b", (3,)),));
+    let mut data = Vec::new();
+    ser::to_write_all(&mut data, &[("/m1", (1,)), ("/m2", (2,))]).unwrap();
+    ser::to_write_all(&mut data, Some(&bundle)).unwrap();
+    let mut expected = ser::to_vec(&("/m1", (1,))).unwrap();
+    expected.extend(ser::to_vec(&("/m2", (2,))).unwrap());
+    expected.extend(ser::to_vec(&bundle).unwrap());
+    assert_eq!(data, expected);
+
+    let mut packets = de::from_read_all::<(String, (i32,)), _>(Cursor::new(&data[..]));
+    assert_eq!(packets.next().unwrap().unwrap(), ("/m1".to_owned(), (1,)));
+    assert_eq!(packets.next().unwrap().unwrap(), ("/m2".to_owned(), (2,)));
+    // The bundle isn't a message, and ends iteration.
+    assert!(packets.next().unwrap().is_err());
+    assert!(packets.next().is_none());
+}
+
+#[test]
+fn stream_truncated() {
+    // Second packet is cut short within its length prefix.
+    let test_input = b"\0\0\0\x0C/m1\0,i\0\0\0\0\0\x01\0\0";
+    let mut stream = PacketStream::<_, (String, (i32,))>::new(Cursor::new(&test_input[..]));
+    assert!(stream.next().unwrap().is_ok());
+    assert!(stream.next().unwrap().is_err());
+    assert!(stream.next().is_none());
+}
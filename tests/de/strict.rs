@@ -0,0 +1,87 @@
+use serde_osc::{de, ser};
+use serde_osc::de::{validate_with, DeserializerOptions, StringDecoding, TypetagCompat};
+use serde_osc::error::Error;
+use serde_osc::framing::Framing;
+use serde_osc::value::Packet;
+
+fn strict() -> DeserializerOptions {
+    DeserializerOptions { strict: true, ..DeserializerOptions::default() }
+}
+
+/// Check that the unframed `packet` is accepted by default, but rejected in
+/// strict mode by every decoding path, with the error `check` expects.
+fn assert_strict_rejects<F: Fn(&Error) -> bool>(packet: &[u8], check: F) {
+    Packet::from_slice_with(packet, Framing::Unframed, DeserializerOptions::default()).unwrap();
+    let mut framed = (packet.len() as u32).to_be_bytes().to_vec();
+    framed.extend_from_slice(packet);
+
+    let errors = vec![
+        Packet::from_slice_with(packet, Framing::Unframed, strict()).unwrap_err(),
+        de::from_slice_with_options::<(String, Vec<String>)>(&framed, strict()).unwrap_err(),
+        validate_with(packet, Framing::Unframed, strict()).unwrap_err(),
+    ];
+    for error in errors {
+        assert!(check(error.kind()), "unexpected error {:?}", error);
+    }
+}
+
+#[test]
+fn accepts_well_formed_packets() {
+    let bundle = ((0u32, 1u32), (("/a", ("x",)), ("/b", (1i32, 2.0f32))));
+    let packet = ser::to_vec(&bundle).unwrap();
+    type Elements = ((String, (String,)), (String, (i32, f32)));
+    let (_, (a, b)): ((u32, u32), Elements) = de::from_slice_with_options(&packet, strict()).unwrap();
+    assert_eq!((a.1, b.1), (("x".to_owned(),), (1, 2.0)));
+    assert!(validate_with(&packet, Framing::LengthPrefixed, strict()).is_ok());
+}
+
+#[test]
+fn address_without_slash() {
+    assert_strict_rejects(b"foo\0,s\0\0x\0\0\0", |e| match *e {
+        Error::BadAddress(ref address) => address == "foo",
+        _ => false,
+    });
+}
+
+#[test]
+fn typetag_without_comma() {
+    assert_strict_rejects(b"/m\0\0s\0\0\0x\0\0\0", |e| matches!(*e, Error::MissingTypetagComma));
+}
+
+#[test]
+fn missing_typetag_despite_legacy() {
+    let options = DeserializerOptions { typetags: TypetagCompat::Legacy, ..strict() };
+    assert!(Packet::from_slice_with(b"/m\0\0", Framing::Unframed, options).is_err());
+    assert!(validate_with(b"/m\0\0", Framing::Unframed, options).is_err());
+}
+
+#[test]
+fn non_ascii_string() {
+    let packet = b"/m\0\0,s\0\0caf\xc3\xa9\0\0\0";
+    assert_strict_rejects(packet, |e| matches!(*e, Error::NonAsciiString));
+    // Also when the string would otherwise be decoded leniently.
+    let options = DeserializerOptions { strings: StringDecoding::Latin1, ..strict() };
+    let err = Packet::from_slice_with(packet, Framing::Unframed, options).unwrap_err();
+    assert!(matches!(*err.kind(), Error::NonAsciiString));
+}
+
+#[test]
+fn misaligned_length() {
+    // A 9-byte bundle element: a message, plus a stray byte.
+    let packet = b"#bundle\0\0\0\0\0\0\0\0\x01\0\0\0\x09/m\0\0,\0\0\0\0";
+    let mut framed = (packet.len() as u32).to_be_bytes().to_vec();
+    framed.extend_from_slice(packet);
+    // Trailing data by default, but misaligned in strict mode.
+    assert!(matches!(*Packet::from_slice(&framed).unwrap_err().kind(), Error::LengthMismatch { declared: 9, consumed: 8 }));
+    let errors = vec![
+        Packet::from_slice_with(&framed, Framing::LengthPrefixed, strict()).unwrap_err(),
+        validate_with(&framed, Framing::LengthPrefixed, strict()).unwrap_err(),
+        de::from_slice_with_options::<((u32, u32), ((String, ()),))>(&framed, strict()).unwrap_err(),
+    ];
+    for error in errors {
+        assert!(matches!(*error.kind(), Error::BadPadding), "unexpected error {:?}", error);
+    }
+    // Likewise for a whole packet.
+    let err = Packet::from_slice_with(b"/m\0\0,\0\0\0\0", Framing::Unframed, strict()).unwrap_err();
+    assert!(matches!(*err.kind(), Error::BadPadding));
+}
@@ -0,0 +1,76 @@
+use std::io::{self, Read};
+use serde_osc::{de, ser};
+use serde_osc::de::{DeserializerOptions, StringDecoding};
+use serde_osc::error::Error;
+
+/// Hands out one byte per `read`, so strings arrive in pieces.
+struct Trickle<'a>(&'a [u8]);
+
+impl<'a> Read for Trickle<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(1);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+// "/s" message with a single 's' argument: "caf\xE9" (Latin-1 encoded).
+const LATIN1_MSG: &[u8] = b"\x00\x00\x00\x10/s\0\0,s\0\0caf\xE9\0\0\0\0";
+
+fn decode(strings: StringDecoding) -> Result<(String, (String,)), Error> {
+    let options = DeserializerOptions { strings, ..DeserializerOptions::default() };
+    de::from_slice_with_options(LATIN1_MSG, options)
+}
+
+#[test]
+fn strict_rejects_invalid_utf8() {
+    match decode(StringDecoding::Strict).as_ref().map_err(Error::kind) {
+        Err(&Error::StrParseError(_)) => {},
+        other => panic!("expected StrParseError, got {:?}", other),
+    }
+    assert!(de::from_slice::<(String, (String,))>(LATIN1_MSG).is_err());
+}
+
+#[test]
+fn lossy_replaces_invalid_utf8() {
+    let (_, (arg,)) = decode(StringDecoding::Lossy).unwrap();
+    assert_eq!(arg, "caf\u{FFFD}");
+}
+
+#[test]
+fn latin1_fallback() {
+    let (address, (arg,)) = decode(StringDecoding::Latin1).unwrap();
+    assert_eq!(address, "/s");
+    assert_eq!(arg, "café");
+}
+
+#[test]
+fn split_reads() {
+    let msg = ("/strings".to_owned(), ("".to_owned(), "abc".to_owned(), "abcd".to_owned()));
+    let data = [ser::to_vec(&msg).unwrap(), ser::to_vec(&("/next", ("x",))).unwrap()].concat();
+    let mut rd = Trickle(&data);
+    let decoded: (String, (String, String, String)) = de::from_read(&mut rd).unwrap();
+    assert_eq!(decoded, msg);
+    // Nothing past the first packet was consumed.
+    let (address, (arg,)): (String, (String,)) = de::from_read(&mut rd).unwrap();
+    assert_eq!((address.as_str(), arg.as_str()), ("/next", "x"));
+}
+
+#[test]
+fn longer_than_read_buffer() {
+    let arg = "x".repeat(20_000);
+    let data = ser::to_vec(&("/long", (&arg,))).unwrap();
+    let (_, (decoded,)): (String, (String,)) = de::from_read(&data[..]).unwrap();
+    assert_eq!(decoded, arg);
+}
+
+#[test]
+fn data_after_terminator() {
+    // The padding after "ab" contains an 'x'.
+    let data = b"\0\0\0\x10/s\0\0,s\0\0ab\0x\0\0\0\0";
+    for result in &[de::from_slice::<(String, (String,))>(data), de::from_read(Trickle(data))] {
+        match result.as_ref().map_err(Error::kind) {
+            Err(&Error::BadPadding) => {},
+            other => panic!("expected BadPadding, got {:?}", other),
+        }
+    }
+}
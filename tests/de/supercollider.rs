@@ -0,0 +1,106 @@
+use serde::de::DeserializeOwned;
+use serde_osc::de::{self, DeserializerOptions};
+use serde_osc::error::ResultE;
+use serde_osc::framing::Framing;
+use serde_osc::value::{Message, Packet, Value};
+
+/// `/n_info` for a synth, whose "is group" flag scsynth sends as an integer.
+const N_INFO: &[u8] = b"/n_info\0,iiiii\0\0\0\0\x03\xE8\0\0\0\x01\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\0\0\0\0";
+/// The same, with the flag sent as a boolean.
+const N_INFO_BOOL: &[u8] = b"/n_info\0,iiiiF\0\0\0\0\x03\xE8\0\0\0\x01\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF";
+const STATUS_REPLY: &[u8] = b"/status.reply\0\0\0,iiiiiffdd\0\0\0\0\0\x01\0\0\0\x05\0\0\0\x02\0\0\0\x02\0\0\0\x0A\
+    ?\xC0\0\0@@\0\0@\xE7p\0\0\0\0\0@\xE7p\x10\0\0\0\0";
+/// The tree of the root group, holding group 1, holding a "sine" synth whose
+/// "amp" control is mapped to control bus 0.
+const QUERY_TREE_REPLY: &[u8] = b"/g_queryTree.reply\0\0,iiiiiiisisfss\0\0\0\0\0\x01\0\0\0\0\0\0\0\x01\
+    \0\0\0\x01\0\0\0\x01\0\0\x03\xE8\xFF\xFF\xFF\xFFsine\0\0\0\0\0\0\0\x02freq\0\0\0\0C\xDC\0\0amp\0c0\0\0";
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct NodeInfo {
+    node: i32,
+    parent: i32,
+    prev: i32,
+    next: i32,
+    is_group: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Status {
+    unused: i32,
+    ugens: i32,
+    synths: i32,
+    groups: i32,
+    synthdefs: i32,
+    avg_cpu: f32,
+    peak_cpu: f32,
+    nominal_sample_rate: f64,
+    actual_sample_rate: f64,
+}
+
+fn from_datagram<T: DeserializeOwned>(mut packet: &[u8]) -> ResultE<T> {
+    let mut de = de::Deserializer::with_options(&mut packet, Framing::Unframed, DeserializerOptions::supercollider());
+    T::deserialize(&mut de)
+}
+
+#[test]
+fn flags_as_integers_or_booleans() {
+    let expected = NodeInfo { node: 1000, parent: 1, prev: -1, next: -1, is_group: false };
+    assert_eq!(from_datagram::<(String, NodeInfo)>(N_INFO).unwrap().1, expected);
+    assert_eq!(from_datagram::<(String, NodeInfo)>(N_INFO_BOOL).unwrap().1, expected);
+    // A flag can also be read as an integer.
+    assert_eq!(from_datagram::<(String, (i32, i32, i32, i32, i32))>(N_INFO_BOOL).unwrap().1 .4, 0);
+    // The default options insist on booleans.
+    assert!(de::from_slice_unframed::<(String, NodeInfo)>(N_INFO).is_err());
+    // Other integers aren't flags.
+    let mut two = N_INFO.to_vec();
+    two[35] = 2;
+    assert!(from_datagram::<(String, NodeInfo)>(&two).is_err());
+}
+
+#[test]
+fn status_reply() {
+    let (address, status): (String, Status) = from_datagram(STATUS_REPLY).unwrap();
+    assert_eq!(address, "/status.reply");
+    assert_eq!(status, Status {
+        unused: 1,
+        ugens: 5,
+        synths: 2,
+        groups: 2,
+        synthdefs: 10,
+        avg_cpu: 1.5,
+        peak_cpu: 3.0,
+        nominal_sample_rate: 48000.0,
+        actual_sample_rate: 48000.5,
+    });
+}
+
+#[test]
+fn query_tree_reply() {
+    let options = DeserializerOptions::supercollider();
+    let msg = match Packet::from_slice_with(QUERY_TREE_REPLY, Framing::Unframed, options).unwrap() {
+        Packet::Message(msg) => msg,
+        other => panic!("expected a message, got {:?}", other),
+    };
+    assert_eq!(msg.address, "/g_queryTree.reply");
+    assert_eq!(&msg.args[7..], &[
+        Value::String("sine".to_owned()),
+        Value::Int(2),
+        Value::String("freq".to_owned()),
+        Value::Float(440.0),
+        Value::String("amp".to_owned()),
+        Value::String("c0".to_owned()),
+    ]);
+}
+
+#[test]
+fn symbols_nils_and_arrays() {
+    let msg = Message::to("/reply")
+        .arg(Value::Symbol("default".to_owned()))
+        .arg(Value::Nil)
+        .begin_array().arg(1).begin_array().arg(true).end_array().end_array()
+        .build();
+    let packet = ::serde_osc::ser::to_vec_unframed(&msg).unwrap();
+    type Args = (String, Option<i32>, (i32, (i32,)));
+    let (_, (name, nil, nested)): (String, Args) = from_datagram(&packet).unwrap();
+    assert_eq!((name.as_str(), nil, nested), ("default", None, (1, (1,))));
+}
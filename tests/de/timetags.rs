@@ -0,0 +1,123 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde_osc::{de, ser, timetag};
+use serde_osc::timetag::TimeTag;
+
+/// A bundle at 2000-01-01T00:00:00.5Z, containing the message `/m i 1`.
+const BUNDLE: &[u8] = b"\0\0\0 #bundle\0\xBC\x17\xC2\0\x80\0\0\0\0\0\0\x0C/m\0\0,i\0\0\0\0\0\x01";
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Bundle<T> {
+    timetag: T,
+    messages: ((String, (i32,)),),
+}
+
+fn expected<T>(timetag: T) -> Bundle<T> {
+    Bundle { timetag, messages: (("/m".to_owned(), (1,)),) }
+}
+
+#[test]
+fn system_time() {
+    let time = UNIX_EPOCH + Duration::from_millis(946_684_800_500);
+    let deserialized: Bundle<SystemTime> = de::from_slice(BUNDLE).unwrap();
+    assert_eq!(deserialized, expected(time));
+    assert_eq!(ser::to_vec(&deserialized).unwrap(), BUNDLE);
+}
+
+#[test]
+fn duration() {
+    let since_1900 = Duration::from_millis(3_155_673_600_500);
+    let deserialized: Bundle<Duration> = de::from_slice(BUNDLE).unwrap();
+    assert_eq!(deserialized, expected(since_1900));
+    assert_eq!(ser::to_vec(&deserialized).unwrap(), BUNDLE);
+}
+
+#[test]
+fn immediately_is_not_a_system_time() {
+    let bundle = expected(timetag::IMMEDIATELY);
+    let packet = ser::to_vec(&bundle).unwrap();
+    assert!(de::from_slice::<Bundle<SystemTime>>(&packet).is_err());
+    // It's a fraction of a nanosecond after 1900.
+    let deserialized: Bundle<Duration> = de::from_slice(&packet).unwrap();
+    assert_eq!(deserialized.timetag, Duration::from_secs(0));
+}
+
+#[test]
+fn immediately_as_none() {
+    let packet = ser::to_vec(&expected(timetag::IMMEDIATELY)).unwrap();
+    let deserialized: Bundle<Option<Duration>> = de::from_slice(&packet).unwrap();
+    assert_eq!(deserialized, expected(None));
+    assert_eq!(ser::to_vec(&deserialized).unwrap(), packet);
+    // Any other time-tag is `Some`.
+    let deserialized: Bundle<Option<SystemTime>> = de::from_slice(BUNDLE).unwrap();
+    assert_eq!(deserialized.timetag, Some(UNIX_EPOCH + Duration::from_millis(946_684_800_500)));
+    assert_eq!(ser::to_vec(&deserialized).unwrap(), BUNDLE);
+    // ... and must still fit the inner type.
+    let packet = ser::to_vec(&expected((0u32, 2u32))).unwrap();
+    assert!(de::from_slice::<Bundle<Option<SystemTime>>>(&packet).is_err());
+}
+
+#[test]
+fn immediately_as_timetag() {
+    let packet = ser::to_vec(&expected(TimeTag::Immediate)).unwrap();
+    assert_eq!(packet, ser::to_vec(&expected(timetag::IMMEDIATELY)).unwrap());
+    let deserialized: Bundle<TimeTag> = de::from_slice(&packet).unwrap();
+    assert_eq!(deserialized, expected(TimeTag::Immediate));
+
+    let deserialized: Bundle<TimeTag> = de::from_slice(BUNDLE).unwrap();
+    assert_eq!(deserialized, expected(TimeTag::At(0xBC17C200, 0x8000_0000)));
+    assert_eq!(ser::to_vec(&deserialized).unwrap(), BUNDLE);
+}
+
+#[test]
+fn secs_f64() {
+    assert_eq!(timetag::to_secs_f64((0xBC17C200, 0x8000_0000)), 3_155_673_600.5);
+    assert_eq!(timetag::from_secs_f64(3_155_673_600.5).unwrap(), (0xBC17C200, 0x8000_0000));
+    assert!(timetag::from_secs_f64(-1.0).is_err());
+    assert!(timetag::from_secs_f64(f64::NAN).is_err());
+    assert!(timetag::from_secs_f64(4_294_967_296.0).is_err());
+}
+
+#[test]
+fn conversions_round_trip() {
+    let timetag = (0xBC17C200, 0x1234_5678);
+    assert_eq!(timetag::from_system_time(timetag::to_system_time(timetag)).unwrap(), timetag);
+    let time = UNIX_EPOCH + Duration::new(1_500_000_000, 123_456_789);
+    assert_eq!(timetag::to_system_time(timetag::from_system_time(time).unwrap()), time);
+    // Beyond the first NTP era.
+    assert!(timetag::from_system_time(UNIX_EPOCH + Duration::from_secs(1 << 32)).is_err());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono_datetime() {
+    use chrono::{DateTime, TimeZone, Utc};
+    let time = Utc.timestamp_opt(946_684_800, 500_000_000).unwrap();
+    let deserialized: Bundle<DateTime<Utc>> = de::from_slice(BUNDLE).unwrap();
+    assert_eq!(deserialized, expected(time));
+    assert_eq!(timetag::from_datetime(time).unwrap(), (0xBC17C200, 0x8000_0000));
+}
+
+#[test]
+fn duration_arithmetic() {
+    let timetag = TimeTag::At(100, 0xC000_0000);
+    assert_eq!(timetag + Duration::from_millis(500), TimeTag::At(101, 0x4000_0000));
+    assert_eq!(timetag - Duration::from_millis(750), TimeTag::At(100, 0));
+    assert_eq!(TimeTag::Immediate + Duration::from_secs(1), TimeTag::Immediate);
+    assert_eq!(timetag.checked_sub(Duration::from_secs(101)), None);
+    assert_eq!(TimeTag::At(0xFFFF_FFFF, 0).checked_add(Duration::from_secs(1)), None);
+
+    let time = UNIX_EPOCH + Duration::from_secs(946_684_800);
+    assert_eq!(TimeTag::from_system_time_plus(time, Duration::from_millis(500)).unwrap(),
+               TimeTag::At(0xBC17C200, 0x8000_0000));
+}
+
+#[test]
+fn compensation() {
+    let time = UNIX_EPOCH + Duration::from_secs(946_684_800);
+    let ahead = timetag::Compensation { clock_offset: 2.25, latency: Duration::from_millis(250) };
+    assert_eq!(ahead.timetag(time).unwrap(), TimeTag::At(0xBC17C202, 0x8000_0000));
+    let behind = timetag::Compensation { clock_offset: -0.5, ..timetag::Compensation::default() };
+    assert_eq!(behind.timetag(time).unwrap(), TimeTag::At(0xBC17C1FF, 0x8000_0000));
+    let broken = timetag::Compensation { clock_offset: f64::NAN, ..timetag::Compensation::default() };
+    assert!(broken.timetag(time).is_err());
+}
@@ -0,0 +1,83 @@
+//! Packets as sent by the TouchOSC and Lemur control surface apps, byte for byte.
+
+use serde::de::DeserializeOwned;
+use serde_osc::de::{self, Compat, DeserializerOptions};
+use serde_osc::error::ResultE;
+use serde_osc::framing::Framing;
+use serde_osc::types::Color;
+use serde_osc::value::{Packet, Value};
+
+/// TouchOSC: a fader moved to the middle.
+const FADER: &[u8] = b"/1/fader1\0\0\0,f\0\0?\0\0\0";
+/// TouchOSC: the same fader being touched.
+const FADER_TOUCH: &[u8] = b"/1/fader1/z\0,f\0\0?\x80\0\0";
+/// TouchOSC: the toggle in column 2, row 3 of a multi-toggle, switched off.
+const MULTI_TOGGLE: &[u8] = b"/1/multitoggle1/2/3\0,f\0\0\0\0\0\0";
+/// TouchOSC: an XY pad, as (x, y).
+const XY: &[u8] = b"/1/xy1\0\0,ff\0>\x80\0\0?@\0\0";
+/// TouchOSC: the user switched to page 2.
+const PAGE_CHANGE: &[u8] = b"/2\0\0,\0\0\0";
+/// TouchOSC (since version 2): the color of a label, opaque orange.
+const COLOR: &[u8] = b"/label1/color\0\0\0,r\0\0\xFF\x80\0\xFF";
+/// Lemur: a fader, and the x positions of the balls of a two-ball Multiball,
+/// in a bundle timestamped "immediately".
+const LEMUR_BUNDLE: &[u8] = b"#bundle\0\0\0\0\0\0\0\0\x01\
+    \0\0\0\x14/Fader/x\0\0\0\0,f\0\0?@\0\0\
+    \0\0\0\x1C/Multiball/x\0\0\0\0,ff\0>\x80\0\0?\0\0\0";
+
+fn from_datagram<T: DeserializeOwned>(mut packet: &[u8]) -> ResultE<T> {
+    let mut de = de::Deserializer::with_options(&mut packet, Framing::Unframed, DeserializerOptions::touchosc());
+    T::deserialize(&mut de)
+}
+
+#[test]
+fn controls() {
+    let (address, (level,)): (String, (f32,)) = from_datagram(FADER).unwrap();
+    assert_eq!((address.as_str(), level), ("/1/fader1", 0.5));
+    let (_, (x, y)): (String, (f32, f32)) = from_datagram(XY).unwrap();
+    assert_eq!((x, y), (0.25, 0.75));
+}
+
+#[test]
+fn touches_and_toggles() {
+    let (address, (touched,)): (String, (bool,)) = from_datagram(FADER_TOUCH).unwrap();
+    assert_eq!((address.as_str(), touched), ("/1/fader1/z", true));
+    let (address, (on,)): (String, (bool,)) = from_datagram(MULTI_TOGGLE).unwrap();
+    assert_eq!((address.as_str(), on), ("/1/multitoggle1/2/3", false));
+    // The default options insist on booleans.
+    assert!(de::from_slice_unframed::<(String, (bool,))>(FADER_TOUCH).is_err());
+    // Only 1 and 0 are flags.
+    assert!(from_datagram::<(String, (bool,))>(FADER).is_err());
+}
+
+#[test]
+fn page_change() {
+    let (page, ()): (String, ()) = from_datagram(PAGE_CHANGE).unwrap();
+    assert_eq!(page, "/2");
+}
+
+#[test]
+fn colors() {
+    let (_, (color,)): (String, (Color,)) = from_datagram(COLOR).unwrap();
+    assert_eq!(color, Color::new(0xFF, 0x80, 0, 0xFF));
+    let packet = Packet::from_slice_with(COLOR, Framing::Unframed, DeserializerOptions::touchosc()).unwrap();
+    match packet {
+        Packet::Message(msg) => assert_eq!(msg.args, vec![Value::Color(Color::new(0xFF, 0x80, 0, 0xFF))]),
+        other => panic!("expected a message, got {:?}", other),
+    }
+}
+
+#[test]
+fn lemur_bundle() {
+    type Elements = ((String, (f32,)), (String, Vec<f32>));
+    let (timetag, ((_, (fader,)), (address, balls))): (Option<(u32, u32)>, Elements) =
+        from_datagram(LEMUR_BUNDLE).unwrap();
+    assert_eq!(timetag, None);
+    assert_eq!(fader, 0.75);
+    assert_eq!((address.as_str(), balls), ("/Multiball/x", vec![0.25, 0.5]));
+}
+
+#[test]
+fn profile() {
+    assert_eq!(DeserializerOptions::from(Compat::TouchOsc), DeserializerOptions::touchosc());
+}
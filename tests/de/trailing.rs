@@ -0,0 +1,55 @@
+use serde::de::IgnoredAny;
+use serde_osc::de::{self, DeserializerOptions, TrailingArgs};
+use serde_osc::error::Error;
+use serde_osc::ser;
+
+fn reject() -> DeserializerOptions {
+    DeserializerOptions {
+        trailing_args: TrailingArgs::Reject,
+        ..DeserializerOptions::default()
+    }
+}
+
+fn message() -> Vec<u8> {
+    ser::to_vec(&("/m", (1, 2.0f32, "three", (4, 5)))).unwrap()
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Msg {
+    #[serde(rename = "/m")]
+    M(i32, f32),
+}
+
+#[test]
+fn skipped_by_default() {
+    let (_, args): (String, (i32, f32)) = de::from_slice(&message()).unwrap();
+    assert_eq!(args, (1, 2.0));
+    assert_eq!(de::from_slice::<Msg>(&message()).unwrap(), Msg::M(1, 2.0));
+}
+
+#[test]
+fn rejected() {
+    let err = de::from_slice_with_options::<(String, (i32, f32))>(&message(), reject()).unwrap_err();
+    assert_eq!(err.context().unwrap().address.as_ref().unwrap(), "/m");
+    // The array counts as a single argument.
+    match err.into_kind() {
+        Error::TrailingArguments(2) => {},
+        other => panic!("expected 2 trailing arguments, got {:?}", other),
+    }
+    match de::from_slice_with_options::<Msg>(&message(), reject()).unwrap_err().into_kind() {
+        Error::TrailingArguments(2) => {},
+        other => panic!("expected 2 trailing arguments, got {:?}", other),
+    }
+}
+
+#[test]
+fn consumed_args_are_accepted() {
+    type All = (String, (i32, f32, String, (i32, i32)));
+    assert!(de::from_slice_with_options::<All>(&message(), reject()).is_ok());
+    // Surplus array elements don't count.
+    assert!(de::from_slice_with_options::<(String, (i32, f32, String, (i32,)))>(&message(), reject()).is_ok());
+    // Ignoring the arguments consumes them.
+    type Ignored = (String, (i32, IgnoredAny, IgnoredAny, IgnoredAny));
+    assert!(de::from_slice_with_options::<Ignored>(&message(), reject()).is_ok());
+    assert!(de::from_slice_with_options::<(String, IgnoredAny)>(&message(), reject()).is_ok());
+}
@@ -0,0 +1,37 @@
+use serde_bytes::ByteBuf;
+use serde_osc::{de, ser};
+
+#[test]
+fn mixed_args() {
+    let packet = ser::to_vec(&("/m", (1, 2.5f32, vec![1u8, 2, 3]))).unwrap();
+    let (address, args): (String, (i32, f32, Vec<u8>)) = de::from_vec(packet).unwrap();
+    assert_eq!((address.as_str(), args), ("/m", (1, 2.5, vec![1, 2, 3])));
+
+    // A blob also fills a `Vec<u8>`.
+    let packet = ser::to_vec(&("/m", (1, 2.5f32, ByteBuf::from(vec![1u8, 2, 3])))).unwrap();
+    let (_, args): (String, (i32, f32, Vec<u8>)) = de::from_vec(packet).unwrap();
+    assert_eq!(args, (1, 2.5, vec![1, 2, 3]));
+}
+
+#[test]
+fn single_arg() {
+    let (_, (i,)): (String, (i32,)) = de::from_vec(ser::to_vec(&("/i", (7,))).unwrap()).unwrap();
+    assert_eq!(i, 7);
+    let (_, (s,)): (String, (String,)) = de::from_vec(ser::to_vec(&("/s", ("hi",))).unwrap()).unwrap();
+    assert_eq!(s, "hi");
+    let (_, (b,)): (String, (bool,)) = de::from_vec(ser::to_vec(&("/b", (true,))).unwrap()).unwrap();
+    assert!(b);
+    let (_, (nested,)): (String, ((i32, f32),)) = de::from_vec(ser::to_vec(&("/a", ((1, 2.0f32),))).unwrap()).unwrap();
+    assert_eq!(nested, (1, 2.0));
+}
+
+#[test]
+fn no_args() {
+    let packet = ser::to_vec(&("/none", ())).unwrap();
+    let (address, ()): (String, ()) = de::from_vec(packet.clone()).unwrap();
+    assert_eq!(address, "/none");
+    // An empty tuple can't hold any arguments.
+    assert!(de::from_vec::<(String, ())>(ser::to_vec(&("/one", (1,))).unwrap()).is_err());
+    // Nor can a one-element tuple be filled from none.
+    assert!(de::from_vec::<(String, (i32,))>(packet).is_err());
+}
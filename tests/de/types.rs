@@ -0,0 +1,59 @@
+use serde_osc::{de, ser};
+use serde_osc::error::Error;
+use serde_osc::types::{Color, MidiMessage, Symbol};
+use serde_osc::value::Message;
+
+#[test]
+fn midi_de() {
+    let test_input = b"\x00\x00\x00\x10/midi\0\0\0,m\0\0\x00\x90\x3c\x7f";
+    let (_, (midi,)): (String, (MidiMessage,)) = de::from_slice(test_input).unwrap();
+    assert_eq!(midi, MidiMessage::new(0, 0x90, 60, 127));
+    // Can also be read as a plain byte array.
+    let (_, (raw,)): (String, ([u8; 4],)) = de::from_slice(test_input).unwrap();
+    assert_eq!(raw, [0, 0x90, 60, 127]);
+}
+
+#[test]
+fn midi_checks_tag() {
+    // Other arguments of 4 bytes aren't MIDI messages.
+    let packets = [
+        (Message::to("/midi").arg(Color::new(0, 0x90, 60, 127)).build(), 'r'),
+        (Message::to("/midi").arg(vec![0u8, 0x90, 60, 127]).build(), 'b'),
+        (Message::to("/midi").begin_array().arg(0).arg(0x90).arg(60).arg(127).end_array().build(), '['),
+    ];
+    for (packet, tag) in &packets {
+        let err = de::from_slice::<(String, (MidiMessage,))>(&ser::to_vec(packet).unwrap()).unwrap_err();
+        assert!(matches!(err.kind(), Error::TypeMismatch { expected: 'm', found } if found == tag));
+    }
+}
+
+#[test]
+fn color_de() {
+    let test_input = b"\x00\x00\x00\x10/led\0\0\0\0,r\0\0\xff\x80\x00\xff";
+    let (_, (color,)): (String, (Color,)) = de::from_slice(test_input).unwrap();
+    assert_eq!(color, Color::new(0xff, 0x80, 0x00, 0xff));
+}
+
+#[test]
+fn color_checks_tag() {
+    // A MIDI message mustn't silently turn into a color.
+    let packets = [
+        (Message::to("/led").arg(MidiMessage::new(0xff, 0x80, 0x00, 0xff)).build(), 'm'),
+        (Message::to("/led").arg(vec![0xffu8, 0x80, 0x00, 0xff]).build(), 'b'),
+        (Message::to("/led").begin_array().arg(0xff).arg(0x80).arg(0).arg(0xff).end_array().build(), '['),
+    ];
+    for (packet, tag) in &packets {
+        let err = de::from_slice::<(String, (Color,))>(&ser::to_vec(packet).unwrap()).unwrap_err();
+        assert!(matches!(err.kind(), Error::TypeMismatch { expected: 'r', found } if found == tag));
+    }
+}
+
+#[test]
+fn symbol_de() {
+    let test_input = b"\x00\x00\x00\x1C/s_new\0\0,Ss\0sine\0\0\0\0sine\0\0\0\0";
+    // Either tag deserializes into either type.
+    let (_, args): (String, (Symbol, Symbol)) = de::from_slice(test_input).unwrap();
+    assert_eq!(args, (Symbol::from("sine"), Symbol::from("sine")));
+    let (_, args): (String, (String, String)) = de::from_slice(test_input).unwrap();
+    assert_eq!(args, ("sine".to_owned(), "sine".to_owned()));
+}
@@ -0,0 +1,20 @@
+use serde_osc::de;
+
+#[test]
+fn unframed_msg() {
+    let expected = ("/m1".to_owned(), (0x5eeeeeed,));
+    let test_input = b"/m1\0,i\0\0\x5E\xEE\xEE\xED";
+    let deserialized: (String, (i32,)) = de::from_slice_unframed(test_input).unwrap();
+    assert_eq!(deserialized, expected);
+}
+
+type Msg = (String, (i32,));
+
+#[test]
+fn unframed_bundle() {
+    // Only the outermost packet is unframed; bundle elements keep their length.
+    let expected = ((0x01020304, 0x05060708), (("/m1".to_owned(), (0x5eeeeeed,)),));
+    let test_input = b"#bundle\0\x01\x02\x03\x04\x05\x06\x07\x08\x00\x00\x00\x0C/m1\0,i\0\0\x5E\xEE\xEE\xED";
+    let deserialized: ((u32, u32), (Msg,)) = de::from_slice_unframed(test_input).unwrap();
+    assert_eq!(deserialized, expected);
+}
@@ -0,0 +1,33 @@
+use serde_osc::{de, ser};
+
+const PING: &[u8] = b"\x00\x00\x00\x0C/ping\0\0\0,\0\0\0";
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct NoArgs;
+
+#[test]
+fn unit_payload() {
+    assert_eq!(de::from_slice::<(String, ())>(PING).unwrap(), ("/ping".to_owned(), ()));
+    assert_eq!(de::from_slice::<(String, NoArgs)>(PING).unwrap(), ("/ping".to_owned(), NoArgs));
+}
+
+#[test]
+fn unit_payload_with_args() {
+    let packet = ser::to_vec(&("/ping", (1,))).unwrap();
+    assert!(de::from_slice::<(String, ())>(&packet).is_err());
+    assert!(de::from_slice::<(String, NoArgs)>(&packet).is_err());
+}
+
+#[test]
+fn unit_variant_payload() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Cmd {
+        #[serde(rename = "/ping")]
+        Ping(()),
+        #[serde(rename = "/pong")]
+        Pong(NoArgs),
+    }
+    assert_eq!(de::from_slice::<Cmd>(PING).unwrap(), Cmd::Ping(()));
+    let pong = ser::to_vec(&("/pong", ())).unwrap();
+    assert_eq!(de::from_slice::<Cmd>(&pong).unwrap(), Cmd::Pong(NoArgs));
+}
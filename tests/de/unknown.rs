@@ -0,0 +1,89 @@
+use serde_bytes::ByteBuf;
+use serde_osc::{de, ser};
+use serde_osc::de::{validate_with, DeserializerOptions, UnknownTypes};
+use serde_osc::error::Error;
+use serde_osc::framing::Framing;
+use serde_osc::types::RawArg;
+use serde_osc::value::{Message, Packet, Value};
+
+fn capture() -> DeserializerOptions {
+    DeserializerOptions { unknown_types: UnknownTypes::Capture, ..DeserializerOptions::default() }
+}
+
+fn assert_unsupported<T: ::std::fmt::Debug>(result: Result<T, Error>) {
+    match result.as_ref().map_err(Error::kind) {
+        Err(&Error::UnsupportedType) => {},
+        other => panic!("expected UnsupportedType, got {:?}", other),
+    }
+}
+
+// /m ,itci 1 <timetag> 'A' 2
+const EXTENDED: &[u8] = b"/m\0\0,itci\0\0\0\0\0\0\x01\0\0\0\x02\0\0\0\x03\0\0\0\x41\0\0\0\x02";
+
+#[test]
+fn rejected_by_default() {
+    assert_unsupported(Packet::from_slice_unframed(EXTENDED));
+}
+
+#[test]
+fn capture_extended_types() {
+    let packet = Packet::from_slice_with(EXTENDED, Framing::Unframed, capture()).unwrap();
+    let expected = Message::new("/m")
+        .with_arg(1)
+        .with_arg(RawArg::new('t', vec![0, 0, 0, 2, 0, 0, 0, 3]))
+        .with_arg('A')
+        .with_arg(2);
+    assert_eq!(packet, Packet::Message(expected));
+    // Forwarding the packet reproduces it exactly.
+    assert_eq!(ser::to_vec_unframed(&packet).unwrap(), EXTENDED);
+}
+
+#[test]
+fn capture_last_arg() {
+    // An unknown 'x' argument of unknown size, followed by the end of an array.
+    let test_input = b"/m\0\0,i[x]\0\0\0\0\0\0\x01\x01\x02\x03\x04\x05\x06\x07\x08";
+    let packet = Packet::from_slice_with(test_input, Framing::Unframed, capture()).unwrap();
+    let raw = RawArg::new('x', vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    let expected = Message::new("/m").with_arg(1).with_arg(vec![Value::Unknown(raw)]);
+    assert_eq!(packet, Packet::Message(expected));
+    assert_eq!(ser::to_vec_unframed(&packet).unwrap(), &test_input[..]);
+    assert!(validate_with(test_input, Framing::Unframed, capture()).is_ok());
+}
+
+#[test]
+fn size_not_inferable() {
+    // The 'x' argument isn't the last one, so its size is unknown.
+    let test_input = b"/m\0\0,xi\0\0\0\0\x01";
+    assert_unsupported(Packet::from_slice_with(test_input, Framing::Unframed, capture()));
+    assert_unsupported(validate_with(test_input, Framing::Unframed, capture()));
+    // Misaligned data
+    let test_input = b"/m\0\0,x\0\0\x01\x02";
+    match Packet::from_slice_with(test_input, Framing::Unframed, capture()).map_err(Error::into_kind) {
+        Err(Error::BadPadding) => {},
+        other => panic!("expected BadPadding, got {:?}", other),
+    }
+}
+
+#[test]
+fn typed_deserialization() {
+    // Unknown arguments deserialize as byte buffers, or can be ignored.
+    let options = capture();
+    let (_, (i, t, x)) = de::from_slice_with_options::<(String, (i32, ByteBuf, ByteBuf))>(
+        &ser::to_vec(&("/m", (1, RawArg::new('t', vec![0; 8]), RawArg::new('x', vec![0, 0, 0, 1])))).unwrap(),
+        options,
+    ).unwrap();
+    assert_eq!((i, t.len(), Vec::from(x)), (1, 8, vec![0, 0, 0, 1]));
+}
+
+#[test]
+fn invalid_raw_args() {
+    let bad_tag = Message::new("/m").with_arg(RawArg::new('[', vec![]));
+    assert_unsupported(ser::to_vec(&bad_tag));
+    let bad_tag = Message::new("/m").with_arg(RawArg::new('é', vec![]));
+    assert_unsupported(ser::to_vec(&bad_tag));
+    let misaligned = Message::new("/m").with_arg(RawArg::new('x', vec![1]));
+    match ser::to_vec(&misaligned) {
+        Err(Error::BadPadding) => {},
+        other => panic!("expected BadPadding, got {:?}", other),
+    }
+}
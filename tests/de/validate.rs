@@ -0,0 +1,144 @@
+use serde_bytes::ByteBuf;
+use serde_osc::{de, ser};
+use serde_osc::de::{validate, validate_unframed, validate_with, DeserializerOptions, PacketInfo};
+use serde_osc::error::Error;
+use serde_osc::framing::Framing;
+use serde_osc::value::Packet;
+
+fn assert_kind<T: ::std::fmt::Debug>(result: Result<T, Error>, expected: &str) {
+    match result {
+        Err(ref e) if format!("{:?}", e.kind()).starts_with(expected) => {},
+        other => panic!("expected {}, got {:?}", expected, other),
+    }
+}
+
+#[test]
+fn message_info() {
+    let packet = ser::to_vec(&("/mixer/gain", (1, "two", ByteBuf::from(vec![3u8; 5]), [4.0f32, 5.0]))).unwrap();
+    let info = validate(&packet).unwrap();
+    assert_eq!(info, PacketInfo { size: packet.len() - 4, messages: 1, bundles: 0, depth: 0 });
+    assert_eq!(validate_unframed(&packet[4..]).unwrap(), info);
+}
+
+#[test]
+fn bundle_info() {
+    let inner = ((0u32, 2u32), (("/a", (1,)), ("/b", ("x",))));
+    let packet = ser::to_vec(&((0u32, 1u32), (("/c", ()), inner))).unwrap();
+    let info = validate(&packet).unwrap();
+    assert_eq!(info, PacketInfo { size: packet.len() - 4, messages: 3, bundles: 2, depth: 2 });
+}
+
+#[test]
+fn truncated() {
+    let packet = ser::to_vec(&("/m", (1, "two"))).unwrap();
+    for len in 0..packet.len() {
+        assert_kind(validate(&packet[..len]), "Io");
+    }
+}
+
+#[test]
+fn trailing_data() {
+    let mut packet = ser::to_vec(&("/m", (1,))).unwrap();
+    packet.extend_from_slice(&[0; 4]);
+    assert_kind(validate(&packet), "BadFormat");
+    // Data after the last argument, within the packet
+    let test_input = b"\0\0\0\x10/m\0\0,i\0\0\0\0\0\x01\0\0\0\0";
+    assert_kind(validate(&test_input[..]), "LengthMismatch { declared: 16, consumed: 12 }");
+    assert_kind(validate_unframed(&test_input[4..]), "LengthMismatch");
+}
+
+#[test]
+fn malformed_args() {
+    // Non-zero string padding
+    assert_kind(validate_unframed(b"/m\0\x01,\0\0\0"), "BadPadding");
+    // Unknown typetag
+    assert_kind(validate_unframed(b"/m\0\0,q\0\0"), "UnsupportedType");
+    // Unbalanced arrays
+    assert_kind(validate_unframed(b"/m\0\0,[i\0\0\0\0\x01"), "BadFormat");
+    assert_kind(validate_unframed(b"/m\0\0,]\0\0"), "BadFormat");
+    // Invalid UTF-8, unless decoding is lossy
+    assert_kind(validate_unframed(b"/m\0\0,s\0\0\xff\0\0\0"), "StrParseError");
+}
+
+#[test]
+fn error_context() {
+    let err = validate_unframed(b"/m\0\0,iq\0\0\0\0\x01").unwrap_err();
+    let ctx = err.context().unwrap();
+    assert_eq!(ctx.address.as_deref(), Some("/m"));
+    assert_eq!(ctx.arg_index, Some(1));
+    assert_eq!(ctx.offset, 12);
+}
+
+#[test]
+fn limits() {
+    let packet = ser::to_vec(&("/b", (ByteBuf::from(vec![1u8; 9]),))).unwrap();
+    let options = DeserializerOptions { max_blob_size: Some(8), ..DeserializerOptions::default() };
+    assert_kind(validate_with(&packet, Framing::LengthPrefixed, options), "SizeLimitExceeded");
+    let options = DeserializerOptions { max_packet_size: Some(8), ..DeserializerOptions::default() };
+    assert_kind(validate_with(&packet, Framing::LengthPrefixed, options), "SizeLimitExceeded");
+
+    let nested = ser::to_vec(&((0u32, 1u32), (((0u32, 1u32), (("/m", ()),)),))).unwrap();
+    let options = DeserializerOptions { max_depth: Some(1), ..DeserializerOptions::default() };
+    assert_kind(validate_with(&nested, Framing::LengthPrefixed, options), "DepthLimitExceeded");
+    let options = DeserializerOptions { max_depth: Some(2), ..DeserializerOptions::default() };
+    assert_eq!(validate_with(&nested, Framing::LengthPrefixed, options).unwrap().depth, 2);
+}
+
+/// A typetag of deeply nested arrays is rejected up front, rather than
+/// recursing once per '['.
+#[test]
+fn deep_arrays() {
+    let mut packet = b"/m\0\0,".to_vec();
+    packet.extend(vec![b'['; 100_000]);
+    packet.extend(vec![b']'; 100_000]);
+    packet.extend_from_slice(&[0; 4][..4 - packet.len() % 4]);
+    assert_kind(validate_unframed(&packet), "DepthLimitExceeded");
+    assert_kind(Packet::from_slice_unframed(&packet), "DepthLimitExceeded");
+    assert_kind(de::from_slice_unframed::<(String, ())>(&packet), "DepthLimitExceeded");
+}
+
+/// Minimal deterministic generator, so that failures are reproducible.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
+
+/// Corrupt valid packets at random; nothing may panic, and `validate`
+/// must agree with decoding.
+#[test]
+fn mutations_never_panic() {
+    let seeds = vec![
+        ser::to_vec(&("/a/b", (1, 2.5f32, "three", ByteBuf::from(vec![4u8; 6]), [5i64, 6]))).unwrap(),
+        ser::to_vec(&((0u32, 1u32), (("/x", (true, 1.0f64)), ((2u32, 3u32), (("/y", ("z",)),))))).unwrap(),
+    ];
+    let mut rng = Lcg(0x5eed);
+    for seed in &seeds {
+        for _ in 0..2000 {
+            let mut packet = seed.clone();
+            for _ in 0..(1 + rng.next() % 4) {
+                let pos = rng.next() as usize % packet.len();
+                match rng.next() % 4 {
+                    0 => packet[pos] = rng.next() as u8,
+                    1 => packet[pos] ^= 1 << (rng.next() % 8),
+                    2 => packet.truncate(pos),
+                    _ => packet.insert(pos, rng.next() as u8),
+                }
+                if packet.is_empty() {
+                    break;
+                }
+            }
+            let _ = de::from_slice::<(String, Vec<u8>)>(&packet);
+            let _ = Packet::from_slice(&packet);
+            let _ = validate(&packet);
+            if packet.len() >= 4 {
+                let unframed = &packet[4..];
+                assert_eq!(validate_unframed(unframed).is_ok(), Packet::from_slice_unframed(unframed).is_ok(),
+                    "disagreement on {:?}", unframed);
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use serde_osc::diff::{assert_osc_eq, assert_osc_near, diff, diff_with, Difference};
+use serde_osc::framing::Framing;
+use serde_osc::ser;
+use serde_osc::value::{Bundle, Message, Packet, Value};
+
+fn encode<P: Into<Packet>>(packet: P) -> Vec<u8> {
+    ser::to_vec(&packet.into()).unwrap()
+}
+
+#[test]
+fn equal_packets() {
+    let msg = Message::to("/synth").arg(440.0f32).arg("sine").begin_array().arg(1).arg(2).end_array().build();
+    assert_eq!(diff(&encode(msg.clone()), &encode(msg.clone())).unwrap(), vec![]);
+    assert_osc_eq(&encode(msg.clone()), &encode(msg));
+}
+
+#[test]
+fn address_and_values() {
+    let left = Message::to("/a").arg(1).arg("x").build();
+    let right = Message::to("/b").arg(2).arg("x").build();
+    assert_eq!(diff(&encode(left), &encode(right)).unwrap(), vec![
+        Difference::Address { at: "".to_owned(), left: "/a".to_owned(), right: "/b".to_owned() },
+        Difference::Value { at: "arg 0".to_owned(), left: Value::Int(1), right: Value::Int(2) },
+    ]);
+}
+
+#[test]
+fn types_and_counts() {
+    let left = Message::to("/a").arg(1).arg(true).arg(0.5f32).build();
+    let right = Message::to("/a").arg(1i64).arg(false).build();
+    assert_eq!(diff(&encode(left), &encode(right)).unwrap(), vec![
+        Difference::Count { at: "".to_owned(), left: 3, right: 2 },
+        Difference::Type { at: "arg 0".to_owned(), left: "i".to_owned(), right: "h".to_owned() },
+        // 'T' and 'F' are the same type.
+        Difference::Value { at: "arg 1".to_owned(), left: Value::Bool(true), right: Value::Bool(false) },
+    ]);
+}
+
+#[test]
+fn arrays() {
+    let left = Message::to("/a").begin_array().arg(1).arg(2).end_array().build();
+    let right = Message::to("/a").begin_array().arg(1).arg(3).arg(4).end_array().build();
+    assert_eq!(diff(&encode(left), &encode(right)).unwrap(), vec![
+        Difference::Count { at: "arg 0".to_owned(), left: 2, right: 3 },
+        Difference::Value { at: "arg 0[1]".to_owned(), left: Value::Int(2), right: Value::Int(3) },
+    ]);
+}
+
+#[test]
+fn bundles() {
+    let mut left = Bundle::new((1, 0));
+    left.elements.push(Message::to("/a").arg(1).build().into());
+    left.elements.push(Message::to("/b").build().into());
+    let mut right = Bundle::new((2, 0));
+    right.elements.push(Message::to("/a").arg(5).build().into());
+    right.elements.push(Bundle::new((1, 0)).into());
+    let differences = diff(&encode(left), &encode(right)).unwrap();
+    assert_eq!(differences, vec![
+        Difference::Timetag { at: "".to_owned(), left: (1, 0), right: (2, 0) },
+        Difference::Value { at: "element 0, arg 0".to_owned(), left: Value::Int(1), right: Value::Int(5) },
+        Difference::Address { at: "element 1".to_owned(), left: "/b".to_owned(), right: "#bundle".to_owned() },
+    ]);
+    let described: Vec<String> = differences.iter().map(Difference::to_string).collect();
+    assert_eq!(described, vec![
+        "packet: time-tag 1:0 != 2:0",
+        "element 0, arg 0: Int(1) != Int(5)",
+        "element 1: address \"/b\" != \"#bundle\"",
+    ]);
+}
+
+#[test]
+fn float_tolerance() {
+    let left = Message::to("/a").arg(0.3f32).arg(1.0f64).arg(f32::NAN).build();
+    let right = Message::to("/a").arg(0.3001f32).arg(1.0001f64).arg(f32::NAN).build();
+    let (left, right) = (encode(left), encode(right));
+    assert_eq!(diff(&left, &right).unwrap().len(), 2);
+    assert_eq!(diff_with(&left, &right, Framing::LengthPrefixed, 1e-3).unwrap(), vec![]);
+    assert_osc_near(&left, &right, 1e-3);
+}
+
+#[test]
+fn unframed() {
+    let left = ser::to_vec_unframed(&("/a", (1,))).unwrap();
+    let right = ser::to_vec_unframed(&("/a", (1,))).unwrap();
+    assert_eq!(diff_with(&left, &right, Framing::Unframed, 0.0).unwrap(), vec![]);
+}
+
+#[test]
+#[should_panic(expected = "OSC packets differ:\n  arg 0: Int(1) != Int(2)")]
+fn assert_fails() {
+    assert_osc_eq(&encode(Message::to("/a").arg(1).build()), &encode(Message::to("/a").arg(2).build()));
+}
+
+#[test]
+#[should_panic(expected = "OSC packets can't be compared")]
+fn assert_malformed() {
+    assert_osc_eq(&[0, 0, 0, 4, b'/', b'a'], &encode(Message::to("/a").build()));
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+use serde_osc::discovery::{browse, Advertisement, SERVICE_TYPE};
+
+#[test]
+fn service_type() {
+    assert_eq!(SERVICE_TYPE, "_osc._udp.local.");
+}
+
+#[test]
+fn advertise_and_browse() {
+    let advertised = match Advertisement::new("serde_osc test", 9123) {
+        Ok(advertised) => advertised,
+        // No usable network interface (e.g. in a sandbox).
+        Err(_) => return,
+    };
+    assert_eq!(advertised.fullname(), "serde_osc test._osc._udp.local.");
+    let peers = browse(Duration::from_secs(2)).unwrap();
+    // Multicast may not be routed back to this host; if it was, the service
+    // must be reported at the advertised port.
+    if let Some(peer) = peers.iter().find(|peer| peer.name == "serde_osc test") {
+        assert_eq!(peer.host, "serde-osc-test.local.");
+        assert!(peer.addresses.iter().all(|addr| addr.port() == 9123));
+    }
+    advertised.stop().unwrap();
+}
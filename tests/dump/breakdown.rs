@@ -0,0 +1,54 @@
+use serde_osc::{dump, ser};
+use serde_osc::value::{Bundle, Message, Packet};
+
+#[test]
+fn dump_msg() {
+    let bytes = ser::to_vec(&("/audio/play", (1i32, 440.0f32, "hi"))).unwrap();
+    assert_eq!(dump::dump(&bytes), "\
+0000  length 32
+0004  address \"/audio/play\"
+0010  typetag \",ifs\"
+0018  arg 0: i 1
+001c  arg 1: f 440.0
+0020  arg 2: s \"hi\"
+");
+}
+
+#[test]
+fn dump_nested_bundle() {
+    let mut bundle = Bundle::new((0, 1));
+    bundle.elements.push(Message::new("/a").with_arg(true).with_arg(2i64).into());
+    bundle.elements.push(Bundle::new((3, 4)).into());
+    let bytes = ser::to_vec_unframed(&bundle).unwrap();
+    assert_eq!(dump::dump_unframed(&bytes), "\
+0000  #bundle
+0008  timetag 0:1
+0010  element 0 (16 bytes)
+0014    address \"/a\"
+0018    typetag \",Th\"
+001c    arg 0: T
+001c    arg 1: h 2
+0024  element 1 (16 bytes)
+0028    #bundle
+0030    timetag 3:4
+");
+}
+
+#[test]
+fn dump_truncated() {
+    // The second argument is missing.
+    let bytes = b"/a\0\0,ii\0\0\0\0\x01";
+    let dumped = dump::dump_unframed(bytes);
+    assert!(dumped.starts_with("\
+0000  address \"/a\"
+0004  typetag \",ii\"
+0008  arg 0: i 1
+000c  error: "), "{}", dumped);
+}
+
+#[test]
+fn packet_debug() {
+    let packet = Packet::from(Message::new("/m").with_arg("x"));
+    let bytes = ser::to_vec_unframed(&packet).unwrap();
+    assert_eq!(format!("{:?}", packet), dump::dump_unframed(&bytes).trim_end());
+}
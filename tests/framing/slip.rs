@@ -0,0 +1,52 @@
+use std::io::Cursor;
+use serde_osc::framing::slip::{self, SlipReader, SlipWriter};
+
+#[test]
+fn slip_escape() {
+    let mut encoded = Vec::new();
+    slip::encode(b"\x01\xC0\x02\xDB\x03", &mut encoded);
+    assert_eq!(encoded, b"\xC0\x01\xDB\xDC\x02\xDB\xDD\x03\xC0".to_vec());
+
+    let mut rd = SlipReader::new(Cursor::new(encoded));
+    assert_eq!(rd.read_packet().unwrap(), Some(b"\x01\xC0\x02\xDB\x03".to_vec()));
+    assert_eq!(rd.read_packet().unwrap(), None);
+}
+
+#[test]
+fn slip_roundtrip() {
+    // 0x43dc0000 is 440.0 in f32; 0xC0 in the i32 must be escaped.
+    let msgs = [("/m1".to_owned(), (0xC0DBi32, 440.0f32)), ("/m2".to_owned(), (-1, 0.0))];
+    let mut wr = SlipWriter::new(Vec::new());
+    for msg in &msgs {
+        wr.send(msg).unwrap();
+    }
+    let stream = wr.into_inner();
+    assert_eq!(&stream[..20], b"\xC0/m1\0,if\0\0\0\xDB\xDC\xDB\xDD\x43\xdc\0\0\xC0");
+
+    let mut rd = SlipReader::new(Cursor::new(stream));
+    for msg in &msgs {
+        let received: (String, (i32, f32)) = rd.recv().unwrap().unwrap();
+        assert_eq!(&received, msg);
+    }
+    assert!(rd.recv::<(String, (i32, f32))>().unwrap().is_none());
+}
+
+#[test]
+fn slip_truncated() {
+    let mut rd = SlipReader::new(Cursor::new(b"\xC0/m1\0".to_vec()));
+    assert!(rd.read_packet().is_err());
+}
+
+#[test]
+fn slip_bad_escape() {
+    // The whole frame with the bad escape is dropped, whether or not the
+    // byte after `ESC` is the frame's own `END`.
+    for bad in [&b"\xC0\x01\xDB\x02\x03\xC0"[..], &b"\xC0\x01\xDB\xC0"[..]] {
+        let mut stream = bad.to_vec();
+        slip::encode(b"\x04\x05", &mut stream);
+        let mut rd = SlipReader::new(Cursor::new(stream));
+        assert!(rd.read_packet().is_err());
+        assert_eq!(rd.read_packet().unwrap(), Some(b"\x04\x05".to_vec()));
+        assert_eq!(rd.read_packet().unwrap(), None);
+    }
+}
@@ -0,0 +1,110 @@
+use serde_json::{self, Value as Json};
+use serde_osc::json::{from_json_value, to_json_value};
+use serde_osc::types::{Color, MidiMessage, RawArg};
+use serde_osc::value::{Bundle, Message, Packet, Value};
+
+fn json(text: &str) -> Json {
+    serde_json::from_str(text).unwrap()
+}
+
+fn every_type() -> Message {
+    Message::to("/all")
+        .arg(1).arg(-2i64).arg(0.1f32).arg(0.25f64)
+        .arg("text").arg(Value::Symbol("sym".to_owned())).arg('é')
+        .arg(vec![0u8, 1, 2, 253, 254]).arg(true).arg(false).arg(Value::Nil)
+        .arg(MidiMessage { port: 1, status: 0x90, data1: 60, data2: 127 })
+        .arg(Color::new(255, 0, 128, 64))
+        .begin_array().arg(7).begin_array().arg("nested").end_array().end_array()
+        .arg(RawArg::new('t', vec![0, 0, 0, 1, 0, 0, 0, 2]))
+        .build()
+}
+
+#[test]
+fn mapping() {
+    assert_eq!(to_json_value(&every_type().into()), json(r#"{"address": "/all", "args": [
+        {"type": "i", "value": 1},
+        {"type": "h", "value": -2},
+        {"type": "f", "value": 0.1},
+        {"type": "d", "value": 0.25},
+        {"type": "s", "value": "text"},
+        {"type": "S", "value": "sym"},
+        {"type": "c", "value": "é"},
+        {"type": "b", "value": "AAEC/f4="},
+        {"type": "T", "value": true},
+        {"type": "F", "value": false},
+        {"type": "N", "value": null},
+        {"type": "m", "value": [1, 144, 60, 127]},
+        {"type": "r", "value": [255, 0, 128, 64]},
+        {"type": "[", "value": [{"type": "i", "value": 7}, {"type": "[", "value": [{"type": "s", "value": "nested"}]}]},
+        {"type": "t", "value": "AAAAAQAAAAI="}
+    ]}"#));
+}
+
+#[test]
+fn round_trip() {
+    let packet = Packet::Bundle(Bundle {
+        timetag: (3_900_000_000, 1 << 31),
+        elements: vec![every_type().into(), Bundle::new((0, 1)).into()],
+    });
+    let encoded = to_json_value(&packet);
+    assert_eq!(encoded["timetag"], json("[3900000000, 2147483648]"));
+    assert_eq!(from_json_value(&encoded).unwrap(), packet);
+    // Through text, as a web client would receive and send it.
+    let text = serde_json::to_string(&encoded).unwrap();
+    assert_eq!(from_json_value(&serde_json::from_str(&text).unwrap()).unwrap(), packet);
+}
+
+#[test]
+fn blob_lengths() {
+    for len in 0..8 {
+        let blob: Vec<u8> = (0..len).map(|i| i * 37).collect();
+        let packet: Packet = Message::to("/b").arg(blob).build().into();
+        assert_eq!(from_json_value(&to_json_value(&packet)).unwrap(), packet);
+    }
+}
+
+#[test]
+fn non_finite_floats() {
+    let packet = Message::to("/f").arg(f32::INFINITY).arg(f64::NAN).build().into();
+    let encoded = to_json_value(&packet);
+    assert_eq!(encoded["args"][0]["value"], Json::Null);
+    match from_json_value(&encoded).unwrap() {
+        Packet::Message(msg) => match (&msg.args[0], &msg.args[1]) {
+            (&Value::Float(f), &Value::Double(d)) => assert!(f.is_nan() && d.is_nan()),
+            other => panic!("unexpected args {:?}", other),
+        },
+        other => panic!("expected a message, got {:?}", other),
+    }
+}
+
+#[test]
+fn bare_arguments() {
+    let packet = from_json_value(&json(r#"{"address": "/x", "args": [3, 5000000000, 0.5, "s", false, null, [1]]}"#)).unwrap();
+    assert_eq!(packet, Message::to("/x")
+        .arg(3).arg(5_000_000_000i64).arg(0.5f32).arg("s").arg(false).arg(Value::Nil)
+        .begin_array().arg(1).end_array()
+        .build().into());
+    // "args" may be left out.
+    assert_eq!(from_json_value(&json(r#"{"address": "/x"}"#)).unwrap(), Message::new("/x").into());
+}
+
+#[test]
+fn invalid() {
+    for text in &[
+        r#"[]"#,
+        r#"{}"#,
+        r#"{"address": 1}"#,
+        r#"{"timetag": [1]}"#,
+        r#"{"timetag": [1, -1]}"#,
+        r#"{"address": "/x", "args": [{"type": "i", "value": 3000000000}]}"#,
+        r#"{"address": "/x", "args": [{"type": "c", "value": "ab"}]}"#,
+        r#"{"address": "/x", "args": [{"type": "b", "value": "AAE"}]}"#,
+        r#"{"address": "/x", "args": [{"type": "b", "value": "A=AA"}]}"#,
+        r#"{"address": "/x", "args": [{"type": "m", "value": [1, 2, 3, 256]}]}"#,
+        r#"{"address": "/x", "args": [{"type": "t", "value": "AAE="}]}"#,
+        r#"{"address": "/x", "args": [{"type": ",", "value": null}]}"#,
+        r#"{"address": "/x", "args": [{"type": "if", "value": 1}]}"#,
+    ] {
+        assert!(from_json_value(&json(text)).is_err(), "{} should be rejected", text);
+    }
+}
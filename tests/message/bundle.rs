@@ -0,0 +1,38 @@
+use serde_osc::{de, ser};
+use serde_osc::message::{Addressed, Bundle, Message};
+use serde_osc::timetag::TimeTag;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Level {
+    channel: i32,
+    value: f32,
+}
+impl Addressed for Level {
+    const ADDRESS: &'static str = "/mixer/level";
+}
+
+#[test]
+fn bundle_of_messages() {
+    let levels: Vec<_> = (0..4).map(|channel| Message(Level { channel, value: channel as f32 / 4.0 })).collect();
+    let packet = ser::to_vec(&((5u32, 6u32), &levels)).unwrap();
+    let received: Bundle<Message<Level>> = de::from_slice(&packet).unwrap();
+    assert_eq!(received.time, TimeTag::At(5, 6));
+    assert_eq!(received.messages, levels);
+    assert_eq!(ser::to_vec(&received).unwrap(), packet);
+}
+
+#[test]
+fn empty_bundle() {
+    let bundle: Bundle<(String, (i32,))> = Bundle::new(TimeTag::Immediate, Vec::new());
+    let packet = ser::to_vec(&bundle).unwrap();
+    assert_eq!(de::from_slice::<Bundle<(String, (i32,))>>(&packet).unwrap(), bundle);
+}
+
+#[test]
+fn mismatched_message() {
+    let packet = ser::to_vec(&((0u32, 1u32), (("/mixer/level", (1, 0.5f32)), ("/other", ())))).unwrap();
+    assert!(de::from_slice::<Bundle<Message<Level>>>(&packet).is_err());
+    // A message isn't a bundle.
+    let packet = ser::to_vec(&("/mixer/level", (1, 0.5f32))).unwrap();
+    assert!(de::from_slice::<Bundle<Message<Level>>>(&packet).is_err());
+}
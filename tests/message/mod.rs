@@ -0,0 +1,2 @@
+mod typed;
+mod bundle;
@@ -0,0 +1,40 @@
+use serde_osc::{de, ser};
+use serde_osc::message::{Addressed, Message};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Play {
+    channel: i32,
+    freq: f32,
+}
+impl Addressed for Play {
+    const ADDRESS: &'static str = "/audio/play";
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Stop;
+impl Addressed for Stop {
+    const ADDRESS: &'static str = "/audio/stop";
+}
+
+#[test]
+fn typed_roundtrip() {
+    let msg = Message(Play { channel: 1, freq: 440.0 });
+    let packet = ser::to_vec(&msg).unwrap();
+    assert_eq!(packet, ser::to_vec(&("/audio/play", (1i32, 440.0f32))).unwrap());
+    let received: Message<Play> = de::from_slice(&packet).unwrap();
+    assert_eq!(received, msg);
+}
+
+#[test]
+fn typed_unit() {
+    let packet = ser::to_vec(&Message(Stop)).unwrap();
+    assert_eq!(packet, b"\0\0\0\x10/audio/stop\0,\0\0\0".to_vec());
+    let received: Message<Stop> = de::from_slice(&packet).unwrap();
+    assert_eq!(received.address(), "/audio/stop");
+}
+
+#[test]
+fn typed_wrong_address() {
+    let packet = ser::to_vec(&Message(Stop)).unwrap();
+    assert!(de::from_slice::<Message<Play>>(&packet).is_err());
+}
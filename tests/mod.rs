@@ -1,8 +1,51 @@
 #[macro_use]
 extern crate serde_derive;
+extern crate byteorder;
 extern crate serde;
 extern crate serde_bytes;
 extern crate serde_osc;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+extern crate serde_json;
+extern crate serde_transcode;
+#[cfg(feature = "tokio")]
+extern crate bytes;
+#[cfg(feature = "tokio")]
+extern crate tokio_util;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "websocket")]
+extern crate tungstenite;
 
 mod de;
 mod ser;
+mod framing;
+mod address;
+mod router;
+mod rewrite;
+mod cache;
+mod observe;
+mod message;
+mod text;
+mod dump;
+mod diff;
+mod canonical;
+mod schema;
+mod tags;
+mod schedule;
+mod throttle;
+mod recording;
+mod value;
+mod transcode;
+#[cfg(feature = "net")]
+mod net;
+#[cfg(feature = "oscquery")]
+mod oscquery;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "tokio")]
+mod codec;
+#[cfg(feature = "discovery")]
+mod discovery;
+#[cfg(feature = "websocket")]
+mod websocket;
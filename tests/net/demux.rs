@@ -0,0 +1,63 @@
+use std::cell::RefCell;
+use std::net::{IpAddr, Ipv4Addr};
+use serde_osc::demux::Demux;
+use serde_osc::error::Error;
+use serde_osc::net::OscUdpSocket;
+
+#[test]
+fn handlers_by_peer() {
+    let mut left = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut right = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let left_addr = left.socket().local_addr().unwrap();
+    let right_addr = right.socket().local_addr().unwrap();
+    let decks = RefCell::new(vec![]);
+    let mut demux = Demux::bind("127.0.0.1:0").unwrap();
+    let server = demux.socket().local_addr().unwrap();
+    demux.on_peer(left_addr, |(_, (fader,)): (String, (f32,)), peer| {
+        decks.borrow_mut().push(("left", fader));
+        peer.reply(&("/deck/ack", ("left".to_owned(),))).unwrap();
+    }).unwrap();
+    demux.on_peer(right_addr, |(_, (fader,)): (String, (f32,)), peer| {
+        decks.borrow_mut().push(("right", fader));
+        peer.reply(&("/deck/ack", ("right".to_owned(),))).unwrap();
+    }).unwrap();
+
+    right.send_to(&("/deck/fader", (0.25f32,)), server).unwrap();
+    assert_eq!(demux.recv().unwrap(), (right_addr, 1));
+    left.send_to(&("/deck/fader", (0.5f32,)), server).unwrap();
+    assert_eq!(demux.recv().unwrap(), (left_addr, 1));
+    assert_eq!(*decks.borrow(), vec![("right", 0.25), ("left", 0.5)]);
+
+    // Each reply goes back to the controller which sent the packet.
+    let ((_, (deck,)), from): ((String, (String,)), _) = left.recv_from().unwrap();
+    assert_eq!((deck.as_str(), from), ("left", server));
+    let ((_, (deck,)), from): ((String, (String,)), _) = right.recv_from().unwrap();
+    assert_eq!((deck.as_str(), from), ("right", server));
+}
+
+#[test]
+fn any_and_host() {
+    let controller = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let from = controller.socket().local_addr().unwrap();
+    let seen = RefCell::new(vec![]);
+    let mut demux = Demux::bind("127.0.0.1:0").unwrap();
+    let server = demux.socket().local_addr().unwrap();
+    demux
+        .on_any(|(address, ()): (String, ()), peer| seen.borrow_mut().push((address, peer.addr())))
+        .on_host(IpAddr::V4(Ipv4Addr::LOCALHOST), |_: (String, ()), _| seen.borrow_mut().push(("host".to_owned(), from)))
+        .on_host(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), |_: (String, ()), _| panic!("from another host"));
+    controller.send_to(&("/go", ()), server).unwrap();
+    assert_eq!(demux.recv().unwrap(), (from, 2));
+    assert_eq!(*seen.borrow(), vec![("/go".to_owned(), from), ("host".to_owned(), from)]);
+}
+
+#[test]
+fn mismatched_packet() {
+    let controller = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut demux = Demux::bind("127.0.0.1:0").unwrap();
+    let server = demux.socket().local_addr().unwrap();
+    demux.on_any(|_: (String, (i32,)), _| panic!("shouldn't be invoked"));
+    controller.send_to(&("/go", ("now",)), server).unwrap();
+    let err = demux.recv().unwrap_err();
+    assert!(matches!(err.kind(), &Error::TypeMismatch { .. }));
+}
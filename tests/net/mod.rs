@@ -0,0 +1,5 @@
+mod udp;
+mod rpc;
+mod multicast;
+mod sender;
+mod demux;
@@ -0,0 +1,39 @@
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::time::Duration;
+use serde_osc::net::OscUdpSocket;
+
+// Administratively scoped, so packets sent by the tests stay local.
+const GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 77, 77);
+
+#[test]
+fn socket_options() {
+    let socket = OscUdpSocket::bind("0.0.0.0:0").unwrap();
+    socket.set_broadcast(true).unwrap();
+    assert!(socket.socket().broadcast().unwrap());
+    socket.set_multicast_ttl(4).unwrap();
+    assert_eq!(socket.socket().multicast_ttl_v4().unwrap(), 4);
+    socket.set_multicast_loop(false).unwrap();
+    assert!(!socket.socket().multicast_loop_v4().unwrap());
+    socket.set_ttl(16).unwrap();
+    assert_eq!(socket.socket().ttl().unwrap(), 16);
+}
+
+#[test]
+fn multicast_loopback() {
+    // Multicast may not be routable in sandboxed environments; skip if so.
+    let mut receiver = match OscUdpSocket::bind_multicast_v4(GROUP, 0) {
+        Ok(receiver) => receiver,
+        Err(_) => return,
+    };
+    let port = receiver.socket().local_addr().unwrap().port();
+    receiver.socket().set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    let sender = OscUdpSocket::bind("0.0.0.0:0").unwrap();
+    sender.set_multicast_loop(true).unwrap();
+    if sender.send_to(&("/cue/go", (7,)), SocketAddrV4::new(GROUP, port)).is_err() {
+        return;
+    }
+    if let Ok(((address, (cue,)), _)) = receiver.recv_from::<(String, (i32,))>() {
+        assert_eq!((address.as_str(), cue), ("/cue/go", 7));
+    }
+    receiver.leave_multicast_v4(GROUP, Ipv4Addr::UNSPECIFIED).unwrap();
+}
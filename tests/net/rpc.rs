@@ -0,0 +1,75 @@
+use std::io::ErrorKind;
+use std::thread;
+use std::time::Duration;
+use serde_osc::error::Error;
+use serde_osc::net::OscUdpSocket;
+use serde_osc::rpc::{reply_address, RpcClient};
+
+#[test]
+fn reply_addresses() {
+    assert_eq!(reply_address("/status"), "/status.reply");
+}
+
+#[test]
+fn query_and_reply() {
+    let mut server = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let server_addr = server.socket().local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        let ((address, (a, b)), from): ((String, (i32, i32)), _) = server.recv_from().unwrap();
+        assert_eq!(address, "/add");
+        // Other messages, and bundles, are skipped.
+        server.send_to(&("/unrelated", (0,)), from).unwrap();
+        server.send_to(&((0u32, 1u32), (("/add.reply", (-1,)),)), from).unwrap();
+        server.send_to(&("/add.reply", (a + b,)), from).unwrap();
+    });
+    let client = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut rpc = RpcClient::new(client, server_addr).unwrap().with_timeout(Duration::from_secs(5));
+    let (sum,): (i32,) = rpc.call("/add", &(2, 3)).unwrap();
+    assert_eq!(sum, 5);
+    handle.join().unwrap();
+}
+
+#[test]
+fn custom_reply_address() {
+    let mut server = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let server_addr = server.socket().local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        let ((_, ()), from): ((String, ()), _) = server.recv_from().unwrap();
+        server.send_to(&("/done", ("ok",)), from).unwrap();
+    });
+    let client = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut rpc = RpcClient::new(client, server_addr).unwrap().with_timeout(Duration::from_secs(5));
+    let (status,): (String,) = rpc.call_expecting("/sync", &(), "/done").unwrap();
+    assert_eq!(status, "ok");
+    handle.join().unwrap();
+}
+
+#[test]
+fn timeout() {
+    // Nothing answers.
+    let silent = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let client = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut rpc = RpcClient::new(client, silent.socket().local_addr().unwrap()).unwrap()
+        .with_timeout(Duration::from_millis(50));
+    match rpc.call::<_, (i32,)>("/status", &()) {
+        Err(Error::Io(ref e)) if e.kind() == ErrorKind::TimedOut => {},
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+#[test]
+fn read_timeout_is_restored() {
+    let silent = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let client = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    client.socket().set_read_timeout(Some(Duration::from_secs(7))).unwrap();
+    let mut rpc = RpcClient::new(client, silent.socket().local_addr().unwrap()).unwrap()
+        .with_timeout(Duration::from_millis(20));
+    assert!(rpc.call::<_, (i32,)>("/status", &()).is_err());
+    assert_eq!(rpc.socket().socket().read_timeout().unwrap(), Some(Duration::from_secs(7)));
+    let socket = rpc.into_inner();
+    socket.socket().set_read_timeout(None).unwrap();
+    let mut rpc = RpcClient::new(socket, silent.socket().local_addr().unwrap()).unwrap()
+        .with_timeout(Duration::from_millis(20));
+    assert!(rpc.call::<_, (i32,)>("/status", &()).is_err());
+    assert_eq!(rpc.socket().socket().read_timeout().unwrap(), None);
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use std::thread;
+use serde_osc::error::Error;
+use serde_osc::net::OscUdpSocket;
+use serde_osc::sender::OscSender;
+
+#[test]
+fn named_destinations() {
+    let mut lights = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut sound = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender = OscSender::bind("127.0.0.1:0").unwrap();
+    let lights_addr = lights.socket().local_addr().unwrap();
+    sender.add_destination("lights", lights_addr).unwrap();
+    sender.add_destination("sound", sound.socket().local_addr().unwrap()).unwrap();
+    assert_eq!(sender.destination("lights"), Some(lights_addr));
+    let mut names = sender.destinations();
+    names.sort();
+    assert_eq!(names, vec!["lights", "sound"]);
+
+    // The datagram carries no length prefix.
+    assert_eq!(sender.send("lights", &("/cue/go", (12,))).unwrap(), 16);
+    sender.send("sound", &("/play", ("intro".to_owned(),))).unwrap();
+    // Buffers are reused, and mustn't carry anything over.
+    sender.send("lights", &("/blackout", ())).unwrap();
+
+    let from = sender.socket().local_addr().unwrap();
+    let received: ((String, (i32,)), _) = lights.recv_from().unwrap();
+    assert_eq!(received, (("/cue/go".to_owned(), (12,)), from));
+    let received: ((String, (String,)), _) = sound.recv_from().unwrap();
+    assert_eq!(received, (("/play".to_owned(), ("intro".to_owned(),)), from));
+    let received: ((String, ()), _) = lights.recv_from().unwrap();
+    assert_eq!(received, (("/blackout".to_owned(), ()), from));
+
+    sender.send_all(&("/stop", ())).unwrap();
+    let (stop, _): ((String, ()), _) = lights.recv_from().unwrap();
+    assert_eq!(stop.0, "/stop");
+    let (stop, _): ((String, ()), _) = sound.recv_from().unwrap();
+    assert_eq!(stop.0, "/stop");
+}
+
+#[test]
+fn unknown_destination() {
+    let sender = OscSender::bind("127.0.0.1:0").unwrap();
+    sender.add_destination("video", "127.0.0.1:9000").unwrap();
+    assert_eq!(sender.remove_destination("video"), Some("127.0.0.1:9000".parse().unwrap()));
+    assert_eq!(sender.remove_destination("video"), None);
+    assert!(matches!(sender.send("video", &("/play", ())), Err(Error::Message(_))));
+}
+
+#[test]
+fn shared_between_threads() {
+    let mut receiver = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender = Arc::new(OscSender::bind("127.0.0.1:0").unwrap());
+    sender.add_destination("mixer", receiver.socket().local_addr().unwrap()).unwrap();
+    let threads: Vec<_> = (0..4).map(|i| {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send("mixer", &(format!("/ch/{}", i), (i,))).unwrap())
+    }).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let mut received: Vec<i32> = (0..4).map(|_| {
+        let ((address, (i,)), _): ((String, (i32,)), _) = receiver.recv_from().unwrap();
+        assert_eq!(address, format!("/ch/{}", i));
+        i
+    }).collect();
+    received.sort();
+    assert_eq!(received, vec![0, 1, 2, 3]);
+}
@@ -0,0 +1,27 @@
+use serde_bytes::ByteBuf;
+use serde_osc::net::OscUdpSocket;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Message {
+    address: String,
+    args: (i32, f32, ByteBuf),
+}
+
+#[test]
+fn udp_roundtrip() {
+    let sender = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut receiver = OscUdpSocket::bind("127.0.0.1:0").unwrap();
+    let dest = receiver.socket().local_addr().unwrap();
+
+    let message = Message {
+        address: "/audio/play".to_owned(),
+        args: (1, 44100.0, ByteBuf::from(vec![0xde, 0xad, 0xbe, 0xef])),
+    };
+    let sent = sender.send_to(&message, dest).unwrap();
+    // The datagram carries no length prefix.
+    assert_eq!(sent, 36);
+
+    let (received, from): (Message, _) = receiver.recv_from().unwrap();
+    assert_eq!(received, message);
+    assert_eq!(from, sender.socket().local_addr().unwrap());
+}
@@ -0,0 +1,103 @@
+use std::cell::RefCell;
+use std::io::Cursor;
+use serde::{Deserialize, Serialize};
+use serde_osc::de::Deserializer;
+use serde_osc::error::Error;
+use serde_osc::observe::PacketSummary;
+use serde_osc::ser::{Serializer, SerializerOptions};
+use serde_osc::framing::Framing;
+
+/// Address, typetag and size.
+type Seen = (String, String, usize);
+
+thread_local! {
+    static SEEN: RefCell<Vec<Seen>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(packet: &PacketSummary) {
+    SEEN.with(|seen| seen.borrow_mut().push((packet.address.to_owned(), packet.typetag.to_owned(), packet.size)));
+}
+
+/// The packets observed since the last call.
+fn seen() -> Vec<Seen> {
+    SEEN.with(|seen| seen.borrow_mut().drain(..).collect())
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+enum Cmd {
+    #[serde(rename = "/stop")]
+    Stop,
+    #[serde(rename = "/play")]
+    Play(f32),
+}
+
+#[test]
+fn serialized_packets() {
+    let mut output = Vec::new();
+    {
+        let mut ser = Serializer::new(&mut output).with_observer(record);
+        ("/note", (60, 0.5f32)).serialize(&mut ser).unwrap();
+        Cmd::Stop.serialize(&mut ser).unwrap();
+        Cmd::Play(1.0).serialize(&mut ser).unwrap();
+        ((0u32, 1u32), (("/a", ()), ("/b", (1,)))).serialize(&mut ser).unwrap();
+    }
+    assert_eq!(seen(), vec![
+        ("/note".to_owned(), "if".to_owned(), 20),
+        ("/stop".to_owned(), "".to_owned(), 12),
+        ("/play".to_owned(), "f".to_owned(), 16),
+        ("#bundle".to_owned(), "".to_owned(), 44),
+    ]);
+    // The sizes match what was written.
+    assert_eq!(output.len(), 4 * 4 + 20 + 12 + 16 + 44);
+}
+
+#[test]
+fn backpatched_bundle() {
+    let mut output = Cursor::new(Vec::new());
+    {
+        let mut ser = Serializer::with_backpatching(&mut output, Framing::Unframed, SerializerOptions::default())
+            .with_observer(record);
+        ((0u32, 1u32), (("/a", ()),)).serialize(&mut ser).unwrap();
+    }
+    assert_eq!(seen(), vec![("#bundle".to_owned(), "".to_owned(), output.get_ref().len())]);
+}
+
+#[test]
+fn failed_serialization() {
+    let mut output = Vec::new();
+    let mut ser = Serializer::new(&mut output).with_observer(record);
+    assert!(("no slash", (1,)).serialize(&mut ser).is_err());
+    assert_eq!(seen(), vec![]);
+}
+
+#[test]
+fn deserialized_packets() {
+    let mut data = serde_osc::to_vec(&("/note", (60, 0.5f32))).unwrap();
+    data.extend(serde_osc::to_vec(&Cmd::Play(1.0)).unwrap());
+    data.extend(serde_osc::to_vec(&((0u32, 1u32), (("/a", ()),))).unwrap());
+    let mut rd = Cursor::new(data);
+    let (_, (note, _)) = <(String, (i32, f32))>::deserialize(&mut Deserializer::new(&mut rd).with_observer(record)).unwrap();
+    assert_eq!(note, 60);
+    assert_eq!(Cmd::deserialize(&mut Deserializer::new(&mut rd).with_observer(record)).unwrap(), Cmd::Play(1.0));
+    <((u32, u32), ((String, ()),))>::deserialize(&mut Deserializer::new(&mut rd).with_observer(record)).unwrap();
+    assert_eq!(seen(), vec![
+        ("/note".to_owned(), "if".to_owned(), 20),
+        ("/play".to_owned(), "f".to_owned(), 16),
+        ("#bundle".to_owned(), "".to_owned(), 28),
+    ]);
+}
+
+#[test]
+fn failed_deserialization() {
+    let mut data = serde_osc::to_vec(&("/note", (60, 0.5f32))).unwrap();
+    data.extend(serde_osc::to_vec(&("/next", ())).unwrap());
+    let mut rd = Cursor::new(data);
+    // The wrong type isn't reported, but the packet is still skipped.
+    match <(String, (String,))>::deserialize(&mut Deserializer::new(&mut rd).with_observer(record)).map_err(Error::into_kind) {
+        Err(Error::TypeMismatch { .. }) => {},
+        other => panic!("expected TypeMismatch, got {:?}", other),
+    }
+    let (address, ()) = <(String, ())>::deserialize(&mut Deserializer::new(&mut rd).with_observer(record)).unwrap();
+    assert_eq!(address, "/next");
+    assert_eq!(seen(), vec![("/next".to_owned(), "".to_owned(), 12)]);
+}
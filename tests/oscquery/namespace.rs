@@ -0,0 +1,113 @@
+use serde_osc::message::Addressed;
+use serde_osc::oscquery::{Access, Method, Namespace, Range};
+use serde_osc::value::Value;
+
+#[derive(Serialize)]
+struct Filter {
+    cutoff: f32,
+    mode: i32,
+    name: String,
+}
+
+impl Addressed for Filter {
+    const ADDRESS: &'static str = "/synth/filter";
+}
+
+fn example() -> Namespace {
+    let mut filter = Method::of(&Filter { cutoff: 0.0, mode: 0, name: String::new() }).unwrap();
+    filter.ranges = vec![
+        Range { min: Some(20.0), max: Some(20000.0), ..Range::default() },
+        Range { values: vec![Value::Int(0), Value::Int(1), Value::Int(2)], ..Range::default() },
+    ];
+    filter.description = Some("Low-pass filter".to_owned());
+    let mut volume = Method::new("/synth/volume", "f");
+    volume.access = Access::ReadWrite;
+    Namespace { methods: vec![filter, volume, Method::new("/reset", "")] }
+}
+
+#[test]
+fn method_of() {
+    let method = Method::of(&Filter { cutoff: 0.0, mode: 0, name: String::new() }).unwrap();
+    assert_eq!(method, Method::new("/synth/filter", "fis"));
+    assert_eq!(method.access, Access::WriteOnly);
+}
+
+#[test]
+fn round_trip() {
+    let namespace = example();
+    let mut parsed = Namespace::from_json(&namespace.to_json()).unwrap();
+    parsed.methods.sort_by(|a, b| a.address.cmp(&b.address));
+    let mut expected = namespace.methods.clone();
+    expected.sort_by(|a, b| a.address.cmp(&b.address));
+    assert_eq!(parsed.methods, expected);
+}
+
+#[test]
+fn tree_layout() {
+    let json: ::serde_json::Value = ::serde_json::from_str(&example().to_json()).unwrap();
+    assert_eq!(json["FULL_PATH"], "/");
+    let synth = &json["CONTENTS"]["synth"];
+    assert_eq!(synth["FULL_PATH"], "/synth");
+    assert!(synth.get("TYPE").is_none());
+    let filter = &synth["CONTENTS"]["filter"];
+    assert_eq!(filter["FULL_PATH"], "/synth/filter");
+    assert_eq!(filter["TYPE"], "fis");
+    assert_eq!(filter["ACCESS"], 2);
+    assert_eq!(filter["RANGE"][0]["MIN"], 20.0);
+    assert_eq!(filter["RANGE"][1]["VALS"], ::serde_json::json!([0, 1, 2]));
+    assert_eq!(filter["DESCRIPTION"], "Low-pass filter");
+    assert_eq!(synth["CONTENTS"]["volume"]["ACCESS"], 3);
+}
+
+#[test]
+fn parse_remote() {
+    // As served by a typical OSCQuery host, with attributes we don't model.
+    let json = r#"{
+        "FULL_PATH": "/",
+        "DESCRIPTION": "root node",
+        "CONTENTS": {
+            "layer": {
+                "FULL_PATH": "/layer",
+                "CONTENTS": {
+                    "opacity": {
+                        "FULL_PATH": "/layer/opacity",
+                        "TYPE": "f",
+                        "ACCESS": 3,
+                        "VALUE": [0.5],
+                        "RANGE": [{"MIN": 0, "MAX": 1}]
+                    },
+                    "blend": {
+                        "TYPE": ",s",
+                        "RANGE": [{"VALS": ["add", "multiply"]}]
+                    },
+                    "trigger": {
+                        "FULL_PATH": "/layer/trigger",
+                        "TYPE": "ii",
+                        "RANGE": [null, {"MAX": 127}]
+                    }
+                }
+            }
+        }
+    }"#;
+    let mut namespace = Namespace::from_json(json).unwrap();
+    namespace.methods.sort_by(|a, b| a.address.cmp(&b.address));
+    let mut blend = Method::new("/layer/blend", "s");
+    blend.ranges = vec![Range {
+        values: vec![Value::String("add".to_owned()), Value::String("multiply".to_owned())],
+        ..Range::default()
+    }];
+    let mut opacity = Method::new("/layer/opacity", "f");
+    opacity.access = Access::ReadWrite;
+    opacity.ranges = vec![Range { min: Some(0.0), max: Some(1.0), ..Range::default() }];
+    let mut trigger = Method::new("/layer/trigger", "ii");
+    trigger.ranges = vec![Range::default(), Range { max: Some(127.0), ..Range::default() }];
+    assert_eq!(namespace.methods, vec![blend, opacity, trigger]);
+}
+
+#[test]
+fn malformed() {
+    assert!(Namespace::from_json("{").is_err());
+    assert!(Namespace::from_json(r#"{"CONTENTS": {"a": {"TYPE": 5}}}"#).is_err());
+    assert!(Namespace::from_json(r#"{"CONTENTS": {"a": {"TYPE": "i", "ACCESS": 7}}}"#).is_err());
+    assert!(Namespace::from_json(r#"{"CONTENTS": {"a": {"TYPE": "i", "RANGE": [{"VALS": ["x"]}]}}}"#).is_err());
+}
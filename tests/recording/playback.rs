@@ -0,0 +1,65 @@
+use std::cell::Cell;
+use std::io::Cursor;
+use std::time::Duration;
+use serde_osc::{de, ser};
+use serde_osc::recording::{read_entry, Entry, Player, Recorder};
+
+#[test]
+fn entries() {
+    let time = Cell::new((100, 0x8000_0000));
+    let mut recorder = Recorder::with_clock(Vec::new(), || time.get());
+    recorder.record(&("/m", (1,))).unwrap();
+    time.set((101, 0));
+    let raw = ser::to_vec_unframed(&("/raw", ())).unwrap();
+    recorder.record_raw(&raw).unwrap();
+
+    let mut expected = b"\0\0\0\x64\x80\0\0\0".to_vec();
+    expected.extend(ser::to_vec(&("/m", (1,))).unwrap());
+    expected.extend(b"\0\0\0\x65\0\0\0\0\0\0\0\x0C");
+    expected.extend(&raw);
+    assert_eq!(recorder.get_ref(), &expected);
+
+    let mut file = Cursor::new(recorder.into_inner());
+    let entry = read_entry(&mut file).unwrap().unwrap();
+    assert_eq!(entry.timetag, (100, 0x8000_0000));
+    assert_eq!(de::from_slice_unframed::<(String, (i32,))>(&entry.packet).unwrap(), ("/m".to_owned(), (1,)));
+    assert_eq!(read_entry(&mut file).unwrap(), Some(Entry { timetag: (101, 0), packet: raw }));
+    assert_eq!(read_entry(&mut file).unwrap(), None);
+}
+
+#[test]
+fn truncated_entry() {
+    let mut recorder = Recorder::with_clock(Vec::new(), || (1, 0));
+    recorder.record(&("/m", (1,))).unwrap();
+    let mut file = recorder.into_inner();
+    file.pop();
+    assert!(read_entry(&mut Cursor::new(&file)).is_err());
+    // Cut within the time-tag.
+    assert!(read_entry(&mut Cursor::new(&file[..4])).is_err());
+}
+
+#[test]
+fn playback_keeps_spacing() {
+    let time = Cell::new((10, 0));
+    let mut recorder = Recorder::with_clock(Vec::new(), || time.get());
+    recorder.record(&("/a", ())).unwrap();
+    recorder.record(&("/b", ())).unwrap();
+    time.set((12, 0x4000_0000));
+    recorder.record(&("/c", ())).unwrap();
+
+    time.set((1000, 0));
+    let mut player = Player::with_clock(Cursor::new(recorder.into_inner()), || time.get());
+    let address = |packet: Vec<u8>| de::from_slice_unframed::<(String, ())>(&packet).unwrap().0;
+    assert_eq!(player.next_due().unwrap(), Some((1000, 0)));
+    assert_eq!(address(player.pop_due().unwrap().unwrap()), "/a");
+    assert_eq!(address(player.pop_due().unwrap().unwrap()), "/b");
+    assert_eq!(player.pop_due().unwrap(), None);
+    assert_eq!(player.next_due().unwrap(), Some((1002, 0x4000_0000)));
+    assert_eq!(player.time_until_next().unwrap(), Some(Duration::from_millis(2250)));
+
+    time.set((1003, 0));
+    assert_eq!(player.time_until_next().unwrap(), Some(Duration::from_secs(0)));
+    assert_eq!(address(player.wait_next().unwrap().unwrap()), "/c");
+    assert_eq!(player.time_until_next().unwrap(), None);
+    assert_eq!(player.wait_next().unwrap(), None);
+}
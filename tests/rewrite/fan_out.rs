@@ -0,0 +1,59 @@
+use serde_osc::rewrite::{fan_out, fan_out_unframed};
+use serde_osc::ser;
+
+const NAMESPACE: [&str; 4] = ["/track/1/mute", "/track/2/mute", "/track/10/mute", "/track/1/solo"];
+
+fn mute(address: &str) -> (String, (bool, String)) {
+    (address.to_owned(), (true, "fade".to_owned()))
+}
+
+#[test]
+fn pattern() {
+    let packet = ser::to_vec(&mute("/track/?/mute")).unwrap();
+    let packets = fan_out(&packet, &NAMESPACE).unwrap();
+    assert_eq!(packets, vec![
+        ser::to_vec(&mute("/track/1/mute")).unwrap(),
+        ser::to_vec(&mute("/track/2/mute")).unwrap(),
+    ]);
+}
+
+#[test]
+fn literal_and_unmatched() {
+    // Literal addresses are sent as they are, even outside the namespace.
+    let packet = ser::to_vec(&mute("/master/mute")).unwrap();
+    assert_eq!(fan_out(&packet, &NAMESPACE).unwrap(), vec![packet]);
+
+    let packet = ser::to_vec(&mute("/bus/*/mute")).unwrap();
+    assert!(fan_out(&packet, &NAMESPACE).unwrap().is_empty());
+}
+
+#[test]
+fn unframed() {
+    let namespace: Vec<String> = NAMESPACE.iter().map(|a| a.to_string()).collect();
+    let packet = ser::to_vec_unframed(&mute("/track/{1,10}/mute")).unwrap();
+    assert_eq!(fan_out_unframed(&packet, &namespace).unwrap(), vec![
+        ser::to_vec_unframed(&mute("/track/1/mute")).unwrap(),
+        ser::to_vec_unframed(&mute("/track/10/mute")).unwrap(),
+    ]);
+}
+
+#[test]
+fn bundle_expanded_in_place() {
+    let packet = ser::to_vec(&((5u32, 6u32), (
+        mute("/track/1/*"),
+        ((0u32, 1u32), (mute("/bus/*"), mute("/master/mute"))),
+    ))).unwrap();
+    let packets = fan_out(&packet, &NAMESPACE).unwrap();
+    let expected = ser::to_vec(&((5u32, 6u32), (
+        mute("/track/1/mute"),
+        mute("/track/1/solo"),
+        ((0u32, 1u32), (mute("/master/mute"),)),
+    ))).unwrap();
+    assert_eq!(packets, vec![expected]);
+}
+
+#[test]
+fn truncated() {
+    let packet = ser::to_vec(&mute("/track/*/mute")).unwrap();
+    assert!(fan_out(&packet[..packet.len() - 1], &NAMESPACE).is_err());
+}
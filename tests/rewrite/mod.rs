@@ -0,0 +1,65 @@
+mod fan_out;
+
+use serde_bytes::ByteBuf;
+use serde_osc::error::Error;
+use serde_osc::rewrite::{add_prefix, rewrite_address, rewrite_address_unframed, strip_prefix};
+use serde_osc::{de, ser};
+
+type Msg = (String, (i32, String, Vec<u8>));
+
+fn msg(address: &str) -> (String, (i32, String, ByteBuf)) {
+    (address.to_owned(), (1, "two".to_owned(), ByteBuf::from(vec![3, 4, 5])))
+}
+
+#[test]
+fn message() {
+    let packet = ser::to_vec(&msg("/bridge/synth/1")).unwrap();
+    let rewritten = rewrite_address(&packet, strip_prefix("/bridge")).unwrap();
+    // The arguments are copied verbatim.
+    assert_eq!(rewritten, ser::to_vec(&msg("/synth/1")).unwrap());
+    let decoded: Msg = de::from_slice(&rewritten).unwrap();
+    assert_eq!(decoded.0, "/synth/1");
+    assert_eq!((decoded.1).2, vec![3, 4, 5]);
+
+    let restored = rewrite_address(&rewritten, add_prefix("/bridge")).unwrap();
+    assert_eq!(restored, packet);
+}
+
+#[test]
+fn unframed() {
+    let packet = ser::to_vec_unframed(&msg("/a")).unwrap();
+    let rewritten = rewrite_address_unframed(&packet, add_prefix("/longer/prefix")).unwrap();
+    assert_eq!(rewritten, ser::to_vec_unframed(&msg("/longer/prefix/a")).unwrap());
+}
+
+#[test]
+fn bundle_lengths_recomputed() {
+    let bundle = |prefix: &str| {
+        ((5u32, 6u32), (
+            msg(&format!("{}/a", prefix)),
+            ((0u32, 1u32), (msg(&format!("{}/b", prefix)),)),
+        ))
+    };
+    let packet = ser::to_vec(&bundle("/bridge")).unwrap();
+    let rewritten = rewrite_address(&packet, strip_prefix("/bridge")).unwrap();
+    assert_eq!(rewritten, ser::to_vec(&bundle("")).unwrap());
+}
+
+#[test]
+fn strip_prefix_whole_components() {
+    let mut strip = strip_prefix("/bridge");
+    assert_eq!(strip("/bridge"), Some("/".to_owned()));
+    assert_eq!(strip("/bridge/x"), Some("/x".to_owned()));
+    assert_eq!(strip("/bridges/x"), None);
+    assert_eq!(strip("/other"), None);
+}
+
+#[test]
+fn invalid_address() {
+    let packet = ser::to_vec(&msg("/a")).unwrap();
+    match rewrite_address(&packet, |_| Some("no slash".to_owned())).unwrap_err() {
+        Error::BadAddress(address) => assert_eq!(address, "no slash"),
+        other => panic!("expected BadAddress, got {:?}", other),
+    }
+    assert!(rewrite_address(&packet[..packet.len() - 1], |_| None).is_err());
+}
@@ -0,0 +1,90 @@
+use serde_osc::error::Error;
+use serde_osc::ser;
+use serde_osc::router::Router;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct PlayArgs {
+    channel: i32,
+    freq: f32,
+}
+
+#[test]
+fn dispatch() {
+    let mut played = Vec::new();
+    let mut stopped = 0;
+    {
+        let mut router = Router::new();
+        router.at("/audio/play", |args: PlayArgs| played.push(args))
+            .at("/audio/stop", |_: ()| stopped += 1);
+
+        let packet = ser::to_vec(&("/audio/play", (1i32, 440.0f32))).unwrap();
+        assert_eq!(router.dispatch(&packet).unwrap(), 1);
+        let packet = ser::to_vec_unframed(&("/audio/stop", ())).unwrap();
+        assert_eq!(router.dispatch_unframed(&packet).unwrap(), 1);
+        let packet = ser::to_vec(&("/video/stop", ())).unwrap();
+        assert_eq!(router.dispatch(&packet).unwrap(), 0);
+        // Incoming address is a pattern.
+        let packet = ser::to_vec(&("/audio/st*", ())).unwrap();
+        assert_eq!(router.dispatch(&packet).unwrap(), 1);
+        // Wrong argument types surface as an error.
+        let packet = ser::to_vec(&("/audio/play", ("x",))).unwrap();
+        assert!(router.dispatch(&packet).is_err());
+    }
+    assert_eq!(played, vec![PlayArgs { channel: 1, freq: 440.0 }]);
+    assert_eq!(stopped, 2);
+}
+
+#[test]
+fn dispatch_bundle() {
+    let mut seen = Vec::new();
+    {
+        let mut router = Router::new();
+        router.at("/a", |args: (i32,)| seen.push(args.0));
+        let packet = ser::to_vec(&((0u32, 1u32), (("/a", (1i32,)), ("/b", ()), ("/a", (2i32,))))).unwrap();
+        assert_eq!(router.dispatch(&packet).unwrap(), 2);
+    }
+    assert_eq!(seen, vec![1, 2]);
+}
+
+#[test]
+fn deeply_nested_bundle() {
+    // Bundles nested 100,000 deep, around a message: each level is its
+    // header, time-tag and the length of the next.
+    let msg = b"/a\0\0,\0\0\0";
+    let levels = 100_000;
+    let mut packet = Vec::with_capacity(levels * 20 + msg.len());
+    for level in 0..levels {
+        packet.extend_from_slice(b"#bundle\0\0\0\0\0\0\0\0\x01");
+        let inner = (levels - level - 1) * 20 + msg.len();
+        packet.extend_from_slice(&(inner as i32).to_be_bytes());
+    }
+    packet.extend_from_slice(msg);
+    let mut router = Router::new();
+    router.at("/a", |()| panic!("shouldn't be reached"));
+    assert!(matches!(router.dispatch_unframed(&packet), Err(Error::DepthLimitExceeded)));
+}
+
+#[test]
+fn element_longer_than_bundle() {
+    let packet = b"#bundle\0\0\0\0\0\0\0\0\x01\x7F\xFF\xFF\xFF/a\0\0,\0\0\0";
+    assert!(matches!(Router::new().dispatch_unframed(packet), Err(Error::BadFormat)));
+}
+
+#[test]
+fn mismatched_route_doesnt_stop_dispatch() {
+    let mut names = Vec::new();
+    let mut ints = Vec::new();
+    let mut later = Vec::new();
+    let result = {
+        let mut router = Router::new();
+        router.at("/n", |args: (String,)| names.push(args.0))
+            .at("/n", |args: (i32,)| ints.push(args.0))
+            .at("/m", |args: (i32,)| later.push(args.0));
+        let packet = ser::to_vec(&((0u32, 1u32), (("/n", (1i32,)), ("/m", (2i32,))))).unwrap();
+        router.dispatch(&packet)
+    };
+    // The other matching route, and the rest of the bundle, are still dispatched.
+    assert!(matches!(result.unwrap_err().kind(), Error::TypeMismatch { expected: 's', found: 'i' }));
+    assert!(names.is_empty());
+    assert_eq!((ints, later), (vec![1], vec![2]));
+}
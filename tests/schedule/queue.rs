@@ -0,0 +1,60 @@
+use std::cell::Cell;
+use std::time::Duration;
+use serde_osc::schedule::{Clock, Scheduler, SystemClock, IMMEDIATELY};
+use serde_osc::value::{Bundle, Message, Packet};
+
+fn bundle(timetag: (u32, u32), elements: Vec<Packet>) -> Packet {
+    let mut bundle = Bundle::new(timetag);
+    bundle.elements = elements;
+    bundle.into()
+}
+
+fn msg(address: &str) -> Packet {
+    Message::new(address).into()
+}
+
+fn addresses(msgs: Vec<Message>) -> Vec<String> {
+    msgs.into_iter().map(|msg| msg.address).collect()
+}
+
+#[test]
+fn releases_in_timetag_order() {
+    let time = Cell::new((10, 0));
+    let mut scheduler = Scheduler::with_clock(|| time.get());
+    scheduler.push(bundle((12, 0), vec![msg("/c1"), msg("/c2")]));
+    scheduler.push(bundle((11, 0), vec![msg("/b")]));
+    scheduler.push(msg("/a"));
+    assert_eq!(scheduler.len(), 4);
+
+    assert_eq!(addresses(scheduler.drain_due()), vec!["/a"]);
+    assert_eq!(scheduler.next_due(), Some((11, 0)));
+    time.set((11, 0x8000_0000));
+    assert_eq!(addresses(scheduler.drain_due()), vec!["/b"]);
+    assert_eq!(scheduler.time_until_next(), Some(Duration::from_millis(500)));
+    time.set((13, 0));
+    assert_eq!(addresses(scheduler.drain_due()), vec!["/c1", "/c2"]);
+    assert!(scheduler.is_empty());
+    assert_eq!(scheduler.time_until_next(), None);
+}
+
+#[test]
+fn nested_bundles() {
+    let time = Cell::new((10, 0));
+    let mut scheduler = Scheduler::with_clock(|| time.get());
+    // A nested bundle can't be due before its enclosing bundle.
+    scheduler.push(bundle((20, 0), vec![
+        bundle((15, 0), vec![msg("/early")]),
+        bundle(IMMEDIATELY, vec![msg("/immediate")]),
+        bundle((30, 0), vec![msg("/late")]),
+    ]));
+    time.set((25, 0));
+    assert_eq!(addresses(scheduler.drain_due()), vec!["/early", "/immediate"]);
+    time.set((30, 0));
+    assert_eq!(addresses(scheduler.drain_due()), vec!["/late"]);
+}
+
+#[test]
+fn system_clock_uses_osc_epoch() {
+    // 2020-01-01, counted from 1900-01-01.
+    assert!(SystemClock.now().0 > 3_786_825_600);
+}
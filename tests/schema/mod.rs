@@ -0,0 +1,59 @@
+use serde_osc::error::Error;
+use serde_osc::schema::{MessageSchema, Tag};
+use serde_osc::value::{Message, Packet, Value};
+
+#[derive(Serialize)]
+struct Fader {
+    track: i32,
+    level: f32,
+    label: String,
+}
+
+#[test]
+fn forces_typetags() {
+    let schema = MessageSchema::new().arg(Tag::Long).arg(Tag::Double).arg(Tag::Symbol);
+    let fader = Fader { track: 3, level: 0.5, label: "vox".to_owned() };
+    let packet = schema.to_vec(&("/fader", fader)).unwrap();
+    let msg = match Packet::from_slice(&packet).unwrap() {
+        Packet::Message(msg) => msg,
+        Packet::Bundle(_) => panic!("expected a message"),
+    };
+    assert_eq!(msg.typetag(), "hdS");
+    assert_eq!(msg.args, vec![Value::Long(3), Value::Double(0.5), Value::Symbol("vox".to_owned())]);
+}
+
+#[test]
+fn any_and_trailing_args_unchanged() {
+    let schema = MessageSchema::new().arg(Tag::Any).arg(Tag::Float);
+    let packet = schema.to_vec_unframed(&("/a", (1, 2, "three"))).unwrap();
+    let (_, args): (String, (i32, f32, String)) = serde_osc::de::from_slice_unframed(&packet).unwrap();
+    assert_eq!(args, (1, 2.0, "three".to_owned()));
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(Tag::Int.convert(Value::Long(-7)).unwrap(), Value::Int(-7));
+    assert_eq!(Tag::Int.convert(Value::Double(2.9)).unwrap(), Value::Int(2));
+    assert_eq!(Tag::Float.convert(Value::Int(3)).unwrap(), Value::Float(3.0));
+    assert_eq!(Tag::Char.convert(Value::from("x")).unwrap(), Value::Char('x'));
+    assert_eq!(Tag::String.convert(Value::Char('x')).unwrap(), Value::from("x"));
+    assert!(matches!(Tag::Int.convert(Value::Long(1 << 40)), Err(Error::BadCast(_))));
+    assert!(matches!(Tag::Char.convert(Value::from("xy")),
+        Err(Error::TypeMismatch { expected: 'c', found: 's' })));
+    assert!(matches!(Tag::Int.convert(Value::Bool(true)),
+        Err(Error::TypeMismatch { expected: 'i', found: 'T' })));
+}
+
+#[test]
+fn too_few_args() {
+    let mut msg = Message::to("/a").arg(1).build();
+    let schema = MessageSchema::new().arg(Tag::Long).arg(Tag::Long);
+    assert!(matches!(schema.apply(&mut msg), Err(Error::BadFormat)));
+}
+
+#[test]
+fn bundles_rejected() {
+    let schema = MessageSchema::new().arg(Tag::Long);
+    let bundle = ((0u32, 1u32), (("/a", (1,)),));
+    assert!(matches!(schema.to_vec(&bundle), Err(Error::BadFormat)));
+}
@@ -0,0 +1,50 @@
+use serde_osc::error::Error;
+use serde_osc::ser::{self, AddressPolicy, SerializerOptions};
+
+fn with_policy(address_policy: AddressPolicy) -> SerializerOptions {
+    SerializerOptions { address_policy }
+}
+
+fn assert_bad_address<T: ::std::fmt::Debug>(result: Result<T, Error>, address: &str) {
+    match result {
+        Err(Error::BadAddress(ref bad)) if bad == address => {},
+        other => panic!("expected BadAddress({:?}), got {:?}", address, other),
+    }
+}
+
+#[test]
+fn invalid_addresses() {
+    for address in ["no/slash", "/with space", "/#bundle", "/tab\t", ""].iter() {
+        assert_bad_address(ser::to_vec(&(*address, ())), address);
+    }
+    // Patterns are valid addresses.
+    assert!(ser::to_vec(&("/mixer/*/gain{1,2}", ())).is_ok());
+}
+
+#[test]
+fn invalid_in_bundle() {
+    let bundle = ((0u32, 1u32), (("/ok", ()), ("bad", ())));
+    assert_bad_address(ser::to_vec(&bundle), "bad");
+}
+
+#[test]
+fn invalid_variant() {
+    #[derive(Serialize)]
+    enum Cmd {
+        Stop,
+    }
+    assert_bad_address(ser::to_vec(&Cmd::Stop), "Stop");
+    let unchecked = ser::to_vec_with_options(&Cmd::Stop, with_policy(AddressPolicy::Unchecked)).unwrap();
+    assert_eq!(unchecked, b"\0\0\0\x0CStop\0\0\0\0,\0\0\0");
+}
+
+#[test]
+fn sanitize() {
+    let options = with_policy(AddressPolicy::Sanitize);
+    let packet = ser::to_vec_with_options(&("my synth#1\x07", ()), options).unwrap();
+    let (address, ()): (String, ()) = serde_osc::from_slice(&packet).unwrap();
+    assert_eq!(address, "/my%20synth%231");
+    // Valid addresses are left alone.
+    let packet = ser::to_vec_with_options(&("/a/b", ()), options).unwrap();
+    assert_eq!(packet, ser::to_vec(&("/a/b", ())).unwrap());
+}
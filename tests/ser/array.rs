@@ -0,0 +1,25 @@
+use serde_osc::ser;
+
+#[test]
+fn array_ser() {
+    let test_input = ("/arr", (1i32, vec![2.0f32, 3.0], ((4i32, "x"), Vec::<i32>::new())));
+    let expected = b"\x00\x00\x00\x2C/arr\0\0\0\0,i[ff][[is][]]\0\0\0\0\0\x01\x40\0\0\0\x40\x40\0\0\0\0\0\x04x\0\0\0".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn fixed_size_arrays() {
+    #[derive(Serialize)]
+    struct Mixer {
+        levels: [f32; 2],
+        #[serde(with = "serde_osc::array")]
+        mutes: [i32; 3],
+    }
+    let mixer = ("/mix", Mixer { levels: [1.0, 0.5], mutes: [0, 1, 0] });
+    let expected = b"\0\0\0\x28/mix\0\0\0\0,[ff][iii]\0\0\x3f\x80\0\0\x3f\0\0\0\0\0\0\0\0\0\0\x01\0\0\0\0".to_vec();
+    assert_eq!(ser::to_vec(&mixer).unwrap(), expected);
+
+    // As the whole argument list, the elements are the arguments.
+    let expected = b"\0\0\0\x14/lvl\0\0\0\0,ii\0\0\0\0\x01\0\0\0\x02".to_vec();
+    assert_eq!(ser::to_vec(&("/lvl", [1i32, 2])).unwrap(), expected);
+}
@@ -1,6 +1,8 @@
 use std::io::{Cursor, Write};
 use serde::Serialize;
 use serde_osc::ser::Serializer;
+use serde_osc::ser;
+use serde_osc::value::{Bundle, Message, Packet};
 
 #[test]
 fn bundle() {
@@ -43,3 +45,46 @@ fn bundle() {
     }
     assert_eq!(output.into_inner(), expected);
 }
+
+#[test]
+fn nested_bundle() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Inner {
+        timestamp: (u32, u32),
+        messages: ((String, (i32,)),),
+    }
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Outer {
+        timestamp: (u32, u32),
+        bundles: (Inner,),
+    }
+    let test_input = Outer {
+        timestamp: (0, 1),
+        bundles: (Inner {
+            timestamp: (2, 3),
+            messages: (("/m".to_owned(), (7,)),),
+        },),
+    };
+    let expected = b"\0\0\0\x34#bundle\0\0\0\0\0\0\0\0\x01\0\0\0\x20#bundle\0\0\0\0\x02\0\0\0\x03\0\0\0\x0C/m\0\0,i\0\0\0\0\0\x07";
+    let serialized = serde_osc::to_vec(&test_input).unwrap();
+    assert_eq!(serialized, &expected[..]);
+    assert_eq!(serde_osc::from_slice::<Outer>(&serialized).unwrap(), test_input);
+}
+
+#[test]
+fn deeply_nested_bundles() {
+    // Each bundle's length is patched in once its elements are written; they
+    // must come out right at every level.
+    let mut packet: Packet = Message::to("/leaf").arg(vec![1u8, 2, 3]).build().into();
+    for depth in 0..8 {
+        let mut bundle = Bundle::new((depth, 0));
+        bundle.elements.push(Message::to("/before").arg(depth as i32).build().into());
+        bundle.elements.push(packet);
+        bundle.elements.push(Message::to("/after").arg("x").build().into());
+        packet = bundle.into();
+    }
+    let serialized = serde_osc::to_vec(&packet).unwrap();
+    assert_eq!(Packet::from_slice(&serialized).unwrap(), packet);
+    let unframed = ser::to_vec_unframed(&packet).unwrap();
+    assert_eq!(&unframed[..], &serialized[4..]);
+}
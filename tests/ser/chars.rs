@@ -0,0 +1,25 @@
+use serde_osc::error::Error;
+use serde_osc::ser;
+use serde_osc::types::UnicodeChar;
+
+#[test]
+fn ascii_char() {
+    let test_input = ("/key", ('a',));
+    let expected = b"\x00\x00\x00\x10/key\0\0\0\0,c\0\0\x00\x00\x00\x61".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn non_ascii_char() {
+    match ser::to_vec(&("/key", ('é',))) {
+        Err(Error::NonAsciiChar('é')) => {},
+        other => panic!("expected NonAsciiChar, got {:?}", other),
+    }
+}
+
+#[test]
+fn unicode_char() {
+    let test_input = ("/key", (UnicodeChar('é'), UnicodeChar('a')));
+    let expected = b"\x00\x00\x00\x14/key\0\0\0\0,cc\0\x00\x00\x00\xE9\x00\x00\x00\x61".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
@@ -0,0 +1,51 @@
+use serde_osc::{de, ser};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Outgoing {
+    #[serde(rename = "/play")]
+    Play(f32),
+    #[serde(rename = "/stop")]
+    Stop,
+    #[serde(rename = "/pan")]
+    Pan(i32, i32),
+    #[serde(rename = "/move")]
+    Move { x: f32, y: f32 },
+}
+
+#[test]
+fn variant_as_address() {
+    assert_eq!(ser::to_vec(&Outgoing::Play(0.5)).unwrap(),
+        b"\x00\x00\x00\x10/play\0\0\0,f\0\0\x3F\x00\x00\x00".to_vec());
+    assert_eq!(ser::to_vec(&Outgoing::Stop).unwrap(),
+        b"\x00\x00\x00\x0C/stop\0\0\0,\0\0\0".to_vec());
+    assert_eq!(ser::to_vec(&Outgoing::Pan(1, 2)).unwrap(),
+        b"\x00\x00\x00\x14/pan\0\0\0\0,ii\0\x00\x00\x00\x01\x00\x00\x00\x02".to_vec());
+    assert_eq!(ser::to_vec(&Outgoing::Move { x: 1.0, y: 2.0 }).unwrap(),
+        b"\x00\x00\x00\x14/move\0\0\0,ff\0\x3F\x80\x00\x00\x40\x00\x00\x00".to_vec());
+}
+
+#[test]
+fn newtype_struct_payload() {
+    #[derive(Serialize)]
+    struct Seek {
+        bar: i32,
+        beat: i32,
+    }
+    #[derive(Serialize)]
+    enum Cmd {
+        #[serde(rename = "/seek")]
+        Seek(Seek),
+    }
+    // The fields of the payload become the message arguments.
+    let expected = ser::to_vec(&("/seek", (3, 1))).unwrap();
+    assert_eq!(ser::to_vec(&Cmd::Seek(Seek { bar: 3, beat: 1 })).unwrap(), expected);
+}
+
+#[test]
+fn enum_roundtrip() {
+    for msg in &[Outgoing::Play(0.25), Outgoing::Stop, Outgoing::Pan(-1, 7),
+                 Outgoing::Move { x: 0.5, y: -0.5 }] {
+        let bytes = ser::to_vec(msg).unwrap();
+        assert_eq!(&de::from_slice::<Outgoing>(&bytes).unwrap(), msg);
+    }
+}
@@ -0,0 +1,25 @@
+use serde_osc::error::Error;
+use serde_osc::ser;
+
+#[test]
+fn unsigned_as_i32() {
+    let test_input = ("/u", (7u8, 300u16, 0x7fffffffu32));
+    let expected = b"\x00\x00\x00\x18/u\0\0,iii\0\0\0\0\x00\x00\x00\x07\x00\x00\x01\x2C\x7F\xFF\xFF\xFF".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn u32_overflow() {
+    match ser::to_vec(&("/u", (0x80000000u32,))) {
+        Err(Error::BadCast(_)) => {},
+        other => panic!("expected BadCast, got {:?}", other),
+    }
+}
+
+#[test]
+fn wide_as_i64() {
+    let test_input = ("/h", (-2i64, 0x0102030405060708u64));
+    let expected = b"\x00\x00\x00\x18/h\0\0,hh\0\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFE\x01\x02\x03\x04\x05\x06\x07\x08".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+    assert!(ser::to_vec(&("/h", (u64::MAX,))).is_err());
+}
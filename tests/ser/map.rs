@@ -0,0 +1,23 @@
+use std::collections::BTreeMap;
+use serde_osc::ser;
+
+fn params() -> BTreeMap<String, f32> {
+    let mut map = BTreeMap::new();
+    map.insert("a".to_owned(), 1.0);
+    map.insert("b".to_owned(), 2.0);
+    map
+}
+
+#[test]
+fn map_as_args() {
+    let test_input = ("/p", params());
+    let expected = b"\x00\x00\x00\x1C/p\0\0,sfsf\0\0\0a\0\0\0\x3F\x80\x00\x00b\0\0\0\x40\x00\x00\x00".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn nested_map_as_array() {
+    let test_input = ("/p", (7i32, params()));
+    let expected = b"\x00\x00\x00\x24/p\0\0,i[sfsf]\0\0\0\0\0\0\0\x07a\0\0\0\x3F\x80\x00\x00b\0\0\0\x40\x00\x00\x00".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
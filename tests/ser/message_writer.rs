@@ -0,0 +1,136 @@
+use std::io;
+use serde_bytes::ByteBuf;
+use serde_osc::error::Error;
+use serde_osc::ser::{self, MessageWriter};
+
+#[test]
+fn matches_serialized() {
+    // Blobs in between other arguments, with and without padding.
+    let first = vec![1u8, 2, 3];
+    let second = vec![4u8, 5, 6, 7];
+    let expected = ser::to_vec(&("/load", (7, ByteBuf::from(first.clone()), "x", ByteBuf::from(second.clone()), 0.5f32))).unwrap();
+
+    let mut msg = MessageWriter::new("/load").unwrap();
+    msg.arg(&7).unwrap();
+    msg.blob(&first[..], first.len()).unwrap();
+    msg.arg("x").unwrap();
+    msg.blob(&second[..], second.len()).unwrap();
+    msg.arg(&0.5f32).unwrap();
+    let mut packet = Vec::new();
+    msg.write_to(&mut packet).unwrap();
+    assert_eq!(packet, expected);
+}
+
+#[test]
+fn unframed() {
+    let data = vec![9u8; 5];
+    let mut msg = MessageWriter::new("/b").unwrap();
+    msg.blob(&data[..], data.len()).unwrap();
+    let mut packet = Vec::new();
+    msg.write_to_unframed(&mut packet).unwrap();
+    assert_eq!(packet, ser::to_vec_unframed(&("/b", (ByteBuf::from(data),))).unwrap());
+}
+
+#[test]
+fn reader_too_short() {
+    let data = [9u8; 5];
+    let mut msg = MessageWriter::new("/b").unwrap();
+    msg.blob(&data[..], 8).unwrap();
+    match msg.write_to(&mut Vec::new()) {
+        Err(Error::Io(ref e)) if e.kind() == io::ErrorKind::UnexpectedEof => {},
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn only_len_bytes_read() {
+    let data = [9u8; 8];
+    let mut reader = &data[..];
+    let mut msg = MessageWriter::new("/b").unwrap();
+    msg.blob(&mut reader, 5).unwrap();
+    msg.write_to(&mut Vec::new()).unwrap();
+    assert_eq!(reader.len(), 3);
+}
+
+#[test]
+fn bad_address() {
+    match MessageWriter::new("no slash") {
+        Err(Error::BadAddress(address)) => assert_eq!(address, "no slash"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+/// Records the parts passed to each call of `write_vectored`.
+#[derive(Default)]
+struct Vectored {
+    calls: Vec<Vec<Vec<u8>>>,
+}
+
+impl io::Write for Vectored {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.calls.push(vec![buf.to_vec()]);
+        Ok(buf.len())
+    }
+    fn write_vectored(&mut self, bufs: &[io::IoSlice]) -> io::Result<usize> {
+        self.calls.push(bufs.iter().map(|buf| buf.to_vec()).collect());
+        Ok(bufs.iter().map(|buf| buf.len()).sum())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn borrowed_blobs_written_vectored() {
+    let blob = vec![7u8; 10_000];
+    let mut msg = MessageWriter::new("/load").unwrap();
+    msg.arg(&1).unwrap();
+    msg.blob_slice(&blob).unwrap();
+    msg.arg("done").unwrap();
+    let mut output = Vectored::default();
+    msg.write_to(&mut output).unwrap();
+
+    // The whole packet is handed over at once, with the blob as a part of its own.
+    assert_eq!(output.calls.len(), 1);
+    assert!(output.calls[0].contains(&blob));
+    let packet = output.calls[0].concat();
+    assert_eq!(packet, ser::to_vec(&("/load", (1, ByteBuf::from(blob.clone()), "done"))).unwrap());
+}
+
+#[test]
+fn slices_and_readers() {
+    let first = vec![1u8, 2, 3];
+    let second = vec![4u8; 6];
+    let expected = ser::to_vec(&("/b", (ByteBuf::from(first.clone()), ByteBuf::from(second.clone())))).unwrap();
+    let mut msg = MessageWriter::new("/b").unwrap();
+    msg.blob_slice(&first).unwrap();
+    msg.blob(&second[..], second.len()).unwrap();
+    let mut output = Vectored::default();
+    msg.write_to(&mut output).unwrap();
+    assert_eq!(output.calls.concat().concat(), expected);
+}
+
+/// Accepts at most 5 bytes per write.
+struct Trickle(Vec<u8>);
+
+impl io::Write for Trickle {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(5);
+        self.0.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn partial_writes() {
+    let blob = vec![3u8; 9];
+    let mut msg = MessageWriter::new("/b").unwrap();
+    msg.blob_slice(&blob).unwrap();
+    msg.arg(&2).unwrap();
+    let mut output = Trickle(Vec::new());
+    msg.write_to(&mut output).unwrap();
+    assert_eq!(output.0, ser::to_vec(&("/b", (ByteBuf::from(blob), 2))).unwrap());
+}
@@ -2,3 +2,21 @@ mod auto_derive;
 mod bundle;
 mod tuple;
 
+mod unframed;
+mod option;
+mod types;
+mod array;
+mod enum_msg;
+mod slice;
+mod size;
+mod bundle_builder;
+mod map;
+mod ints;
+mod address;
+mod message_writer;
+mod seek;
+mod chars;
+mod stream;
+mod newtypes;
+mod unit;
+mod too_large;
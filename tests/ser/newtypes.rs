@@ -0,0 +1,38 @@
+use serde_osc::ser;
+use serde_osc::types::Symbol;
+
+#[derive(Serialize)]
+struct Freq(f32);
+
+#[derive(Serialize)]
+struct Address(String);
+
+#[derive(Serialize)]
+struct Args((Freq, Symbol));
+
+#[derive(Serialize)]
+struct Note((Address, Args));
+
+#[test]
+fn transparent_arg() {
+    let expected = ser::to_vec(&("/freq", (440.0f32,))).unwrap();
+    assert_eq!(ser::to_vec(&("/freq", (Freq(440.0),))).unwrap(), expected);
+}
+
+#[test]
+fn transparent_address_and_payload() {
+    let expected = ser::to_vec(&("/note", (440.0f32, Symbol::from("sine")))).unwrap();
+    let note = (Address("/note".to_owned()), Args((Freq(440.0), Symbol::from("sine"))));
+    assert_eq!(ser::to_vec(&note).unwrap(), expected);
+    // The packet itself may be wrapped too.
+    assert_eq!(ser::to_vec(&Note(note)).unwrap(), expected);
+}
+
+#[test]
+fn transparent_bundle_parts() {
+    #[derive(Serialize)]
+    struct Elements(Vec<(String, (i32,))>);
+    let elements = vec![("/a".to_owned(), (1,))];
+    let expected = ser::to_vec(&((1u32, 2u32), elements.clone())).unwrap();
+    assert_eq!(ser::to_vec(&((1u32, 2u32), Elements(elements))).unwrap(), expected);
+}
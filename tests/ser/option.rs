@@ -0,0 +1,14 @@
+use serde_osc::ser;
+
+#[test]
+fn option_ser() {
+    #[derive(Serialize)]
+    struct Args {
+        gain: Option<f32>,
+        channel: Option<i32>,
+        muted: bool,
+    }
+    let test_input = ("/mix", Args { gain: None, channel: Some(3), muted: true });
+    let expected = b"\x00\x00\x00\x14/mix\0\0\0\0,NiT\0\0\0\0\0\0\0\x03".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
@@ -0,0 +1,66 @@
+use std::io::{Cursor, Seek, SeekFrom, Write};
+use serde_bytes::ByteBuf;
+use serde_osc::ser;
+use serde_osc::value::{Bundle, Message, Packet};
+
+#[test]
+fn message_matches_vec() {
+    let test_input = ("/m", (1, "two", ByteBuf::from(vec![3u8; 5])));
+    let mut output = Cursor::new(Vec::new());
+    let written = ser::to_write_seek(&mut output, &test_input).unwrap();
+    let expected = ser::to_vec(&test_input).unwrap();
+    assert_eq!(written, expected.len());
+    assert_eq!(output.into_inner(), expected);
+}
+
+#[test]
+fn nested_bundles_match_vec() {
+    let mut inner = Bundle::new((5, 6));
+    inner.elements.push(Message::new("/c").with_arg(3).into());
+    let mut bundle = Bundle::new((0, 1));
+    bundle.elements.push(Message::new("/a").with_arg(1).into());
+    bundle.elements.push(inner.into());
+    bundle.elements.push(Message::new("/b").with_arg("two").into());
+    let packet = Packet::Bundle(bundle);
+
+    let mut output = Cursor::new(Vec::new());
+    let written = ser::to_write_seek(&mut output, &packet).unwrap();
+    let expected = ser::to_vec(&packet).unwrap();
+    assert_eq!(written, expected.len());
+    assert_eq!(output.into_inner(), expected);
+
+    let mut output = Cursor::new(Vec::new());
+    let written = ser::to_write_seek_unframed(&mut output, &packet).unwrap();
+    assert_eq!(written, expected.len() - 4);
+    assert_eq!(output.into_inner(), &expected[4..]);
+}
+
+#[test]
+fn appends_at_current_position() {
+    // Packets written one after another, e.g. to a log file.
+    let first = ((0u32, 1u32), (("/a", (1,)),));
+    let second = ((0u32, 2u32), (("/b", ()),));
+    let mut output = Cursor::new(Vec::new());
+    output.write_all(b"head").unwrap();
+    let written = ser::to_write_seek(&mut output, &first).unwrap()
+        + ser::to_write_seek(&mut output, &second).unwrap();
+    assert_eq!(output.position() as usize, 4 + written);
+
+    let mut expected = b"head".to_vec();
+    expected.extend(ser::to_vec(&first).unwrap());
+    expected.extend(ser::to_vec(&second).unwrap());
+    assert_eq!(output.into_inner(), expected);
+}
+
+#[test]
+fn overwrites_existing_data() {
+    // The length prefix is patched in place; data after the packet is untouched.
+    let bundle = ((0u32, 1u32), (("/a", (1,)),));
+    let len = ser::to_vec(&bundle).unwrap().len();
+    let mut output = Cursor::new(vec![0xffu8; len + 4]);
+    output.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(ser::to_write_seek(&mut output, &bundle).unwrap(), len);
+    let output = output.into_inner();
+    assert_eq!(&output[..len], &ser::to_vec(&bundle).unwrap()[..]);
+    assert_eq!(&output[len..], &[0xff; 4]);
+}
@@ -0,0 +1,35 @@
+use serde_bytes::ByteBuf;
+use serde_osc::ser;
+
+#[test]
+fn size_of_msg() {
+    let test_input = ("/m1", (0x5eeeeeedi32, "hello", ByteBuf::from(vec![1, 2, 3])));
+    let framed = ser::to_vec(&test_input).unwrap();
+    let unframed = ser::to_vec_unframed(&test_input).unwrap();
+    assert_eq!(ser::serialized_size(&test_input).unwrap(), framed.len());
+    assert_eq!(ser::serialized_size_unframed(&test_input).unwrap(), unframed.len());
+    assert_eq!(framed.len(), unframed.len() + 4);
+}
+
+#[test]
+fn size_of_bundle() {
+    let test_input = ((0u32, 1u32), (("/m1", (1i32,)), ("/m2", (0.5f32,))));
+    let expected = ser::to_vec(&test_input).unwrap().len();
+    assert_eq!(ser::serialized_size(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn size_of_nested_bundle() {
+    let test_input = ((0u32, 1u32), (("/m1", (1i32, "two")), ((0u32, 2u32), (("/m2", (vec![0.5f32; 3],)),))));
+    assert_eq!(ser::serialized_size(&test_input).unwrap(), ser::to_vec(&test_input).unwrap().len());
+    assert_eq!(ser::serialized_size_unframed(&test_input).unwrap(), ser::to_vec_unframed(&test_input).unwrap().len());
+}
+
+#[test]
+fn size_of_large_blob() {
+    // Sizes of each padding, up to a blob which is far larger than the rest.
+    for &len in &[0, 1, 2, 3, 4, 5, 10_000_000] {
+        let test_input = ("/blob", (ByteBuf::from(vec![7u8; len]), "tail"));
+        assert_eq!(ser::serialized_size(&test_input).unwrap(), ser::to_vec(&test_input).unwrap().len());
+    }
+}
@@ -0,0 +1,115 @@
+use std::io;
+use serde_bytes::ByteBuf;
+use serde_osc::error::Error;
+use serde_osc::ser;
+use serde_osc::types::{Color, MidiMessage};
+use serde_osc::value::{Bundle, Message, Packet};
+
+#[test]
+fn to_slice_matches_vec() {
+    let test_input = ("/m1", (0x5eeeeeedi32, 0.5f32));
+    let expected = ser::to_vec(&test_input).unwrap();
+    let mut buf = [0u8; 64];
+    let len = ser::to_slice(&test_input, &mut buf).unwrap();
+    assert_eq!(&buf[..len], &expected[..]);
+
+    let expected = ser::to_vec_unframed(&test_input).unwrap();
+    let len = ser::to_slice_unframed(&test_input, &mut buf).unwrap();
+    assert_eq!(&buf[..len], &expected[..]);
+}
+
+#[test]
+fn to_slice_too_small() {
+    let test_input = ("/m1", (0x5eeeeeedi32,));
+    // The framed packet needs 16 bytes.
+    let mut buf = [0u8; 15];
+    match ser::to_slice(&test_input, &mut buf) {
+        Err(Error::Io(ref err)) => assert_eq!(err.kind(), io::ErrorKind::WriteZero),
+        other => panic!("expected WriteZero, got {:?}", other),
+    }
+    let mut buf = [0u8; 16];
+    assert_eq!(ser::to_slice(&test_input, &mut buf).unwrap(), 16);
+}
+
+#[test]
+fn to_vec_reuse_replaces_contents() {
+    let long = ("/a/long/address", (1i32, 2i32, "three"));
+    let short = ("/s", ());
+    let mut buf = Vec::new();
+    ser::to_vec_reuse(&mut buf, &long).unwrap();
+    assert_eq!(buf, ser::to_vec(&long).unwrap());
+    let capacity = buf.capacity();
+    // The buffer is overwritten, not appended to, and keeps its allocation.
+    ser::to_vec_reuse(&mut buf, &short).unwrap();
+    assert_eq!(buf, ser::to_vec(&short).unwrap());
+    assert_eq!(buf.capacity(), capacity);
+
+    ser::to_vec_reuse_unframed(&mut buf, &long).unwrap();
+    assert_eq!(buf, ser::to_vec_unframed(&long).unwrap());
+}
+
+/// Serialize `value` into slices of every size up to the one it needs, which
+/// must hold exactly what `to_vec` produces.
+fn check_fits<T: ::serde::Serialize>(value: &T) {
+    let expected = ser::to_vec(value).unwrap();
+    let mut buf = vec![0xffu8; expected.len()];
+    for size in 0..expected.len() {
+        assert!(ser::to_slice(value, &mut buf[..size]).is_err(), "fit into {} bytes", size);
+    }
+    assert_eq!(ser::to_slice(value, &mut buf).unwrap(), expected.len());
+    assert_eq!(buf, expected);
+
+    let expected = ser::to_vec_unframed(value).unwrap();
+    let mut buf = vec![0xffu8; expected.len() + 16];
+    assert_eq!(ser::to_slice_unframed(value, &mut buf).unwrap(), expected.len());
+    assert_eq!(&buf[..expected.len()], &expected[..]);
+}
+
+#[derive(Serialize)]
+enum Outgoing {
+    #[serde(rename = "/stop")]
+    Stop,
+    #[serde(rename = "/play")]
+    Play(f32),
+    #[serde(rename = "/pan")]
+    Pan(i32, i32),
+    #[serde(rename = "/move")]
+    Move { x: f32, y: f32 },
+}
+
+#[test]
+fn to_slice_messages() {
+    check_fits(&("/m", ()));
+    check_fits(&("/m", (1, 2, 3)));
+    check_fits(&("/m", (1, 2.0f32, "three", true, None::<i32>, 5i64)));
+    check_fits(&("/blobs", (ByteBuf::from(vec![1u8; 5]), ByteBuf::from(vec![]), "x")));
+    check_fits(&("/arrays", (vec![1, 2, 3], (4.0f32, vec!["five"]), Vec::<i32>::new())));
+    check_fits(&("/many", vec![7u8; 100]));
+    check_fits(&("/midi", (MidiMessage::new(0, 0x90, 60, 100), Color::new(1, 2, 3, 4))));
+    check_fits(&Outgoing::Stop);
+    check_fits(&Outgoing::Play(0.5));
+    check_fits(&Outgoing::Pan(-1, 1));
+    check_fits(&Outgoing::Move { x: 0.25, y: 0.75 });
+}
+
+#[test]
+fn to_slice_bundles() {
+    let mut inner = Bundle::new((5, 6));
+    inner.elements.push(Message::new("/c").with_arg(3).with_arg("three").into());
+    let mut bundle = Bundle::new((0, 1));
+    bundle.elements.push(Message::new("/a").with_arg(1).into());
+    bundle.elements.push(inner.into());
+    bundle.elements.push(Message::new("/b").with_arg(vec![2u8; 9]).into());
+    check_fits(&Packet::Bundle(bundle));
+    check_fits(&(None::<(u32, u32)>, (Outgoing::Pan(1, 2), ("/x", (true,)))));
+}
+
+#[test]
+fn to_vec_reuse_grows() {
+    // Larger than the space first allocated, so that it's retried.
+    let large = ("/large", (ByteBuf::from(vec![9u8; 1000]), "end"));
+    let mut buf = Vec::new();
+    ser::to_vec_reuse(&mut buf, &large).unwrap();
+    assert_eq!(buf, ser::to_vec(&large).unwrap());
+    assert!(ser::to_vec_reuse(&mut buf, &("bad address", ())).is_err());
+}
@@ -0,0 +1,35 @@
+use serde_osc::ser;
+use serde_osc::value::{Bundle, Message, Packet};
+
+fn mixed() -> Vec<Packet> {
+    let mut bundle = Bundle::new((0, 1));
+    bundle.elements.push(Message::to("/b").arg(2.0f32).build().into());
+    bundle.elements.push(Message::new("/c").into());
+    vec![
+        Message::to("/a").arg(1).arg("one").build().into(),
+        bundle.into(),
+        Message::new("/d").into(),
+    ]
+}
+
+#[test]
+fn mixed_packets() {
+    let packets = mixed();
+    let mut stream = Vec::new();
+    ser::to_write_all(&mut stream, &packets).unwrap();
+    // Each packet is framed as if it were written on its own.
+    let expected: Vec<u8> = packets.iter().flat_map(|p| serde_osc::to_vec(p).unwrap()).collect();
+    assert_eq!(stream, expected);
+    // Owned packets give the same stream.
+    let mut from_iter = Vec::new();
+    ser::to_write_all(&mut from_iter, mixed()).unwrap();
+    assert_eq!(from_iter, stream);
+}
+
+#[test]
+fn stops_at_failure() {
+    let packets = vec![Message::new("/ok"), Message::new("no slash"), Message::new("/never")];
+    let mut stream = Vec::new();
+    assert!(ser::to_write_all(&mut stream, &packets).is_err());
+    assert_eq!(stream, serde_osc::to_vec(&packets[0]).unwrap());
+}
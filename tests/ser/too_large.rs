@@ -0,0 +1,69 @@
+use std::io::{self, Read, Write};
+use serde_osc::error::Error;
+use serde_osc::ser::MessageWriter;
+
+/// A blob of `len` bytes, none of which may actually be read.
+fn unread_blob(len: usize) -> (impl Read, usize) {
+    struct Unread;
+    impl Read for Unread {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            panic!("the blob data shouldn't be read");
+        }
+    }
+    (Unread, len)
+}
+
+/// Keeps the length prefix written to it, and fails on anything after it.
+#[derive(Default)]
+struct PrefixOnly(Vec<u8>);
+
+impl Write for PrefixOnly {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let wanted = 4 - self.0.len();
+        if wanted == 0 {
+            return Err(io::Error::other("only the prefix is kept"));
+        }
+        let taken = wanted.min(buf.len());
+        self.0.extend_from_slice(&buf[..taken]);
+        Ok(taken)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn blob_length() {
+    let mut msg = MessageWriter::new("/a").unwrap();
+    let (data, len) = unread_blob(1 << 31);
+    assert!(matches!(msg.blob(data, len), Err(Error::TooLarge)));
+    let (data, len) = unread_blob(i32::MAX as usize);
+    assert!(msg.blob(data, len).is_ok());
+}
+
+#[test]
+fn packet_length() {
+    // "/a", ",b", the blob length and the blob data: 2^31 bytes in all.
+    let mut msg = MessageWriter::new("/a").unwrap();
+    let (data, len) = unread_blob((1 << 31) - 12);
+    msg.blob(data, len).unwrap();
+    let mut out = PrefixOnly::default();
+    assert!(matches!(msg.write_to(&mut out), Err(Error::TooLarge)));
+    assert!(out.0.is_empty());
+}
+
+#[test]
+fn largest_packet() {
+    // 4 bytes fewer, which is the longest a packet can be (its length must
+    // be a multiple of 4).
+    let mut msg = MessageWriter::new("/a").unwrap();
+    msg.blob(io::repeat(0).take((1 << 31) - 16), (1 << 31) - 16).unwrap();
+    let mut out = PrefixOnly::default();
+    assert!(matches!(msg.write_to(&mut out), Err(Error::Io(_))));
+    assert_eq!(out.0, ((1u32 << 31) - 4).to_be_bytes());
+}
+
+#[test]
+fn display() {
+    assert_eq!(Error::TooLarge.to_string(), "OSC packet or blob is too large for its length prefix (at most 2147483647 bytes)");
+}
@@ -0,0 +1,30 @@
+use serde_osc::ser;
+use serde_osc::types::{Color, MidiMessage, Symbol};
+
+#[test]
+fn midi_ser() {
+    let test_input = ("/midi", (MidiMessage::new(0, 0x90, 60, 127),));
+    let expected = b"\x00\x00\x00\x10/midi\0\0\0,m\0\0\x00\x90\x3c\x7f".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn color_ser() {
+    let test_input = ("/led", (Color::new(0xff, 0x80, 0x00, 0xff),));
+    let expected = b"\x00\x00\x00\x10/led\0\0\0\0,r\0\0\xff\x80\x00\xff".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn symbol_ser() {
+    let test_input = ("/s_new", (Symbol::from("sine"), "sine"));
+    let expected = b"\x00\x00\x00\x1C/s_new\0\0,Ss\0sine\0\0\0\0sine\0\0\0\0".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn double_ser() {
+    let test_input = ("/d", (0.25f64,));
+    let expected = b"\0\0\0\x10/d\0\0,d\0\0?\xD0\0\0\0\0\0\0".to_vec();
+    assert_eq!(ser::to_vec(&test_input).unwrap(), expected);
+}
@@ -0,0 +1,16 @@
+use serde_osc::ser;
+
+#[test]
+fn unframed_msg() {
+    let test_input = ("/m1", (0x5eeeeeedi32,));
+    let expected = b"/m1\0,i\0\0\x5E\xEE\xEE\xED".to_vec();
+    assert_eq!(ser::to_vec_unframed(&test_input).unwrap(), expected);
+}
+
+#[test]
+fn unframed_bundle() {
+    // Only the outermost packet is unframed; bundle elements keep their length.
+    let test_input = ((0x01020304u32, 0x05060708u32), (("/m1", (0x5eeeeeedi32,)),));
+    let expected = b"#bundle\0\x01\x02\x03\x04\x05\x06\x07\x08\x00\x00\x00\x0C/m1\0,i\0\0\x5E\xEE\xEE\xED".to_vec();
+    assert_eq!(ser::to_vec_unframed(&test_input).unwrap(), expected);
+}
@@ -0,0 +1,42 @@
+use serde_osc::ser;
+
+const PING: &[u8] = b"\x00\x00\x00\x0C/ping\0\0\0,\0\0\0";
+
+#[derive(Serialize)]
+struct NoArgs;
+
+#[test]
+fn unit_payload() {
+    assert_eq!(ser::to_vec(&("/ping", ())).unwrap(), PING);
+    assert_eq!(ser::to_vec(&("/ping", NoArgs)).unwrap(), PING);
+}
+
+#[test]
+fn unit_payload_of_struct() {
+    #[derive(Serialize)]
+    struct Ping {
+        address: &'static str,
+        args: (),
+    }
+    assert_eq!(ser::to_vec(&Ping { address: "/ping", args: () }).unwrap(), PING);
+}
+
+#[test]
+fn unit_variant_payload() {
+    #[derive(Serialize)]
+    enum Cmd {
+        #[serde(rename = "/ping")]
+        Ping(()),
+        #[serde(rename = "/pong")]
+        Pong(NoArgs),
+    }
+    assert_eq!(ser::to_vec(&Cmd::Ping(())).unwrap(), PING);
+    assert_eq!(ser::to_vec(&Cmd::Pong(NoArgs)).unwrap(), ser::to_vec(&("/pong", ())).unwrap());
+}
+
+#[test]
+fn unit_payload_in_bundle() {
+    let bundle = ser::to_vec(&((0u32, 1u32), (("/ping", ()),))).unwrap();
+    // After the length prefix, "#bundle" and the time-tag.
+    assert_eq!(&bundle[20..], PING);
+}
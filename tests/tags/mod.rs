@@ -0,0 +1,52 @@
+use serde_bytes::ByteBuf;
+use serde_osc::tags::{self, arg_size, fixed_size, is_supported, TAG_ARRAY_BEGIN, TAG_BLOB, TAG_INT64,
+    TAG_STRING, TAG_TIMETAG, TYPETAG_PREFIX};
+use serde_osc::types::{Color, Symbol};
+
+/// Split an unframed message into its typetag and argument data.
+fn typetag_and_args(packet: &[u8]) -> (&[u8], &[u8]) {
+    let address = arg_size(TAG_STRING, packet).unwrap();
+    let typetag = arg_size(TAG_STRING, &packet[address..]).unwrap();
+    let tags = &packet[address..address + typetag];
+    let end = tags.iter().position(|&c| c == 0).unwrap();
+    assert_eq!(tags[0], TYPETAG_PREFIX);
+    (&tags[1..end], &packet[address + typetag..])
+}
+
+#[test]
+fn walks_arguments() {
+    let msg = ("/walk", (1, "four", ByteBuf::from(vec![9u8; 5]), 2i64, [0.5f32, 1.5], Symbol::from("s"),
+        Color::new(1, 2, 3, 4), true, None::<i32>));
+    let packet = serde_osc::ser::to_vec_unframed(&msg).unwrap();
+    let (typetag, mut args) = typetag_and_args(&packet);
+    assert_eq!(typetag, b"isbh[ff]SrTN");
+    for &tag in typetag {
+        assert!(is_supported(tag), "{}", tag as char);
+        let size = arg_size(tag, args).unwrap();
+        args = &args[size..];
+    }
+    assert!(args.is_empty());
+}
+
+#[test]
+fn sizes() {
+    assert_eq!(fixed_size(TAG_INT64), Some(8));
+    assert_eq!(fixed_size(TAG_TIMETAG), Some(8));
+    assert_eq!(fixed_size(TAG_ARRAY_BEGIN), Some(0));
+    assert_eq!(fixed_size(TAG_STRING), None);
+    assert_eq!(fixed_size(b'X'), None);
+    assert!(!is_supported(TAG_TIMETAG));
+    assert!(!is_supported(tags::TAG_INFINITUM));
+}
+
+#[test]
+fn truncated_payloads() {
+    assert_eq!(arg_size(TAG_INT64, &[0; 4]), None);
+    assert_eq!(arg_size(TAG_STRING, b"abc\0"), Some(4));
+    assert_eq!(arg_size(TAG_STRING, b"abcd\0\0"), None);
+    // A blob declared longer than the data, or with a negative length.
+    assert_eq!(arg_size(TAG_BLOB, &[0, 0, 0, 9, 1, 2, 3, 4]), None);
+    assert_eq!(arg_size(TAG_BLOB, &[0xff, 0xff, 0xff, 0xff]), None);
+    assert_eq!(arg_size(TAG_BLOB, &[0, 0]), None);
+    assert_eq!(arg_size(b'X', &[0; 16]), None);
+}
@@ -0,0 +1,104 @@
+use serde_bytes::ByteBuf;
+use serde_osc::{de, ser, text};
+use serde_osc::error::Error;
+use serde_osc::types::{Color, RawArg, Symbol};
+use serde_osc::value::{Bundle, Message, Packet, Value};
+
+#[test]
+fn msg_to_string() {
+    let msg = ("/audio/play", (1i32, 440.0f32, "a \"quoted\" string", ByteBuf::from(vec![0xde, 0xad])));
+    assert_eq!(text::to_string(&msg).unwrap(),
+        r#"/audio/play ifsb 1 440.0 "a \"quoted\" string" #dead"#);
+}
+
+#[test]
+fn msg_without_args() {
+    assert_eq!(text::to_string(&("/ping", ())).unwrap(), "/ping");
+    let parsed: (String, ()) = text::from_str("/ping").unwrap();
+    assert_eq!(parsed, ("/ping".to_owned(), ()));
+}
+
+#[test]
+fn tokenless_and_array_args() {
+    let msg = Message::new("/m")
+        .with_arg(true)
+        .with_arg(vec![Value::Int(1), Value::Nil, Value::Long(-2)])
+        .with_arg(Symbol::from("sym"))
+        .with_arg(Color::new(1, 2, 3, 255));
+    let as_text = msg.to_string();
+    assert_eq!(as_text, r#"/m T[iNh]Sr 1 -2 "sym" #010203ff"#);
+    assert_eq!(as_text.parse::<Message>().unwrap(), msg);
+}
+
+#[test]
+fn bundle_round_trip() {
+    let mut inner = Bundle::new((5, 6));
+    inner.elements.push(Message::new("/c").into());
+    let mut bundle = Bundle::new((0, 1));
+    bundle.elements.push(Message::new("/a").with_arg(1).into());
+    bundle.elements.push(inner.into());
+    let packet = Packet::Bundle(bundle);
+
+    let as_text = packet.to_string();
+    assert_eq!(as_text, "#bundle 0:1 { /a i 1 } { #bundle 5:6 { /c } }");
+    assert_eq!(as_text.parse::<Packet>().unwrap(), packet);
+    // The binary encoding decodes to the same packet.
+    assert_eq!(Packet::from_slice(&ser::to_vec(&packet).unwrap()).unwrap(), packet);
+}
+
+#[test]
+fn from_str_into_typed() {
+    let parsed: (String, (i32, f32, String)) = text::from_str(" /audio/play ,ifs 1 440 unquoted\n").unwrap();
+    assert_eq!(parsed, ("/audio/play".to_owned(), (1, 440.0, "unquoted".to_owned())));
+}
+
+#[test]
+fn text_to_binary() {
+    let packet: Packet = r#"/s s "tab\there""#.parse().unwrap();
+    let binary = ser::to_vec(&packet).unwrap();
+    let decoded: (String, (String,)) = de::from_slice(&binary).unwrap();
+    assert_eq!(decoded.1, ("tab\there".to_owned(),));
+}
+
+#[test]
+fn parse_errors() {
+    let invalid = [
+        "",
+        "/m i",
+        "/m i x",
+        "/m f 1 2",
+        "/m [i 1",
+        "/m s \"unterminated",
+        "/m m #0102",
+        "#bundle 1 { /a }",
+        "#bundle 0:1 { /a",
+    ];
+    for text in invalid.iter() {
+        assert!(text.parse::<Packet>().is_err(), "parsed {:?}", text);
+    }
+}
+
+#[test]
+fn double_args() {
+    let msg = Message::new("/d").with_arg(0.1f64).with_arg(0.1f32);
+    let as_text = msg.to_string();
+    assert_eq!(as_text, "/d df 0.1 0.1");
+    assert_eq!(as_text.parse::<Message>().unwrap(), msg);
+}
+
+#[test]
+fn raw_args() {
+    let msg = Message::new("/t").with_arg(RawArg::new('t', vec![0, 0, 0, 1, 0, 0, 0, 2]));
+    let as_text = msg.to_string();
+    assert_eq!(as_text, "/t t #0000000100000002");
+    assert_eq!(as_text.parse::<Message>().unwrap(), msg);
+}
+
+#[test]
+fn deeply_nested_bundles() {
+    let nested = |depth: usize| format!("{}/a{}", "#bundle 0:1 { ".repeat(depth), " }".repeat(depth));
+    assert!(nested(32).parse::<Packet>().is_ok());
+    assert!(matches!(nested(33).parse::<Packet>(), Err(Error::DepthLimitExceeded)));
+    // Deep enough to overflow the stack, were bundles not limited.
+    assert!(matches!(nested(1_000_000).parse::<Packet>(), Err(Error::DepthLimitExceeded)));
+}
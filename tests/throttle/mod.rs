@@ -0,0 +1,84 @@
+use std::cell::Cell;
+use std::time::Duration;
+use serde_osc::throttle::Throttle;
+use serde_osc::value::Message;
+
+fn fader(channel: u32, position: f32) -> Message {
+    Message::to(format!("/ch/{}/fader", channel)).arg(position).build()
+}
+
+/// `ms` milliseconds after the start of second 100, as a time-tag. Times and
+/// windows in the tests are multiples of 125ms, which time-tags hold exactly.
+fn at(ms: u64) -> (u32, u32) {
+    (100 + (ms / 1000) as u32, (((ms % 1000) << 32) / 1000) as u32)
+}
+
+#[test]
+fn coalesced_per_address() {
+    let time = Cell::new(at(0));
+    let mut throttle = Throttle::with_clock(Duration::from_millis(250), || time.get());
+    assert_eq!(throttle.window(), Duration::from_millis(250));
+    throttle.push(fader(1, 0.1));
+    throttle.push(fader(2, 0.1));
+    assert_eq!(throttle.len(), 2);
+    // First messages to each address go out at once, in order.
+    assert_eq!(throttle.drain_due(), vec![fader(1, 0.1), fader(2, 0.1)]);
+    assert!(throttle.is_empty());
+    assert_eq!(throttle.time_until_next(), None);
+
+    time.set(at(125));
+    throttle.push(fader(1, 0.2));
+    throttle.push(fader(1, 0.3));
+    throttle.push(fader(2, 0.2));
+    throttle.push(fader(1, 0.4));
+    assert_eq!(throttle.len(), 2);
+    assert_eq!(throttle.pop_due(), None);
+    assert_eq!(throttle.time_until_next(), Some(Duration::from_millis(125)));
+
+    time.set(at(250));
+    // Released in the order of the latest pushes.
+    assert_eq!(throttle.drain_due(), vec![fader(2, 0.2), fader(1, 0.4)]);
+    assert_eq!(throttle.time_until_next(), None);
+}
+
+#[test]
+fn window_restarts_on_release() {
+    let time = Cell::new(at(0));
+    let mut throttle = Throttle::with_clock(Duration::from_millis(250), || time.get());
+    throttle.push(fader(1, 0.1));
+    assert_eq!(throttle.drain_due().len(), 1);
+    // Long after the last release, a message is due at once.
+    time.set(at(1000));
+    throttle.push(fader(1, 0.2));
+    assert_eq!(throttle.time_until_next(), Some(Duration::from_secs(0)));
+    assert_eq!(throttle.pop_due(), Some(fader(1, 0.2)));
+    time.set(at(1125));
+    throttle.push(fader(1, 0.3));
+    assert_eq!(throttle.pop_due(), None);
+    time.set(at(1250));
+    assert_eq!(throttle.pop_due(), Some(fader(1, 0.3)));
+}
+
+#[test]
+fn clear() {
+    let time = Cell::new(at(0));
+    let mut throttle = Throttle::with_clock(Duration::from_secs(1), || time.get());
+    throttle.push(fader(1, 0.1));
+    throttle.drain_due();
+    throttle.push(fader(1, 0.2));
+    throttle.clear();
+    assert!(throttle.is_empty());
+    throttle.push(fader(1, 0.3));
+    assert_eq!(throttle.pop_due(), Some(fader(1, 0.3)));
+}
+
+#[test]
+fn system_clock() {
+    let mut throttle = Throttle::new(Duration::from_secs(60));
+    throttle.push(fader(1, 0.1));
+    throttle.push(fader(1, 0.2));
+    assert_eq!(throttle.pop_due(), Some(fader(1, 0.2)));
+    throttle.push(fader(1, 0.3));
+    assert_eq!(throttle.pop_due(), None);
+    assert!(throttle.time_until_next().unwrap() > Duration::from_secs(59));
+}
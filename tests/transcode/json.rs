@@ -0,0 +1,66 @@
+use std::io::Cursor;
+use serde_bytes::ByteBuf;
+use serde_json;
+use serde_json::Value as Json;
+use serde_osc::{de, ser};
+use serde_osc::value::{Bundle, Value};
+use serde_transcode;
+
+fn osc_to_json(bytes: &[u8]) -> String {
+    let mut rd = Cursor::new(bytes);
+    let mut json = Vec::new();
+    serde_transcode::transcode(&mut de::Deserializer::new(&mut rd), &mut serde_json::Serializer::new(&mut json)).unwrap();
+    String::from_utf8(json).unwrap()
+}
+
+fn json_to_osc(json: &str) -> Vec<u8> {
+    let mut osc = Vec::new();
+    serde_transcode::transcode(&mut serde_json::Deserializer::from_str(json), &mut ser::Serializer::new(&mut osc)).unwrap();
+    osc
+}
+
+#[test]
+fn message_to_json() {
+    let args = (1i32, 2.5f32, "x", true, None::<i32>, vec![Value::Int(3), Value::Long(4)], ByteBuf::from(vec![5, 6]));
+    let packet = ser::to_vec(&("/m", args)).unwrap();
+    assert_eq!(osc_to_json(&packet), r#"["/m",[1,2.5,"x",true,null,[3,4],[5,6]]]"#);
+}
+
+#[test]
+fn nested_bundles_to_json() {
+    let inner = ((0u32, 2u32), (("/b", (2,)),));
+    let outer = ((0u32, 1u32), (("/a", (1,)), inner, ("/c", ())));
+    let packet = ser::to_vec(&outer).unwrap();
+    assert_eq!(osc_to_json(&packet), r#"[[0,1],[["/a",[1]],[[0,2],[["/b",[2]]]],["/c",[]]]]"#);
+}
+
+#[test]
+fn empty_bundle_to_json() {
+    let packet = ser::to_vec(&Bundle::new((0, 1))).unwrap();
+    assert_eq!(osc_to_json(&packet), "[[0,1],[]]");
+}
+
+#[test]
+fn any_packet_into_json_value() {
+    // Self-describing types, such as `serde_json::Value`, accept any packet.
+    let packet = ser::to_vec(&((0u32, 1u32), (("/a", (1, "s")), ((0u32, 2u32), (("/b", ()),))))).unwrap();
+    let json: Json = de::from_slice(&packet).unwrap();
+    assert_eq!(json, serde_json::from_str::<Json>(r#"[[0,1],[["/a",[1,"s"]],[[0,2],[["/b",[]]]]]]"#).unwrap());
+    let from_read: Json = de::from_read(Cursor::new(&packet)).unwrap();
+    assert_eq!(from_read, json);
+}
+
+#[test]
+fn json_to_message() {
+    let packet = json_to_osc(r#"["/synth/1", [440.5, -3, "sine", false, null, [1, 2]]]"#);
+    // JSON numbers are 64 bits wide.
+    type Args = (f64, i64, String, bool, Option<i32>, (i64, i64));
+    let msg: (String, Args) = de::from_slice(&packet).unwrap();
+    assert_eq!(msg, ("/synth/1".to_owned(), (440.5, -3, "sine".to_owned(), false, None, (1, 2))));
+}
+
+#[test]
+fn json_round_trip() {
+    let json = r#"[[0,1],[["/mixer/gain",[3,0.75]],[[5,6],[["/mixer/mute",[true]]]]]]"#;
+    assert_eq!(osc_to_json(&json_to_osc(json)), json);
+}
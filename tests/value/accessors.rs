@@ -0,0 +1,59 @@
+use serde_osc::error::Error;
+use serde_osc::types::Symbol;
+use serde_osc::value::{Message, Value};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Note {
+    pitch: i32,
+    velocity: f32,
+    instrument: String,
+}
+
+fn note() -> Message {
+    Message::to("/note").arg(60).arg(0.5f32).arg("piano").build()
+}
+
+#[test]
+fn single_args() {
+    let msg = note();
+    assert_eq!(msg.arg::<i32>(0).unwrap(), 60);
+    // Integers widen, as when decoding a packet.
+    assert_eq!(msg.arg::<i64>(0).unwrap(), 60);
+    assert_eq!(msg.arg::<f32>(1).unwrap(), 0.5);
+    assert_eq!(msg.arg::<String>(2).unwrap(), "piano");
+    assert!(msg.arg::<i32>(2).is_err());
+    match msg.arg::<i32>(3).unwrap_err() {
+        Error::Message(msg) => assert_eq!(msg, "no argument 3 (the message has 3)"),
+        other => panic!("expected Error::Message, got {:?}", other),
+    }
+}
+
+#[test]
+fn special_types() {
+    let msg = Message::to("/x").arg(Value::Symbol("sym".to_owned())).arg(vec![1u8, 2])
+        .begin_array().arg(1).arg(2).end_array()
+        .arg(Value::Nil)
+        .build();
+    assert_eq!(msg.arg::<Symbol>(0).unwrap(), Symbol("sym".to_owned()));
+    assert_eq!(Vec::from(msg.arg::<serde_bytes::ByteBuf>(1).unwrap()), vec![1, 2]);
+    assert_eq!(msg.arg::<Vec<i32>>(2).unwrap(), vec![1, 2]);
+    assert_eq!(msg.arg::<Option<i32>>(3).unwrap(), None);
+}
+
+#[test]
+fn all_args() {
+    let msg = note();
+    assert_eq!(msg.args_as::<(i32, f32, String)>().unwrap(), (60, 0.5, "piano".to_owned()));
+    assert_eq!(msg.args_as::<Note>().unwrap(), Note { pitch: 60, velocity: 0.5, instrument: "piano".to_owned() });
+    // Leading arguments only; trailing ones are skipped by default.
+    assert_eq!(msg.args_as::<(i32,)>().unwrap(), (60,));
+    assert!(msg.args_as::<(String, f32, String)>().is_err());
+    assert_eq!(Message::new("/ping").args_as::<()>().unwrap(), ());
+}
+
+#[test]
+fn address_not_checked() {
+    // Arguments can be read even if the address couldn't be sent.
+    let msg = Message::to("no slash").arg(1).build();
+    assert_eq!(msg.arg::<i32>(0).unwrap(), 1);
+}
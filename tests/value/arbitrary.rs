@@ -0,0 +1,45 @@
+use arbitrary::{Arbitrary, Unstructured};
+use serde_osc::ser;
+use serde_osc::value::{Packet, Value};
+
+/// Deterministic pseudo-random bytes (xorshift), to drive `Unstructured`.
+fn noise(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..len).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as u8
+    }).collect()
+}
+
+#[test]
+fn packets_round_trip() {
+    for seed in 0..500 {
+        let data = noise(seed, 1024);
+        let packet = Packet::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        let encoded = ser::to_vec(&packet).unwrap();
+        // NaNs aren't equal to themselves, so compare the encodings.
+        let decoded = Packet::from_slice(&encoded).unwrap();
+        assert_eq!(encoded, ser::to_vec(&decoded).unwrap(), "seed {}", seed);
+    }
+}
+
+#[test]
+fn all_kinds_generated() {
+    let mut tags = String::new();
+    for seed in 0..200 {
+        let data = noise(seed, 64);
+        tags += &Value::arbitrary(&mut Unstructured::new(&data)).unwrap().typetag();
+    }
+    for tag in "ihfdsSbTFNcmr[]".chars() {
+        assert!(tags.contains(tag), "no '{}' in {}", tag, tags);
+    }
+}
+
+#[test]
+fn exhausted_input() {
+    // Runs out of data straight away, yet still produces a valid packet.
+    let packet = Packet::arbitrary(&mut Unstructured::new(&[])).unwrap();
+    assert_eq!(Packet::from_slice(&ser::to_vec(&packet).unwrap()).unwrap(), packet);
+}
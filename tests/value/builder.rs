@@ -0,0 +1,54 @@
+use serde_osc::ser;
+use serde_osc::value::{Message, Packet, Value};
+
+#[test]
+fn builds_message() {
+    let msg = Message::to("/synth/1/freq")
+        .arg(440.0f32)
+        .arg("sine")
+        .arg(3i64)
+        .build();
+    assert_eq!(msg, Message::new("/synth/1/freq").with_arg(440.0f32).with_arg("sine").with_arg(3i64));
+
+    // Same encoding as the equivalent tuple.
+    assert_eq!(ser::to_vec(&msg).unwrap(), ser::to_vec(&("/synth/1/freq", (440.0f32, "sine", 3i64))).unwrap());
+}
+
+#[test]
+fn no_args() {
+    let msg = Message::to("/ping").build();
+    assert_eq!(msg.typetag(), "");
+    let packet = Packet::from_slice(&ser::to_vec(&msg).unwrap()).unwrap();
+    assert_eq!(packet, Packet::Message(msg));
+}
+
+#[test]
+fn args_from_iterator() {
+    let msg = Message::to("/mixer/levels")
+        .arg(1)
+        .args(vec![0.5f32, 0.25, 1.0])
+        .build();
+    assert_eq!(msg.typetag(), "ifff");
+}
+
+#[test]
+fn nested_arrays() {
+    let msg = Message::to("/grid")
+        .arg("row")
+        .begin_array()
+            .arg(1)
+            .begin_array().args(vec![2, 3]).end_array()
+            .begin_array().end_array()
+        .end_array()
+        .arg(true)
+        .build();
+    assert_eq!(msg.typetag(), "s[i[ii][]]T");
+    assert_eq!(msg.args[1], Value::Array(vec![
+        Value::Int(1),
+        Value::Array(vec![Value::Int(2), Value::Int(3)]),
+        Value::Array(vec![]),
+    ]));
+
+    let decoded = Packet::from_slice(&ser::to_vec(&msg).unwrap()).unwrap();
+    assert_eq!(decoded, Packet::Message(msg));
+}
@@ -0,0 +1,74 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use serde_osc::types::{Color, RawArg};
+use serde_osc::value::{Bundle, Message, Packet, Value};
+
+#[test]
+fn floats_compared_by_bits() {
+    assert_eq!(Value::Float(f32::NAN), Value::Float(f32::NAN));
+    assert_eq!(Value::Double(f64::NAN), Value::Double(f64::NAN));
+    assert_ne!(Value::Float(0.0), Value::Float(-0.0));
+    assert_ne!(Value::Double(0.0), Value::Double(-0.0));
+    assert_eq!(Value::Float(0.5), Value::Float(0.5));
+    // Different types never compare equal, whatever their values.
+    assert_ne!(Value::Float(1.0), Value::Double(1.0));
+    assert_ne!(Value::Int(1), Value::Long(1));
+    assert_ne!(Value::String("a".to_owned()), Value::Symbol("a".to_owned()));
+}
+
+#[test]
+fn hash_map_keys() {
+    let mut last_sent: HashMap<Message, usize> = HashMap::new();
+    let msg = Message::to("/level").arg(f32::NAN).arg("x").build();
+    last_sent.insert(msg.clone(), 1);
+    *last_sent.entry(msg.clone()).or_insert(0) += 1;
+    assert_eq!(last_sent[&msg], 2);
+    assert_eq!(last_sent.get(&Message::to("/level").arg(-f32::NAN).arg("x").build()), None);
+
+    let mut bundle = Bundle::new((0, 1));
+    bundle.elements.push(msg.into());
+    let packets: HashSet<Packet> = vec![bundle.clone().into(), bundle.into(), Message::new("/a").into()]
+        .into_iter().collect();
+    assert_eq!(packets.len(), 2);
+}
+
+#[test]
+fn ordering() {
+    let values: BTreeSet<Value> = vec![
+        Value::Unknown(RawArg::new('t', vec![0; 8])),
+        Value::Double(-1.0),
+        Value::Float(f32::NAN),
+        Value::Float(1.0),
+        Value::Float(-0.0),
+        Value::Float(0.0),
+        Value::Int(3),
+        Value::Int(-3),
+        Value::Color(Color::new(1, 2, 3, 4)),
+        Value::Array(vec![Value::Nil]),
+        Value::Array(vec![]),
+    ].into_iter().collect();
+    let sorted: Vec<Value> = values.into_iter().collect();
+    assert_eq!(sorted, vec![
+        Value::Int(-3),
+        Value::Int(3),
+        Value::Float(-0.0),
+        Value::Float(0.0),
+        Value::Float(1.0),
+        Value::Float(f32::NAN),
+        Value::Double(-1.0),
+        Value::Color(Color::new(1, 2, 3, 4)),
+        Value::Array(vec![]),
+        Value::Array(vec![Value::Nil]),
+        Value::Unknown(RawArg::new('t', vec![0; 8])),
+    ]);
+
+    // Messages by address, then arguments; messages before bundles.
+    let mut packets: Vec<Packet> = vec![
+        Bundle::new((0, 1)).into(),
+        Message::to("/b").build().into(),
+        Message::to("/a").arg(2).build().into(),
+        Message::to("/a").arg(1).build().into(),
+    ];
+    packets.sort();
+    assert_eq!(packets.iter().map(Packet::address).collect::<Vec<_>>(), vec![Some("/a"), Some("/a"), Some("/b"), None]);
+    assert!(packets[0] < packets[1]);
+}
@@ -0,0 +1,5 @@
+mod builder;
+mod compare;
+mod accessors;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
@@ -0,0 +1,70 @@
+use std::net::TcpListener;
+use std::thread;
+use serde_osc::error::Error;
+use serde_osc::websocket::OscWebSocket;
+use tungstenite::Message;
+
+/// Serve one connection, replying to each "/ping" with "/pong" and the same
+/// argument, until the client closes it. Returns the server's address.
+fn serve_pings() -> (String, thread::JoinHandle<usize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = OscWebSocket::accept(stream).unwrap();
+        let mut replies = 0;
+        while let Some((address, (n,))) = socket.recv::<(String, (i32,))>().unwrap() {
+            assert_eq!(address, "/ping");
+            socket.send(&("/pong", (n,))).unwrap();
+            replies += 1;
+        }
+        replies
+    });
+    (url, server)
+}
+
+#[test]
+fn round_trip() {
+    let (url, server) = serve_pings();
+    let mut client = OscWebSocket::connect(url.as_str()).unwrap();
+    for n in 0..3 {
+        client.send(&("/ping", (n,))).unwrap();
+        let reply: (String, (i32,)) = client.recv().unwrap().unwrap();
+        assert_eq!(reply, ("/pong".to_owned(), (n,)));
+    }
+    client.close().unwrap();
+    assert!(client.recv::<(String, (i32,))>().unwrap().is_none());
+    assert_eq!(server.join().unwrap(), 3);
+}
+
+#[test]
+fn binary_message_is_unframed_packet() {
+    let (url, server) = serve_pings();
+    let (mut raw, _) = tungstenite::connect(url.as_str()).unwrap();
+    raw.send(Message::binary(serde_osc::ser::to_vec_unframed(&("/ping", (7,))).unwrap())).unwrap();
+    match raw.read().unwrap() {
+        Message::Binary(packet) => assert_eq!(&packet[..], &serde_osc::ser::to_vec_unframed(&("/pong", (7,))).unwrap()[..]),
+        other => panic!("expected a binary message, got {:?}", other),
+    }
+    let mut client = OscWebSocket::from_websocket(raw);
+    client.close().unwrap();
+    assert!(client.recv::<(String, (i32,))>().unwrap().is_none());
+    assert_eq!(server.join().unwrap(), 1);
+}
+
+#[test]
+fn text_rejected() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let client = thread::spawn(move || {
+        let (mut raw, _) = tungstenite::connect(url.as_str()).unwrap();
+        raw.send(Message::text("/ping")).unwrap();
+        // Wait for the server to close.
+        while raw.read().is_ok() {}
+    });
+    let (stream, _) = listener.accept().unwrap();
+    let mut socket = OscWebSocket::accept(stream).unwrap();
+    assert!(matches!(socket.recv::<(String, ())>(), Err(Error::Message(_))));
+    drop(socket);
+    client.join().unwrap();
+}